@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+
+/// Condition below which a subsystem is considered failed
+pub const SUBSYSTEM_FAILURE_THRESHOLD: f32 = 0.2;
+
+/// Subsystems that can be degraded by impacts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Subsystem {
+    Power,
+    Propulsion,
+}
+
+/// Component tracking the condition of a satellite's subsystems
+#[derive(Component)]
+pub struct Health {
+    /// Power subsystem condition (1.0 = nominal, 0.0 = destroyed)
+    pub power: f32,
+    /// Propulsion subsystem condition (1.0 = nominal, 0.0 = destroyed)
+    pub propulsion: f32,
+    /// Battery state of charge (1.0 = full, 0.0 = browned out)
+    pub battery: f32,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            power: 1.0,
+            propulsion: 1.0,
+            battery: 1.0,
+        }
+    }
+}
+
+impl Health {
+    /// Whether the satellite can still perform maneuvers
+    pub fn has_propulsion(&self) -> bool {
+        self.propulsion > SUBSYSTEM_FAILURE_THRESHOLD && self.has_power()
+    }
+
+    /// Whether the satellite still has enough power to operate
+    pub fn has_power(&self) -> bool {
+        self.power > SUBSYSTEM_FAILURE_THRESHOLD && self.battery > 0.0
+    }
+
+    /// Whether a power or propulsion subsystem has failed for good
+    pub fn is_crippled(&self) -> bool {
+        self.power <= SUBSYSTEM_FAILURE_THRESHOLD || self.propulsion <= SUBSYSTEM_FAILURE_THRESHOLD
+    }
+
+    /// Reduce the condition of a subsystem, returning true if it failed as a result
+    pub fn degrade(&mut self, subsystem: Subsystem, amount: f32) -> bool {
+        let was_functional = match subsystem {
+            Subsystem::Power => self.has_power(),
+            Subsystem::Propulsion => self.has_propulsion(),
+        };
+
+        let condition = match subsystem {
+            Subsystem::Power => &mut self.power,
+            Subsystem::Propulsion => &mut self.propulsion,
+        };
+        *condition = (*condition - amount).max(0.0);
+
+        let is_functional = match subsystem {
+            Subsystem::Power => self.has_power(),
+            Subsystem::Propulsion => self.has_propulsion(),
+        };
+
+        was_functional && !is_functional
+    }
+}
@@ -1,7 +1,9 @@
+pub mod health;
 pub mod objects;
 pub mod orbital;
 pub mod physics;
 
+pub use health::*;
 pub use objects::*;
 pub use orbital::*;
 pub use physics::*;
\ No newline at end of file
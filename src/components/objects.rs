@@ -18,6 +18,147 @@ impl Satellite {
     }
 }
 
+/// Component naming the organization that operates a satellite
+#[derive(Component, Clone)]
+pub struct Operator {
+    pub name: String,
+}
+
+impl Operator {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+
+    /// Guess the operator from well-known satellite naming conventions (simplified heuristic)
+    pub fn from_satellite_name(name: &str) -> Self {
+        let name_upper = name.to_uppercase();
+
+        let operator = if name_upper.contains("STARLINK") {
+            "SpaceX"
+        } else if name_upper.contains("ONEWEB") {
+            "OneWeb"
+        } else if name_upper.contains("IRIDIUM") {
+            "Iridium"
+        } else if name_upper.contains("GLOBALSTAR") {
+            "Globalstar"
+        } else if name_upper.contains("ISS") || name_upper.contains("ZARYA") {
+            "NASA"
+        } else if name_upper.contains("GPS") {
+            "US Space Force"
+        } else if name_upper.contains("GALILEO") {
+            "EUSPA"
+        } else if name_upper.contains("GLONASS") || name_upper.contains("COSMOS") {
+            "Roscosmos"
+        } else if name_upper.contains("BEIDOU") {
+            "CNSA"
+        } else if name_upper.contains("NOAA") || name_upper.contains("GOES") || name_upper.contains("JPSS") {
+            "NOAA"
+        } else if name_upper.contains("INTELSAT") {
+            "Intelsat"
+        } else if name_upper.contains("ASTRA") || name_upper.contains("SES") {
+            "SES"
+        } else if name_upper.contains("EUTELSAT") {
+            "Eutelsat"
+        } else {
+            "Unknown"
+        };
+
+        Self::new(operator)
+    }
+}
+
+/// Component grouping satellites into a constellation (or constellation shell) for bulk operations
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct Constellation {
+    pub name: String,
+}
+
+impl Constellation {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+
+    /// Identify the constellation from the satellite name, splitting Starlink into shells by inclination
+    pub fn classify(name: &str, inclination_deg: f64) -> Option<Self> {
+        let name_upper = name.to_uppercase();
+
+        let constellation = if name_upper.contains("STARLINK") {
+            if inclination_deg < 50.0 {
+                "Starlink 43°"
+            } else if inclination_deg < 53.1 {
+                "Starlink Shell 1"
+            } else if inclination_deg < 60.0 {
+                "Starlink Shell 4"
+            } else if inclination_deg < 90.0 {
+                "Starlink Shell 2"
+            } else {
+                "Starlink Shell 3"
+            }
+        } else if name_upper.contains("ONEWEB") {
+            "OneWeb"
+        } else if name_upper.contains("IRIDIUM") {
+            "Iridium"
+        } else if name_upper.contains("GLOBALSTAR") {
+            "Globalstar"
+        } else if name_upper.contains("GPS") || name_upper.contains("NAVSTAR") {
+            "GPS"
+        } else if name_upper.contains("GALILEO") || name_upper.contains("GSAT0") {
+            "Galileo"
+        } else if name_upper.contains("GLONASS") {
+            "GLONASS"
+        } else if name_upper.contains("BEIDOU") {
+            "BeiDou"
+        } else {
+            return None;
+        };
+
+        Some(Self::new(constellation))
+    }
+}
+
+/// Component holding the SATCAT owner/country code of an object, e.g. "US", "PRC", "CIS"
+#[derive(Component, Clone)]
+pub struct Owner {
+    pub code: String,
+}
+
+/// Component marking derelicts: rocket bodies, debris and nonoperational payloads per SATCAT
+#[derive(Component)]
+pub struct Derelict;
+
+/// Component marking spent rocket stages
+#[derive(Component)]
+pub struct RocketBody;
+
+impl RocketBody {
+    /// Whether a catalog name designates a rocket body (e.g. "CZ-4C R/B")
+    pub fn is_rocket_body_name(name: &str) -> bool {
+        name.to_uppercase().contains("R/B")
+    }
+}
+
+/// Component holding an object's catalogued shape, dimensions and dry mass (from ESA DISCOS)
+#[derive(Component, Clone, Debug, Default)]
+pub struct ObjectProperties {
+    /// Shape description, e.g. "Box + 2 Pan"
+    pub shape: String,
+    /// Dry mass (kg)
+    pub dry_mass: Option<f64>,
+    /// Height, width and depth (m)
+    pub dimensions: Option<[f64; 3]>,
+    /// Largest dimension, deployed appendages included (m)
+    pub span: Option<f64>,
+    /// Average cross-section over random orientations (m²)
+    pub cross_section: Option<f64>,
+}
+
+impl ObjectProperties {
+    /// Largest dimension (m), from the span or the body's own dimensions
+    pub fn characteristic_length(&self) -> Option<f64> {
+        self.span.or_else(|| self.dimensions.map(|[h, w, d]| h.max(w).max(d))).filter(|&length| length > 0.0)
+    }
+}
+
 /// Component for debris objects
 #[derive(Component)]
 pub struct Debris {
@@ -25,27 +166,34 @@ pub struct Debris {
     pub parent_collision: Option<u32>,
     /// Generation number (0 = original object, 1 = first-gen debris, etc.)
     pub generation: u32,
-    /// When this debris was created (simulation time)
-    pub creation_time: f64,
 }
 
 impl Debris {
-    pub fn new(parent_collision: Option<u32>, generation: u32, creation_time: f64) -> Self {
+    pub fn new(parent_collision: Option<u32>, generation: u32) -> Self {
         Self {
             parent_collision,
             generation,
-            creation_time,
         }
     }
+}
 
-    /// Create debris from an original satellite collision
-    pub fn from_collision(collision_id: u32, creation_time: f64) -> Self {
-        Self::new(Some(collision_id), 1, creation_time)
+/// Component marking a debris entity that statistically represents many fragments
+#[derive(Component)]
+pub struct SuperParticle {
+    /// Number of fragments represented by this entity
+    pub weight: u32,
+    /// RMS radius of the fragment cloud around the shared orbit (km)
+    pub spread: f64,
+}
+
+impl SuperParticle {
+    pub fn new(weight: u32, spread: f64) -> Self {
+        Self { weight, spread }
     }
 
-    /// Create higher generation debris from existing debris
-    pub fn from_debris(parent: &Debris, collision_id: u32, creation_time: f64) -> Self {
-        Self::new(Some(collision_id), parent.generation + 1, creation_time)
+    /// Mass of a single represented fragment, given the entity's combined mass
+    pub fn fragment_mass(&self, total_mass: f64) -> f64 {
+        total_mass / self.weight.max(1) as f64
     }
 }
 
@@ -53,6 +201,10 @@ impl Debris {
 #[derive(Component)]
 pub struct RenderAsSatellite;
 
+/// Marker component for objects placed by the user, whose orbits can be reshaped in the orbit designer
+#[derive(Component)]
+pub struct UserSpawned;
+
 /// Marker component for objects that should be rendered as debris
 #[derive(Component)]
 pub struct RenderAsDebris;
\ No newline at end of file
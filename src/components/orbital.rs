@@ -1,19 +1,22 @@
 use bevy::prelude::*;
-use nalgebra::{Vector3, Unit};
+use bevy::math::{DMat3, DVec3};
+use std::collections::VecDeque;
 
 /// Core orbital state component containing position and velocity vectors
+///
+/// Kept in f64 throughout the physics; rendering converts to `Vec3` at the `Transform`.
 #[derive(Component)]
 pub struct OrbitalState {
     /// Position vector in kilometers from Earth center
-    pub position: Vec3,
+    pub position: DVec3,
     /// Velocity vector in km/s
-    pub velocity: Vec3,
+    pub velocity: DVec3,
     /// Object mass in kilograms
     pub mass: f64,
 }
 
 impl OrbitalState {
-    pub fn new(position: Vec3, velocity: Vec3, mass: f64) -> Self {
+    pub fn new(position: DVec3, velocity: DVec3, mass: f64) -> Self {
         Self {
             position,
             velocity,
@@ -23,12 +26,12 @@ impl OrbitalState {
 
     /// Calculate distance from Earth center in km
     pub fn altitude(&self) -> f64 {
-        self.position.length() as f64
+        self.position.length()
     }
 
     /// Calculate orbital speed in km/s
     pub fn speed(&self) -> f64 {
-        self.velocity.length() as f64
+        self.velocity.length()
     }
 
     /// Calculate kinetic energy in Joules
@@ -48,6 +51,136 @@ impl OrbitalState {
     }
 }
 
+/// Component selecting how physics advances an object's orbit
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Propagation {
+    /// Numerical integration of the state vector under the full force model
+    #[default]
+    Numerical,
+    /// Analytic Kepler propagation with the secular J2 drift of the node and perigee
+    MeanElements,
+}
+
+/// Component caching the range of distances from Earth's center an orbit sweeps (km)
+///
+/// Objects whose ranges are further apart than their collision distance can never meet, so screening
+/// skips such pairs without looking at their states.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct AltitudeBand {
+    pub perigee: f64,
+    pub apogee: f64,
+}
+
+impl AltitudeBand {
+    /// Osculating perigee and apogee radii of a state (km, km/s); unbound orbits reach infinity
+    pub fn from_state(position: DVec3, velocity: DVec3, mu: f64) -> Self {
+        let r = position.length();
+        let energy = velocity.length_squared() / 2.0 - mu / r;
+        let eccentricity = ((velocity.length_squared() - mu / r) * position - position.dot(velocity) * velocity) / mu;
+        let e = eccentricity.length();
+        if energy >= 0.0 || e >= 1.0 {
+            let h_squared = position.cross(velocity).length_squared();
+            return Self { perigee: (h_squared / (mu * (1.0 + e))).min(r), apogee: f64::INFINITY };
+        }
+        let semi_major_axis = -mu / (2.0 * energy);
+        Self { perigee: semi_major_axis * (1.0 - e), apogee: semi_major_axis * (1.0 + e) }
+    }
+
+    /// Whether the two ranges stay more than `margin` (km) apart
+    pub fn separated_from(&self, other: &AltitudeBand, margin: f64) -> bool {
+        self.perigee - margin > other.apogee || other.perigee - margin > self.apogee
+    }
+}
+
+/// Component holding a debris fragment's estimated drag lifetime, re-estimated as its orbit and the atmosphere change
+#[derive(Component, Clone, Copy, Debug)]
+pub struct DecayLifetime {
+    /// Simulation time the fragment is expected to have decayed by (seconds)
+    pub decays_at: f64,
+    /// Simulation time of the estimate (seconds)
+    pub estimated_at: f64,
+}
+
+/// Longest TLE age (days) the position uncertainty keeps growing for
+const MAX_COVARIANCE_AGE_DAYS: f64 = 30.0;
+
+/// Component holding an object's 1-sigma position uncertainty along its radial, transverse and normal directions (km)
+///
+/// The axes are kept uncorrelated; `inertial` turns them into a full covariance in the inertial frame.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct PositionCovariance {
+    pub sigma_rtn: DVec3,
+}
+
+impl PositionCovariance {
+    /// The same uncertainty along every axis
+    pub fn isotropic(sigma: f64) -> Self {
+        Self { sigma_rtn: DVec3::splat(sigma) }
+    }
+
+    /// Typical uncertainty of a TLE propagated `age_days` from its epoch, dominated by along-track growth
+    pub fn from_tle_age(age_days: f64) -> Self {
+        let age = age_days.abs().min(MAX_COVARIANCE_AGE_DAYS);
+        Self { sigma_rtn: DVec3::new(0.2 + 0.1 * age, 1.0 + 1.5 * age, 0.3 + 0.1 * age) }
+    }
+
+    /// Covariance (km²) in the inertial frame of an object at `position` moving with `velocity`
+    pub fn inertial(&self, position: DVec3, velocity: DVec3) -> DMat3 {
+        let radial = position.normalize_or_zero();
+        let normal = position.cross(velocity).normalize_or_zero();
+        let transverse = normal.cross(radial);
+        let rotation = DMat3::from_cols(radial, transverse, normal);
+        rotation * DMat3::from_diagonal(self.sigma_rtn * self.sigma_rtn) * rotation.transpose()
+    }
+}
+
+/// Illumination of an object by the Sun
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EclipseState {
+    #[default]
+    Sunlit,
+    Penumbra,
+    Umbra,
+}
+
+/// Component holding an object's eclipse state and the fraction of the solar disk it sees
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct Eclipse {
+    pub state: EclipseState,
+    /// Visible fraction of the solar disk (1.0 = full sunlight, 0.0 = umbra)
+    pub sunlight: f32,
+}
+
+impl Default for Eclipse {
+    fn default() -> Self {
+        Self {
+            state: EclipseState::Sunlit,
+            sunlight: 1.0,
+        }
+    }
+}
+
+impl Eclipse {
+    /// Whether any part of the solar disk is hidden by the Earth
+    pub fn is_eclipsed(&self) -> bool {
+        self.state != EclipseState::Sunlit
+    }
+}
+
+/// Impulsive burn scheduled at a simulation time
+#[derive(Clone, Copy, Debug)]
+pub struct PlannedBurn {
+    pub execute_at: f64,
+    /// Velocity change (km/s) along the radial, transverse and orbit-normal directions at execution
+    pub delta_v: DVec3,
+}
+
+/// Component holding the burns an object still has to execute, earliest first
+#[derive(Component, Clone, Debug, Default)]
+pub struct ManeuverPlan {
+    pub burns: VecDeque<PlannedBurn>,
+}
+
 /// Component to store the original TLE data for reference
 #[derive(Component)]
 pub struct TleData {
@@ -1,5 +1,8 @@
 use bevy::prelude::*;
 
+/// SGP4 reference air density times an Earth radius, relating B* to the ballistic coefficient (kg/m²/ER)
+const BSTAR_REFERENCE_DENSITY: f64 = 0.15696615;
+
 /// Physics properties for objects that can experience forces
 #[derive(Component)]
 pub struct PhysicsObject {
@@ -9,17 +12,30 @@ pub struct PhysicsObject {
     pub drag_coefficient: f64,
     /// Radius for collision detection in meters
     pub collision_radius: f64,
+    /// Cross-sectional area over mass in m²/kg, which sets how strongly drag decelerates the object
+    pub area_to_mass: f64,
 }
 
 impl PhysicsObject {
-    pub fn new(cross_section: f64, drag_coefficient: f64, collision_radius: f64) -> Self {
+    pub fn new(cross_section: f64, drag_coefficient: f64, collision_radius: f64, mass_kg: f64) -> Self {
         Self {
             cross_section,
             drag_coefficient,
             collision_radius,
+            area_to_mass: if mass_kg > 0.0 { cross_section / mass_kg } else { 0.0 },
         }
     }
 
+    /// Drag coefficient × area / mass (m²/kg)
+    pub fn ballistic_coefficient(&self) -> f64 {
+        self.drag_coefficient * self.area_to_mass
+    }
+
+    /// SGP4 B* drag term (1/Earth radii) matching the ballistic coefficient
+    pub fn bstar(&self) -> f64 {
+        self.ballistic_coefficient() * BSTAR_REFERENCE_DENSITY / 2.0
+    }
+
     /// Create physics object for a typical satellite
     pub fn satellite(mass_kg: f64) -> Self {
         // Rough estimates based on satellite mass
@@ -28,9 +44,15 @@ impl PhysicsObject {
             radius * radius * std::f64::consts::PI, // Cross section
             2.2, // Typical drag coefficient for satellites
             radius, // Collision radius
+            mass_kg,
         )
     }
 
+    /// Create physics object of a known size: a sphere of `radius` meters
+    pub fn sized(mass_kg: f64, radius: f64, drag_coefficient: f64) -> Self {
+        Self::new(radius * radius * std::f64::consts::PI, drag_coefficient, radius, mass_kg)
+    }
+
     /// Create physics object for debris
     pub fn debris(mass_kg: f64) -> Self {
         let radius = (mass_kg / 2000.0).powf(1.0/3.0); // Debris typically less dense
@@ -38,28 +60,7 @@ impl PhysicsObject {
             radius * radius * std::f64::consts::PI,
             2.5, // Higher drag coefficient for irregular debris
             radius,
+            mass_kg,
         )
     }
-}
-
-/// Component to track collision events
-#[derive(Component)]
-pub struct CollisionEvent {
-    pub id: u32,
-    pub time: f64,
-    pub position: Vec3,
-    pub objects_involved: Vec<Entity>,
-    pub debris_generated: Vec<Entity>,
-}
-
-impl CollisionEvent {
-    pub fn new(id: u32, time: f64, position: Vec3, objects_involved: Vec<Entity>) -> Self {
-        Self {
-            id,
-            time,
-            position,
-            objects_involved,
-            debris_generated: Vec::new(),
-        }
-    }
 }
\ No newline at end of file
@@ -1,32 +1,289 @@
 use bevy::prelude::*;
 use bevy::math::primitives::Sphere;
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin};
 use bevy::log::LogPlugin;
+use bevy::render::settings::{RenderCreation, WgpuSettings};
+use bevy::render::view::VisibilitySystems;
+use bevy::render::RenderPlugin;
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
+use std::time::Duration;
 
 mod components;
 mod resources;
 mod systems;
 mod utils;
 
-use components::*;
 use resources::*;
 use systems::*;
+use utils::CliOptions;
 
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins.set(LogPlugin {
-            level: bevy::log::Level::INFO,
-            filter: std::env::var("RUST_LOG")
-                .unwrap_or_else(|_| "info,kessler_simulator=info,bevy_render=warn,bevy_ecs=warn".to_string()),
-            ..default()
-        }))
-        .init_resource::<Constants>()
-        .init_resource::<SimulationTime>()
+    let cli = CliOptions::from_env();
+    let settings = Settings::load(cli.settings.clone().unwrap_or_else(|| DEFAULT_SETTINGS_PATH.into()));
+
+    let scenario = match (&cli.scenario, &cli.preset) {
+        (Some(path), _) => Some(Scenario::load(path)),
+        (None, Some(name)) => Some(Scenario::preset(name)),
+        (None, None) => None,
+    };
+    let scenario = scenario.map(|scenario| {
+        scenario.unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        })
+    });
+    let scenario_constants = scenario.as_ref().map(|scenario| scenario.constants.clone()).unwrap_or_default();
+
+    let mut sim_time = SimulationTime::default();
+    if let Some(start) = cli.start.or(scenario.as_ref().and_then(Scenario::start_unix)) {
+        sim_time.epoch_unix = start;
+    }
+    let governor = TimestepGovernor {
+        enabled: !cli.no_governor,
+        substep_budget: cli.substep_budget.unwrap_or(DEFAULT_SUBSTEP_BUDGET),
+        ..default()
+    };
+    if let Some(timestep) = cli.timestep {
+        sim_time.set_timestep(timestep);
+        // The governor keeps substeps within the stability limit regardless of the timestep
+        if let Some(warning) = sim_time.stability_warning().filter(|_| !governor.enabled) {
+            eprintln!("Warning: {}", warning);
+        }
+    }
+
+    let mut export_config = ExportConfig::default();
+    if let Some(span) = cli.export_span {
+        export_config.span = span.max(0.0);
+    }
+    if let Some(step) = cli.export_step {
+        export_config.step = step.max(1.0);
+    }
+    export_config.checkpoint_interval = cli.checkpoint.filter(|&hours| hours > 0.0).map(|hours| hours * 3600.0);
+
+    let mut telemetry_config = TelemetryConfig {
+        pending: cli.telemetry.clone(),
+        ..default()
+    };
+    if let Some(cadence) = cli.telemetry_cadence {
+        telemetry_config.cadence = cadence.max(0.0);
+    }
+
+    let mut tle_source = TleSource {
+        format: cli.gp_format.unwrap_or_default(),
+        files: cli.tle_files.clone(),
+        cap: cli.max_objects.unwrap_or(settings.object_cap),
+        ..default()
+    };
+    if !settings.tle_groups.is_empty() {
+        tle_source.groups = settings.tle_groups.clone();
+    }
+    if cli.full_catalog {
+        tle_source.groups = FULL_CATALOG_GROUPS.iter().map(|group| group.to_string()).collect();
+        tle_source.cap = cli.max_objects.unwrap_or(ObjectCap::All);
+    }
+    if !cli.groups.is_empty() {
+        tle_source.groups = cli.groups.iter().map(|group| celestrak_group(group)).collect();
+    }
+    if let Some(scenario) = &scenario {
+        tle_source.catalog = scenario.catalog;
+    }
+    let mut tle_archive = match &cli.tle_archive {
+        Some(directory) => TleArchive::load(directory).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }),
+        None => TleArchive::default(),
+    };
+    if let Some(first) = tle_archive.snapshots.first() {
+        // The first snapshot is the startup catalog, and the replay starts from its date unless told otherwise
+        tle_source.files = vec![first.path.clone()];
+        tle_source.cap = cli.max_objects.unwrap_or(ObjectCap::All);
+        tle_archive.next = 1;
+        if cli.start.is_none() && scenario.as_ref().and_then(Scenario::start_unix).is_none() {
+            sim_time.epoch_unix = first.time;
+        }
+    }
+    let tle_refresh = cli.tle_refresh.filter(|&hours| hours > 0.0).map(|hours| hours * 3600.0);
+    let discos_token = cli.discos.then(|| std::env::var(DISCOS_TOKEN_VAR).ok()).flatten();
+    if cli.discos && discos_token.is_none() {
+        eprintln!("Warning: --discos needs a DISCOSweb API token in ${}; physical properties will be estimated", DISCOS_TOKEN_VAR);
+    }
+    let ephemeris_imports = EphemerisImports::from_sources(&cli.ephemerides);
+    let cdm_imports = CdmImports { pending: cli.cdms.clone() };
+    let cdm_export = CdmExport { format: cli.cdm_export, ..default() };
+    let sso_spawns = SsoSpawns { pending: cli.sso.clone() };
+    let background = BackgroundPopulation {
+        min_diameter: cli.background,
+        weight: cli.background_weight.unwrap_or(DEFAULT_BACKGROUND_WEIGHT),
+    };
+    let launch_profile = cli.launch_traffic.as_deref().map(|path| LaunchProfile::load(path).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    }));
+    let decay_validation = DecayValidation { enabled: cli.validate_decay, ..default() };
+    let octree = SpatialOctree::new(settings.octree);
+    let broad_phase = cli.broad_phase.unwrap_or(settings.broad_phase);
+    let collision_exclusions = settings.collision_exclusions.clone();
+    let collision_schedule = CollisionSchedule::new(cli.collision_interval.unwrap_or(settings.collision_interval));
+    let constants = Constants {
+        force_model: cli.force_model.or(scenario_constants.force_model).unwrap_or(settings.force_model),
+        third_body: !cli.no_third_body && scenario_constants.third_body.unwrap_or(true),
+        reentry_altitude: cli.reentry_altitude.or(scenario_constants.reentry_altitude).unwrap_or(REENTRY_ALTITUDE).max(0.0),
+        ..default()
+    };
+    let space_weather = cli.space_weather.unwrap_or_default();
+    let mut integrator = IntegratorConfig {
+        method: cli.integrator.unwrap_or(settings.integrator),
+        ..default()
+    };
+    if let Some(tolerance) = cli.integrator_tolerance {
+        integrator.tolerance = tolerance.max(1e-9);
+    }
+    let mean_elements = MeanElementPropagation { enabled: cli.mean_elements, ..default() };
+    // Avoidance maneuvers react to the screened conjunctions
+    let mut screening = ConjunctionScreening {
+        enabled: cli.pc_threshold.is_some() || cli.collision_avoidance || cli.cdm_export.is_some(),
+        ..default()
+    };
+    let avoidance = CollisionAvoidance { enabled: cli.collision_avoidance };
+    let stm_regime = match cli.stm_regime {
+        Some(regime) => regime,
+        // Avoidance needs a regime in which operators maneuver
+        None if cli.collision_avoidance && settings.stm_regime == StmRegime::Unregulated => StmRegime::SelfManaged,
+        None => settings.stm_regime,
+    };
+    let mut traffic_rules = TrafficRules::from_regime(stm_regime);
+    if let Some(right_of_way) = cli.right_of_way.or(settings.right_of_way) {
+        traffic_rules.right_of_way = right_of_way;
+    }
+    if let Some(threshold) = cli.pc_threshold {
+        screening.threshold = threshold.clamp(0.0, 1.0);
+    }
+    let physics_backend = cli.physics_backend.unwrap_or(settings.physics_backend);
+    let collision_resolution = cli.collision_resolution.unwrap_or(settings.collision_resolution);
+    let publisher = cli.publish.clone().map(EventPublisher::start).unwrap_or_default();
+    let (stress_config, stress_sweep) = configure_stress_test(&cli);
+    let filter = PopulationFilter::parse(cli.filter.as_deref().unwrap_or("")).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    });
+    let features = SimulationFeatures::with_disabled(&cli.disable).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    });
+
+    let mut plugins = DefaultPlugins.set(LogPlugin {
+        level: bevy::log::Level::INFO,
+        filter: std::env::var("RUST_LOG")
+            .unwrap_or_else(|_| "info,kessler_simulator=info,bevy_render=warn,bevy_ecs=warn".to_string()),
+        ..default()
+    });
+    if cli.headless {
+        // Benchmark runs: no window, no GPU, update as fast as possible
+        plugins = plugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            })
+            .set(RenderPlugin {
+                render_creation: RenderCreation::Automatic(WgpuSettings { backends: None, ..default() }),
+                ..default()
+            })
+            .disable::<WinitPlugin>()
+            .add(ScheduleRunnerPlugin::run_loop(Duration::ZERO));
+    }
+
+    let mut app = App::new();
+    app.add_plugins(plugins)
+        .add_plugins(SimulationDiagnosticsPlugin);
+    if cli.diagnostics {
+        app.add_plugins((FrameTimeDiagnosticsPlugin::default(), LogDiagnosticsPlugin::default()));
+    }
+
+    app
+        .insert_resource(constants)
+        .insert_resource(space_weather)
+        .insert_resource(sim_time)
+        .insert_resource(governor)
+        .insert_resource(integrator)
+        .insert_resource(physics_backend)
+        .insert_resource(mean_elements)
+        .insert_resource(screening)
+        .insert_resource(avoidance)
+        .insert_resource(cli.look_ahead.map(LookAhead::with_hours).unwrap_or_default())
+        .insert_resource(cli.near_misses.map(CloseApproaches::with_count).unwrap_or_default())
+        .init_resource::<CollisionStats>()
+        .init_resource::<DebrisGenealogy>()
+        .insert_resource(cli)
+        .insert_resource(settings)
+        .insert_resource(export_config)
+        .init_resource::<EventLocations>()
+        .insert_resource(ephemeris_imports)
+        .insert_resource(cdm_imports)
+        .insert_resource(cdm_export)
+        .insert_resource(sso_spawns)
+        .insert_resource(background)
+        .insert_resource(LaunchTraffic::new(launch_profile))
+        .insert_resource(decay_validation)
+        .insert_resource(publisher)
+        .insert_resource(telemetry_config)
+        .init_resource::<ObjectMaterials>()
+        .init_resource::<GroupMaterials>()
+        .init_resource::<SatcatCatalog>()
+        .init_resource::<ConstellationGroups>()
+        .init_resource::<GeoSlots>()
+        .init_resource::<ProtectionMonitor>()
+        .insert_resource(filter)
         .init_resource::<EnergyAnalytics>()
         .init_resource::<TleDataCache>()
-        .init_resource::<SpatialOctree>()
+        .insert_resource(tle_source)
+        .init_resource::<TleSpawnQueue>()
+        .insert_resource(DiscosClient::new(discos_token))
+        .insert_resource(TleRefresh::new(tle_refresh))
+        .insert_resource(tle_archive)
+        .insert_resource(scenario.map(ActiveScenario::new).unwrap_or_default())
+        .insert_resource(octree)
+        .insert_resource(broad_phase)
+        .insert_resource(collision_exclusions)
+        .init_resource::<MortonBroadPhase>()
+        .init_resource::<HashGridBroadPhase>()
+        .init_resource::<SmartSieve>()
         .init_resource::<CollisionPairs>()
+        .insert_resource(collision_schedule)
+        .insert_resource(collision_resolution)
         .init_resource::<OptimizedPhysicsData>()
-        .init_resource::<StressTestConfig>()
+        .insert_resource(stress_config)
+        .insert_resource(stress_sweep)
+        .init_resource::<BurstSpawn>()
+        .init_resource::<StageTimings>()
+        .init_resource::<DegradationConfig>()
+        .init_resource::<SuperParticleConfig>()
+        .init_resource::<LiveMode>()
+        .init_resource::<SlowMotion>()
+        .init_resource::<ConjunctionForecast>()
+        .init_resource::<ApproachReport>()
+        .init_resource::<OrbitDesigner>()
+        .init_resource::<ReentryStreaks>()
+        .init_resource::<DirectorMode>()
+        .insert_resource(traffic_rules)
+        .init_resource::<TrafficCoordination>()
+        .init_resource::<CameraRig>()
+        .init_resource::<FlyCamera>()
+        .init_resource::<Selection>()
+        .init_resource::<GroundTrace>()
+        .init_resource::<SearchBox>()
+        .init_resource::<LayerVisibility>()
+        .init_resource::<DebrisCulling>()
+        .insert_resource(features)
+        .add_event::<ImpactEvent>()
+        .add_event::<FocusRequest>()
+        .add_event::<ConjunctionEvent>()
+        .add_event::<ReentryEvent>()
+        .add_event::<CollisionEvent>()
+        .add_event::<SearchCommand>()
         // Add ambient lighting for overall scene brightness
         .insert_resource(AmbientLight {
             color: Color::srgb(0.8, 0.9, 1.0), // Slightly blue-tinted like space
@@ -35,46 +292,339 @@ fn main() {
         })
         .add_systems(Startup, (
             setup_scene,
+            setup_object_materials_system,
             initialize_tle_data_system,
+            setup_hud_system,
+            setup_search_box_system,
+            load_satcat_system,
+            setup_risk_panel_system,
+            setup_lookahead_panel_system,
+            setup_close_approach_panel_system,
+            setup_genealogy_panel_system,
+            setup_group_checklist_system,
+            setup_scenario_menu_system,
+            setup_designer_text_system,
         ))
         .add_systems(Update, (
-            camera_control_system,
-            time_control_system,
-            // Original physics system (disable when using optimized)
-            physics_system,
+            camera_control_system
+                .run_if(orbit_camera_active)
+                .run_if(designer_idle)
+                .after(designer_drag_system),
+            fly_camera_control_system.run_if(search_inactive),
+            fly_camera_system
+                .run_if(search_inactive)
+                .after(fly_camera_control_system),
+            time_control_system
+                .before(physics_system)
+                .before(optimized_physics_system),
+            live_mode_control_system.run_if(search_inactive),
+            live_mode_sync_system,
+            slow_motion_control_system.run_if(search_inactive),
+            slow_motion_system.after(time_control_system),
+            simulation_clock_system
+                .after(slow_motion_system)
+                .before(physics_system)
+                .before(optimized_physics_system),
+            // Exactly one physics backend runs; F12 switches between them
+            physics_backend_control_system
+                .run_if(search_inactive)
+                .before(physics_system)
+                .before(prepare_optimized_physics_system),
+            physics_system.run_if(legacy_physics_active),
+            ephemeris_drive_system
+                .after(physics_system)
+                .after(apply_optimized_physics_system),
+            reentry_system.after(ephemeris_drive_system),
         ))
         .add_systems(Update, (
             // Optimized physics systems
-            prepare_optimized_physics_system,
-            optimized_physics_system,
-            apply_optimized_physics_system,
-            optimized_physics_monitor_system,
+            timestep_governor_system
+                .after(time_control_system)
+                .before(physics_system)
+                .before(optimized_physics_system),
+            (
+                prepare_optimized_physics_system,
+                optimized_physics_system,
+                apply_optimized_physics_system,
+                optimized_physics_monitor_system,
+            ).run_if(optimized_physics_active),
+            // Stage timers for stress sweeps
+            stage_timer_begin("physics")
+                .before(physics_system)
+                .before(prepare_optimized_physics_system)
+                .before(optimized_physics_system)
+                .before(apply_optimized_physics_system),
+            stage_timer_end("physics")
+                .after(physics_system)
+                .after(prepare_optimized_physics_system)
+                .after(optimized_physics_system)
+                .after(apply_optimized_physics_system),
+            stage_timer_begin("collision")
+                .before(update_spatial_octree_system)
+                .before(collision_detection_system)
+                .before(debris_generation_system),
+            stage_timer_end("collision")
+                .after(update_spatial_octree_system)
+                .after(collision_detection_system)
+                .after(debris_generation_system),
         ))
         .add_systems(Update, (
             // Collision and debris systems
+            octree_control_system
+                .run_if(search_inactive)
+                .before(update_spatial_octree_system),
             update_spatial_octree_system,
-            collision_detection_system,
-            debris_generation_system,
+            altitude_band_system
+                .before(collision_detection_system)
+                .before(conjunction_forecast_system),
+            collision_detection_system.run_if(collisions_enabled),
+            debris_generation_system.run_if(debris_enabled),
+            super_particle_aggregation_system,
+            conjunction_forecast_system.after(update_spatial_octree_system),
+            publish_conjunctions_system.after(conjunction_forecast_system),
+            conjunction_screening_system.after(conjunction_forecast_system),
+            collision_avoidance_system
+                .after(conjunction_screening_system)
+                .before(maneuver_execution_system),
+            // CDMs record the encounter as screened, before any avoidance burn executes
+            cdm_export_system
+                .after(conjunction_screening_system)
+                .before(maneuver_execution_system),
+            publish_events_system.after(debris_generation_system),
+            simulation_diagnostics_system
+                .after(collision_detection_system)
+                .after(update_spatial_octree_system),
+        ))
+        .add_systems(Update, (
+            // Satellite health and degradation systems
+            small_debris_flux_system,
+            impact_damage_system,
+            power_budget_system.after(eclipse_system),
+        ))
+        .add_systems(Update, (
+            // Eclipse and solar radiation pressure systems
+            eclipse_system.after(reentry_system),
+            solar_radiation_pressure_system.after(eclipse_system),
+            // Atmospheric drag
+            solar_cycle_system,
+            atmospheric_drag_system.after(reentry_system).after(solar_cycle_system),
+            debris_decay_system.after(atmospheric_drag_system).after(altitude_band_system),
+        ))
+        .add_systems(Update, (
+            // Space traffic coordination systems
+            traffic_rules_control_system.run_if(search_inactive),
+            traffic_screening_system.before(lookahead_system),
+            traffic_response_system
+                .after(traffic_screening_system)
+                .before(maneuver_execution_system),
+            debug_traffic_system,
+        ))
+        .add_systems(Update, (
+            // Rendezvous planning and scheduled burns
+            transfer_planning_system.after(search_input_system),
+            burn_scheduling_system.after(search_input_system),
+            maneuver_execution_system
+                .after(transfer_planning_system)
+                .after(burn_scheduling_system),
+            // Mean-element propagation of quiescent objects
+            propagation_mode_system
+                .before(physics_system)
+                .before(optimized_physics_system),
+        ))
+        .add_systems(Update, (
+            // Watchlist systems
+            watchlist_tagging_system.after(process_tle_fetch_system),
+            watch_command_system.after(search_input_system),
+            watch_decoration_system
+                .after(watchlist_tagging_system)
+                .after(watch_command_system)
+                .after(selection_decoration_system),
+        ))
+        .add_systems(Update, (
+            // Crewed-asset protection systems
+            protected_asset_tagging_system.after(process_tle_fetch_system),
+            protection_screening_system.after(update_spatial_octree_system),
+            risk_panel_system.after(protection_screening_system),
+        ))
+        .add_systems(Update, (
+            // Look-ahead conjunction prediction systems
+            lookahead_command_system.after(search_input_system),
+            lookahead_system.after(lookahead_command_system),
+            lookahead_panel_system.after(lookahead_system),
+        ))
+        .add_systems(Update, (
+            // Orbit designer systems
+            design_spawn_system.after(search_input_system),
+            designer_drag_system,
+            designer_overlay_system.run_if(rendering_enabled),
+            designer_text_system,
+        ))
+        .add_systems(Update, (
+            // Selection and camera focus systems
+            selection_system.run_if(designer_idle).after(designer_drag_system),
+            focus_control_system.run_if(search_inactive),
+            object_action_system.run_if(search_inactive),
+            oem_export_system.run_if(search_inactive),
+            catalog_export_system.run_if(search_inactive),
+            telemetry_control_system.run_if(search_inactive),
+            ground_track_export_system.run_if(search_inactive),
+            event_location_log_system,
+            search_input_system.after(focus_control_system),
+            search_box_display_system.after(search_input_system),
+            approach_query_system.after(search_input_system),
+            camera_focus_system
+                .after(selection_system)
+                .after(search_input_system),
+            gamepad_camera_system.after(camera_control_system),
+            gamepad_control_system.before(time_control_system),
+            trackpad_gesture_system
+                .run_if(orbit_camera_active)
+                .after(camera_control_system),
+            touch_camera_system
+                .run_if(orbit_camera_active)
+                .after(camera_control_system),
+            director_control_system.run_if(search_inactive),
+            director_system.after(camera_focus_system),
+            camera_follow_system
+                .run_if(orbit_camera_active)
+                .after(director_system)
+                .after(camera_focus_system)
+                .after(camera_control_system)
+                .after(update_positions_system),
+        ))
+        .add_systems(Update, (
+            // HUD systems
+            hud_status_system,
+            hud_measurement_system,
+            measurement_line_system,
+            hud_button_system.before(time_control_system),
         ))
         .add_systems(Update, (
             // Rendering and analytics systems
-            satellite_rendering_system,
-            debris_rendering_system,
-            update_positions_system,
-            energy_analytics_system,
+            satellite_rendering_system.run_if(rendering_enabled),
+            debris_rendering_system.run_if(rendering_enabled),
+            update_positions_system.run_if(rendering_enabled),
+            energy_analytics_system.run_if(analytics_enabled),
+            reentry_analytics_system.after(reentry_system),
+            collision_stats_system.after(debris_generation_system),
+            geo_slot_tracking_system.run_if(analytics_enabled),
+            geo_overlay_system
+                .run_if(rendering_enabled)
+                .after(geo_slot_tracking_system),
+            earth_rotation_system.run_if(rendering_enabled),
+            ground_trace_system.after(selection_system),
+            ground_trace_drawing_system
+                .run_if(rendering_enabled)
+                .after(ground_trace_system),
+        ))
+        .add_systems(Update, (
+            // Render layer, trail and label systems
+            layer_toggle_system.run_if(search_inactive),
+            feature_toggle_system.run_if(search_inactive),
+            palette_control_system.run_if(search_inactive),
+            apply_palette_system.after(palette_control_system),
+            color_mode_control_system.run_if(search_inactive),
+            group_color_system
+                .after(color_mode_control_system)
+                .after(constellation_command_system)
+                .after(constellation_tagging_system)
+                .after(satcat_ingest_system)
+                .after(satellite_rendering_system)
+                .after(debris_rendering_system),
+            satcat_ingest_system.after(process_tle_fetch_system),
+            discos_system.after(tle_spawn_queue_system),
+            constellation_tagging_system.after(process_tle_fetch_system),
+            constellation_stats_control_system.run_if(search_inactive),
+            constellation_command_system
+                .after(search_input_system)
+                .after(constellation_stats_control_system),
+            layer_visibility_system
+                .after(satcat_ingest_system)
+                .after(search_input_system)
+                .after(constellation_command_system)
+                .after(satellite_rendering_system)
+                .after(debris_rendering_system),
+            selection_decoration_system.after(camera_focus_system),
+            trail_recording_system
+                .run_if(rendering_enabled)
+                .after(update_positions_system),
+            trail_drawing_system
+                .run_if(rendering_enabled)
+                .after(trail_recording_system),
+            reentry_streak_spawn_system
+                .run_if(rendering_enabled)
+                .after(reentry_system),
+            reentry_streak_drawing_system
+                .run_if(rendering_enabled)
+                .after(reentry_streak_spawn_system),
+            label_position_system.after(camera_follow_system),
         ))
         .add_systems(Update, (
             // Debug and stress test systems
             debug_orbital_system,
-            debug_analytics_system,
+            debug_analytics_system.run_if(analytics_enabled),
+            close_approach_tracking_system.after(conjunction_forecast_system),
+            close_approach_panel_system.after(close_approach_tracking_system),
+            genealogy_recording_system.after(debris_generation_system),
+            genealogy_panel_system
+                .after(genealogy_recording_system)
+                .after(selection_system),
             process_tle_fetch_system,
+            tle_file_drop_system.after(process_tle_fetch_system),
+            object_cap_command_system
+                .after(search_input_system)
+                .after(process_tle_fetch_system),
+            tle_spawn_queue_system
+                .after(object_cap_command_system)
+                .after(tle_file_drop_system),
+            ephemeris_import_system.after(process_tle_fetch_system),
+            cdm_import_system.after(process_tle_fetch_system),
+            sso_spawn_system,
+            background_population_system,
+            launch_traffic_system,
+            decay_validation_system.after(process_tle_fetch_system),
+            telemetry_watch_system.after(process_tle_fetch_system),
+            telemetry_recording_system.after(reentry_system),
+            catalog_checkpoint_system.after(reentry_system),
+        ))
+        .add_systems(Update, (
+            // Celestrak group checklist, element set refresh, archive replay and scenarios
+            group_command_system.after(search_input_system),
+            group_fetch_system
+                .after(group_command_system)
+                .after(process_tle_fetch_system)
+                .before(tle_spawn_queue_system),
+            group_checklist_system.after(group_fetch_system),
+            tle_refresh_system
+                .after(process_tle_fetch_system)
+                .before(tle_spawn_queue_system),
+            archive_replay_system
+                .after(process_tle_fetch_system)
+                .before(tle_spawn_queue_system),
+            // Scenario presets, objects and scheduled events
+            scenario_command_system.after(search_input_system),
+            scenario_spawn_system
+                .after(scenario_command_system)
+                .before(tle_spawn_queue_system),
+            scenario_event_system.after(scenario_spawn_system),
+            scenario_menu_system.after(scenario_command_system),
         ))
         .add_systems(Update, (
             // Stress testing systems
-            stress_test_spawn_system,
-            stress_test_cleanup_system,
+            stress_test_spawn_system.run_if(search_inactive),
+            stress_test_cleanup_system.run_if(search_inactive),
             performance_comparison_system,
+            stress_sweep_control_system.run_if(search_inactive),
+            burst_spawn_system.run_if(search_inactive),
+            stress_sweep_system.after(stress_test_spawn_system),
         ))
+        .add_systems(
+            PostUpdate,
+            debris_occlusion_culling_system
+                .after(VisibilitySystems::CheckVisibility)
+                .before(VisibilitySystems::MarkNewlyHiddenEntitiesInvisible),
+        )
+        .add_systems(Last, collision_stats_export_system)
         .run();
 }
 
@@ -97,6 +647,7 @@ fn setup_scene(
         })),
         Mesh3d(meshes.add(Sphere::new(6.371).mesh().uv(32, 18))),
         Transform::default(),
+        Earth,
     ));
     
     // Add a fallback colored sphere in case texture doesn't load
@@ -134,7 +685,7 @@ fn setup_scene(
     // Add camera
     commands.spawn((
         Camera3d::default(),
-        Transform::from_xyz(0.0, 0.0, 15.0)
+        Transform::from_xyz(0.0, 0.0, DEFAULT_CAMERA_DISTANCE)
             .looking_at(Vec3::ZERO, Vec3::Y),
     ));
 }
\ No newline at end of file
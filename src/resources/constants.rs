@@ -1,46 +1,210 @@
+use bevy::math::DVec3;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::utils::{moon_position, sun_position};
+
+/// EGM96 unnormalized zonal coefficients J3..J6
+const EGM96_ZONALS: [f64; 4] = [-2.53265649e-6, -1.61962159e-6, -2.27296083e-7, 5.40681239e-7];
+/// EGM96 unnormalized sectoral coefficients C22 and S22
+const EGM96_C22: f64 = 1.57446037e-6;
+const EGM96_S22: f64 = -9.03803806e-7;
+/// Default altitude below which an object is considered to have reentered (km)
+pub const REENTRY_ALTITUDE: f64 = 100.0;
+/// Highest zonal degree of the gravity model
+pub const MAX_ZONAL_DEGREE: u8 = 6;
+
+/// Gravity field used by the integrators
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ForceModel {
+    /// Point-mass Earth
+    TwoBody,
+    /// Oblateness only
+    #[default]
+    J2,
+    /// Zonal harmonics J2..Jdegree
+    Zonal { degree: u8 },
+    /// Truncated EGM96: zonals to J6 plus the C22/S22 ellipticity of the equator
+    Geopotential,
+}
+
+impl std::str::FromStr for ForceModel {
+    type Err = String;
+
+    /// Parse "two-body", "j2", "zonal", "zonal:DEGREE" or "geopotential"
+    fn from_str(value: &str) -> Result<Self, String> {
+        let (model, degree) = value.split_once(':').unwrap_or((value, ""));
+        match (model, degree) {
+            ("two-body", "") => Ok(ForceModel::TwoBody),
+            ("j2", "") => Ok(ForceModel::J2),
+            ("zonal", "") => Ok(ForceModel::Zonal { degree: MAX_ZONAL_DEGREE }),
+            ("zonal", degree) => match degree.parse::<u8>() {
+                Ok(degree) if (2..=MAX_ZONAL_DEGREE).contains(&degree) => Ok(ForceModel::Zonal { degree }),
+                _ => Err(format!("zonal degree must be 2 to {}, got {}", MAX_ZONAL_DEGREE, degree)),
+            },
+            ("geopotential", "") => Ok(ForceModel::Geopotential),
+            _ => Err(format!("unknown force model {}", value)),
+        }
+    }
+}
+
+/// Spherical-harmonic gravity field beyond the central term, in km and seconds
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Geopotential {
+    /// Gravitational parameter (km³/s²)
+    pub mu: f64,
+    pub reference_radius: f64,
+    /// Unnormalized zonal coefficients J2..J6, zero beyond the model's degree
+    pub zonals: [f64; 5],
+    /// Unnormalized sectoral coefficients, evaluated in the Earth-fixed frame
+    pub c22: f64,
+    pub s22: f64,
+}
+
+/// Sun and Moon as point masses, each a position (km, ECI) and gravitational parameter (km³/s²)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ThirdBodies {
+    pub bodies: [(DVec3, f64); 2],
+}
+
+impl ThirdBodies {
+    /// Tidal acceleration (km/s²) at an ECI position (km): each body's pull there less its pull on the Earth
+    pub fn acceleration(&self, position: DVec3) -> DVec3 {
+        self.bodies.iter()
+            .filter(|(_, mu)| *mu > 0.0)
+            .map(|&(body, mu)| {
+                let to_body = body - position;
+                mu * (to_body / to_body.length().powi(3) - body / body.length().powi(3))
+            })
+            .sum()
+    }
+}
+
+impl Geopotential {
+    /// Perturbing acceleration (km/s²) at an ECI position (km) while the Earth is rotated by `earth_angle`
+    /// (radians, Greenwich sidereal angle)
+    pub fn acceleration(&self, position: DVec3, earth_angle: f64) -> DVec3 {
+        let r2 = position.length_squared();
+        if r2 <= 0.0 {
+            return DVec3::ZERO;
+        }
+        let r = r2.sqrt();
+        let u = position.z / r;
+        let r_hat = position / r;
+        let radius_ratio = self.reference_radius / r;
+
+        // a_n = μ Jn Rⁿ / r⁽ⁿ⁺²⁾ · [((n+1) Pn + u Pn') r̂ - Pn' ẑ], with Legendre recurrences for Pn and Pn'
+        let mut acceleration = DVec3::ZERO;
+        let (mut p_prev, mut p) = (1.0, u);
+        let (mut dp_prev, mut dp) = (0.0, 1.0);
+        let mut scale = self.mu / r2 * radius_ratio;
+        for (i, &jn) in self.zonals.iter().enumerate() {
+            let n = (i + 2) as f64;
+            let p_next = ((2.0 * n - 1.0) * u * p - (n - 1.0) * p_prev) / n;
+            let dp_next = dp_prev + (2.0 * n - 1.0) * p;
+            (p_prev, p, dp_prev, dp) = (p, p_next, dp, dp_next);
+            scale *= radius_ratio;
+            if jn != 0.0 {
+                acceleration += (r_hat * ((n + 1.0) * p + u * dp) - DVec3::Z * dp) * (jn * scale);
+            }
+        }
+
+        // V22 = 3 μ R² / r⁵ · (C22 (x² - y²) + 2 S22 x y) in Earth-fixed coordinates
+        if self.c22 != 0.0 || self.s22 != 0.0 {
+            let (sin, cos) = earth_angle.sin_cos();
+            let (x, y, z) = (cos * position.x + sin * position.y, cos * position.y - sin * position.x, position.z);
+            let f = self.c22 * (x * x - y * y) + 2.0 * self.s22 * x * y;
+            let factor = 3.0 * self.mu * self.reference_radius * self.reference_radius / (r2 * r2 * r);
+            let gradient = DVec3::new(
+                2.0 * (self.c22 * x + self.s22 * y),
+                2.0 * (self.s22 * x - self.c22 * y),
+                0.0,
+            );
+            let fixed = (gradient - DVec3::new(x, y, z) * (5.0 * f / r2)) * factor;
+            acceleration += DVec3::new(cos * fixed.x - sin * fixed.y, sin * fixed.x + cos * fixed.y, fixed.z);
+        }
+        acceleration
+    }
+}
 
 /// Physical and mathematical constants for the simulation
 #[derive(Resource)]
 pub struct Constants {
-    /// Earth's mass in kg
-    pub earth_mass: f64,
     /// Earth's gravitational parameter GM in m³/s²
     pub gravitational_parameter: f64,
     /// Earth's radius in km
     pub earth_radius: f64,
-    /// Earth's radius in meters
-    pub earth_radius_m: f64,
+    /// Earth's equatorial radius in km, the reference radius of the zonal harmonics
+    pub equatorial_radius: f64,
+    /// Second zonal harmonic (oblateness)
+    pub j2: f64,
+    /// Earth's sidereal rotation rate in rad/s
+    pub earth_rotation_rate: f64,
+    /// Gravity field used by the integrators
+    pub force_model: ForceModel,
+    /// Sun's and Moon's gravitational parameters in km³/s²
+    pub sun_gravitational_parameter: f64,
+    pub moon_gravitational_parameter: f64,
+    /// Whether the Sun and Moon perturb orbits
+    pub third_body: bool,
+    /// Altitude below which an object is considered to have reentered and is removed (km)
+    pub reentry_altitude: f64,
 }
 
 impl Default for Constants {
     fn default() -> Self {
         Self {
-            earth_mass: 5.972e24,              // kg
             gravitational_parameter: 3.986004418e14, // m³/s²
             earth_radius: 6371.0,              // km
-            earth_radius_m: 6.371e6,           // m
+            equatorial_radius: 6378.137,       // km (WGS-84)
+            j2: 1.08262668e-3,                 // EGM96
+            earth_rotation_rate: 7.292115e-5,  // rad/s
+            force_model: ForceModel::default(),
+            sun_gravitational_parameter: 1.32712440018e11,  // km³/s²
+            moon_gravitational_parameter: 4902.800066,       // km³/s²
+            third_body: true,
+            reentry_altitude: REENTRY_ALTITUDE,
         }
     }
 }
 
 impl Constants {
-    /// Calculate gravitational acceleration at distance r (in km) from Earth center
-    pub fn gravity_acceleration(&self, r_km: f64) -> f64 {
-        let r_m = r_km * 1000.0; // Convert to meters
-        self.gravitational_parameter / (r_m * r_m)
+    /// Perturbing gravity field of the selected force model
+    pub fn geopotential(&self) -> Geopotential {
+        let degree = match self.force_model {
+            ForceModel::TwoBody => 0,
+            ForceModel::J2 => 2,
+            ForceModel::Zonal { degree } => degree.min(MAX_ZONAL_DEGREE),
+            ForceModel::Geopotential => MAX_ZONAL_DEGREE,
+        };
+        let mut zonals = [0.0; 5];
+        for (n, zonal) in (2..=degree).zip(zonals.iter_mut()) {
+            *zonal = if n == 2 { self.j2 } else { EGM96_ZONALS[n as usize - 3] };
+        }
+        let tesseral = self.force_model == ForceModel::Geopotential;
+        Geopotential {
+            mu: self.gravitational_parameter / 1e9,
+            reference_radius: self.equatorial_radius,
+            zonals,
+            c22: if tesseral { EGM96_C22 } else { 0.0 },
+            s22: if tesseral { EGM96_S22 } else { 0.0 },
+        }
     }
 
-    /// Calculate orbital velocity for circular orbit at altitude h (km above surface)
-    pub fn circular_velocity(&self, altitude_km: f64) -> f64 {
-        let r_km = self.earth_radius + altitude_km;
-        let r_m = r_km * 1000.0;
-        (self.gravitational_parameter / r_m).sqrt() / 1000.0 // Return in km/s
+    /// Sun and Moon at a Unix time; both without mass when third-body gravity is off
+    pub fn third_bodies(&self, unix_seconds: f64) -> ThirdBodies {
+        if !self.third_body {
+            return ThirdBodies::default();
+        }
+        ThirdBodies {
+            bodies: [
+                (sun_position(unix_seconds), self.sun_gravitational_parameter),
+                (moon_position(unix_seconds), self.moon_gravitational_parameter),
+            ],
+        }
     }
 
-    /// Calculate escape velocity at distance r (km from center)
-    pub fn escape_velocity(&self, r_km: f64) -> f64 {
-        let r_m = r_km * 1000.0;
-        (2.0 * self.gravitational_parameter / r_m).sqrt() / 1000.0 // Return in km/s
+    /// J2 used for the secular drift of analytic propagation; zero for two-body gravity
+    pub fn secular_j2(&self) -> f64 {
+        if self.force_model == ForceModel::TwoBody { 0.0 } else { self.j2 }
     }
 }
\ No newline at end of file
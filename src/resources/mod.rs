@@ -1,5 +1,9 @@
 pub mod constants;
+pub mod settings;
 pub mod simulation;
+pub mod space_weather;
 
 pub use constants::*;
-pub use simulation::*;
\ No newline at end of file
+pub use settings::*;
+pub use simulation::*;
+pub use space_weather::*;
\ No newline at end of file
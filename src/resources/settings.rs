@@ -0,0 +1,488 @@
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use super::ForceModel;
+
+/// Default location of the user settings file
+pub const DEFAULT_SETTINGS_PATH: &str = "kessler_settings.json";
+
+/// Selectable color palettes for object categories
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Palette {
+    /// Original green satellites / red debris
+    #[default]
+    Classic,
+    /// Okabe-Ito palette, distinguishable with deuteranopia and protanopia
+    OkabeIto,
+    /// Blue/orange scheme safe for tritanopia as well
+    BlueOrange,
+    /// Maximum-contrast scheme for projectors and low vision
+    HighContrast,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 4] = [Palette::Classic, Palette::OkabeIto, Palette::BlueOrange, Palette::HighContrast];
+
+    /// Next palette in the cycle
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&p| p == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// How often collision detection runs
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum CollisionInterval {
+    /// Every frame, thinned out under coarse time compression
+    #[default]
+    EveryFrame,
+    /// Every N physics timesteps of simulated time
+    Steps(u32),
+    /// Every X simulated seconds
+    Seconds(f64),
+}
+
+impl CollisionInterval {
+    /// Simulated seconds between checks, or None when checks follow the frame rate
+    pub fn seconds(&self, timestep: f64) -> Option<f64> {
+        match *self {
+            CollisionInterval::EveryFrame => None,
+            CollisionInterval::Steps(steps) => Some(steps.max(1) as f64 * timestep),
+            CollisionInterval::Seconds(seconds) => Some(seconds.max(0.0)),
+        }
+    }
+}
+
+impl std::str::FromStr for CollisionInterval {
+    type Err = String;
+
+    /// Parse "frame", a step count ("10") or simulated seconds ("60s")
+    fn from_str(value: &str) -> Result<Self, String> {
+        if value == "frame" {
+            return Ok(CollisionInterval::EveryFrame);
+        }
+        match value.strip_suffix('s') {
+            Some(seconds) => seconds.parse().map(CollisionInterval::Seconds).map_err(|e| e.to_string()),
+            None => value.parse().map(CollisionInterval::Steps).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// Cataloged objects spawned when no cap is configured
+pub const DEFAULT_OBJECT_CAP: usize = 100;
+
+/// How many objects of the loaded catalog are spawned
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObjectCap {
+    /// At most this many, in catalog order
+    Limit(usize),
+    /// The whole catalog
+    All,
+}
+
+impl Default for ObjectCap {
+    fn default() -> Self {
+        ObjectCap::Limit(DEFAULT_OBJECT_CAP)
+    }
+}
+
+impl ObjectCap {
+    /// Largest number of objects spawned
+    pub fn limit(&self) -> usize {
+        match *self {
+            ObjectCap::Limit(limit) => limit,
+            ObjectCap::All => usize::MAX,
+        }
+    }
+}
+
+impl std::str::FromStr for ObjectCap {
+    type Err = String;
+
+    /// Parse an object count ("5000") or "all"
+    fn from_str(value: &str) -> Result<Self, String> {
+        if value.eq_ignore_ascii_case("all") {
+            return Ok(ObjectCap::All);
+        }
+        value.parse().map(ObjectCap::Limit).map_err(|e| e.to_string())
+    }
+}
+
+impl std::fmt::Display for ObjectCap {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ObjectCap::Limit(limit) => write!(f, "{}", limit),
+            ObjectCap::All => write!(f, "all"),
+        }
+    }
+}
+
+/// How screened encounters are turned into collisions
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum CollisionResolution {
+    /// Objects collide when their (enlarged) collision spheres intersect
+    #[default]
+    HardSphere,
+    /// Each encounter collides with its computed collision probability, scaled by `inflation`
+    Probabilistic { inflation: f64 },
+}
+
+impl std::str::FromStr for CollisionResolution {
+    type Err = String;
+
+    /// Parse "hard", "probabilistic" or "probabilistic:INFLATION"
+    fn from_str(value: &str) -> Result<Self, String> {
+        let (mode, inflation) = value.split_once(':').unwrap_or((value, "1"));
+        match mode {
+            "hard" if inflation == "1" => Ok(CollisionResolution::HardSphere),
+            "probabilistic" => match inflation.parse::<f64>() {
+                Ok(inflation) if inflation > 0.0 => Ok(CollisionResolution::Probabilistic { inflation }),
+                _ => Err(format!("invalid inflation factor {}", inflation)),
+            },
+            other => Err(format!("unknown collision mode {}", other)),
+        }
+    }
+}
+
+/// Numerical integrator of the physics pipelines
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Integrator {
+    /// Semi-implicit Euler, first order
+    #[default]
+    Euler,
+    /// Velocity Verlet (leapfrog), second order and symplectic: orbital energy stays bounded
+    Verlet,
+    /// Adaptive Dormand-Prince 5(4): each object picks its own step sizes to meet the error tolerance
+    DormandPrince,
+}
+
+impl Integrator {
+    pub fn name(self) -> &'static str {
+        match self {
+            Integrator::Euler => "Euler",
+            Integrator::Verlet => "Verlet",
+            Integrator::DormandPrince => "Dormand-Prince",
+        }
+    }
+}
+
+impl std::str::FromStr for Integrator {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        match value {
+            "euler" => Ok(Integrator::Euler),
+            "verlet" => Ok(Integrator::Verlet),
+            "dopri" | "rk45" => Ok(Integrator::DormandPrince),
+            other => Err(format!("unknown integrator {}", other)),
+        }
+    }
+}
+
+/// Broad-phase used to find collision candidates
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BroadPhase {
+    /// Spatial octree query per object
+    #[default]
+    Octree,
+    /// Objects sorted by the Morton code of their grid cell
+    Morton,
+    /// Objects bucketed in a hash map of occupied grid cells
+    HashGrid,
+    /// Objects sorted along x and sieved by successively costlier filters
+    SmartSieve,
+}
+
+impl BroadPhase {
+    pub fn name(self) -> &'static str {
+        match self {
+            BroadPhase::Octree => "octree",
+            BroadPhase::Morton => "Morton",
+            BroadPhase::HashGrid => "hash grid",
+            BroadPhase::SmartSieve => "smart sieve",
+        }
+    }
+}
+
+impl std::str::FromStr for BroadPhase {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        match value {
+            "octree" => Ok(BroadPhase::Octree),
+            "morton" => Ok(BroadPhase::Morton),
+            "hash-grid" | "grid" => Ok(BroadPhase::HashGrid),
+            "sieve" | "smart-sieve" => Ok(BroadPhase::SmartSieve),
+            other => Err(format!("unknown broad-phase {}", other)),
+        }
+    }
+}
+
+/// Physics pipeline integrating the orbits; exactly one runs each frame
+///
+/// The compute-shader pipeline in `gpu_physics` is disabled and cannot be selected yet.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PhysicsBackend {
+    /// Single-threaded integration of the `OrbitalState` components in place
+    Legacy,
+    /// Parallel integration into a double buffer on a worker thread
+    #[default]
+    Optimized,
+}
+
+impl PhysicsBackend {
+    pub fn name(self) -> &'static str {
+        match self {
+            PhysicsBackend::Legacy => "Legacy",
+            PhysicsBackend::Optimized => "Optimized",
+        }
+    }
+
+    /// The other backend, for switching at runtime
+    pub fn toggled(self) -> Self {
+        match self {
+            PhysicsBackend::Legacy => PhysicsBackend::Optimized,
+            PhysicsBackend::Optimized => PhysicsBackend::Legacy,
+        }
+    }
+}
+
+impl std::str::FromStr for PhysicsBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        match value {
+            "legacy" => Ok(PhysicsBackend::Legacy),
+            "optimized" => Ok(PhysicsBackend::Optimized),
+            other => Err(format!("unknown physics backend {}", other)),
+        }
+    }
+}
+
+/// Named space traffic management policy regimes
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StmRegime {
+    /// No coordination: nobody maneuvers
+    #[default]
+    Unregulated,
+    /// Operators screen and respond on their own without coordinating
+    SelfManaged,
+    /// Established assets have right of way and newcomers coordinate quickly
+    Coordinated,
+}
+
+impl StmRegime {
+    pub fn next(self) -> Self {
+        match self {
+            StmRegime::Unregulated => StmRegime::SelfManaged,
+            StmRegime::SelfManaged => StmRegime::Coordinated,
+            StmRegime::Coordinated => StmRegime::Unregulated,
+        }
+    }
+}
+
+impl std::str::FromStr for StmRegime {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        match value {
+            "unregulated" => Ok(StmRegime::Unregulated),
+            "self-managed" => Ok(StmRegime::SelfManaged),
+            "coordinated" => Ok(StmRegime::Coordinated),
+            other => Err(format!("unknown STM regime {}", other)),
+        }
+    }
+}
+
+/// Who is expected to maneuver when two objects are on a close approach
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RightOfWay {
+    /// Nobody maneuvers - the "do nothing" baseline
+    NoManeuver,
+    /// Every maneuverable object involved responds independently
+    BothManeuver,
+    /// The newer object (higher NORAD ID) yields to the established one
+    NewerYields,
+    /// The lighter, cheaper-to-move object yields
+    LighterYields,
+}
+
+impl std::str::FromStr for RightOfWay {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, String> {
+        match value {
+            "none" => Ok(RightOfWay::NoManeuver),
+            "both" => Ok(RightOfWay::BothManeuver),
+            "newer-yields" => Ok(RightOfWay::NewerYields),
+            "lighter-yields" => Ok(RightOfWay::LighterYields),
+            other => Err(format!("unknown right-of-way rule {}", other)),
+        }
+    }
+}
+
+/// Spatial octree parameters
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct OctreeSettings {
+    pub max_depth: u32,
+    /// Objects a node holds before it subdivides
+    pub max_objects_per_node: usize,
+    /// Half-size of the root cube (km)
+    pub root_half_size: f64,
+}
+
+impl Default for OctreeSettings {
+    fn default() -> Self {
+        // Half-size of 50,000 km covers LEO to GEO
+        Self {
+            max_depth: 6,
+            max_objects_per_node: 4,
+            root_half_size: 50000.0,
+        }
+    }
+}
+
+/// Rule leaving co-orbiting members of a constellation plane out of collision screening
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct CollisionExclusion {
+    /// Constellations whose names start with this, ignoring case, e.g. "Starlink" for every shell
+    pub constellation: String,
+    /// Largest angle between two members' orbit normals for them to share a plane (degrees)
+    pub plane_tolerance: f64,
+    /// Members of a plane closer than this along the orbit (degrees) share a slot and are still screened
+    pub phasing_tolerance: f64,
+}
+
+impl Default for CollisionExclusion {
+    fn default() -> Self {
+        Self {
+            constellation: String::new(),
+            plane_tolerance: 0.5,
+            phasing_tolerance: 0.1,
+        }
+    }
+}
+
+/// Resource holding the collision exclusion rules
+///
+/// Formations keep their members phased apart within a plane, so pairs in the same plane only approach if
+/// one leaves its slot; pairs in different planes cross and are always screened.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct CollisionExclusions {
+    pub rules: Vec<CollisionExclusion>,
+}
+
+impl Default for CollisionExclusions {
+    fn default() -> Self {
+        let rule = |constellation: &str| CollisionExclusion { constellation: constellation.to_string(), ..default() };
+        Self { rules: vec![rule("Starlink"), rule("OneWeb")] }
+    }
+}
+
+impl CollisionExclusions {
+    /// Whether a pair of constellation members, each given as (position km, velocity km/s), is left unscreened
+    pub fn excludes(&self, constellation: &str, other_constellation: &str, state: (DVec3, DVec3), other_state: (DVec3, DVec3)) -> bool {
+        if constellation != other_constellation {
+            return false;
+        }
+        let Some(rule) = self.rules.iter().find(|rule| {
+            constellation.to_lowercase().starts_with(&rule.constellation.to_lowercase())
+        }) else {
+            return false;
+        };
+        let angle = |a: DVec3, b: DVec3| a.angle_between(b).to_degrees();
+        let (normal, other_normal) = (state.0.cross(state.1), other_state.0.cross(other_state.1));
+        angle(normal, other_normal) <= rule.plane_tolerance && angle(state.0, other_state.0) >= rule.phasing_tolerance
+    }
+}
+
+/// What object colors encode
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Satellite / debris / rocket body / stress test
+    #[default]
+    Category,
+    /// One color per operator
+    Operator,
+    /// One color per SATCAT owner/country
+    Owner,
+}
+
+impl ColorMode {
+    pub const ALL: [ColorMode; 3] = [ColorMode::Category, ColorMode::Operator, ColorMode::Owner];
+
+    /// Next mode in the cycle
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&m| m == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// Color configuration stored in the settings file
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ColorSettings {
+    pub palette: Palette,
+    pub mode: ColorMode,
+    /// Per-category hex color overrides, e.g. {"debris": "#ffcc00"}
+    pub overrides: HashMap<String, String>,
+}
+
+/// User settings persisted between sessions
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Settings {
+    pub colors: ColorSettings,
+    pub collision_interval: CollisionInterval,
+    pub collision_resolution: CollisionResolution,
+    pub octree: OctreeSettings,
+    pub broad_phase: BroadPhase,
+    pub force_model: ForceModel,
+    pub integrator: Integrator,
+    pub physics_backend: PhysicsBackend,
+    pub collision_exclusions: CollisionExclusions,
+    /// Space traffic management regime at startup
+    pub stm_regime: StmRegime,
+    /// Right-of-way rule replacing the starting regime's own
+    pub right_of_way: Option<RightOfWay>,
+    /// Cataloged objects spawned at startup
+    pub object_cap: ObjectCap,
+    /// Celestrak groups fetched at startup; empty fetches the active satellites
+    pub tle_groups: Vec<String>,
+    /// NORAD IDs of watched objects
+    pub watchlist: BTreeSet<u32>,
+    /// File the settings were loaded from and are saved back to
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl Settings {
+    /// Load settings from a file, falling back to defaults when it is missing or invalid
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let mut settings = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str::<Settings>(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring invalid settings file {}: {}", path.display(), e);
+                Settings::default()
+            }),
+            Err(_) => Settings::default(),
+        };
+        settings.path = path.to_path_buf();
+        settings
+    }
+
+    /// Write settings back to the file they were loaded from
+    pub fn save(&self) {
+        let result = serde_json::to_string_pretty(self)
+            .map_err(|e| e.to_string())
+            .and_then(|json| std::fs::write(&self.path, json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("Failed to save settings to {}: {}", self.path.display(), e);
+        }
+    }
+}
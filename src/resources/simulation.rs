@@ -1,10 +1,33 @@
 use bevy::prelude::*;
 use std::collections::HashMap;
+use crate::resources::Integrator;
+use crate::utils::{tai_to_utc, unix_to_iso8601, unix_to_julian_date, utc_to_tai};
+
+/// Smallest physics timestep accepted at runtime (seconds)
+pub const MIN_TIMESTEP: f64 = 0.1;
+/// Largest physics timestep accepted at runtime (seconds)
+pub const MAX_TIMESTEP: f64 = 600.0;
+/// Largest timestep that keeps Euler integration of low LEO orbits (~90 min period) stable (seconds)
+pub const LEO_STABLE_TIMESTEP: f64 = 30.0;
+/// Speed multiplier at or above which physics switches to analytic Kepler propagation
+pub const COARSE_PROPAGATION_SPEED: f64 = 7.0 * 86400.0;
+/// Frames between collision checks while coarse propagation is active
+pub const COARSE_COLLISION_STRIDE: u32 = 10;
+
+/// Arc (radians) a 90-minute orbit sweeps over the LEO stability limit, the default cap per integration step
+pub const DEFAULT_MAX_STEP_ANGLE: f64 = std::f64::consts::TAU * LEO_STABLE_TIMESTEP / 5400.0;
+/// Integration substeps per frame beyond which the governor switches to Kepler propagation
+pub const DEFAULT_MAX_SUBSTEPS: u32 = 256;
+/// Object-substeps integrated per frame before the governor switches to Kepler propagation
+pub const DEFAULT_SUBSTEP_BUDGET: u64 = 1_000_000;
+
+/// Time compression presets from real time to one month per second
+pub const SPEED_PRESETS: [f64; 6] = [1.0, 60.0, 3600.0, 86400.0, 7.0 * 86400.0, 30.0 * 86400.0];
 
 /// Global simulation time and control
 #[derive(Resource)]
 pub struct SimulationTime {
-    /// Current simulation time in seconds since epoch
+    /// Current simulation time in SI seconds since epoch
     pub current: f64,
     /// Time speed multiplier (1.0 = real time, 3600.0 = 1 hour per second)
     pub speed_multiplier: f64,
@@ -12,6 +35,12 @@ pub struct SimulationTime {
     pub paused: bool,
     /// Simulation timestep in seconds
     pub timestep: f64,
+    /// UTC Unix time (seconds) corresponding to simulation time zero
+    pub epoch_unix: f64,
+    /// Set when a single physics step has been requested while paused
+    pub step_requested: bool,
+    /// Whether a single paused physics step runs this frame
+    pub stepping: bool,
 }
 
 impl Default for SimulationTime {
@@ -21,10 +50,21 @@ impl Default for SimulationTime {
             speed_multiplier: 3600.0, // Default to 1 hour per second
             paused: false,
             timestep: 1.0, // 1 second timesteps
+            epoch_unix: wall_clock_unix_seconds(),
+            step_requested: false,
+            stepping: false,
         }
     }
 }
 
+/// Current wall-clock time as Unix seconds
+pub fn wall_clock_unix_seconds() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
 impl SimulationTime {
     pub fn advance(&mut self, delta_time: f32) {
         if !self.paused {
@@ -40,9 +80,176 @@ impl SimulationTime {
         self.paused = false;
     }
 
+    /// Set the physics timestep, clamped to the supported range
+    pub fn set_timestep(&mut self, timestep: f64) {
+        self.timestep = timestep.clamp(MIN_TIMESTEP, MAX_TIMESTEP);
+    }
+
+    /// Describe why the current timestep is unsafe for LEO orbits, if it is
+    pub fn stability_warning(&self) -> Option<String> {
+        if self.timestep > LEO_STABLE_TIMESTEP {
+            Some(format!(
+                "dt {:.1}s exceeds LEO stability limit of {:.0}s ({:.0} simulated s per real s at {:.0}x)",
+                self.timestep, LEO_STABLE_TIMESTEP, self.speed_multiplier * self.timestep, self.speed_multiplier
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Request that exactly one physics step runs while paused
+    pub fn request_step(&mut self) {
+        if self.paused {
+            self.step_requested = true;
+        }
+    }
+
+    /// Whether physics should integrate this frame
+    pub fn should_integrate(&self) -> bool {
+        !self.paused || self.stepping
+    }
+
     pub fn set_speed(&mut self, multiplier: f64) {
         self.speed_multiplier = multiplier.max(0.0);
     }
+
+    /// Whether time compression is high enough to require coarse propagation
+    pub fn is_coarse(&self) -> bool {
+        self.speed_multiplier >= COARSE_PROPAGATION_SPEED
+    }
+
+    /// Simulated seconds covered by this frame under coarse propagation
+    pub fn coarse_step(&self, real_delta: f32) -> f64 {
+        if self.stepping {
+            self.timestep
+        } else {
+            real_delta as f64 * self.speed_multiplier
+        }
+    }
+
+    /// Number of frames between collision checks at the current time compression
+    pub fn collision_stride(&self) -> u32 {
+        if self.is_coarse() {
+            COARSE_COLLISION_STRIDE
+        } else {
+            1
+        }
+    }
+
+    /// UTC Unix time of a simulation time, accounting for leap seconds since the epoch
+    pub fn unix_at(&self, sim_seconds: f64) -> f64 {
+        tai_to_utc(utc_to_tai(self.epoch_unix) + sim_seconds)
+    }
+
+    /// UTC Unix time of the current simulation time
+    pub fn current_unix(&self) -> f64 {
+        self.unix_at(self.current)
+    }
+
+    /// UTC Julian date of the current simulation time
+    pub fn current_julian_date(&self) -> f64 {
+        unix_to_julian_date(self.current_unix())
+    }
+
+    /// ISO 8601 UTC timestamp of the current simulation time
+    pub fn current_iso8601(&self) -> String {
+        format!("{}Z", unix_to_iso8601(self.current_unix()))
+    }
+}
+
+/// How physics covers the simulated time of one frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StepPlan {
+    /// `count` integrator steps of `dt` seconds
+    Integrate { count: u32, dt: f64 },
+    /// One analytic Kepler step of `dt` seconds
+    Kepler { dt: f64 },
+}
+
+/// Resource capping the integration step × fastest orbital angular rate (a CFL-like criterion)
+///
+/// While enabled, physics keeps pace with the simulation clock by splitting each frame into as many
+/// substeps as the criterion needs, and falls back to Kepler propagation when that would take too many
+/// or would integrate more object-substeps than the work budget allows.
+#[derive(Resource)]
+pub struct TimestepGovernor {
+    pub enabled: bool,
+    /// Largest orbital arc an object may sweep in one integration step (radians)
+    pub max_step_angle: f64,
+    /// Most substeps per frame before switching to Kepler propagation
+    pub max_substeps: u32,
+    /// Most object-substeps per frame before switching to Kepler propagation
+    pub substep_budget: u64,
+    /// Fastest orbital angular rate in the population (rad/s), measured every frame
+    pub max_angular_rate: f64,
+    /// Objects integrated each substep, measured every frame
+    pub object_count: usize,
+}
+
+impl Default for TimestepGovernor {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_step_angle: DEFAULT_MAX_STEP_ANGLE,
+            max_substeps: DEFAULT_MAX_SUBSTEPS,
+            substep_budget: DEFAULT_SUBSTEP_BUDGET,
+            max_angular_rate: 0.0,
+            object_count: 0,
+        }
+    }
+}
+
+impl TimestepGovernor {
+    /// Longest integration step (seconds) meeting the criterion, never above the configured timestep
+    pub fn max_step(&self, timestep: f64) -> f64 {
+        if self.max_angular_rate > 0.0 {
+            timestep.min(self.max_step_angle / self.max_angular_rate)
+        } else {
+            timestep
+        }
+    }
+
+    /// Most substeps per frame the population can afford within the work budget, at least one
+    pub fn substep_limit(&self) -> u32 {
+        let affordable = self.substep_budget / self.object_count.max(1) as u64;
+        affordable.clamp(1, self.max_substeps.max(1) as u64) as u32
+    }
+
+    /// Integration of a frame lasting `real_delta` seconds
+    pub fn plan(&self, sim_time: &SimulationTime, real_delta: f32) -> StepPlan {
+        if sim_time.is_coarse() {
+            return StepPlan::Kepler { dt: sim_time.coarse_step(real_delta) };
+        }
+        // Without the governor, and for single steps, each frame integrates one configured timestep
+        if !self.enabled || sim_time.stepping {
+            return StepPlan::Integrate { count: 1, dt: sim_time.timestep };
+        }
+
+        let frame_step = real_delta as f64 * sim_time.speed_multiplier;
+        let count = (frame_step / self.max_step(sim_time.timestep)).ceil().max(1.0);
+        if count > self.substep_limit() as f64 {
+            StepPlan::Kepler { dt: frame_step }
+        } else {
+            StepPlan::Integrate { count: count as u32, dt: frame_step / count }
+        }
+    }
+}
+
+/// Position error (km) the adaptive integrator allows per step unless configured otherwise
+pub const DEFAULT_INTEGRATOR_TOLERANCE: f64 = 1e-3;
+
+/// Resource selecting the numerical integrator
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct IntegratorConfig {
+    pub method: Integrator,
+    /// Position error (km) allowed per adaptive step
+    pub tolerance: f64,
+}
+
+impl Default for IntegratorConfig {
+    fn default() -> Self {
+        Self { method: Integrator::default(), tolerance: DEFAULT_INTEGRATOR_TOLERANCE }
+    }
 }
 
 /// Resource for tracking energy analytics
@@ -50,16 +257,25 @@ impl SimulationTime {
 pub struct EnergyAnalytics {
     /// Altitude bins for energy averaging (in km)
     pub altitude_bins: Vec<f64>,
-    /// Energy values binned by altitude
-    pub energy_by_altitude: HashMap<usize, Vec<f64>>,
+    /// Energy values binned by altitude, each with the number of objects sharing it
+    pub energy_by_altitude: HashMap<usize, Vec<(f64, usize)>>,
     /// Total number of tracked objects
     pub total_objects: usize,
     /// Number of active satellites
     pub total_satellites: usize,
     /// Number of debris objects
     pub total_debris: usize,
+    /// Number of super particle entities standing in for many fragments
+    pub total_super_particles: usize,
     /// Total system energy
     pub total_energy: f64,
+    /// Satellites and debris fragments removed by reentry since the start, kept across frames
+    pub reentered_satellites: usize,
+    pub reentered_debris: usize,
+    /// Combined mass of the reentered objects (kg)
+    pub reentered_mass: f64,
+    /// Debris fragments removed once their estimated drag lifetime ran out, kept across frames
+    pub decayed_debris: usize,
 }
 
 impl Default for EnergyAnalytics {
@@ -76,7 +292,12 @@ impl Default for EnergyAnalytics {
             total_objects: 0,
             total_satellites: 0,
             total_debris: 0,
+            total_super_particles: 0,
             total_energy: 0.0,
+            reentered_satellites: 0,
+            reentered_debris: 0,
+            reentered_mass: 0.0,
+            decayed_debris: 0,
         }
     }
 }
@@ -92,29 +313,33 @@ impl EnergyAnalytics {
         None
     }
 
-    /// Add energy measurement for a specific altitude
-    pub fn add_energy_measurement(&mut self, altitude_km: f64, energy: f64) {
+    /// Add a measurement standing in for `weight` identical objects sharing the given total energy
+    pub fn add_weighted_energy_measurement(&mut self, altitude_km: f64, total_energy: f64, weight: usize) {
         if let Some(bin_index) = self.get_altitude_bin(altitude_km) {
             self.energy_by_altitude
                 .entry(bin_index)
-                .or_insert_with(Vec::new)
-                .push(energy);
+                .or_default()
+                .push((total_energy, weight.max(1)));
         }
     }
 
-    /// Get average energy for a specific altitude bin
+    /// Get average energy per object for a specific altitude bin
     pub fn get_average_energy(&self, bin_index: usize) -> Option<f64> {
         self.energy_by_altitude.get(&bin_index).map(|energies| {
-            energies.iter().sum::<f64>() / energies.len() as f64
+            let (energy, objects) = energies
+                .iter()
+                .fold((0.0, 0), |(energy, objects), &(sample, weight)| (energy + sample, objects + weight));
+            energy / objects as f64
         })
     }
 
-    /// Clear all measurements (called each frame)
+    /// Clear all measurements (called each frame); reentry totals are kept
     pub fn clear_measurements(&mut self) {
         self.energy_by_altitude.clear();
         self.total_objects = 0;
         self.total_satellites = 0;
         self.total_debris = 0;
+        self.total_super_particles = 0;
         self.total_energy = 0.0;
     }
 }
\ No newline at end of file
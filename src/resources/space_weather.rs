@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+use crate::utils::{thermosphere_density, unix_to_julian_date};
+
+/// Julian date of the solar minimum starting cycle 25 (December 2019)
+const CYCLE_MINIMUM_JULIAN_DATE: f64 = 2_458_818.5;
+/// Mean length of a solar cycle (days)
+const CYCLE_LENGTH_DAYS: f64 = 11.0 * 365.25;
+/// 81-day mean F10.7 at solar minimum and maximum (sfu)
+const CYCLE_FLUX: (f64, f64) = (70.0, 180.0);
+/// Ap index at solar minimum and maximum
+const CYCLE_AP: (f64, f64) = (5.0, 20.0);
+/// Orbit-average exospheric temperature over Jacchia's nighttime minimum
+const DIURNAL_MEAN_FACTOR: f64 = 1.1;
+
+/// Solar and geomagnetic activity driving the thermosphere density
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct SpaceWeather {
+    /// Daily 10.7 cm solar radio flux (sfu)
+    pub f107: f64,
+    /// 81-day centered mean of F10.7 (sfu)
+    pub f107_average: f64,
+    /// Daily planetary geomagnetic index
+    pub ap: f64,
+    /// Follow a nominal 11-year solar cycle from the simulation date instead of fixed indices
+    pub cycle: bool,
+}
+
+impl Default for SpaceWeather {
+    /// Moderate activity, close to the US Standard Atmosphere's 1000 K thermosphere
+    fn default() -> Self {
+        Self { f107: 150.0, f107_average: 150.0, ap: 15.0, cycle: false }
+    }
+}
+
+impl SpaceWeather {
+    /// Quiet conditions at solar minimum
+    pub fn solar_minimum() -> Self {
+        Self { f107: CYCLE_FLUX.0, f107_average: CYCLE_FLUX.0, ap: CYCLE_AP.0, cycle: false }
+    }
+
+    /// Active conditions at solar maximum
+    pub fn solar_maximum() -> Self {
+        Self { f107: CYCLE_FLUX.1, f107_average: CYCLE_FLUX.1, ap: CYCLE_AP.1, cycle: false }
+    }
+
+    /// Nominal indices of the solar cycle at a Unix time
+    pub fn at_cycle_phase(unix_seconds: f64) -> Self {
+        let phase = ((unix_to_julian_date(unix_seconds) - CYCLE_MINIMUM_JULIAN_DATE) / CYCLE_LENGTH_DAYS).rem_euclid(1.0);
+        let activity = (1.0 - (std::f64::consts::TAU * phase).cos()) / 2.0;
+        let flux = CYCLE_FLUX.0 + (CYCLE_FLUX.1 - CYCLE_FLUX.0) * activity;
+        Self {
+            f107: flux,
+            f107_average: flux,
+            ap: CYCLE_AP.0 + (CYCLE_AP.1 - CYCLE_AP.0) * activity,
+            cycle: true,
+        }
+    }
+
+    /// Exospheric temperature (K) from Jacchia's solar flux and geomagnetic relations
+    pub fn exospheric_temperature(&self) -> f64 {
+        let nighttime = 379.0 + 3.24 * self.f107_average + 1.3 * (self.f107 - self.f107_average);
+        let geomagnetic = self.ap + 100.0 * (1.0 - (-0.08 * self.ap).exp());
+        nighttime * DIURNAL_MEAN_FACTOR + geomagnetic
+    }
+
+    /// Atmospheric density (kg/m³) at an altitude (km)
+    pub fn density(&self, altitude_km: f64) -> f64 {
+        thermosphere_density(altitude_km, self.exospheric_temperature())
+    }
+}
+
+impl std::str::FromStr for SpaceWeather {
+    type Err = String;
+
+    /// Parse "min", "max", "cycle" or "F107[:AP]"
+    fn from_str(value: &str) -> Result<Self, String> {
+        match value {
+            "min" => return Ok(Self::solar_minimum()),
+            "max" => return Ok(Self::solar_maximum()),
+            "cycle" => return Ok(Self { cycle: true, ..default() }),
+            _ => {}
+        }
+        let (flux, ap) = value.split_once(':').unwrap_or((value, "15"));
+        match (flux.parse::<f64>(), ap.parse::<f64>()) {
+            (Ok(flux), Ok(ap)) if flux > 0.0 && ap >= 0.0 => Ok(Self { f107: flux, f107_average: flux, ap, cycle: false }),
+            _ => Err(format!("invalid space weather {}", value)),
+        }
+    }
+}
@@ -1,15 +1,168 @@
 // Energy analytics system
 
 use bevy::prelude::*;
+use std::collections::HashMap;
 use crate::components::*;
 use crate::resources::*;
+use crate::systems::collision::CollisionEvent;
+use crate::systems::conjunctions::ConjunctionForecast;
+use crate::systems::export::{write_export, ExportConfig};
+use crate::systems::physics::ReentryEvent;
+use crate::utils::unix_to_iso8601;
+
+/// Default number of closest approaches tracked
+pub const DEFAULT_CLOSE_APPROACHES: usize = 10;
+/// Simulated seconds a close approach stays in the tracker
+pub const CLOSE_APPROACH_WINDOW: f64 = 86400.0;
+/// Altitude bin width of the collision histogram (km)
+pub const COLLISION_ALTITUDE_BIN: f64 = 50.0;
+/// Altitude bins of the collision histogram; the last one also holds everything above 2000 km
+pub const COLLISION_ALTITUDE_BINS: usize = 41;
+/// Relative speed bin width of the collision histogram (km/s)
+pub const COLLISION_SPEED_BIN: f64 = 1.0;
+/// Relative speed bins of the collision histogram; the last one also holds everything above 16 km/s
+pub const COLLISION_SPEED_BINS: usize = 17;
+/// Seconds in a simulated (Julian) year
+const SECONDS_PER_YEAR: f64 = 365.25 * 86400.0;
+
+/// A close approach that has happened
+#[derive(Clone, Debug)]
+pub struct CloseApproach {
+    pub names: (String, String),
+    /// Simulation time of closest approach (seconds)
+    pub time: f64,
+    /// Miss distance (km)
+    pub miss_distance: f64,
+    /// Relative speed at closest approach (km/s)
+    pub relative_speed: f64,
+}
+
+/// Resource tracking the closest approaches of the rolling window, so near misses show and not only collisions
+#[derive(Resource)]
+pub struct CloseApproaches {
+    pub enabled: bool,
+    /// Number of approaches reported
+    pub count: usize,
+    /// Rolling window (simulated seconds)
+    pub window: f64,
+    /// Approaches within the window, closest first
+    pub approaches: Vec<CloseApproach>,
+    /// Latest forecast of each encounter whose closest approach is still ahead
+    pending: HashMap<(Entity, Entity), CloseApproach>,
+}
+
+impl Default for CloseApproaches {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            count: DEFAULT_CLOSE_APPROACHES,
+            window: CLOSE_APPROACH_WINDOW,
+            approaches: Vec::new(),
+            pending: HashMap::new(),
+        }
+    }
+}
+
+impl CloseApproaches {
+    /// Tracker of the `count` closest approaches
+    pub fn with_count(count: usize) -> Self {
+        Self { enabled: true, count: count.max(1), ..default() }
+    }
+
+    /// The reported approaches, closest first
+    pub fn closest(&self) -> impl Iterator<Item = &CloseApproach> {
+        self.approaches.iter().take(self.count)
+    }
+}
+
+/// Resource accumulating collision statistics over the whole run
+#[derive(Resource)]
+pub struct CollisionStats {
+    /// Collisions so far
+    pub total: u32,
+    /// Collisions energetic enough to destroy both objects
+    pub catastrophic: u32,
+    /// Fragments generated by those collisions
+    pub fragments: u64,
+    /// Collisions per `COLLISION_ALTITUDE_BIN` of altitude, lowest first
+    pub altitude_histogram: Vec<u32>,
+    /// Collisions per `COLLISION_SPEED_BIN` of relative speed, slowest first
+    pub speed_histogram: Vec<u32>,
+    /// Simulation time the statistics started at (seconds)
+    pub started_at: Option<f64>,
+    /// Simulation time of the latest update (seconds)
+    pub updated_at: f64,
+}
+
+impl Default for CollisionStats {
+    fn default() -> Self {
+        Self {
+            total: 0,
+            catastrophic: 0,
+            fragments: 0,
+            altitude_histogram: vec![0; COLLISION_ALTITUDE_BINS],
+            speed_histogram: vec![0; COLLISION_SPEED_BINS],
+            started_at: None,
+            updated_at: 0.0,
+        }
+    }
+}
+
+impl CollisionStats {
+    /// Tally a collision at an altitude (km) and relative speed (km/s)
+    pub fn record(&mut self, altitude: f64, relative_speed: f64, catastrophic: bool, fragments: u32) {
+        self.total += 1;
+        self.catastrophic += catastrophic as u32;
+        self.fragments += fragments as u64;
+        let bin = |value: f64, width: f64, bins: usize| ((value.max(0.0) / width) as usize).min(bins - 1);
+        self.altitude_histogram[bin(altitude, COLLISION_ALTITUDE_BIN, COLLISION_ALTITUDE_BINS)] += 1;
+        self.speed_histogram[bin(relative_speed, COLLISION_SPEED_BIN, COLLISION_SPEED_BINS)] += 1;
+    }
+
+    /// Simulated time covered by the statistics (seconds)
+    pub fn elapsed(&self) -> f64 {
+        self.started_at.map_or(0.0, |start| (self.updated_at - start).max(0.0))
+    }
+
+    /// Average collision rate over the simulated time covered (collisions per year)
+    pub fn per_year(&self) -> f64 {
+        let elapsed = self.elapsed();
+        if elapsed > 0.0 { self.total as f64 * SECONDS_PER_YEAR / elapsed } else { 0.0 }
+    }
+
+    /// Average number of fragments a collision generated
+    pub fn fragments_per_event(&self) -> f64 {
+        if self.total > 0 { self.fragments as f64 / self.total as f64 } else { 0.0 }
+    }
+
+    /// The statistics as a JSON document
+    pub fn to_json(&self) -> String {
+        let altitudes: Vec<_> = self.altitude_histogram.iter().enumerate()
+            .map(|(i, &count)| serde_json::json!({ "altitude_km": i as f64 * COLLISION_ALTITUDE_BIN, "collisions": count }))
+            .collect();
+        let speeds: Vec<_> = self.speed_histogram.iter().enumerate()
+            .map(|(i, &count)| serde_json::json!({ "relative_speed_km_s": i as f64 * COLLISION_SPEED_BIN, "collisions": count }))
+            .collect();
+        let stats = serde_json::json!({
+            "total_collisions": self.total,
+            "catastrophic_collisions": self.catastrophic,
+            "fragments": self.fragments,
+            "fragments_per_collision": self.fragments_per_event(),
+            "simulated_days": self.elapsed() / 86400.0,
+            "collisions_per_year": self.per_year(),
+            "altitude_histogram": altitudes,
+            "relative_speed_histogram": speeds,
+        });
+        serde_json::to_string_pretty(&stats).unwrap_or_default()
+    }
+}
 
 /// System to calculate and track energy vs altitude analytics
 pub fn energy_analytics_system(
     mut analytics: ResMut<EnergyAnalytics>,
-    orbital_query: Query<&OrbitalState>,
+    orbital_query: Query<(&OrbitalState, Option<&SuperParticle>)>,
     satellite_query: Query<&Satellite>,
-    debris_query: Query<&Debris>,
+    debris_query: Query<Option<&SuperParticle>, With<Debris>>,
     constants: Res<Constants>,
 ) {
     // Clear previous frame's measurements
@@ -18,25 +171,168 @@ pub fn energy_analytics_system(
     let mut total_energy = 0.0;
     let mut total_objects = 0;
 
-    for orbital_state in orbital_query.iter() {
+    for (orbital_state, super_particle) in orbital_query.iter() {
         let altitude = orbital_state.altitude() - constants.earth_radius;
         let energy = orbital_state.total_energy(constants.gravitational_parameter);
         
-        analytics.add_energy_measurement(altitude, energy);
+        // Super particles carry the combined mass of all the fragments they represent
+        let weight = super_particle.map(|sp| sp.weight).unwrap_or(1) as usize;
+        analytics.add_weighted_energy_measurement(altitude, energy, weight);
         total_energy += energy;
-        total_objects += 1;
+        total_objects += weight;
     }
 
     // Count satellites and debris separately
     analytics.total_satellites = satellite_query.iter().count();
-    analytics.total_debris = debris_query.iter().count();
+    analytics.total_debris = debris_query.iter()
+        .map(|super_particle| super_particle.map(|sp| sp.weight).unwrap_or(1) as usize)
+        .sum();
+    analytics.total_super_particles = debris_query.iter().filter(|sp| sp.is_some()).count();
     analytics.total_objects = total_objects;
     analytics.total_energy = total_energy;
 }
 
+/// System to tally reentered objects, so the population curves account for what decayed
+pub fn reentry_analytics_system(mut analytics: ResMut<EnergyAnalytics>, mut reentries: EventReader<ReentryEvent>) {
+    for reentry in reentries.read() {
+        if reentry.debris {
+            analytics.reentered_debris += reentry.weight as usize;
+        } else {
+            analytics.reentered_satellites += 1;
+        }
+        analytics.reentered_mass += reentry.mass;
+    }
+}
+
+/// System to add each collision to the run's statistics
+pub fn collision_stats_system(
+    mut stats: ResMut<CollisionStats>,
+    mut collisions: EventReader<CollisionEvent>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+) {
+    stats.started_at.get_or_insert(sim_time.current);
+    stats.updated_at = sim_time.current;
+    for collision in collisions.read() {
+        let altitude = collision.position.length() - constants.earth_radius;
+        stats.record(altitude, collision.relative_speed, collision.catastrophic, collision.fragments);
+    }
+}
+
+/// System to write the collision statistics to the export directory when the app exits
+pub fn collision_stats_export_system(
+    stats: Res<CollisionStats>,
+    config: Res<ExportConfig>,
+    mut exits: EventReader<AppExit>,
+) {
+    if exits.read().count() == 0 {
+        return;
+    }
+    match write_export(&config.directory, "collision_stats.json", &stats.to_json()) {
+        Ok(path) => info!("Exported statistics of {} collisions to {}", stats.total, path.display()),
+        Err(e) => warn!("Collision statistics export failed: {}", e),
+    }
+}
+
+/// System to record forecast encounters in the close-approach tracker once their closest approach has passed
+pub fn close_approach_tracking_system(
+    mut tracker: ResMut<CloseApproaches>,
+    mut forecast: ResMut<ConjunctionForecast>,
+    sim_time: Res<SimulationTime>,
+    satellites: Query<&Satellite>,
+) {
+    if !tracker.enabled {
+        return;
+    }
+    forecast.requested = true;
+
+    let now = sim_time.current;
+    let CloseApproaches { pending, approaches, window, .. } = &mut *tracker;
+    pending.retain(|_, approach| {
+        if approach.time > now {
+            return true;
+        }
+        let index = approaches.partition_point(|a| a.miss_distance <= approach.miss_distance);
+        approaches.insert(index, approach.clone());
+        false
+    });
+    // Time running backwards (a replay or reset) drops approaches from the future too
+    approaches.retain(|approach| approach.time <= now && approach.time >= now - *window);
+
+    let name = |entity: Entity| satellites.get(entity).map(|s| s.name.trim().to_string()).unwrap_or_else(|_| "Debris".to_string());
+    for conjunction in &forecast.upcoming {
+        pending.insert((conjunction.primary, conjunction.secondary), CloseApproach {
+            names: (name(conjunction.primary), name(conjunction.secondary)),
+            time: conjunction.tca,
+            miss_distance: conjunction.miss_distance,
+            relative_speed: conjunction.relative_speed,
+        });
+    }
+}
+
+/// Marker for the close-approach panel text
+#[derive(Component)]
+pub struct CloseApproachPanelText;
+
+/// System to create the (initially empty) close-approach panel
+pub fn setup_close_approach_panel_system(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.8, 0.9, 0.6)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(160.0),
+            right: Val::Px(10.0),
+            ..default()
+        },
+        CloseApproachPanelText,
+    ));
+}
+
+/// Lines listing the tracked approaches, ages relative to `now`
+fn close_approach_lines(tracker: &CloseApproaches, now: f64) -> Vec<String> {
+    tracker.closest().enumerate()
+        .map(|(rank, approach)| format!(
+            "{}. {} / {} {:.0} min ago: {:.3} km at {:.1} km/s",
+            rank + 1, approach.names.0, approach.names.1, (now - approach.time) / 60.0,
+            approach.miss_distance, approach.relative_speed
+        ))
+        .collect()
+}
+
+/// System to list the closest approaches of the rolling window
+pub fn close_approach_panel_system(
+    tracker: Res<CloseApproaches>,
+    sim_time: Res<SimulationTime>,
+    mut text_query: Query<&mut Text, With<CloseApproachPanelText>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+    if !tracker.enabled {
+        if !text.0.is_empty() {
+            text.0.clear();
+        }
+        return;
+    }
+
+    let mut lines = vec![format!("Closest approaches, last {:.0} h", tracker.window / 3600.0)];
+    lines.extend(close_approach_lines(&tracker, sim_time.current));
+    if lines.len() == 1 {
+        lines.push("none yet".to_string());
+    }
+    text.0 = lines.join("\n");
+}
+
 /// Debug system to print analytics information
 pub fn debug_analytics_system(
     analytics: Res<EnergyAnalytics>,
+    close_approaches: Res<CloseApproaches>,
+    collision_stats: Res<CollisionStats>,
     sim_time: Res<SimulationTime>,
     mut last_print: Local<f64>,
 ) {
@@ -47,6 +343,28 @@ pub fn debug_analytics_system(
         // Basic info always logged at info level
         info!("System Analytics: {} objects (Satellites: {}, Debris: {}), Total Energy: {:.2e} J",
               analytics.total_objects, analytics.total_satellites, analytics.total_debris, analytics.total_energy);
+        if analytics.reentered_satellites + analytics.reentered_debris > 0 {
+            info!("  Reentered: {} satellites and {} debris ({:.0} kg)",
+                  analytics.reentered_satellites, analytics.reentered_debris, analytics.reentered_mass);
+        }
+        if analytics.decayed_debris > 0 {
+            info!("  Decayed: {} debris past their drag lifetime", analytics.decayed_debris);
+        }
+        if analytics.total_super_particles > 0 {
+            info!("  {} debris represented by {} super particles",
+                  analytics.total_debris, analytics.total_super_particles);
+        }
+        if collision_stats.total > 0 {
+            info!("  Collisions: {} ({} catastrophic), {:.1} per simulated year, {:.1} fragments per collision",
+                  collision_stats.total, collision_stats.catastrophic, collision_stats.per_year(),
+                  collision_stats.fragments_per_event());
+        }
+        if close_approaches.enabled && !close_approaches.approaches.is_empty() {
+            info!("  Closest approaches in the last {:.0} h:", close_approaches.window / 3600.0);
+            for (line, approach) in close_approach_lines(&close_approaches, sim_time.current).iter().zip(close_approaches.closest()) {
+                info!("    {} ({}Z)", line, unix_to_iso8601(sim_time.unix_at(approach.time)));
+            }
+        }
         
         // Detailed altitude breakdown only in debug builds at debug level
         #[cfg(debug_assertions)]
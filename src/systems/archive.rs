@@ -0,0 +1,149 @@
+// Historical TLE archive replay: dated catalog snapshots applied as the simulation clock reaches them
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::data::{load_element_set_file, merge_records, TleDataCache, TleSource, TleSpawnQueue};
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+use crate::utils::*;
+
+/// The catalog as it stood at one time
+pub struct ArchiveSnapshot {
+    /// UTC Unix time the snapshot describes
+    pub time: f64,
+    pub path: PathBuf,
+    pub records: Vec<TleRecord>,
+}
+
+/// Resource holding a directory of dated catalog snapshots and how far the replay has got through them
+#[derive(Resource, Default)]
+pub struct TleArchive {
+    /// Snapshots in time order
+    pub snapshots: Vec<ArchiveSnapshot>,
+    /// Index of the next snapshot to apply
+    pub next: usize,
+}
+
+impl TleArchive {
+    /// Load every element set file in the directory as a snapshot
+    pub fn load(directory: &Path) -> Result<Self, String> {
+        let entries = std::fs::read_dir(directory)
+            .map_err(|e| format!("Failed to read TLE archive {}: {}", directory.display(), e))?;
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().and_then(|e| e.to_str()).and_then(GpFormat::from_extension).is_some())
+            .collect();
+        paths.sort();
+
+        let mut snapshots = Vec::new();
+        for path in paths {
+            let records = merge_records(load_element_set_file(&path)?);
+            let Some(time) = snapshot_time(&path, &records) else {
+                warn!("Skipping archive file {}: no date in its name and no element sets", path.display());
+                continue;
+            };
+            snapshots.push(ArchiveSnapshot { time, path, records });
+        }
+        if snapshots.is_empty() {
+            return Err(format!("No element set snapshots found in {}", directory.display()));
+        }
+        snapshots.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Ok(Self { snapshots, next: 0 })
+    }
+}
+
+/// Time a snapshot describes: the date at the start of its file name ("20090210_catalog.tle" or
+/// "2009-02-10.json"), otherwise the latest epoch among its element sets
+pub fn snapshot_time(path: &Path, records: &[TleRecord]) -> Option<f64> {
+    let stem = path.file_stem()?.to_str()?;
+    let digits: String = stem.chars().filter(|c| *c != '-').take_while(|c| c.is_ascii_digit()).collect();
+    if digits.len() >= 8 {
+        let (year, month, day) = (digits[..4].parse().ok()?, digits[4..6].parse().ok()?, digits[6..8].parse().ok()?);
+        if (1..=12).contains(&month) && (1..=31).contains(&day) {
+            return Some(utc_to_unix(year, month, day, 0, 0, 0.0));
+        }
+    }
+    records.iter()
+        .map(|record| tle_epoch_unix(record.epoch_year, record.epoch_day))
+        .max_by(f64::total_cmp)
+}
+
+/// System to apply the latest snapshot the simulation clock has reached
+///
+/// Objects new in the snapshot are cataloged and queued, objects missing from it are removed, and objects with
+/// newer element sets take them over and are repositioned on them. When the clock passes several snapshots in one
+/// frame only the latest is applied.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn archive_replay_system(
+    mut commands: Commands,
+    mut archive: ResMut<TleArchive>,
+    sim_time: Res<SimulationTime>,
+    source: Res<TleSource>,
+    mut tle_cache: ResMut<TleDataCache>,
+    mut spawn_queue: ResMut<TleSpawnQueue>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+    mut objects: Query<(Entity, &mut TleData, &mut OrbitalState, Option<&mut PositionCovariance>, Option<&OptimizedPhysics>)>,
+) {
+    // Scenarios without the catalog keep only their own objects
+    if !source.catalog {
+        return;
+    }
+    let now = sim_time.current_unix();
+    let reached = archive.snapshots[archive.next..].iter().take_while(|snapshot| snapshot.time <= now).count();
+    if reached == 0 {
+        return;
+    }
+    archive.next += reached;
+    let snapshot = &archive.snapshots[archive.next - 1];
+
+    let records: HashMap<u32, &TleRecord> = snapshot.records.iter().map(|record| (record.norad_id, record)).collect();
+    let epoch = |record: &TleRecord| tle_epoch_unix(record.epoch_year, record.epoch_day);
+    let (mut updated, mut removed) = (0, 0);
+    for (entity, mut tle_data, mut state, covariance, optimized) in objects.iter_mut() {
+        let Some(record) = records.get(&tle_data.norad_id) else {
+            commands.entity(entity).despawn();
+            // A later snapshot may catalog the object again
+            spawn_queue.queued.remove(&tle_data.norad_id);
+            removed += 1;
+            continue;
+        };
+        if tle_data.line1 == record.line1 && tle_data.line2 == record.line2 {
+            continue;
+        }
+        let Ok((position, velocity)) = propagate_tle(record, now) else {
+            continue;
+        };
+        tle_data.line1 = record.line1.clone();
+        tle_data.line2 = record.line2.clone();
+        tle_data.epoch = record.epoch_day;
+        state.position = position;
+        state.velocity = velocity;
+        if let Some(optimized) = optimized {
+            optimized_data.set_state(optimized.index, position, velocity);
+        }
+        if let Some(mut covariance) = covariance {
+            *covariance = PositionCovariance::from_tle_age((now - epoch(record)) / 86400.0);
+        }
+        updated += 1;
+    }
+
+    // Objects that left the catalog before they were spawned are not spawned at all
+    let dropped: Vec<u32> = spawn_queue.pending.iter()
+        .map(|record| record.norad_id)
+        .filter(|norad_id| !records.contains_key(norad_id))
+        .collect();
+    for norad_id in dropped {
+        spawn_queue.queued.remove(&norad_id);
+    }
+    spawn_queue.pending.retain(|record| records.contains_key(&record.norad_id));
+    let known = tle_cache.records.len();
+    tle_cache.records = snapshot.records.clone();
+    tle_cache.last_updated = wall_clock_unix_seconds();
+    let queued = spawn_queue.enqueue(&tle_cache.records, source.cap);
+    info!(
+        "Archive snapshot {} ({}Z): {} cataloged objects (was {}), {} updated, {} removed, {} queued",
+        snapshot.path.display(), unix_to_iso8601(snapshot.time), tle_cache.records.len(), known, updated, removed, queued
+    );
+}
@@ -0,0 +1,111 @@
+// Autonomous collision avoidance: active satellites dodge screened high-Pc conjunctions with along-track burns
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::conjunctions::ConjunctionEvent;
+use crate::systems::maneuvers::schedule_burn;
+use crate::systems::traffic::{encounter_pair, select_responders, TrafficCoordination, TrafficRules};
+use crate::utils::unix_to_iso8601;
+
+/// Distance an avoidance burn adds to the predicted miss (km)
+pub const AVOIDANCE_MISS_MARGIN: f64 = 2.0;
+/// Largest single avoidance burn (km/s)
+pub const MAX_AVOIDANCE_DELTA_V: f64 = 0.01;
+
+/// Resource switching autonomous avoidance of Pc-screened conjunctions on and off
+///
+/// Who maneuvers follows the `TrafficRules` right of way and operator rules, and the burns are tallied in
+/// `TrafficCoordination` with the scheduled traffic responses.
+#[derive(Resource, Default)]
+pub struct CollisionAvoidance {
+    pub enabled: bool,
+}
+
+/// System to schedule an along-track burn for the responding satellites of each conjunction screened above the Pc threshold
+///
+/// The burn executes at once, without a coordination delay, and for straight-line relative motion up to the
+/// closest approach shifts the satellite along-track just far enough to open the miss distance by
+/// `AVOIDANCE_MISS_MARGIN`. Encounters the traffic engine already screened are left to it.
+#[allow(clippy::type_complexity)]
+pub fn collision_avoidance_system(
+    mut commands: Commands,
+    avoidance: Res<CollisionAvoidance>,
+    rules: Res<TrafficRules>,
+    mut coordination: ResMut<TrafficCoordination>,
+    mut conjunctions: EventReader<ConjunctionEvent>,
+    sim_time: Res<SimulationTime>,
+    mut objects: Query<(&OrbitalState, Option<&Satellite>, Option<&Health>, Option<&Operator>, Option<&Constellation>, Option<&mut ManeuverPlan>)>,
+) {
+    if !avoidance.enabled {
+        conjunctions.clear();
+        return;
+    }
+
+    for conjunction in conjunctions.read() {
+        let time_to_go = conjunction.tca - sim_time.current;
+        if time_to_go <= 0.0 {
+            continue;
+        }
+        let pair = encounter_pair(conjunction.primary, conjunction.secondary);
+        if coordination.already_screened(pair, conjunction.tca, rules.response_cooldown) {
+            continue;
+        }
+        let (Ok((state, satellite, health, operator, constellation, _)), Ok((other_state, other_satellite, other_health, other_operator, other_constellation, _))) =
+            (objects.get(conjunction.primary), objects.get(conjunction.secondary)) else {
+            continue;
+        };
+        let mut parties = [
+            rules.party(conjunction.primary, state.mass, (satellite, health, operator, constellation), conjunction.miss_distance),
+            rules.party(conjunction.secondary, other_state.mass, (other_satellite, other_health, other_operator, other_constellation), conjunction.miss_distance),
+        ];
+        let busy = coordination.busy();
+        for party in parties.iter_mut() {
+            party.can_maneuver &= !busy.contains(&party.entity);
+        }
+        let responders: Vec<Entity> = select_responders(rules.right_of_way, &parties[0], &parties[1])
+            .iter()
+            .map(|party| party.entity)
+            .collect();
+        if responders.is_empty() {
+            continue;
+        }
+        coordination.screen(pair, conjunction.tca);
+
+        for maneuvering in responders {
+            let threat = if maneuvering == conjunction.primary { conjunction.secondary } else { conjunction.primary };
+            let (Ok((state, ..)), Ok((threat_state, ..))) = (objects.get(maneuvering), objects.get(threat)) else {
+                continue;
+            };
+
+            let transverse = state.position.cross(state.velocity).cross(state.position).normalize_or_zero();
+            let miss = (threat_state.position - state.position) + (threat_state.velocity - state.velocity) * time_to_go;
+            // Moving the satellite `shift` km along-track turns the miss vector into `miss - shift * transverse`,
+            // so it backs away from the along-track side the other object passes on
+            let along = miss.dot(transverse);
+            let across_squared = (miss.length_squared() - along * along).max(0.0);
+            let target = miss.length() + AVOIDANCE_MISS_MARGIN;
+            let extra = (target * target - across_squared).sqrt() - along.abs();
+            let shift = -along.signum() * extra;
+            let delta_v = (shift / time_to_go).clamp(-MAX_AVOIDANCE_DELTA_V, MAX_AVOIDANCE_DELTA_V);
+            if delta_v == 0.0 {
+                continue;
+            }
+
+            let Ok((_, satellite, _, operator, _, plan)) = objects.get_mut(maneuvering) else {
+                continue;
+            };
+            let burn = PlannedBurn { execute_at: sim_time.current, delta_v: DVec3::new(0.0, delta_v, 0.0) };
+            schedule_burn(&mut commands, maneuvering, plan, burn);
+            coordination.record_maneuver(operator, delta_v);
+
+            let name = satellite.map(|s| s.name.trim().to_string()).unwrap_or_default();
+            warn!(
+                "{} avoiding conjunction at {}Z (Pc {:.1e}, miss {:.3} km): {:+.2} m/s along-track; {} avoidance burns, {:.2} m/s total",
+                name, unix_to_iso8601(sim_time.unix_at(conjunction.tca)), conjunction.probability, conjunction.miss_distance,
+                delta_v * 1000.0, coordination.maneuvers_executed, coordination.total_delta_v()
+            );
+        }
+    }
+}
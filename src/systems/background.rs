@@ -0,0 +1,167 @@
+// Statistical background population of uncataloged debris from a MASTER-like density model
+
+use bevy::prelude::*;
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::utils::*;
+
+/// Fragments represented by each background super particle unless overridden
+pub const DEFAULT_BACKGROUND_WEIGHT: u32 = 100;
+/// Upper bound on background entities; the weight is raised to stay below it
+pub const MAX_BACKGROUND_ENTITIES: usize = 20000;
+/// Spread of each background fragment cloud (km)
+const BACKGROUND_SPREAD_KM: f64 = 5.0;
+/// Bulk density of fragments, used to turn diameters into masses (kg/m³)
+const FRAGMENT_DENSITY: f64 = 1500.0;
+/// Eccentricity of background orbits is drawn up to this value
+const MAX_BACKGROUND_ECCENTRICITY: f64 = 0.01;
+
+/// Fragment size class with a power-law cumulative distribution N(>d) ∝ d^-slope
+#[derive(Clone, Copy, Debug)]
+pub struct SizeClass {
+    /// Smallest and largest diameter (cm)
+    pub min_diameter: f64,
+    pub max_diameter: f64,
+    pub slope: f64,
+}
+
+impl SizeClass {
+    /// Fraction of the class larger than `diameter` (cm)
+    fn fraction_above(&self, diameter: f64) -> f64 {
+        let d = diameter.clamp(self.min_diameter, self.max_diameter);
+        let cumulative = |d: f64| d.powf(-self.slope);
+        (cumulative(d) - cumulative(self.max_diameter)) / (cumulative(self.min_diameter) - cumulative(self.max_diameter))
+    }
+
+    /// Draw a diameter (cm) at or above `min_diameter` from the class distribution
+    fn sample_diameter(&self, min_diameter: f64, rng: &mut impl Rng) -> f64 {
+        let low = min_diameter.clamp(self.min_diameter, self.max_diameter);
+        let (a, b) = (low.powf(-self.slope), self.max_diameter.powf(-self.slope));
+        (a - rng.gen::<f64>() * (a - b)).powf(-1.0 / self.slope)
+    }
+}
+
+/// Size classes of the model: 1 mm - 1 cm and 1 - 10 cm; larger objects are cataloged
+pub const SIZE_CLASSES: [SizeClass; 2] = [
+    SizeClass { min_diameter: 0.1, max_diameter: 1.0, slope: 2.1 },
+    SizeClass { min_diameter: 1.0, max_diameter: 10.0, slope: 1.3 },
+];
+
+/// Altitude/inclination bin of the density model with its object count per size class
+#[derive(Clone, Copy, Debug)]
+pub struct DensityBin {
+    /// Altitude band (km)
+    pub altitude: (f64, f64),
+    /// Inclination center and half-width (degrees)
+    pub inclination: (f64, f64),
+    pub counts: [f64; 2],
+}
+
+/// Population of the debris environment, roughly following ESA MASTER
+pub const DENSITY_BINS: [DensityBin; 10] = [
+    DensityBin { altitude: (200.0, 500.0), inclination: (51.6, 15.0), counts: [3.0e6, 40_000.0] },
+    DensityBin { altitude: (500.0, 700.0), inclination: (60.0, 35.0), counts: [15.0e6, 150_000.0] },
+    DensityBin { altitude: (700.0, 900.0), inclination: (98.5, 3.0), counts: [35.0e6, 300_000.0] }, // Fengyun-1C, SSO breakups
+    DensityBin { altitude: (750.0, 900.0), inclination: (78.0, 8.0), counts: [20.0e6, 150_000.0] }, // Cosmos-Iridium
+    DensityBin { altitude: (900.0, 1100.0), inclination: (65.0, 3.0), counts: [15.0e6, 120_000.0] }, // RORSAT NaK droplets
+    DensityBin { altitude: (900.0, 1500.0), inclination: (85.0, 15.0), counts: [12.0e6, 90_000.0] },
+    DensityBin { altitude: (1500.0, 2000.0), inclination: (75.0, 25.0), counts: [8.0e6, 50_000.0] },
+    DensityBin { altitude: (2000.0, 19000.0), inclination: (25.0, 20.0), counts: [5.0e6, 25_000.0] }, // GTO fragments
+    DensityBin { altitude: (19000.0, 24000.0), inclination: (55.0, 10.0), counts: [2.0e6, 15_000.0] }, // GNSS
+    DensityBin { altitude: (35586.0, 35986.0), inclination: (7.5, 7.5), counts: [10.0e6, 60_000.0] }, // GEO
+];
+
+/// Component marking debris synthesized from the density model
+#[derive(Component, Clone, Copy, Debug)]
+pub struct BackgroundDebris;
+
+/// Resource holding the background population still to be synthesized
+#[derive(Resource, Debug, Default)]
+pub struct BackgroundPopulation {
+    /// Smallest fragment diameter to synthesize (cm); None disables the background
+    pub min_diameter: Option<f64>,
+    /// Fragments represented by each super particle
+    pub weight: u32,
+}
+
+impl BackgroundPopulation {
+    /// Modeled number of fragments at or above `min_diameter` in one bin
+    fn bin_count(bin: &DensityBin, min_diameter: f64) -> f64 {
+        SIZE_CLASSES.iter().zip(bin.counts).map(|(class, count)| count * class.fraction_above(min_diameter)).sum()
+    }
+
+    /// Total modeled number of fragments at or above `min_diameter`
+    pub fn total_count(min_diameter: f64) -> f64 {
+        DENSITY_BINS.iter().map(|bin| Self::bin_count(bin, min_diameter)).sum()
+    }
+}
+
+/// System to synthesize the background population as super particles once the simulation starts
+pub fn background_population_system(
+    mut commands: Commands,
+    mut population: ResMut<BackgroundPopulation>,
+    constants: Res<Constants>,
+) {
+    let Some(min_diameter) = population.min_diameter.take() else {
+        return;
+    };
+
+    let total = BackgroundPopulation::total_count(min_diameter);
+    let weight = population.weight.max(1).max((total / MAX_BACKGROUND_ENTITIES as f64).ceil() as u32);
+    if weight > population.weight.max(1) {
+        info!("Background weight raised to {} fragments per particle to stay below {} entities",
+              weight, MAX_BACKGROUND_ENTITIES);
+    }
+
+    let mu_km = constants.gravitational_parameter / 1e9;
+    let mut rng = thread_rng();
+    let mut spawned = 0;
+    for bin in DENSITY_BINS.iter() {
+        // Probabilistic rounding keeps sparse bins represented on average
+        let particles = BackgroundPopulation::bin_count(bin, min_diameter) / weight as f64;
+        let particles = particles.floor() as usize + rng.gen_bool(particles.fract()) as usize;
+
+        for _ in 0..particles {
+            let class_weights = SIZE_CLASSES.iter().zip(bin.counts)
+                .map(|(class, count)| count * class.fraction_above(min_diameter));
+            let class = SIZE_CLASSES[WeightedIndex::new(class_weights)
+                .map(|index| index.sample(&mut rng))
+                .unwrap_or(SIZE_CLASSES.len() - 1)];
+            let diameter = class.sample_diameter(min_diameter, &mut rng);
+
+            // Eccentricity is limited so the perigee stays inside the bin
+            let semi_major_axis = constants.earth_radius + rng.gen_range(bin.altitude.0..bin.altitude.1);
+            let max_eccentricity = (1.0 - (constants.earth_radius + bin.altitude.0) / semi_major_axis)
+                .min(MAX_BACKGROUND_ECCENTRICITY);
+            let (inclination, spread) = bin.inclination;
+            let elements = OrbitalElements {
+                semi_major_axis,
+                eccentricity: rng.gen::<f64>() * max_eccentricity,
+                inclination: (inclination + rng.gen_range(-spread..=spread)).clamp(0.0, 180.0).to_radians(),
+                raan: rng.gen_range(0.0..std::f64::consts::TAU),
+                argument_of_perigee: rng.gen_range(0.0..std::f64::consts::TAU),
+                mean_anomaly: 0.0,
+            };
+            let (position, velocity) = state_at_true_anomaly(&elements, rng.gen_range(0.0..std::f64::consts::TAU), mu_km);
+
+            // Drag and SRP act on the cloud like on any debris; area and mass cover the whole cloud so both see a single fragment's area-to-mass ratio
+            let radius_m = diameter / 200.0;
+            let fragment_mass = FRAGMENT_DENSITY * 4.0 / 3.0 * std::f64::consts::PI * radius_m.powi(3);
+            let area = std::f64::consts::PI * radius_m * radius_m;
+            commands.spawn((
+                Debris::new(None, 0),
+                BackgroundDebris,
+                SuperParticle::new(weight, BACKGROUND_SPREAD_KM),
+                OrbitalState::new(position, velocity, fragment_mass * weight as f64),
+                PhysicsObject::new(area * weight as f64, 2.5, radius_m, fragment_mass * weight as f64),
+                RenderAsDebris,
+            ));
+            spawned += 1;
+        }
+    }
+
+    info!("Background population: {:.2e} fragments >= {} cm as {} super particles of {}",
+          total, min_diameter, spawned, weight);
+}
@@ -0,0 +1,182 @@
+// Conjunction Data Messages: replay operational conjunction warnings and export screened conjunctions
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use std::path::PathBuf;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::conjunctions::{screening_covariance, ConjunctionEvent};
+use crate::systems::export::{write_export, ExportConfig};
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+use crate::systems::rendering::CameraRig;
+use crate::systems::selection::Selection;
+use crate::utils::*;
+
+/// Simulation seconds between loading a CDM and its replayed time of closest approach
+pub const CDM_LEAD_TIME: f64 = 600.0;
+
+/// Resource holding CDM files waiting to be replayed
+#[derive(Resource, Default)]
+pub struct CdmImports {
+    pub pending: Vec<PathBuf>,
+}
+
+/// System to reconstruct CDM encounters so they play out `CDM_LEAD_TIME` seconds from now
+///
+/// Both objects are back-propagated from their TCA states; cataloged objects with a matching
+/// NORAD ID are moved onto the encounter, others are spawned. The pair is selected and framed.
+pub fn cdm_import_system(
+    mut commands: Commands,
+    mut imports: ResMut<CdmImports>,
+    constants: Res<Constants>,
+    mut selection: ResMut<Selection>,
+    mut rig: ResMut<CameraRig>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+    mut satellites: Query<(Entity, &Satellite, &mut OrbitalState, Option<&OptimizedPhysics>)>,
+) {
+    // Wait for the catalog so CDM objects can be matched to existing objects
+    if imports.pending.is_empty() || satellites.is_empty() {
+        return;
+    }
+
+    let mu_km = constants.gravitational_parameter / 1e9;
+    for path in std::mem::take(&mut imports.pending) {
+        let cdm = match std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| parse_cdm(&text).map_err(|e| e.to_string()))
+        {
+            Ok(cdm) => cdm,
+            Err(e) => {
+                warn!("Skipping CDM {}: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        let mut states = [(DVec3::ZERO, DVec3::ZERO); 2];
+        let mut entities = [Entity::PLACEHOLDER; 2];
+        for (index, object) in cdm.objects.iter().enumerate() {
+            let (position, velocity) = object.inertial_state(cdm.tca_unix);
+            let (position, velocity) = propagate_kepler(position, velocity, -CDM_LEAD_TIME, mu_km);
+            states[index] = (position, velocity);
+
+            let existing = object.norad_id().and_then(|id| {
+                satellites.iter_mut().find(|(_, satellite, _, _)| satellite.norad_id == id)
+            });
+            entities[index] = match existing {
+                Some((entity, _, mut orbital_state, optimized)) => {
+                    orbital_state.position = position;
+                    orbital_state.velocity = velocity;
+                    if let Some(optimized) = optimized {
+                        optimized_data.set_state(optimized.index, position, velocity);
+                    }
+                    entity
+                }
+                None => {
+                    let name = if object.name.is_empty() { object.designator.clone() } else { object.name.clone() };
+                    let mut entity = commands.spawn((
+                        Satellite::new(name.clone(), object.norad_id().unwrap_or(0), true),
+                        Operator::from_satellite_name(&name),
+                        OrbitalState::new(position, velocity, 1000.0),
+                        PhysicsObject::satellite(1000.0),
+                        Health::default(),
+                        RenderAsSatellite,
+                    ));
+                    if RocketBody::is_rocket_body_name(&name) {
+                        entity.insert(RocketBody);
+                    }
+                    entity.id()
+                }
+            };
+        }
+
+        let (tca, miss_km) = closest_approach(states[0], states[1], 2.0 * CDM_LEAD_TIME, mu_km);
+        info!(
+            "CDM {}: {} vs {} (TCA {}Z, reported miss {} m, Pc {}) replayed in {:.0} s, reconstructed miss {:.0} m",
+            cdm.message_id,
+            cdm.objects[0].name, cdm.objects[1].name,
+            unix_to_iso8601(cdm.tca_unix),
+            cdm.miss_distance.map_or("?".to_string(), |m| format!("{:.0}", m)),
+            cdm.collision_probability.map_or("?".to_string(), |p| format!("{:.2e}", p)),
+            tca, miss_km * 1000.0,
+        );
+
+        selection.selected = Some(entities[0]);
+        selection.secondary = Some(entities[1]);
+        rig.frame_pair(entities[0], entities[1]);
+    }
+}
+
+/// Resource controlling CDM export of conjunctions screened above the Pc threshold
+#[derive(Resource, Default)]
+pub struct CdmExport {
+    /// Encoding CDMs are written in, `None` while export is off
+    pub format: Option<CdmFormat>,
+    /// CDMs written so far
+    pub written: u32,
+}
+
+/// System to write a CDM for each conjunction screened above the Pc threshold
+///
+/// Both objects are propagated on their conics to the TCA; the covariance is the position uncertainty
+/// screening used for the probability of collision.
+#[allow(clippy::type_complexity)]
+pub fn cdm_export_system(
+    mut export: ResMut<CdmExport>,
+    mut conjunctions: EventReader<ConjunctionEvent>,
+    config: Res<ExportConfig>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    objects: Query<(&OrbitalState, Option<&Satellite>, Option<&TleData>, Option<&PositionCovariance>, Option<&Health>)>,
+) {
+    let Some(format) = export.format else {
+        conjunctions.clear();
+        return;
+    };
+
+    let mu_km = constants.gravitational_parameter / 1e9;
+    for conjunction in conjunctions.read() {
+        let mut cdm_objects = [CdmObject::default(), CdmObject::default()];
+        let mut norad_ids = [0; 2];
+        for (index, entity) in [conjunction.primary, conjunction.secondary].into_iter().enumerate() {
+            let Ok((state, satellite, tle_data, covariance, health)) = objects.get(entity) else {
+                continue;
+            };
+            let (position, velocity) = propagate_kepler(state.position, state.velocity, conjunction.tca - sim_time.current, mu_km);
+            norad_ids[index] = satellite.map_or(0, |s| s.norad_id);
+            cdm_objects[index] = CdmObject {
+                designator: satellite.filter(|s| s.norad_id != 0).map(|s| s.norad_id.to_string()).unwrap_or_default(),
+                name: satellite.map_or_else(|| "DEBRIS".to_string(), |s| s.name.trim().to_string()),
+                ref_frame: "EME2000".to_string(),
+                position,
+                velocity,
+                international_designator: tle_data
+                    .and_then(|tle| tle.line1.get(9..17))
+                    .map(|designator| designator.trim().to_string())
+                    .unwrap_or_default(),
+                maneuverable: Some(satellite.is_some_and(|s| s.active) && health.is_none_or(|h| h.has_propulsion())),
+                position_sigma_rtn: screening_covariance(covariance).sigma_rtn,
+            };
+        }
+
+        let tca_unix = sim_time.unix_at(conjunction.tca);
+        let (year, month, day, hour, minute, second) = unix_to_utc(tca_unix);
+        let stem = format!("cdm_{}_{}_{:04}{:02}{:02}T{:02}{:02}{:02}",
+                           norad_ids[0], norad_ids[1], year, month, day, hour, minute, second as u32);
+        let cdm = ConjunctionDataMessage {
+            message_id: stem.to_ascii_uppercase(),
+            tca_unix,
+            miss_distance: None,
+            relative_speed: None,
+            collision_probability: Some(conjunction.probability),
+            objects: cdm_objects,
+        };
+        let contents = format_cdm(&cdm, format, wall_clock_unix_seconds());
+        match write_export(&config.directory, &format!("{}.{}", stem, format.extension()), &contents) {
+            Ok(path) => {
+                export.written += 1;
+                info!("Exported CDM {} to {}", cdm.message_id, path.display());
+            }
+            Err(e) => warn!("CDM export failed: {}", e),
+        }
+    }
+}
@@ -1,103 +1,275 @@
 // Collision detection system with octree spatial partitioning
 
+use bevy::math::DVec3;
 use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 use crate::components::*;
-use std::collections::HashMap;
+use crate::resources::*;
+use crate::systems::health::{ImpactEvent, ImpactSource};
+use crate::systems::conjunctions::linear_closest_approach;
+use crate::systems::hash_grid::HashGridBroadPhase;
+use crate::systems::morton::MortonBroadPhase;
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+use crate::systems::protection::{encounter_probability, POSITION_SIGMA_KM};
 
-/// Octree node for spatial partitioning
+/// Event emitted for every collision processed by the debris generator
+#[derive(Event, Clone, Debug)]
+pub struct CollisionEvent {
+    /// Collision ID, recorded as the `parent_collision` of its fragments
+    pub id: u32,
+    pub entities: (Entity, Entity),
+    pub names: (String, String),
+    pub norad_ids: (Option<u32>, Option<u32>),
+    /// Collision point (km, ECI)
+    pub position: DVec3,
+    /// Simulation time of the collision (seconds)
+    pub time: f64,
+    /// Relative speed at impact (km/s)
+    pub relative_speed: f64,
+    /// Whether both objects were destroyed
+    pub catastrophic: bool,
+    /// Number of fragments generated
+    pub fragments: u32,
+    /// Collisions that created the colliding objects, for those that were debris
+    pub parent_collisions: (Option<u32>, Option<u32>),
+}
+
+/// Longest span screened along swept paths (seconds); straight-line motion and search radii stay reasonable
+const MAX_SWEPT_SPAN: f64 = 30.0;
+
+/// Resource controlling how often collision detection runs
+#[derive(Resource, Default)]
+pub struct CollisionSchedule {
+    pub interval: CollisionInterval,
+    /// Simulation time of the last check (seconds)
+    last_check: Option<f64>,
+}
+
+impl CollisionSchedule {
+    pub fn new(interval: CollisionInterval) -> Self {
+        Self { interval, last_check: None }
+    }
+
+    /// Whether a check is due, returning the simulated seconds it has to cover
+    fn due(&mut self, sim_time: &SimulationTime, frame_counter: u32) -> Option<f64> {
+        let Some(interval) = self.interval.seconds(sim_time.timestep) else {
+            // Reduce collision cadence under coarse time compression
+            return frame_counter.is_multiple_of(sim_time.collision_stride()).then_some(0.0);
+        };
+        match self.last_check {
+            Some(last) if sim_time.current >= last => {
+                let span = sim_time.current - last;
+                if span == 0.0 || span < interval {
+                    return None;
+                }
+                self.last_check = Some(sim_time.current);
+                Some(span)
+            }
+            // First check, or a rewind restarts the interval
+            _ => {
+                self.last_check = Some(sim_time.current);
+                Some(0.0)
+            }
+        }
+    }
+}
+
+/// Uncertainty radii (in sigmas) screened around each object in probabilistic mode
+const PC_SCREEN_SIGMAS: f64 = 5.0;
+
+/// Closest distance of a relative trajectory over the last `span` seconds, assuming straight-line motion
+fn swept_distance(relative_position: DVec3, relative_velocity: DVec3, span: f64) -> f64 {
+    let speed_squared = relative_velocity.length_squared();
+    if span <= 0.0 || speed_squared <= f64::EPSILON {
+        return relative_position.length();
+    }
+    let t = (-relative_position.dot(relative_velocity) / speed_squared).clamp(-span, 0.0);
+    (relative_position + relative_velocity * t).length()
+}
+
+/// Energy-to-mass ratio above which a collision is catastrophic (J/g, NASA breakup model)
+pub const CATASTROPHIC_EMR_THRESHOLD: f64 = 40.0;
+/// Decades of mass between the smallest and largest fragment shares of a breakup
+const FRAGMENT_MASS_DECADES: f64 = 3.0;
+
+/// Component recording the octree node an object was last inserted into
+///
+/// The node is addressed by its path from the root, three bits per level with the first octant lowest.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OctreeCell {
+    /// Octree generation the path belongs to; reconfiguring the tree invalidates every cell
+    pub generation: u32,
+    pub depth: u32,
+    pub path: u64,
+}
+
+/// Factor by which a node's bounds are enlarged beyond its cell, so objects near a boundary still fit one child
+pub const OCTREE_LOOSENESS: f64 = 2.0;
+
+/// Loose octree node for spatial partitioning
+///
+/// Objects are always held by leaves. A node covers its cell enlarged by `OCTREE_LOOSENESS`, so an object may
+/// drift past the cell boundary without being moved, and queries test the enlarged bounds.
 #[derive(Debug, Clone)]
 pub struct OctreeNode {
     /// Center of this octree node (km)
-    pub center: Vec3,
-    /// Half the width/height/depth of this node (km)
-    pub half_size: f32,
+    pub center: DVec3,
+    /// Half the width/height/depth of this node's cell (km)
+    pub half_size: f64,
     /// Maximum depth for subdivision
     pub max_depth: u32,
+    /// Objects held before subdividing
+    pub max_objects: usize,
     /// Current depth of this node
     pub depth: u32,
-    /// Objects contained in this node
-    pub objects: Vec<Entity>,
+    /// Path from the root, as recorded in `OctreeCell`
+    pub path: u64,
+    /// Objects contained in this node with their positions when last inserted or refreshed
+    pub objects: Vec<(Entity, DVec3)>,
     /// Child nodes (8 octants)
     pub children: Option<Box<[OctreeNode; 8]>>,
 }
 
 impl OctreeNode {
     /// Create a new octree node
-    pub fn new(center: Vec3, half_size: f32, max_depth: u32, depth: u32) -> Self {
+    pub fn new(center: DVec3, half_size: f64, max_depth: u32, max_objects: usize, depth: u32, path: u64) -> Self {
         Self {
             center,
             half_size,
             max_depth,
+            max_objects,
             depth,
+            path,
             objects: Vec::new(),
             children: None,
         }
     }
 
-    /// Insert an object into the octree
-    pub fn insert(&mut self, entity: Entity, position: Vec3) -> bool {
+    /// Insert an object, returning the depth and path of the leaf that took it
+    ///
+    /// Objects pushed down into new children when a full leaf subdivides are appended to `relocated`.
+    pub fn insert_at(&mut self, entity: Entity, position: DVec3, relocated: &mut Vec<(Entity, u32, u64)>) -> Option<(u32, u64)> {
         // Check if point is within this node
         if !self.contains_point(position) {
-            return false;
+            return None;
         }
+        Some(self.insert_within(entity, position, relocated))
+    }
 
-        // If we can subdivide and have too many objects, subdivide
-        const MAX_OBJECTS_PER_NODE: usize = 4;
-        if self.objects.len() >= MAX_OBJECTS_PER_NODE && self.depth < self.max_depth && self.children.is_none() {
+    fn insert_within(&mut self, entity: Entity, position: DVec3, relocated: &mut Vec<(Entity, u32, u64)>) -> (u32, u64) {
+        if self.children.is_none() {
+            if self.objects.len() < self.max_objects || self.depth >= self.max_depth {
+                self.objects.push((entity, position));
+                return (self.depth, self.path);
+            }
+            // A full leaf splits and hands its objects down
             self.subdivide();
+            for (object, object_position) in std::mem::take(&mut self.objects) {
+                let (depth, path) = self.child_for(object_position).insert_within(object, object_position, relocated);
+                relocated.push((object, depth, path));
+            }
+        }
+        self.child_for(position).insert_within(entity, position, relocated)
+    }
+
+    /// Child whose cell contains a point; its loose bounds always contain it too
+    fn child_for(&mut self, position: DVec3) -> &mut OctreeNode {
+        let octant = (position.x >= self.center.x) as usize
+            | ((position.y >= self.center.y) as usize) << 1
+            | ((position.z >= self.center.z) as usize) << 2;
+        let children = self.children.as_mut().expect("child_for called on a leaf");
+        &mut children[octant]
+    }
+
+    /// Node `depth` levels below this one along `path`
+    fn descendant_mut(&mut self, depth: u32, path: u64) -> Option<&mut OctreeNode> {
+        let mut node = self;
+        for level in node.depth..depth {
+            let octant = (path >> (3 * level)) & 7;
+            node = node.children.as_mut()?.get_mut(octant as usize)?;
+        }
+        Some(node)
+    }
+
+    /// Record an object's new position if it is still within the leaf at `depth` and `path`
+    pub fn refresh(&mut self, entity: Entity, depth: u32, path: u64, position: DVec3) -> bool {
+        let Some(node) = self.descendant_mut(depth, path) else {
+            return false;
+        };
+        if !node.contains_point(position) {
+            return false;
+        }
+        match node.objects.iter_mut().find(|(object, _)| *object == entity) {
+            Some((_, stored)) => {
+                *stored = position;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove an object from the node at `depth` and `path`
+    pub fn remove_at(&mut self, entity: Entity, depth: u32, path: u64) -> bool {
+        let Some(node) = self.descendant_mut(depth, path) else {
+            return false;
+        };
+        match node.objects.iter().position(|&(object, _)| object == entity) {
+            Some(index) => {
+                node.objects.swap_remove(index);
+                true
+            }
+            None => false,
         }
+    }
 
-        // Try to insert into children first
+    /// Remove a set of objects wherever they are in this subtree
+    pub fn remove_all(&mut self, entities: &HashSet<Entity>) {
+        self.objects.retain(|(entity, _)| !entities.contains(entity));
         if let Some(ref mut children) = self.children {
             for child in children.iter_mut() {
-                if child.insert(entity, position) {
-                    return true;
-                }
+                child.remove_all(entities);
             }
         }
+    }
 
-        // If no child could contain it, or we have no children, add to this node
-        self.objects.push(entity);
-        true
+    /// Half-size of the node's loose bounds (km)
+    fn loose_half_size(&self) -> f64 {
+        self.half_size * OCTREE_LOOSENESS
     }
 
-    /// Check if a point is within this node's boundaries
-    fn contains_point(&self, point: Vec3) -> bool {
-        point.x >= self.center.x - self.half_size && point.x <= self.center.x + self.half_size &&
-        point.y >= self.center.y - self.half_size && point.y <= self.center.y + self.half_size &&
-        point.z >= self.center.z - self.half_size && point.z <= self.center.z + self.half_size
+    /// Check if a point is within this node's loose boundaries
+    fn contains_point(&self, point: DVec3) -> bool {
+        (point - self.center).abs().max_element() <= self.loose_half_size()
     }
 
     /// Subdivide this node into 8 children
     fn subdivide(&mut self) {
         let quarter_size = self.half_size / 2.0;
         let new_depth = self.depth + 1;
-
-        self.children = Some(Box::new([
-            // Bottom layer (z - quarter_size)
-            OctreeNode::new(self.center + Vec3::new(-quarter_size, -quarter_size, -quarter_size), quarter_size, self.max_depth, new_depth),
-            OctreeNode::new(self.center + Vec3::new(quarter_size, -quarter_size, -quarter_size), quarter_size, self.max_depth, new_depth),
-            OctreeNode::new(self.center + Vec3::new(-quarter_size, quarter_size, -quarter_size), quarter_size, self.max_depth, new_depth),
-            OctreeNode::new(self.center + Vec3::new(quarter_size, quarter_size, -quarter_size), quarter_size, self.max_depth, new_depth),
-            // Top layer (z + quarter_size)
-            OctreeNode::new(self.center + Vec3::new(-quarter_size, -quarter_size, quarter_size), quarter_size, self.max_depth, new_depth),
-            OctreeNode::new(self.center + Vec3::new(quarter_size, -quarter_size, quarter_size), quarter_size, self.max_depth, new_depth),
-            OctreeNode::new(self.center + Vec3::new(-quarter_size, quarter_size, quarter_size), quarter_size, self.max_depth, new_depth),
-            OctreeNode::new(self.center + Vec3::new(quarter_size, quarter_size, quarter_size), quarter_size, self.max_depth, new_depth),
-        ]));
+        let child = |octant: u64| {
+            let sign = |bit: u64| if octant >> bit & 1 == 1 { quarter_size } else { -quarter_size };
+            OctreeNode::new(
+                self.center + DVec3::new(sign(0), sign(1), sign(2)),
+                quarter_size,
+                self.max_depth,
+                self.max_objects,
+                new_depth,
+                self.path | octant << (3 * self.depth),
+            )
+        };
+        self.children = Some(Box::new([child(0), child(1), child(2), child(3), child(4), child(5), child(6), child(7)]));
     }
 
     /// Get all objects within a sphere (for collision detection)
-    pub fn query_sphere(&self, center: Vec3, radius: f32, results: &mut Vec<Entity>) {
+    pub fn query_sphere(&self, center: DVec3, radius: f64, results: &mut Vec<Entity>) {
         // Early exit if sphere doesn't intersect with this node
         if !self.sphere_intersects_cube(center, radius) {
             return;
         }
 
         // Add objects from this node that are within the sphere
-        for &entity in &self.objects {
-            results.push(entity);
-        }
+        results.extend(self.objects.iter().map(|&(entity, _)| entity));
 
         // Recursively check children
         if let Some(ref children) = self.children {
@@ -107,42 +279,144 @@ impl OctreeNode {
         }
     }
 
-    /// Check if a sphere intersects with this cube
-    fn sphere_intersects_cube(&self, sphere_center: Vec3, sphere_radius: f32) -> bool {
+    /// Check if a sphere intersects with this node's loose cube
+    fn sphere_intersects_cube(&self, sphere_center: DVec3, sphere_radius: f64) -> bool {
         // Find the closest point on the cube to the sphere center
-        let closest = Vec3::new(
-            sphere_center.x.clamp(self.center.x - self.half_size, self.center.x + self.half_size),
-            sphere_center.y.clamp(self.center.y - self.half_size, self.center.y + self.half_size),
-            sphere_center.z.clamp(self.center.z - self.half_size, self.center.z + self.half_size),
-        );
+        let half = DVec3::splat(self.loose_half_size());
+        let closest = sphere_center.clamp(self.center - half, self.center + half);
 
         // Check if the distance to the closest point is less than the sphere radius
         (closest - sphere_center).length() <= sphere_radius
     }
 
-    /// Clear all objects from this node and its children
-    pub fn clear(&mut self) {
-        self.objects.clear();
-        if let Some(ref mut children) = self.children {
-            for child in children.iter_mut() {
-                child.clear();
+    /// Accumulate node count, deepest level and largest occupancy of this subtree
+    fn collect_statistics(&self, stats: &mut OctreeStats) {
+        stats.nodes += 1;
+        stats.deepest = stats.deepest.max(self.depth);
+        stats.max_occupancy = stats.max_occupancy.max(self.objects.len());
+        if let Some(ref children) = self.children {
+            for child in children.iter() {
+                child.collect_statistics(stats);
             }
         }
     }
 }
 
+/// Octree statistics from the latest update
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OctreeStats {
+    pub nodes: usize,
+    pub deepest: u32,
+    /// Most objects held by a single node
+    pub max_occupancy: usize,
+    /// Objects inserted or moved to another leaf by the latest update
+    pub reinserted: usize,
+    pub build_ms: f32,
+}
+
 /// Resource to hold the octree for spatial partitioning
 #[derive(Resource)]
 pub struct SpatialOctree {
     pub root: OctreeNode,
+    pub settings: OctreeSettings,
+    pub stats: OctreeStats,
+    /// Bumped whenever the subdivision is discarded, so stale `OctreeCell`s are re-inserted
+    pub generation: u32,
 }
 
 impl Default for SpatialOctree {
     fn default() -> Self {
-        // Create octree covering Earth orbit space
-        // Center at origin, half-size of 50,000 km (covers LEO to GEO)
+        Self::new(OctreeSettings::default())
+    }
+}
+
+impl SpatialOctree {
+    /// Create an octree covering Earth orbit space, centered at the origin
+    pub fn new(settings: OctreeSettings) -> Self {
         Self {
-            root: OctreeNode::new(Vec3::ZERO, 50000.0, 6, 0),
+            root: Self::root_node(&settings),
+            settings,
+            stats: OctreeStats::default(),
+            generation: 0,
+        }
+    }
+
+    fn root_node(settings: &OctreeSettings) -> OctreeNode {
+        OctreeNode::new(
+            DVec3::ZERO,
+            settings.root_half_size.max(1.0),
+            settings.max_depth,
+            settings.max_objects_per_node.max(1),
+            0,
+            0,
+        )
+    }
+
+    /// Change parameters, discarding the existing subdivision
+    pub fn reconfigure(&mut self, settings: OctreeSettings) {
+        self.root = Self::root_node(&settings);
+        self.settings = settings;
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
+
+/// Candidates rejected by each stage of the smart sieve in the latest check
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SieveStats {
+    /// Outside the sorted x window
+    pub coordinate_x: usize,
+    /// Outside the window along y or z
+    pub coordinate_yz: usize,
+    /// Outside the search sphere
+    pub radius: usize,
+    /// Too far away to close the gap at their relative speed within the span
+    pub relative_velocity: usize,
+}
+
+/// Resource screening the full catalog with ESA's smart sieve sequence of cheap filters
+///
+/// Objects are sorted along x once per check; a query then sieves its neighbours with successively
+/// costlier tests (x window, y/z windows, squared distance, reachable distance at the pair's relative
+/// speed), so only the survivors reach the narrow phase. Nothing is spatially indexed beyond the sort.
+#[derive(Resource, Default)]
+pub struct SmartSieve {
+    entries: Vec<(f64, Entity, DVec3, DVec3)>,
+    pub stats: SieveStats,
+}
+
+impl SmartSieve {
+    /// Re-sort objects' states (km, km/s) along x
+    pub fn rebuild(&mut self, objects: impl Iterator<Item = (Entity, DVec3, DVec3)>) {
+        self.entries.clear();
+        self.entries.extend(objects.map(|(entity, position, velocity)| (position.x, entity, position, velocity)));
+        self.entries.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+        self.stats = SieveStats::default();
+    }
+
+    /// Objects that may come within `contact` (km) of a state over the last `span` seconds
+    ///
+    /// `window` bounds how far any neighbour can be for that to happen, from the fastest object's speed.
+    pub fn query(&mut self, position: DVec3, velocity: DVec3, contact: f64, span: f64, window: f64, results: &mut Vec<Entity>) {
+        let start = self.entries.partition_point(|entry| entry.0 < position.x - window);
+        let end = self.entries.partition_point(|entry| entry.0 <= position.x + window);
+        self.stats.coordinate_x += self.entries.len() - (end - start);
+
+        for &(_, entity, other_position, other_velocity) in &self.entries[start..end] {
+            let offset = other_position - position;
+            if offset.y.abs() > window || offset.z.abs() > window {
+                self.stats.coordinate_yz += 1;
+                continue;
+            }
+            let distance_squared = offset.length_squared();
+            if distance_squared > window * window {
+                self.stats.radius += 1;
+                continue;
+            }
+            if distance_squared.sqrt() - (other_velocity - velocity).length() * span > contact {
+                self.stats.relative_velocity += 1;
+                continue;
+            }
+            results.push(entity);
         }
     }
 }
@@ -151,39 +425,241 @@ impl Default for SpatialOctree {
 #[derive(Resource, Default)]
 pub struct CollisionPairs {
     pub pairs: Vec<(Entity, Entity)>,
+    /// Candidate pairs tested by the narrow phase in the latest check
+    pub checks: usize,
+    /// Candidate pairs dropped in the latest check because their altitude bands never meet
+    pub prefiltered: usize,
+    /// Candidate pairs left out in the latest check as members of the same constellation plane
+    pub excluded: usize,
+}
+
+/// Simulated seconds between refreshes of the cached altitude bands
+pub const ALTITUDE_BAND_REFRESH: f64 = 60.0;
+/// Widening of each cached band covering drag decay and short-period J2 oscillation between refreshes (km)
+pub const ALTITUDE_BAND_MARGIN: f64 = 20.0;
+
+/// System to cache each object's perigee/apogee band for the screening pre-filter
+///
+/// Bands are refreshed every `ALTITUDE_BAND_REFRESH` simulated seconds, at once for new objects and every
+/// frame for objects with burns planned, whose orbits can change between refreshes.
+#[allow(clippy::type_complexity)]
+pub fn altitude_band_system(
+    mut commands: Commands,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    objects: Query<(Entity, &OrbitalState, Has<AltitudeBand>, Has<ManeuverPlan>), With<PhysicsObject>>,
+    mut last_refresh: Local<Option<f64>>,
+) {
+    let due = last_refresh.is_none_or(|last| (sim_time.current - last).abs() >= ALTITUDE_BAND_REFRESH);
+    if due {
+        *last_refresh = Some(sim_time.current);
+    }
+    let mu_km = constants.gravitational_parameter / 1e9;
+    for (entity, state, cached, maneuvering) in objects.iter() {
+        if due || !cached || maneuvering {
+            let mut band = AltitudeBand::from_state(state.position, state.velocity, mu_km);
+            band.perigee -= ALTITUDE_BAND_MARGIN;
+            band.apogee += ALTITUDE_BAND_MARGIN;
+            // The object may be destroyed before the command applies
+            commands.entity(entity).try_insert(band);
+        }
+    }
 }
 
 /// System to update octree with current object positions
+///
+/// Objects are re-inserted only when they have left the loose bounds of the leaf recorded in their
+/// `OctreeCell`; despawned objects are removed, and objects outside the root are retried every frame.
 pub fn update_spatial_octree_system(
+    mut commands: Commands,
     mut octree: ResMut<SpatialOctree>,
-    orbital_query: Query<(Entity, &OrbitalState), With<PhysicsObject>>,
+    mut orbital_query: Query<(Entity, &OrbitalState, Option<&mut OctreeCell>), With<PhysicsObject>>,
+    mut removed_cells: RemovedComponents<OctreeCell>,
+    mut removed_physics: RemovedComponents<PhysicsObject>,
 ) {
-    // Clear the octree for fresh population
-    octree.root.clear();
-    
-    // Insert all objects into the octree
-    for (entity, orbital_state) in orbital_query.iter() {
-        octree.root.insert(entity, orbital_state.position);
+    let started = Instant::now();
+    let generation = octree.generation;
+
+    let mut removed: HashSet<Entity> = removed_cells.read().collect();
+    for entity in removed_physics.read() {
+        // An object that regains its physics is inserted afresh
+        commands.entity(entity).try_remove::<OctreeCell>();
+        removed.insert(entity);
+    }
+    if !removed.is_empty() {
+        octree.root.remove_all(&removed);
     }
+
+    // New leaves of objects inserted this frame or pushed down by a split, applied to their cells afterwards
+    let mut placed: HashMap<Entity, Option<OctreeCell>> = HashMap::new();
+    let mut relocated = Vec::new();
+    for (entity, orbital_state, cell) in orbital_query.iter() {
+        let current = match placed.get(&entity) {
+            Some(&placement) => placement,
+            None => cell.copied().filter(|cell| cell.generation == generation),
+        };
+        if let Some(current) = current {
+            if octree.root.refresh(entity, current.depth, current.path, orbital_state.position) {
+                continue;
+            }
+            octree.root.remove_at(entity, current.depth, current.path);
+        }
+
+        let inserted = octree.root.insert_at(entity, orbital_state.position, &mut relocated);
+        placed.insert(entity, inserted.map(|(depth, path)| OctreeCell { generation, depth, path }));
+        for (object, depth, path) in relocated.drain(..) {
+            placed.insert(object, Some(OctreeCell { generation, depth, path }));
+        }
+    }
+
+    let reinserted = placed.len();
+    for (entity, placement) in placed {
+        let Ok((_, _, cell)) = orbital_query.get_mut(entity) else {
+            continue;
+        };
+        match (cell, placement) {
+            (Some(mut cell), Some(placement)) => *cell = placement,
+            // Left the root: drop the cell so it is retried from scratch
+            (Some(_), None) => {
+                commands.entity(entity).remove::<OctreeCell>();
+            }
+            // The object may be destroyed before the command applies
+            (None, Some(placement)) => {
+                commands.entity(entity).try_insert(placement);
+            }
+            (None, None) => {}
+        }
+    }
+
+    let mut stats = OctreeStats {
+        reinserted,
+        build_ms: started.elapsed().as_secs_f32() * 1000.0,
+        ..default()
+    };
+    octree.root.collect_statistics(&mut stats);
+    octree.stats = stats;
 }
 
-/// Collision detection system using octree spatial partitioning
+/// System to tune octree depth ('Y', Shift+Y lowers) and node capacity ('U', Shift+U lowers)
+pub fn octree_control_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut octree: ResMut<SpatialOctree>,
+) {
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let mut settings = octree.settings;
+    if keyboard.just_pressed(KeyCode::KeyY) {
+        settings.max_depth = if shift { settings.max_depth.saturating_sub(1) } else { (settings.max_depth + 1).min(12) };
+    }
+    if keyboard.just_pressed(KeyCode::KeyU) {
+        settings.max_objects_per_node = if shift {
+            (settings.max_objects_per_node / 2).max(1)
+        } else {
+            (settings.max_objects_per_node * 2).min(1024)
+        };
+    }
+    if settings == octree.settings {
+        return;
+    }
+
+    let previous = octree.stats;
+    octree.reconfigure(settings);
+    info!("Octree: max depth {}, {} objects per node, root half-size {:.0} km (was {} nodes, max occupancy {}, {:.2} ms build)",
+          settings.max_depth, settings.max_objects_per_node, settings.root_half_size,
+          previous.nodes, previous.max_occupancy, previous.build_ms);
+}
+
+/// Collision detection system finding candidates with the selected broad-phase
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn collision_detection_system(
+    broad_phase: Res<BroadPhase>,
     octree: Res<SpatialOctree>,
+    mut morton: ResMut<MortonBroadPhase>,
+    mut hash_grid: ResMut<HashGridBroadPhase>,
+    mut sieve: ResMut<SmartSieve>,
     mut collision_pairs: ResMut<CollisionPairs>,
-    orbital_query: Query<(Entity, &OrbitalState, &PhysicsObject)>,
+    exclusions: Res<CollisionExclusions>,
+    orbital_query: Query<(Entity, &OrbitalState, &PhysicsObject, Option<&SuperParticle>, Option<&AltitudeBand>, Option<&Constellation>)>,
+    sim_time: Res<SimulationTime>,
+    mut schedule: ResMut<CollisionSchedule>,
+    resolution: Res<CollisionResolution>,
+    mut frame_counter: Local<u32>,
+    mut last_sampled: Local<Option<f64>>,
 ) {
+    use rand::prelude::*;
+    use rayon::prelude::*;
+
     collision_pairs.pairs.clear();
-    let mut checked_pairs = std::collections::HashSet::new();
-    
-    for (entity, orbital_state, physics_object) in orbital_query.iter() {
-        // Query octree for nearby objects
-        let mut nearby_objects = Vec::new();
-        let search_radius = physics_object.collision_radius as f32 * 2.0; // Search within 2x collision radius
+    collision_pairs.checks = 0;
+    collision_pairs.prefiltered = 0;
+    collision_pairs.excluded = 0;
+
+    *frame_counter = frame_counter.wrapping_add(1);
+    let Some(span) = schedule.due(&sim_time, *frame_counter) else {
+        return;
+    };
+    // Objects skipped over by a long interval are caught by screening their swept paths
+    let span = span.min(MAX_SWEPT_SPAN);
+
+    // Probabilistic mode samples each encounter once, when its closest approach falls between two checks
+    let inflation = match *resolution {
+        CollisionResolution::HardSphere => None,
+        CollisionResolution::Probabilistic { inflation } => Some(inflation),
+    };
+    let window = match *last_sampled {
+        Some(last) if sim_time.current >= last => (sim_time.current - last).min(MAX_SWEPT_SPAN),
+        _ => 0.0,
+    };
+    *last_sampled = Some(sim_time.current);
+    if inflation.is_some() && window <= 0.0 {
+        return;
+    }
+    let span = if inflation.is_some() { span.max(window) } else { span };
+    let screen_margin = if inflation.is_some() { PC_SCREEN_SIGMAS * POSITION_SIGMA_KM } else { 0.0 };
+    let max_speed = if span > 0.0 {
+        orbital_query.iter().map(|(_, state, ..)| state.velocity.length()).fold(0.0, f64::max)
+    } else {
+        0.0
+    };
+
+    if *broad_phase == BroadPhase::SmartSieve {
+        sieve.rebuild(orbital_query.iter().map(|(entity, state, ..)| (entity, state.position, state.velocity)));
+    }
+    // Grid broad-phases are rebuilt with cells as wide as the largest search radius
+    if matches!(*broad_phase, BroadPhase::Morton | BroadPhase::HashGrid) {
+        let max_radius = orbital_query
+            .iter()
+            .map(|(_, _, physics, sp, ..)| (physics.collision_radius / 1000.0 + sp.map_or(0.0, |sp| sp.spread)) * 2.0)
+            .fold(0.0, f64::max)
+            + 2.0 * (max_speed * span + screen_margin);
+        let positions = orbital_query.iter().map(|(entity, state, ..)| (entity, state.position));
+        match *broad_phase {
+            BroadPhase::Morton => morton.rebuild(positions, max_radius),
+            BroadPhase::HashGrid => hash_grid.rebuild(positions, max_radius),
+            BroadPhase::Octree | BroadPhase::SmartSieve => {}
+        }
+    }
+
+    // Gather each candidate pair once from the broad-phase
+    let mut checked_pairs = HashSet::new();
+    let mut candidates = Vec::new();
+    let mut nearby_objects = Vec::new();
+    for (entity, orbital_state, physics_object, super_particle, ..) in orbital_query.iter() {
+        nearby_objects.clear();
+        let cloud_spread = super_particle.map(|sp| sp.spread).unwrap_or(0.0);
+        // Search within 2x collision radius, converted from metres to km like the cloud spread
+        let contact = (physics_object.collision_radius / 1000.0 + cloud_spread) * 2.0
+            + 2.0 * screen_margin;
+        let search_radius = contact + (orbital_state.velocity.length() + max_speed) * span;
         
-        octree.root.query_sphere(orbital_state.position, search_radius, &mut nearby_objects);
+        match *broad_phase {
+            BroadPhase::Octree => octree.root.query_sphere(orbital_state.position, search_radius, &mut nearby_objects),
+            BroadPhase::Morton => morton.query_sphere(orbital_state.position, search_radius, &mut nearby_objects),
+            BroadPhase::HashGrid => hash_grid.query_sphere(orbital_state.position, search_radius, &mut nearby_objects),
+            BroadPhase::SmartSieve => sieve.query(
+                orbital_state.position, orbital_state.velocity, contact, span, search_radius, &mut nearby_objects,
+            ),
+        }
         
-        // Check collisions with nearby objects
         for &other_entity in &nearby_objects {
             if entity == other_entity {
                 continue;
@@ -195,77 +671,275 @@ pub fn collision_detection_system(
             } else {
                 (other_entity, entity)
             };
-            
-            if checked_pairs.contains(&pair) {
-                continue;
+            if checked_pairs.insert(pair) {
+                candidates.push((entity, other_entity));
+            }
+        }
+    }
+
+    // Narrow phase over the candidates in parallel, each thread collecting into its own buffer
+    let narrow_phase = candidates
+        .par_iter()
+        .fold(NarrowPhaseResult::default, |mut result, &(entity, other_entity)| {
+            let (Ok((_, orbital_state, physics_object, super_particle, band, constellation)),
+                 Ok((_, other_orbital, other_physics, other_super_particle, other_band, other_constellation))) =
+                (orbital_query.get(entity), orbital_query.get(other_entity)) else {
+                return result;
+            };
+            let cloud_spread = super_particle.map(|sp| sp.spread).unwrap_or(0.0);
+            let other_spread = other_super_particle.map(|sp| sp.spread).unwrap_or(0.0);
+
+            // Orbits whose altitude bands never come within the collision distance cannot meet
+            let hard_radius = (physics_object.collision_radius + other_physics.collision_radius) / 1000.0;
+            let reach = hard_radius + cloud_spread + other_spread + 2.0 * screen_margin;
+            if band.zip(other_band).is_some_and(|(band, other_band)| band.separated_from(other_band, reach)) {
+                result.prefiltered += 1;
+                return result;
+            }
+            if constellation.zip(other_constellation).is_some_and(|(constellation, other)| exclusions.excludes(
+                &constellation.name, &other.name,
+                (orbital_state.position, orbital_state.velocity), (other_orbital.position, other_orbital.velocity),
+            )) {
+                result.excluded += 1;
+                return result;
+            }
+            let weight = super_particle.map(|sp| sp.weight).unwrap_or(1)
+                * other_super_particle.map(|sp| sp.weight).unwrap_or(1);
+
+            if let Some(inflation) = inflation {
+                let (tca, miss) = linear_closest_approach(
+                    orbital_state.position - other_orbital.position,
+                    orbital_state.velocity - other_orbital.velocity,
+                );
+                if tca < -window || tca >= 0.0 {
+                    return result;
+                }
+                // Fragment clouds widen the uncertainty, and every fragment is a candidate
+                let sigma = (POSITION_SIGMA_KM.powi(2) + cloud_spread.powi(2) + other_spread.powi(2)).sqrt();
+                let probability = (encounter_probability(miss, hard_radius, sigma) * inflation * weight as f64).min(1.0);
+                if thread_rng().gen::<f64>() < probability {
+                    result.pairs.push((entity, other_entity));
+                    warn!("Collision sampled! Objects {} <-> {} at miss {:.2}km (Pc {:.1e}, inflation {})",
+                          entity.index(), other_entity.index(), miss, probability, inflation);
+                }
+                return result;
             }
+
+            // Check if objects are close enough to collide
+            let distance = swept_distance(
+                orbital_state.position - other_orbital.position,
+                orbital_state.velocity - other_orbital.velocity,
+                span,
+            );
+            let combined_radius = hard_radius + cloud_spread + other_spread;
             
-            checked_pairs.insert(pair);
+            // Overlapping a super particle's cloud only hits one of its fragments with some probability
+            let hit_probability = cloud_hit_probability(weight, hard_radius, combined_radius);
             
-            // Get other object's data
-            if let Ok((_, other_orbital, other_physics)) = orbital_query.get(other_entity) {
-                // Check if objects are close enough to collide
-                let distance = (orbital_state.position - other_orbital.position).length();
-                let combined_radius = (physics_object.collision_radius + other_physics.collision_radius) as f32;
-                
-                if distance <= combined_radius {
-                    collision_pairs.pairs.push((entity, other_entity));
-                    warn!("Collision detected! Objects {} <-> {} at distance {:.2}km (threshold: {:.2}km)",
-                            entity.index(), other_entity.index(), distance, combined_radius);
-                }
+            if distance <= combined_radius && (hit_probability >= 1.0 || thread_rng().gen::<f64>() < hit_probability) {
+                result.pairs.push((entity, other_entity));
+                warn!("Collision detected! Objects {} <-> {} at distance {:.2}km (threshold: {:.2}km)",
+                        entity.index(), other_entity.index(), distance, combined_radius);
             }
-        }
-    }
+            result
+        })
+        .reduce(NarrowPhaseResult::default, NarrowPhaseResult::merge);
+
+    // Threads finish in any order; resolve collisions in a stable one
+    let NarrowPhaseResult { mut pairs, prefiltered, excluded } = narrow_phase;
+    pairs.sort_by_key(|&(a, b)| (a.index(), b.index()));
+    collision_pairs.pairs = pairs;
+    collision_pairs.prefiltered = prefiltered;
+    collision_pairs.excluded = excluded;
     
+    collision_pairs.checks = candidates.len() - prefiltered - excluded;
+
     // Debug output for collision detection
     if !collision_pairs.pairs.is_empty() {
         info!("Collision detection: {} pairs identified this frame", collision_pairs.pairs.len());
     }
 }
 
+/// Pairs found and pairs skipped by one thread's share of the narrow phase
+#[derive(Default)]
+struct NarrowPhaseResult {
+    pairs: Vec<(Entity, Entity)>,
+    prefiltered: usize,
+    excluded: usize,
+}
+
+impl NarrowPhaseResult {
+    fn merge(mut self, other: Self) -> Self {
+        self.pairs.extend(other.pairs);
+        self.prefiltered += other.prefiltered;
+        self.excluded += other.excluded;
+        self
+    }
+}
+
+/// Probability that an overlap of fragment clouds is an actual hit between their fragments
+fn cloud_hit_probability(weight: u32, hard_radius: f64, cloud_radius: f64) -> f64 {
+    if cloud_radius <= hard_radius || cloud_radius <= 0.0 {
+        return 1.0;
+    }
+    (weight as f64 * (hard_radius / cloud_radius).powi(2)).min(1.0)
+}
+
+/// Remove a destroyed object, or only the colliding fragment if it is a super particle
+fn destroy_object(
+    commands: &mut Commands,
+    optimized_data: &mut OptimizedPhysicsData,
+    entity: Entity,
+    orbital: &OrbitalState,
+    super_particle: Option<&SuperParticle>,
+    optimized: Option<&OptimizedPhysics>,
+) {
+    match super_particle {
+        Some(sp) if sp.weight > 1 => {
+            let remaining_mass = orbital.mass - sp.fragment_mass(orbital.mass);
+            commands.entity(entity).insert((
+                SuperParticle::new(sp.weight - 1, sp.spread),
+                OrbitalState::new(orbital.position, orbital.velocity, remaining_mass),
+            ));
+            if let Some(optimized) = optimized {
+                optimized_data.set_mass(optimized.index, remaining_mass);
+            }
+        }
+        _ => {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
 /// Debris generation system
+///
+/// Fragment masses add up to the mass destroyed and the fragment cloud carries the colliding objects' momentum.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn debris_generation_system(
     mut commands: Commands,
     collision_pairs: Res<CollisionPairs>,
-    orbital_query: Query<(Entity, &OrbitalState, &PhysicsObject, Option<&Satellite>)>,
-    mut debris_count: Local<u32>,
+    mut orbital_query: Query<(
+        Entity,
+        &mut OrbitalState,
+        &PhysicsObject,
+        Option<&Satellite>,
+        Option<&SuperParticle>,
+        Option<&OptimizedPhysics>,
+        Option<&Debris>,
+    )>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+    mut impacts: EventWriter<ImpactEvent>,
+    mut collisions: EventWriter<CollisionEvent>,
+    sim_time: Res<SimulationTime>,
+    mut collision_count: Local<u32>,
 ) {
+    // Despawns and super particle updates are deferred, so an object already broken up or struck by a fragment this
+    // frame sits out its later pairs rather than giving its mass to a second cloud
+    let mut consumed = HashSet::new();
     for &(entity1, entity2) in &collision_pairs.pairs {
-        if let (Ok((_, orbital1, physics1, sat1)), Ok((_, orbital2, physics2, sat2))) =
+        if consumed.contains(&entity1) || consumed.contains(&entity2) {
+            continue;
+        }
+        if let (Ok((_, orbital1, _, sat1, super1, optimized1, debris1)), Ok((_, orbital2, _, sat2, super2, optimized2, debris2))) =
             (orbital_query.get(entity1), orbital_query.get(entity2)) {
             
+            // Only a single fragment of a super particle takes part in the collision
+            let mass1 = super1.map(|sp| sp.fragment_mass(orbital1.mass)).unwrap_or(orbital1.mass);
+            let mass2 = super2.map(|sp| sp.fragment_mass(orbital2.mass)).unwrap_or(orbital2.mass);
+            
             // Calculate collision properties
             let collision_point = (orbital1.position + orbital2.position) / 2.0;
             let relative_velocity = orbital2.velocity - orbital1.velocity;
-            let collision_energy = 0.5 * (orbital1.mass + orbital2.mass) as f32 * relative_velocity.length_squared();
+            let collision_energy = 0.5 * (mass1 + mass2) * relative_velocity.length_squared();
             
             // Log collision event
             let obj1_name = sat1.as_ref().map(|s| s.name.as_str()).unwrap_or("Unknown");
             let obj2_name = sat2.as_ref().map(|s| s.name.as_str()).unwrap_or("Unknown");
             
             warn!("COLLISION EVENT: {} ({:.0}kg) <-> {} ({:.0}kg) at ({:.1}, {:.1}, {:.1})km - Energy: {:.2e}J",
-                  obj1_name, orbital1.mass, obj2_name, orbital2.mass,
+                  obj1_name, mass1, obj2_name, mass2,
                   collision_point.x, collision_point.y, collision_point.z, collision_energy);
+
+            // Fragments are one generation past the youngest debris that collided, and intact objects count as generation 0
+            *collision_count += 1;
+            let collision_id = *collision_count;
+            let generation = debris1.map_or(0, |d| d.generation).max(debris2.map_or(0, |d| d.generation)) + 1;
+
+            let mut collision_event = CollisionEvent {
+                id: collision_id,
+                entities: (entity1, entity2),
+                names: (obj1_name.to_string(), obj2_name.to_string()),
+                norad_ids: (sat1.map(|s| s.norad_id), sat2.map(|s| s.norad_id)),
+                position: collision_point,
+                time: sim_time.current,
+                relative_speed: relative_velocity.length(),
+                catastrophic: false,
+                fragments: 0,
+                parent_collisions: (debris1.and_then(|d| d.parent_collision), debris2.and_then(|d| d.parent_collision)),
+            };
+
+            // Specific energy of the lighter projectile relative to the heavier target decides
+            // whether the target is shattered or only cratered
+            let (projectile, projectile_mass, projectile_orbital, projectile_super, projectile_optimized, target, target_mass, target_orbital) =
+                if mass1 <= mass2 {
+                    (entity1, mass1, orbital1, super1, optimized1, entity2, mass2, orbital2)
+                } else {
+                    (entity2, mass2, orbital2, super2, optimized2, entity1, mass1, orbital1)
+                };
+            let relative_speed_m_s = relative_velocity.length() * 1000.0;
+            let impact_emr = 0.5 * projectile_mass * relative_speed_m_s * relative_speed_m_s
+                / (target_mass * 1000.0);
+
+            if impact_emr < CATASTROPHIC_EMR_THRESHOLD {
+                // Non-catastrophic: the projectile is destroyed while the target survives damaged
+                impacts.write(ImpactEvent {
+                    target,
+                    severity: (impact_emr / CATASTROPHIC_EMR_THRESHOLD) as f32,
+                    source: ImpactSource::Collision,
+                });
+
+                // The projectile's mass leaves as ejecta moving with the target, and the target absorbs the rest
+                // of the projectile's momentum
+                const EJECTA_PIECES: u32 = 2;
+                let ejecta_masses = fragment_masses(projectile_mass, EJECTA_PIECES);
+                let ejecta_velocities = fragment_velocities(&ejecta_masses, target_orbital.velocity, relative_velocity.length() * 0.1);
+                for (&ejecta_mass, &ejecta_velocity) in ejecta_masses.iter().zip(&ejecta_velocities) {
+                    commands.spawn((
+                        Debris::new(Some(collision_id), generation),
+                        OrbitalState::new(collision_point, ejecta_velocity, ejecta_mass),
+                        PhysicsObject::debris(ejecta_mass),
+                        RenderAsDebris,
+                    ));
+                }
+                let target_velocity = target_orbital.velocity
+                    + (projectile_orbital.velocity - target_orbital.velocity) * projectile_mass / target_orbital.mass;
+
+                info!("Non-catastrophic impact (EMR {:.1} J/g) - target survives with damage", impact_emr);
+                collision_event.fragments = EJECTA_PIECES;
+                collisions.write(collision_event);
+                consumed.insert(projectile);
+                destroy_object(&mut commands, &mut optimized_data, projectile, projectile_orbital, projectile_super, projectile_optimized);
+                if let Ok((_, mut state, _, _, _, optimized, _)) = orbital_query.get_mut(target) {
+                    let delta_v = target_velocity - state.velocity;
+                    state.velocity = target_velocity;
+                    if let Some(optimized) = optimized {
+                        optimized_data.change_velocity(optimized.index, delta_v);
+                    }
+                }
+                continue;
+            }
             
             // Generate debris based on collision energy
             // NASA standard breakup model: more energy = more debris
-            let debris_pieces = calculate_debris_count(collision_energy, orbital1.mass + orbital2.mass);
-            
-            for i in 0..debris_pieces {
-                *debris_count += 1;
-                
-                // Generate debris with random velocity distribution around collision point
-                let debris_velocity = generate_debris_velocity(
-                    orbital1.velocity,
-                    orbital2.velocity,
-                    relative_velocity.length()
-                );
-                
-                // Create debris entity
-                let debris_mass = (orbital1.mass + orbital2.mass) / debris_pieces as f64 * 0.1; // Smaller fragments
-                
+            let debris_pieces = calculate_debris_count(collision_energy, mass1 + mass2);
+            // Fragments fly apart from the pair's centre of mass
+            let center_velocity = (orbital1.velocity * mass1 + orbital2.velocity * mass2) / (mass1 + mass2);
+            let debris_masses = fragment_masses(mass1 + mass2, debris_pieces);
+            let debris_velocities = fragment_velocities(&debris_masses, center_velocity, relative_velocity.length());
+
+            for (&debris_mass, &debris_velocity) in debris_masses.iter().zip(&debris_velocities) {
                 commands.spawn((
-                    Debris::from_collision(*debris_count, 0.0), // collision_id, creation_time
+                    Debris::new(Some(collision_id), generation),
                     OrbitalState::new(collision_point, debris_velocity, debris_mass),
                     PhysicsObject::debris(debris_mass),
                     RenderAsDebris,
@@ -273,16 +947,20 @@ pub fn debris_generation_system(
             }
 
             info!("Generated {} debris pieces from collision", debris_pieces);
+            collision_event.catastrophic = true;
+            collision_event.fragments = debris_pieces;
+            collisions.write(collision_event);
             
             // Remove collided objects (they've been destroyed)
-            commands.entity(entity1).despawn();
-            commands.entity(entity2).despawn();
+            consumed.extend([entity1, entity2]);
+            destroy_object(&mut commands, &mut optimized_data, entity1, orbital1, super1, optimized1);
+            destroy_object(&mut commands, &mut optimized_data, entity2, orbital2, super2, optimized2);
         }
     }
 }
 
 /// Calculate number of debris pieces from collision energy (NASA breakup model)
-fn calculate_debris_count(collision_energy: f32, total_mass: f64) -> u32 {
+fn calculate_debris_count(collision_energy: f64, total_mass: f64) -> u32 {
     // Simplified NASA standard breakup model
     // More massive objects and higher energy create more debris
     let base_debris = (total_mass / 1000.0).sqrt() as u32; // Base on mass
@@ -291,26 +969,40 @@ fn calculate_debris_count(collision_energy: f32, total_mass: f64) -> u32 {
     (base_debris + energy_multiplier).clamp(2, 50) // Minimum 2, maximum 50 pieces
 }
 
-/// Generate debris velocity with realistic distribution
-fn generate_debris_velocity(vel1: Vec3, vel2: Vec3, relative_speed: f32) -> Vec3 {
+/// Split `total_mass` over `pieces` fragments with log-uniform shares, so a few large fragments and many small ones
+/// add up to exactly the mass broken up
+pub fn fragment_masses(total_mass: f64, pieces: u32) -> Vec<f64> {
     use rand::prelude::*;
-    
-    // Average velocity of colliding objects
-    let avg_velocity = (vel1 + vel2) / 2.0;
-    
-    // Generate random direction for debris
+
     let mut rng = thread_rng();
-    let theta = rng.gen::<f32>() * 2.0 * std::f32::consts::PI;
-    let phi = rng.gen::<f32>() * std::f32::consts::PI;
-    
-    let random_dir = Vec3::new(
-        phi.sin() * theta.cos(),
-        phi.sin() * theta.sin(),
-        phi.cos(),
-    );
-    
-    // Debris gets kicked with fraction of relative collision speed
-    let debris_kick_speed = relative_speed * rng.gen_range(0.1..0.5);
-    
-    avg_velocity + random_dir * debris_kick_speed
-}
\ No newline at end of file
+    let shares: Vec<f64> = (0..pieces.max(1))
+        .map(|_| 10f64.powf(-FRAGMENT_MASS_DECADES * rng.gen::<f64>()))
+        .collect();
+    let total_share: f64 = shares.iter().sum();
+    shares.into_iter().map(|share| total_mass * share / total_share).collect()
+}
+
+/// Fragment velocities kicked in random directions by a fraction of `relative_speed` around `center_velocity`
+///
+/// The mass-weighted mean kick is removed, so the cloud's momentum is its mass times `center_velocity`.
+pub fn fragment_velocities(masses: &[f64], center_velocity: DVec3, relative_speed: f64) -> Vec<DVec3> {
+    use rand::prelude::*;
+
+    let mut rng = thread_rng();
+    let kicks: Vec<DVec3> = masses.iter()
+        .map(|_| {
+            let theta = rng.gen::<f64>() * 2.0 * std::f64::consts::PI;
+            let phi = rng.gen::<f64>() * std::f64::consts::PI;
+            let random_dir = DVec3::new(phi.sin() * theta.cos(), phi.sin() * theta.sin(), phi.cos());
+            // Debris gets kicked with fraction of relative collision speed
+            random_dir * relative_speed * rng.gen_range(0.1..0.5)
+        })
+        .collect();
+    let total_mass: f64 = masses.iter().sum();
+    let mean_kick = if total_mass > 0.0 {
+        masses.iter().zip(&kicks).map(|(&mass, &kick)| kick * mass).sum::<DVec3>() / total_mass
+    } else {
+        DVec3::ZERO
+    };
+    kicks.into_iter().map(|kick| center_velocity + kick - mean_kick).collect()
+}
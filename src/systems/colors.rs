@@ -0,0 +1,241 @@
+// Color mode: palettes and per-category materials for rendered objects
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::constellation::ConstellationGroups;
+use crate::systems::metadata::UNKNOWN_OWNER;
+use crate::systems::rendering::RenderedObject;
+use crate::systems::stress_test::StressTestObject;
+
+/// Rendering category of an object
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ObjectCategory {
+    Satellite,
+    Debris,
+    RocketBody,
+    StressTest,
+}
+
+impl ObjectCategory {
+    pub const ALL: [ObjectCategory; 4] = [
+        ObjectCategory::Satellite,
+        ObjectCategory::Debris,
+        ObjectCategory::RocketBody,
+        ObjectCategory::StressTest,
+    ];
+
+    /// Classify an object from its marker components (stress test wins over rocket body over debris)
+    pub fn classify(is_stress_test: bool, is_rocket_body: bool, is_debris: bool) -> Self {
+        if is_stress_test {
+            ObjectCategory::StressTest
+        } else if is_rocket_body {
+            ObjectCategory::RocketBody
+        } else if is_debris {
+            ObjectCategory::Debris
+        } else {
+            ObjectCategory::Satellite
+        }
+    }
+
+    /// Key used for color overrides in the settings file
+    pub fn key(&self) -> &'static str {
+        match self {
+            ObjectCategory::Satellite => "satellite",
+            ObjectCategory::Debris => "debris",
+            ObjectCategory::RocketBody => "rocket_body",
+            ObjectCategory::StressTest => "stress_test",
+        }
+    }
+}
+
+/// Default color of a category in a palette
+pub fn palette_color(palette: Palette, category: ObjectCategory) -> Color {
+    match (palette, category) {
+        (Palette::Classic, ObjectCategory::Satellite) => Color::srgb(0.0, 1.0, 0.0),
+        (Palette::Classic, ObjectCategory::Debris) => Color::srgb(1.0, 0.0, 0.0),
+        (Palette::Classic, ObjectCategory::RocketBody) => Color::srgb(1.0, 0.6, 0.0),
+        (Palette::Classic, ObjectCategory::StressTest) => Color::srgb(0.0, 1.0, 0.0),
+        // Okabe-Ito: sky blue, vermillion, yellow, bluish green
+        (Palette::OkabeIto, ObjectCategory::Satellite) => Color::srgb_u8(86, 180, 233),
+        (Palette::OkabeIto, ObjectCategory::Debris) => Color::srgb_u8(213, 94, 0),
+        (Palette::OkabeIto, ObjectCategory::RocketBody) => Color::srgb_u8(240, 228, 66),
+        (Palette::OkabeIto, ObjectCategory::StressTest) => Color::srgb_u8(0, 158, 115),
+        (Palette::BlueOrange, ObjectCategory::Satellite) => Color::srgb_u8(0, 114, 178),
+        (Palette::BlueOrange, ObjectCategory::Debris) => Color::srgb_u8(230, 159, 0),
+        (Palette::BlueOrange, ObjectCategory::RocketBody) => Color::srgb_u8(204, 121, 167),
+        (Palette::BlueOrange, ObjectCategory::StressTest) => Color::srgb_u8(120, 170, 220),
+        (Palette::HighContrast, ObjectCategory::Satellite) => Color::WHITE,
+        (Palette::HighContrast, ObjectCategory::Debris) => Color::srgb(1.0, 1.0, 0.0),
+        (Palette::HighContrast, ObjectCategory::RocketBody) => Color::srgb(1.0, 0.0, 1.0),
+        (Palette::HighContrast, ObjectCategory::StressTest) => Color::srgb(0.0, 1.0, 1.0),
+    }
+}
+
+/// Effective color of a category after applying any settings override
+pub fn category_color(colors: &ColorSettings, category: ObjectCategory) -> Color {
+    colors.overrides
+        .get(category.key())
+        .and_then(|hex| Srgba::hex(hex).ok())
+        .map(Color::from)
+        .unwrap_or_else(|| palette_color(colors.palette, category))
+}
+
+/// Resource holding one shared material per object category
+#[derive(Resource, Default)]
+pub struct ObjectMaterials {
+    pub materials: HashMap<ObjectCategory, Handle<StandardMaterial>>,
+}
+
+impl ObjectMaterials {
+    /// Shared material for a category
+    pub fn get(&self, category: ObjectCategory) -> Handle<StandardMaterial> {
+        self.materials.get(&category).cloned().unwrap_or_default()
+    }
+}
+
+/// System to create the shared category materials from the configured palette
+pub fn setup_object_materials_system(
+    settings: Res<Settings>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut object_materials: ResMut<ObjectMaterials>,
+) {
+    for category in ObjectCategory::ALL {
+        let handle = materials.add(StandardMaterial {
+            base_color: category_color(&settings.colors, category),
+            ..default()
+        });
+        object_materials.materials.insert(category, handle);
+    }
+}
+
+/// System to cycle palettes with 'P', saving the choice to the settings file
+pub fn palette_control_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<Settings>,
+) {
+    if keyboard.just_pressed(KeyCode::KeyP) {
+        settings.colors.palette = settings.colors.palette.next();
+        settings.save();
+        info!("Color palette: {:?}", settings.colors.palette);
+    }
+}
+
+/// System to recolor the shared category materials when color settings change
+pub fn apply_palette_system(
+    settings: Res<Settings>,
+    object_materials: Res<ObjectMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for category in ObjectCategory::ALL {
+        if let Some(material) = materials.get_mut(&object_materials.get(category)) {
+            material.base_color = category_color(&settings.colors, category);
+        }
+    }
+}
+
+/// Color of an operator or owner group: a stable hue per name, grey for unknown
+pub fn group_color(name: &str) -> Color {
+    if name.eq_ignore_ascii_case("Unknown") || name == UNKNOWN_OWNER {
+        return Color::srgb(0.5, 0.5, 0.5);
+    }
+    let hash = name.bytes().fold(2166136261u32, |hash, byte| (hash ^ byte as u32).wrapping_mul(16777619));
+    Color::hsl((hash % 360) as f32, 0.85, 0.55)
+}
+
+/// Resource holding one shared material per operator or owner group
+#[derive(Resource, Default)]
+pub struct GroupMaterials {
+    pub materials: HashMap<String, Handle<StandardMaterial>>,
+}
+
+impl GroupMaterials {
+    /// Shared material for a group, created on first use and recolored if the color changed
+    pub fn get_or_add(&mut self, key: &str, color: Color, materials: &mut Assets<StandardMaterial>) -> Handle<StandardMaterial> {
+        let handle = self.materials
+            .entry(key.to_string())
+            .or_insert_with(|| materials.add(StandardMaterial {
+                base_color: color,
+                ..default()
+            }))
+            .clone();
+        if let Some(material) = materials.get_mut(&handle) {
+            if material.base_color != color {
+                material.base_color = color;
+            }
+        }
+        handle
+    }
+}
+
+/// System to cycle the color mode (category, operator, owner) with 'H', saving the choice
+pub fn color_mode_control_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<Settings>,
+) {
+    if keyboard.just_pressed(KeyCode::KeyH) {
+        settings.colors.mode = settings.colors.mode.next();
+        settings.save();
+        info!("Color mode: {:?}", settings.colors.mode);
+    }
+}
+
+/// System to assign category, group or constellation materials when the color mode changes or objects gain metadata
+#[allow(clippy::type_complexity)]
+pub fn group_color_system(
+    settings: Res<Settings>,
+    constellation_groups: Res<ConstellationGroups>,
+    object_materials: Res<ObjectMaterials>,
+    mut group_materials: ResMut<GroupMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut objects: Query<(
+        &mut MeshMaterial3d<StandardMaterial>,
+        Option<Ref<Operator>>,
+        Option<Ref<Owner>>,
+        Option<Ref<Constellation>>,
+        Has<StressTestObject>,
+        Has<RocketBody>,
+        Has<Debris>,
+    ), With<RenderedObject>>,
+    mut last_mode: Local<ColorMode>,
+) {
+    let mode = settings.colors.mode;
+    let refresh_all = mode != *last_mode || constellation_groups.is_changed();
+    *last_mode = mode;
+
+    for (mut material, operator, owner, constellation, is_stress_test, is_rocket_body, is_debris) in objects.iter_mut() {
+        // Constellation color overrides win over every color mode
+        let override_color = constellation.as_ref()
+            .and_then(|c| constellation_groups.colors.get(&c.name).map(|&color| (c.name.as_str(), color, c.is_added())));
+        if let Some((name, color, added)) = override_color {
+            if refresh_all || added || material.is_added() {
+                let handle = group_materials.get_or_add(&format!("constellation/{}", name), color, &mut materials);
+                if material.0 != handle {
+                    material.0 = handle;
+                }
+            }
+            continue;
+        }
+
+        let group = match mode {
+            ColorMode::Category => None,
+            ColorMode::Operator => operator.as_ref().map(|operator| (operator.name.as_str(), operator.is_added())),
+            ColorMode::Owner => owner.as_ref().map(|owner| (owner.code.as_str(), owner.is_added())),
+        };
+        if !refresh_all && !material.is_added() && !group.is_some_and(|(_, added)| added) {
+            continue;
+        }
+        // Objects without group metadata keep their category color
+        let handle = match group {
+            Some((name, _)) => group_materials.get_or_add(name, group_color(name), &mut materials),
+            None => object_materials.get(ObjectCategory::classify(is_stress_test, is_rocket_body, is_debris)),
+        };
+        if material.0 != handle {
+            material.0 = handle;
+        }
+    }
+}
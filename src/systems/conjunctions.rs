@@ -0,0 +1,364 @@
+// Short-term conjunction forecast shared by slow motion and director mode
+
+use bevy::math::{DMat3, DVec3};
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use std::collections::HashMap;
+use crate::systems::collision::SpatialOctree;
+use crate::systems::protection::{POSITION_SIGMA_KM, RISK_ALERT_PROBABILITY};
+use crate::systems::search::SearchCommand;
+use crate::systems::selection::Selection;
+use crate::systems::watchlist::Watched;
+use crate::utils::{foster_probability, next_approach_within, unix_to_iso8601};
+
+/// Default miss distance for a close-approach query (km)
+pub const DEFAULT_APPROACH_DISTANCE: f64 = 5.0;
+/// Default look-ahead for a close-approach query (hours)
+pub const DEFAULT_APPROACH_HOURS: f64 = 24.0;
+/// Longest look-ahead accepted for a close-approach query (hours)
+pub const MAX_APPROACH_HOURS: f64 = 720.0;
+
+/// A predicted close approach between two objects
+#[derive(Clone, Copy, Debug)]
+pub struct PredictedConjunction {
+    /// Satellite whose screening found the approach
+    pub primary: Entity,
+    pub secondary: Entity,
+    /// Simulation time of closest approach (seconds)
+    pub tca: f64,
+    /// Predicted miss distance (km)
+    pub miss_distance: f64,
+    /// Relative speed at closest approach (km/s)
+    pub relative_speed: f64,
+    /// Probability of collision from the objects' position covariances
+    pub probability: f64,
+    /// Whether either object is watched
+    pub watched: bool,
+}
+
+/// Resource holding upcoming conjunctions, soonest first
+#[derive(Resource)]
+pub struct ConjunctionForecast {
+    /// Look-ahead window for predictions (simulated seconds)
+    pub window: f64,
+    /// Predicted miss distance below which an approach counts as a conjunction (km)
+    pub miss_distance: f64,
+    /// Radius searched around each satellite for approaching objects (km)
+    pub search_radius: f64,
+    /// Whether any consumer needs the forecast this frame
+    pub requested: bool,
+    pub upcoming: Vec<PredictedConjunction>,
+}
+
+impl Default for ConjunctionForecast {
+    fn default() -> Self {
+        Self {
+            window: 300.0,
+            miss_distance: 5.0,
+            search_radius: 500.0,
+            requested: false,
+            upcoming: Vec::new(),
+        }
+    }
+}
+
+impl ConjunctionForecast {
+    /// Soonest predicted conjunction
+    pub fn next(&self) -> Option<&PredictedConjunction> {
+        self.upcoming.first()
+    }
+
+    /// Closest predicted conjunction with TCA no later than `before`, watched objects first
+    pub fn highest_risk_before(&self, before: f64) -> Option<&PredictedConjunction> {
+        self.upcoming
+            .iter()
+            .filter(|c| c.tca <= before)
+            .min_by(|a, b| b.watched.cmp(&a.watched).then(a.miss_distance.total_cmp(&b.miss_distance)))
+    }
+}
+
+/// Event emitted once per encounter whose probability of collision reaches the screening threshold
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ConjunctionEvent {
+    pub primary: Entity,
+    pub secondary: Entity,
+    /// Simulation time of closest approach (seconds)
+    pub tca: f64,
+    /// Predicted miss distance (km)
+    pub miss_distance: f64,
+    pub probability: f64,
+}
+
+/// Resource controlling probability-of-collision screening of the forecast
+#[derive(Resource)]
+pub struct ConjunctionScreening {
+    pub enabled: bool,
+    /// Probability of collision at or above which a conjunction is reported
+    pub threshold: f64,
+    /// TCA of each pair already reported, so an encounter is reported once
+    pub reported: HashMap<(Entity, Entity), f64>,
+}
+
+impl Default for ConjunctionScreening {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: RISK_ALERT_PROBABILITY,
+            reported: HashMap::new(),
+        }
+    }
+}
+
+/// Time of closest approach (s from now) and miss distance (km) assuming straight-line relative motion
+pub fn linear_closest_approach(relative_position: DVec3, relative_velocity: DVec3) -> (f64, f64) {
+    let speed_squared = relative_velocity.length_squared();
+    if speed_squared <= 0.0 {
+        return (0.0, relative_position.length());
+    }
+    let tca = -relative_position.dot(relative_velocity) / speed_squared;
+    let miss = (relative_position + relative_velocity * tca).length();
+    (tca, miss)
+}
+
+/// Position uncertainty screening assumes for an object, falling back to an even split of the default screening sigma
+pub fn screening_covariance(covariance: Option<&PositionCovariance>) -> PositionCovariance {
+    covariance
+        .copied()
+        .unwrap_or_else(|| PositionCovariance::isotropic(POSITION_SIGMA_KM / 2f64.sqrt()))
+}
+
+/// Inertial position covariance (km²) of an object as screening assumes it
+fn object_covariance(state: &OrbitalState, covariance: Option<&PositionCovariance>) -> DMat3 {
+    screening_covariance(covariance).inertial(state.position, state.velocity)
+}
+
+/// System to predict conjunctions around each satellite within the forecast window
+#[allow(clippy::type_complexity)]
+pub fn conjunction_forecast_system(
+    mut forecast: ResMut<ConjunctionForecast>,
+    sim_time: Res<SimulationTime>,
+    octree: Res<SpatialOctree>,
+    exclusions: Res<CollisionExclusions>,
+    satellite_query: Query<(Entity, &OrbitalState, Has<Watched>, Option<&PositionCovariance>, Option<&PhysicsObject>, Option<&AltitudeBand>), With<Satellite>>,
+    orbital_query: Query<(&OrbitalState, Has<Watched>, Option<&PositionCovariance>, Option<&PhysicsObject>, Option<&AltitudeBand>)>,
+    constellations: Query<&Constellation>,
+) {
+    // Consumers re-request every frame they need a forecast
+    if !std::mem::take(&mut forecast.requested) {
+        forecast.upcoming.clear();
+        return;
+    }
+    if sim_time.paused {
+        return;
+    }
+
+    let mut upcoming = Vec::new();
+    let mut nearby_objects = Vec::new();
+
+    for (entity, orbital_state, watched, covariance, physics, band) in satellite_query.iter() {
+        nearby_objects.clear();
+        octree.root.query_sphere(orbital_state.position, forecast.search_radius, &mut nearby_objects);
+
+        for &other in &nearby_objects {
+            if other == entity {
+                continue;
+            }
+            let Ok((other_state, other_watched, other_covariance, other_physics, other_band)) = orbital_query.get(other) else {
+                continue;
+            };
+            // Objects whose altitude bands stay further apart than a conjunction's miss distance never conjunct
+            if band.zip(other_band).is_some_and(|(band, other_band)| band.separated_from(other_band, forecast.miss_distance)) {
+                continue;
+            }
+            // Co-orbiting members of a constellation plane stay phased apart
+            if let (Ok(constellation), Ok(other_constellation)) = (constellations.get(entity), constellations.get(other)) {
+                if exclusions.excludes(&constellation.name, &other_constellation.name,
+                                       (orbital_state.position, orbital_state.velocity), (other_state.position, other_state.velocity)) {
+                    continue;
+                }
+            }
+            // Report each satellite pair once
+            if satellite_query.contains(other) && other.index() < entity.index() {
+                continue;
+            }
+
+            let relative_position = other_state.position - orbital_state.position;
+            let relative_velocity = other_state.velocity - orbital_state.velocity;
+            let (tca, miss) = linear_closest_approach(relative_position, relative_velocity);
+            if tca > 0.0 && tca <= forecast.window && miss <= forecast.miss_distance {
+                let hard_body = [physics, other_physics].iter()
+                    .map(|physics| physics.map_or(0.0, |p| p.collision_radius / 1000.0))
+                    .sum();
+                let covariance = object_covariance(orbital_state, covariance) + object_covariance(other_state, other_covariance);
+                let probability = foster_probability(
+                    relative_position + relative_velocity * tca,
+                    relative_velocity,
+                    covariance,
+                    hard_body,
+                );
+                upcoming.push(PredictedConjunction {
+                    primary: entity,
+                    secondary: other,
+                    tca: sim_time.current + tca,
+                    miss_distance: miss,
+                    relative_speed: relative_velocity.length(),
+                    probability,
+                    watched: watched || other_watched,
+                });
+            }
+        }
+    }
+
+    upcoming.sort_by(|a, b| a.tca.total_cmp(&b.tca));
+    forecast.upcoming = upcoming;
+}
+
+/// System to raise a `ConjunctionEvent` for each forecast conjunction at or above the screening threshold
+pub fn conjunction_screening_system(
+    mut screening: ResMut<ConjunctionScreening>,
+    mut forecast: ResMut<ConjunctionForecast>,
+    sim_time: Res<SimulationTime>,
+    satellites: Query<&Satellite>,
+    mut events: EventWriter<ConjunctionEvent>,
+) {
+    if !screening.enabled {
+        return;
+    }
+    forecast.requested = true;
+
+    // Forget encounters that have passed so later approaches of the same pair are screened again
+    let window = forecast.window;
+    screening.reported.retain(|_, tca| *tca > sim_time.current - window);
+
+    let name = |entity: Entity| satellites.get(entity).map(|s| s.name.trim().to_string()).unwrap_or_else(|_| "Debris".to_string());
+    for conjunction in &forecast.upcoming {
+        let pair = (conjunction.primary, conjunction.secondary);
+        if conjunction.probability < screening.threshold || screening.reported.contains_key(&pair) {
+            continue;
+        }
+        screening.reported.insert(pair, conjunction.tca);
+        warn!(
+            "Conjunction {} / {} at {}Z (in {:.0}s): miss {:.3} km, Pc {:.1e}",
+            name(conjunction.primary), name(conjunction.secondary),
+            unix_to_iso8601(sim_time.unix_at(conjunction.tca)), conjunction.tca - sim_time.current,
+            conjunction.miss_distance, conjunction.probability
+        );
+        events.write(ConjunctionEvent {
+            primary: conjunction.primary,
+            secondary: conjunction.secondary,
+            tca: conjunction.tca,
+            miss_distance: conjunction.miss_distance,
+            probability: conjunction.probability,
+        });
+    }
+}
+
+/// Command requesting the next approach closer than `distance` (km) between the two selected objects
+#[derive(Clone, Copy, Debug)]
+pub struct ApproachQuery {
+    pub distance: f64,
+    /// Look-ahead (simulated seconds)
+    pub horizon: f64,
+}
+
+impl ApproachQuery {
+    /// Parse `approach [KM] [HOURS]`; returns `None` when the text is not an approach query
+    pub fn parse(text: &str) -> Option<Result<Self, String>> {
+        let mut words = text.split_whitespace();
+        if !words.next()?.eq_ignore_ascii_case("approach") {
+            return None;
+        }
+        let mut number = |default: f64, what: &str| match words.next() {
+            Some(word) => word.parse::<f64>().ok().filter(|v| *v > 0.0).ok_or_else(|| format!("invalid {} {}", what, word)),
+            None => Ok(default),
+        };
+        let distance = match number(DEFAULT_APPROACH_DISTANCE, "distance") {
+            Ok(distance) => distance,
+            Err(e) => return Some(Err(e)),
+        };
+        let hours = match number(DEFAULT_APPROACH_HOURS, "look-ahead") {
+            Ok(hours) if hours <= MAX_APPROACH_HOURS => hours,
+            Ok(hours) => return Some(Err(format!("look-ahead {} h exceeds {} h", hours, MAX_APPROACH_HOURS))),
+            Err(e) => return Some(Err(e)),
+        };
+        Some(Ok(Self { distance, horizon: hours * 3600.0 }))
+    }
+}
+
+/// Result of the latest close-approach query
+#[derive(Clone, Copy, Debug)]
+pub struct ApproachFound {
+    /// Simulation time of closest approach (seconds)
+    pub tca: f64,
+    pub miss_distance: f64,
+    /// Relative speed at closest approach (km/s)
+    pub relative_speed: f64,
+}
+
+/// Resource holding the answer to the latest close-approach query for the selected pair
+#[derive(Resource, Default)]
+pub struct ApproachReport {
+    pub pair: Option<(Entity, Entity)>,
+    pub query: Option<ApproachQuery>,
+    /// `None` when no approach under the distance was found
+    pub found: Option<ApproachFound>,
+}
+
+impl ApproachReport {
+    /// Whether the report belongs to the given pair
+    pub fn is_for(&self, primary: Entity, secondary: Entity) -> bool {
+        self.pair == Some((primary, secondary))
+    }
+}
+
+/// System to search forward for the next approach of the selected pair under the queried distance
+pub fn approach_query_system(
+    mut queries: EventReader<SearchCommand>,
+    selection: Res<Selection>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    objects: Query<(&OrbitalState, Option<&Satellite>)>,
+    mut report: ResMut<ApproachReport>,
+) {
+    let query = queries.read().filter_map(|command| match command {
+        SearchCommand::Approach(query) => Some(*query),
+        _ => None,
+    }).last();
+    let Some(query) = query else {
+        return;
+    };
+    let (Some(primary), Some(secondary)) = (selection.selected, selection.secondary) else {
+        info!("Approach query needs two objects: select one and Shift+click another");
+        return;
+    };
+    let (Ok((a, a_satellite)), Ok((b, b_satellite))) = (objects.get(primary), objects.get(secondary)) else {
+        return;
+    };
+
+    let name = |satellite: Option<&Satellite>| satellite.map(|s| s.name.trim().to_string()).unwrap_or_else(|| "Debris".to_string());
+    let mu_km = constants.gravitational_parameter / 1e9;
+    let found = next_approach_within((a.position, a.velocity), (b.position, b.velocity), query.distance, query.horizon, mu_km)
+        .map(|(tca, miss_distance, relative_speed)| ApproachFound {
+            tca: sim_time.current + tca,
+            miss_distance,
+            relative_speed,
+        });
+    match found {
+        Some(approach) => info!(
+            "{} / {}: next approach under {:.1} km at {}Z (in {:.0}s), miss {:.3} km, relative speed {:.3} km/s",
+            name(a_satellite), name(b_satellite), query.distance,
+            unix_to_iso8601(sim_time.unix_at(approach.tca)), approach.tca - sim_time.current,
+            approach.miss_distance, approach.relative_speed
+        ),
+        None => info!(
+            "{} / {}: no approach under {:.1} km in the next {:.1} h",
+            name(a_satellite), name(b_satellite), query.distance, query.horizon / 3600.0
+        ),
+    }
+    *report = ApproachReport {
+        pair: Some((primary, secondary)),
+        query: Some(query),
+        found,
+    };
+}
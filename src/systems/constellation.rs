@@ -0,0 +1,280 @@
+// Constellation grouping and bulk operations on groups
+
+use bevy::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+use crate::systems::search::SearchCommand;
+use crate::systems::selection::{deorbit_velocity, DEORBIT_PERIGEE_MARGIN};
+use crate::systems::stress_test::StressTestObject;
+use crate::systems::traffic::{OperatorRules, TrafficRules};
+
+/// Resource holding per-constellation display state
+#[derive(Resource, Default)]
+pub struct ConstellationGroups {
+    pub hidden: HashSet<String>,
+    /// Color overrides keyed by constellation name
+    pub colors: HashMap<String, Color>,
+}
+
+/// Bulk action applied to every member of a group
+#[derive(Clone, Debug)]
+pub enum GroupAction {
+    Hide,
+    Show,
+    /// Color override; `None` restores the normal color
+    Color(Option<Color>),
+    Deorbit,
+    /// Conjunction-response rules; `None` restores the regime defaults
+    Policy(Option<OperatorRules>),
+    Stats,
+}
+
+/// Command requesting a bulk action on the constellations whose names start with `group` (all when empty)
+#[derive(Clone, Debug)]
+pub struct ConstellationCommand {
+    pub group: String,
+    pub action: GroupAction,
+}
+
+impl ConstellationCommand {
+    /// Parse a command such as `hide Starlink`, `color GPS #ff8800`, `policy OneWeb none`, `deorbit Starlink Shell 2`
+    /// or `stats`; returns `None` when the text does not start with a group verb
+    pub fn parse(text: &str) -> Option<Result<Self, String>> {
+        let text = text.trim();
+        let (verb, rest) = text.split_once(' ').unwrap_or((text, ""));
+        let rest = rest.trim();
+        let verb = verb.to_lowercase();
+        if !matches!(verb.as_str(), "hide" | "show" | "color" | "deorbit" | "policy" | "stats") {
+            return None;
+        }
+
+        // Color and policy take their argument as the last word
+        let (group, argument) = match verb.as_str() {
+            "color" | "policy" => match rest.rsplit_once(' ') {
+                Some((group, argument)) => (group.trim(), argument),
+                None => return Some(Err(format!("usage: {} <group> <value>", verb))),
+            },
+            _ => (rest, ""),
+        };
+        if group.is_empty() && verb != "stats" {
+            return Some(Err(format!("usage: {} <group>", verb)));
+        }
+
+        let action = match verb.as_str() {
+            "hide" => GroupAction::Hide,
+            "show" => GroupAction::Show,
+            "deorbit" => GroupAction::Deorbit,
+            "stats" => GroupAction::Stats,
+            "color" if argument.eq_ignore_ascii_case("default") => GroupAction::Color(None),
+            "color" => match Srgba::hex(argument) {
+                Ok(color) => GroupAction::Color(Some(color.into())),
+                Err(_) => return Some(Err(format!("invalid color {} (expected #rrggbb or default)", argument))),
+            },
+            _ => match policy_rules(argument) {
+                Ok(rules) => GroupAction::Policy(rules),
+                Err(e) => return Some(Err(e)),
+            },
+        };
+        Some(Ok(Self { group: group.to_string(), action }))
+    }
+
+    /// Whether a constellation belongs to the addressed group
+    pub fn addresses(&self, constellation: &str) -> bool {
+        constellation.to_lowercase().starts_with(&self.group.to_lowercase())
+    }
+}
+
+/// Conjunction-response rules for a named maneuver policy
+fn policy_rules(name: &str) -> Result<Option<OperatorRules>, String> {
+    match name.to_lowercase().as_str() {
+        "default" => Ok(None),
+        "none" => Ok(Some(OperatorRules {
+            maneuvers: false,
+            ..default()
+        })),
+        // Automated screening as run by large constellation operators
+        "responsive" => Ok(Some(OperatorRules {
+            screening_distance: 10.0,
+            coordination_delay: 600.0,
+            ..default()
+        })),
+        _ => Err(format!("unknown policy {} (expected none, default or responsive)", name)),
+    }
+}
+
+/// Group-level statistics of a constellation
+#[derive(Clone, Debug, Default)]
+pub struct GroupStatistics {
+    pub members: usize,
+    pub active: usize,
+    pub derelict: usize,
+    pub min_altitude: f64,
+    pub max_altitude: f64,
+    pub mean_altitude: f64,
+    pub mean_inclination: f64,
+    pub total_mass: f64,
+}
+
+/// Orbital inclination in degrees of a state vector (z is the polar axis)
+pub fn inclination_deg(state: &OrbitalState) -> f64 {
+    let h = state.position.cross(state.velocity);
+    (h.z / h.length().max(f64::EPSILON)).clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+/// Statistics of every constellation, sorted by name
+pub fn group_statistics<'a>(
+    members: impl Iterator<Item = (&'a Constellation, &'a OrbitalState, &'a Satellite, bool)>,
+    earth_radius: f64,
+) -> BTreeMap<String, GroupStatistics> {
+    let mut groups: BTreeMap<String, GroupStatistics> = BTreeMap::new();
+    for (constellation, state, satellite, is_derelict) in members {
+        let altitude = state.position.length() - earth_radius;
+        let stats = groups.entry(constellation.name.clone()).or_insert(GroupStatistics {
+            min_altitude: f64::MAX,
+            max_altitude: f64::MIN,
+            ..default()
+        });
+        stats.members += 1;
+        stats.active += satellite.active as usize;
+        stats.derelict += is_derelict as usize;
+        stats.min_altitude = stats.min_altitude.min(altitude);
+        stats.max_altitude = stats.max_altitude.max(altitude);
+        // Running sums, divided into means below
+        stats.mean_altitude += altitude;
+        stats.mean_inclination += inclination_deg(state);
+        stats.total_mass += state.mass;
+    }
+    for stats in groups.values_mut() {
+        stats.mean_altitude /= stats.members as f64;
+        stats.mean_inclination /= stats.members as f64;
+    }
+    groups
+}
+
+/// System to tag newly cataloged satellites with their constellation
+#[allow(clippy::type_complexity)]
+pub fn constellation_tagging_system(
+    mut commands: Commands,
+    satellites: Query<(Entity, &Satellite, &OrbitalState), (Added<Satellite>, Without<StressTestObject>)>,
+) {
+    for (entity, satellite, state) in satellites.iter() {
+        if let Some(constellation) = Constellation::classify(&satellite.name, inclination_deg(state)) {
+            commands.entity(entity).insert(constellation);
+        }
+    }
+}
+
+/// System to log group statistics for all constellations with 'Z'
+pub fn constellation_stats_control_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut commands: EventWriter<SearchCommand>,
+) {
+    if keyboard.just_pressed(KeyCode::KeyZ) {
+        commands.write(SearchCommand::Constellation(ConstellationCommand {
+            group: String::new(),
+            action: GroupAction::Stats,
+        }));
+    }
+}
+
+/// System to apply bulk constellation commands
+#[allow(clippy::type_complexity)]
+pub fn constellation_command_system(
+    mut commands: EventReader<SearchCommand>,
+    constants: Res<Constants>,
+    mut groups: ResMut<ConstellationGroups>,
+    mut rules: ResMut<TrafficRules>,
+    mut members: Query<(&Constellation, &mut OrbitalState, &Satellite, Option<&Health>, Option<&OptimizedPhysics>, Has<Derelict>)>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+) {
+    for command in commands.read() {
+        let SearchCommand::Constellation(command) = command else {
+            continue;
+        };
+        let names: HashSet<String> = members.iter()
+            .map(|(constellation, ..)| constellation.name.clone())
+            .filter(|name| command.addresses(name))
+            .collect();
+        if names.is_empty() {
+            info!("No constellation matches '{}'", command.group);
+            continue;
+        }
+        let mut sorted_names: Vec<_> = names.iter().cloned().collect();
+        sorted_names.sort();
+        let label = sorted_names.join(", ");
+
+        match &command.action {
+            GroupAction::Hide => {
+                groups.hidden.extend(names);
+                info!("Hidden: {}", label);
+            }
+            GroupAction::Show => {
+                groups.hidden.retain(|name| !names.contains(name));
+                info!("Shown: {}", label);
+            }
+            GroupAction::Color(color) => {
+                for name in names {
+                    match color {
+                        Some(color) => groups.colors.insert(name, *color),
+                        None => groups.colors.remove(&name),
+                    };
+                }
+                info!("Recolored: {}", label);
+            }
+            GroupAction::Policy(policy) => {
+                for name in names {
+                    match policy {
+                        Some(policy) => rules.constellation_rules.insert(name, policy.clone()),
+                        None => rules.constellation_rules.remove(&name),
+                    };
+                }
+                match policy {
+                    Some(policy) => info!("{}: maneuvers {}, screening {:.1}km, delay {:.0}s", label,
+                                          if policy.maneuvers { "on" } else { "off" },
+                                          policy.screening_distance, policy.coordination_delay),
+                    None => info!("{}: regime default maneuver policy", label),
+                }
+            }
+            GroupAction::Deorbit => {
+                let mu_km = constants.gravitational_parameter / 1e9;
+                let perigee_radius = constants.earth_radius + constants.reentry_altitude - DEORBIT_PERIGEE_MARGIN;
+                let (mut burned, mut skipped, mut total_delta_v) = (0, 0, 0.0);
+                for (constellation, mut state, satellite, health, optimized, _) in members.iter_mut() {
+                    if !names.contains(&constellation.name) {
+                        continue;
+                    }
+                    // Only satellites that can still fire their engines take part
+                    if !satellite.active || !health.is_none_or(Health::has_propulsion) {
+                        skipped += 1;
+                        continue;
+                    }
+                    let delta_v = deorbit_velocity(state.position, state.velocity, perigee_radius, mu_km) - state.velocity;
+                    total_delta_v += delta_v.length() * 1000.0;
+                    state.velocity += delta_v;
+                    if let Some(optimized) = optimized {
+                        optimized_data.change_velocity(optimized.index, delta_v);
+                    }
+                    burned += 1;
+                }
+                info!("{}: {} satellites performed deorbit burns ({:.0} m/s total), {} without propulsion",
+                      label, burned, total_delta_v, skipped);
+            }
+            GroupAction::Stats => {
+                let group_members = members.iter().filter_map(|(constellation, state, satellite, _, _, derelict)| {
+                    names.contains(&constellation.name).then_some((constellation, state, satellite, derelict))
+                });
+                for (name, stats) in group_statistics(group_members, constants.earth_radius) {
+                    info!("{}: {} satellites ({} active, {} derelict), altitude {:.0}-{:.0} km (mean {:.0}), \
+                           mean inclination {:.1}°, total mass {:.0} kg{}{}",
+                          name, stats.members, stats.active, stats.derelict,
+                          stats.min_altitude, stats.max_altitude, stats.mean_altitude,
+                          stats.mean_inclination, stats.total_mass,
+                          if groups.hidden.contains(&name) { ", hidden" } else { "" },
+                          if rules.constellation_rules.contains_key(&name) { ", custom policy" } else { "" });
+                }
+            }
+        }
+    }
+}
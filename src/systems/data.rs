@@ -1,28 +1,226 @@
 use bevy::prelude::*;
 use reqwest;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Mutex;
 use crate::utils::*;
 use crate::components::*;
-use crate::utils::sgp4_wrapper::*;
+use crate::resources::{wall_clock_unix_seconds, Constants, ObjectCap, Settings, SimulationTime};
+use crate::systems::live::LiveMode;
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+use crate::systems::scenario::{spawn_scenario, Scenario, TEST_DATASET_SCENARIO};
+use crate::systems::search::SearchCommand;
+
+/// Celestrak group of the operational satellites
+pub const ACTIVE_GROUP: &str = "active";
+/// Celestrak groups of the full catalog: the active satellites plus the tracked fragments of the major breakups
+pub const FULL_CATALOG_GROUPS: [&str; 5] = [
+    ACTIVE_GROUP,
+    "cosmos-1408-debris",
+    "fengyun-1c-debris",
+    "iridium-33-debris",
+    "cosmos-2251-debris",
+];
+/// Celestrak groups offered in the group checklist
+pub const CELESTRAK_GROUPS: [&str; 12] = [
+    ACTIVE_GROUP,
+    "stations",
+    "starlink",
+    "oneweb",
+    "gps-ops",
+    "galileo",
+    "iridium-NEXT",
+    "geo",
+    "cosmos-1408-debris",
+    "fengyun-1c-debris",
+    "iridium-33-debris",
+    "cosmos-2251-debris",
+];
+/// Cataloged objects spawned per frame, so a large catalog streams in without stalling the app
+pub const OBJECTS_SPAWNED_PER_FRAME: usize = 500;
 
 /// System to fetch TLE data from Celestrak
-pub async fn fetch_tle_data_system() -> Result<Vec<TleRecord>, Box<dyn std::error::Error>> {
+pub async fn fetch_tle_data_system(groups: Vec<String>, format: GpFormat) -> Result<Vec<TleRecord>, Box<dyn std::error::Error>> {
     let client = reqwest::Client::new();
+    let mut records = Vec::new();
     
-    // Fetch active satellites from Celestrak
-    let response = client
-        .get("https://celestrak.org/NORAD/elements/gp.php?GROUP=active&FORMAT=tle")
-        .send()
-        .await?;
-    
-    let tle_text = response.text().await?;
-    let records = parse_tle_data(&tle_text)?;
+    // Fetch each group from Celestrak
+    for group in &groups {
+        let response = client
+            .get(format!("https://celestrak.org/NORAD/elements/gp.php?GROUP={}&FORMAT={}", group, format.query_value()))
+            .send()
+            .await?;
+        
+        let tle_text = response.text().await?;
+        let group_records = parse_gp_data(&tle_text, format)?;
+        info!("Fetched {} TLE records of group {} from Celestrak", group_records.len(), group);
+        records.extend(group_records);
+    }
+    let records = merge_records(records);
     
     info!("Successfully fetched {} TLE records from Celestrak", records.len());
     Ok(records)
 }
 
+/// Drop repeated NORAD IDs, keeping the first record of each object
+pub fn merge_records(records: Vec<TleRecord>) -> Vec<TleRecord> {
+    let mut seen = HashSet::new();
+    records.into_iter().filter(|record| seen.insert(record.norad_id)).collect()
+}
+
+/// Element sets fetched on a background thread
+type TleDownload = Mutex<Receiver<Result<Vec<TleRecord>, String>>>;
+
+/// Resource describing where element sets are loaded from
+#[derive(Resource)]
+pub struct TleSource {
+    /// Encoding requested from Celestrak
+    pub format: GpFormat,
+    /// Celestrak groups fetched
+    pub groups: Vec<String>,
+    /// Element set files to load instead of the TLE directory or Celestrak
+    pub files: Vec<PathBuf>,
+    /// Objects of the loaded catalog that are spawned
+    pub cap: ObjectCap,
+    /// Whether the catalog is loaded at all; scenarios may bring only their own objects
+    pub catalog: bool,
+    /// Whether the group checklist is shown
+    pub checklist: bool,
+    /// Groups being fetched in the background, at startup or after being checked
+    pub in_flight: Vec<(String, TleDownload)>,
+    /// Startup groups still in flight; their objects are spawned up to the cap
+    pub startup_pending: HashSet<String>,
+}
+
+impl Default for TleSource {
+    fn default() -> Self {
+        Self {
+            format: GpFormat::default(),
+            groups: vec![ACTIVE_GROUP.to_string()],
+            files: Vec::new(),
+            cap: ObjectCap::default(),
+            catalog: true,
+            checklist: false,
+            in_flight: Vec::new(),
+            startup_pending: HashSet::new(),
+        }
+    }
+}
+
+/// Celestrak group by name, spelled as Celestrak lists it when it is one of `CELESTRAK_GROUPS`
+pub fn celestrak_group(name: &str) -> String {
+    CELESTRAK_GROUPS.iter()
+        .find(|group| group.eq_ignore_ascii_case(name))
+        .map_or_else(|| name.to_string(), |group| group.to_string())
+}
+
+/// Resource re-fetching the element sets every few wall-clock hours while the simulation runs
+#[derive(Resource, Default)]
+pub struct TleRefresh {
+    /// Wall-clock seconds between refreshes; none fetches only at startup
+    pub interval: Option<f64>,
+    /// Wall-clock time of the last refresh (Unix seconds)
+    pub last_refresh: f64,
+    /// Element sets replaced by newer ones so far
+    pub updated: usize,
+    in_flight: Option<TleDownload>,
+}
+
+impl TleRefresh {
+    pub fn new(interval: Option<f64>) -> Self {
+        Self { interval, last_refresh: wall_clock_unix_seconds(), ..default() }
+    }
+}
+
+/// Resource holding cataloged objects waiting to be spawned, a frame's worth at a time
+#[derive(Resource, Default)]
+pub struct TleSpawnQueue {
+    pub pending: VecDeque<TleRecord>,
+    /// NORAD IDs spawned or queued so far
+    pub queued: HashSet<u32>,
+}
+
+impl TleSpawnQueue {
+    /// Queue records whose objects are not spawned or queued yet, up to `cap` objects in all
+    pub fn enqueue<'a>(&mut self, records: impl IntoIterator<Item = &'a TleRecord>, cap: ObjectCap) -> usize {
+        let mut added = 0;
+        for record in records {
+            if self.queued.len() >= cap.limit() {
+                break;
+            }
+            if self.queued.insert(record.norad_id) {
+                self.pending.push_back(record.clone());
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// Drop queued records beyond `cap`; objects already spawned stay
+    pub fn limit(&mut self, cap: ObjectCap) {
+        while self.queued.len() > cap.limit() {
+            let Some(record) = self.pending.pop_back() else {
+                break;
+            };
+            self.queued.remove(&record.norad_id);
+        }
+    }
+}
+
+/// Command changing how many cataloged objects are spawned
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ObjectCapCommand(pub ObjectCap);
+
+impl ObjectCapCommand {
+    /// Parse `cap N` or `cap all`; returns `None` when the text is not a cap command
+    pub fn parse(text: &str) -> Option<Result<Self, String>> {
+        let mut words = text.split_whitespace();
+        if !words.next()?.eq_ignore_ascii_case("cap") {
+            return None;
+        }
+        Some(match words.next() {
+            Some(value) => value.parse().map(ObjectCapCommand).map_err(|_| format!("invalid object cap {}", value)),
+            None => Err("expected an object count or all".to_string()),
+        })
+    }
+}
+
+/// Command changing which Celestrak groups are fetched
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GroupCommand {
+    /// Show or hide the group checklist
+    Checklist,
+    /// Check a group, fetching it now and at every start, or uncheck it
+    Toggle(String),
+}
+
+impl GroupCommand {
+    /// Parse `groups`, or `fetch GROUP` with a group name or its number in the checklist;
+    /// returns `None` when the text is not a group command
+    pub fn parse(text: &str) -> Option<Result<Self, String>> {
+        let mut words = text.split_whitespace();
+        let verb = words.next()?;
+        if verb.eq_ignore_ascii_case("groups") {
+            return Some(Ok(GroupCommand::Checklist));
+        }
+        if !verb.eq_ignore_ascii_case("fetch") {
+            return None;
+        }
+        Some(match words.next() {
+            Some(word) => match word.parse::<usize>() {
+                Ok(number) => number.checked_sub(1)
+                    .and_then(|index| CELESTRAK_GROUPS.get(index))
+                    .map(|group| GroupCommand::Toggle(group.to_string()))
+                    .ok_or_else(|| format!("no group {} in the checklist", number)),
+                Err(_) => Ok(GroupCommand::Toggle(celestrak_group(word))),
+            },
+            None => Err("expected a Celestrak group".to_string()),
+        })
+    }
+}
+
 /// Resource to hold fetched TLE data
 #[derive(Resource)]
 pub struct TleDataCache {
@@ -42,10 +240,11 @@ impl Default for TleDataCache {
 /// System to initialize TLE data on startup by fetching from Celestrak
 pub fn initialize_tle_data_system(
     mut commands: Commands,
-    mut tle_cache: ResMut<TleDataCache>,
+    tle_cache: Res<TleDataCache>,
+    source: Res<TleSource>,
 ) {
     // Only fetch if we don't have data yet
-    if tle_cache.records.is_empty() {
+    if source.catalog && tle_cache.records.is_empty() {
         info!("Initializing satellite data source...");
         commands.spawn_empty().insert(TleFetchTask);
     }
@@ -55,10 +254,17 @@ pub fn initialize_tle_data_system(
 #[derive(Component)]
 pub struct TleFetchTask;
 
-/// System to handle TLE data fetching from Celestrak
+/// System to load TLE data, or to start fetching it from Celestrak in the background
+///
+/// Each group is fetched on its own thread and merged by `group_fetch_system` as it arrives, so a slow or failing
+/// group neither stalls the app nor discards the others.
 pub fn process_tle_fetch_system(
     mut commands: Commands,
     mut tle_cache: ResMut<TleDataCache>,
+    mut spawn_queue: ResMut<TleSpawnQueue>,
+    mut source: ResMut<TleSource>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
     query: Query<Entity, With<TleFetchTask>>,
 ) {
     for entity in query.iter() {
@@ -67,71 +273,108 @@ pub fn process_tle_fetch_system(
         
         debug!("Attempting to load TLE data (local files first, then Celestrak)...");
         
-        // Try to load TLE data (local first, then network), fallback to test data if it fails
-        match try_fetch_live_tle_data() {
-            Ok(records) => {
-                // Take the first 100 satellites for enhanced simulation
-                let limited_records: Vec<_> = records.into_iter().take(100).collect();
-                info!("Successfully loaded {} satellite records", limited_records.len());
+        // Try to load TLE data from disk, fetch it from the network otherwise, and fall back to test data if it fails
+        match try_load_tle_files(&source) {
+            Ok(Some(records)) => {
+                info!("Successfully loaded {} satellite records", records.len());
                 
-                // Store in cache
-                tle_cache.records = limited_records.clone();
-                tle_cache.last_updated = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs_f64();
+                // Store the whole catalog in the cache
+                tle_cache.records = merge_records(records);
+                tle_cache.last_updated = wall_clock_unix_seconds();
                 
-                // Spawn satellites from TLE data
-                spawn_satellites_from_records(&mut commands, &limited_records);
+                // Spawn satellites from TLE data over the following frames, up to the cap
+                let queued = spawn_queue.enqueue(&tle_cache.records, source.cap);
+                info!("Spawning {} of {} cataloged objects (cap: {})", queued, tle_cache.records.len(), source.cap);
+            }
+            Ok(None) => {
+                info!("Fetching {} from Celestrak in the background", source.groups.join(", "));
+                for group in source.groups.clone() {
+                    let rx = spawn_group_fetch(vec![group.clone()], source.format);
+                    source.in_flight.push((group.clone(), Mutex::new(rx)));
+                    source.startup_pending.insert(group);
+                }
             }
             Err(e) => {
                 warn!("Failed to load TLE data: {} - Using test dataset instead", e);
-                
-                // Use expanded test dataset with 100 realistic satellites
-                create_extended_test_dataset(&mut commands, &mut tle_cache);
+                spawn_test_dataset(&mut commands, &sim_time, &constants, &mut tle_cache, &mut spawn_queue);
             }
         }
     }
 }
 
-/// Try to load TLE data from local files first, then from Celestrak if no local files exist
-fn try_fetch_live_tle_data() -> Result<Vec<TleRecord>, String> {
-    // First, try to load from local files
-    match try_load_local_tle_data() {
-        Ok(records) => {
-            info!("Successfully loaded {} TLE records from local files", records.len());
-            return Ok(records);
+/// Spawn the built-in scenario of 100 well-known satellites
+fn spawn_test_dataset(
+    commands: &mut Commands,
+    sim_time: &SimulationTime,
+    constants: &Constants,
+    tle_cache: &mut TleDataCache,
+    spawn_queue: &mut TleSpawnQueue,
+) {
+    match Scenario::parse(TEST_DATASET_SCENARIO) {
+        Ok(scenario) => {
+            spawn_scenario(commands, &scenario, sim_time, constants, tle_cache, spawn_queue);
         }
-        Err(e) => {
-            info!("No local TLE files found or failed to load: {} - Falling back to network", e);
+        Err(e) => error!("Invalid built-in test dataset: {}", e),
+    }
+}
+
+/// Try to load TLE data from local files, returning none when it has to be fetched from Celestrak
+///
+/// Files given on the command line are the only source when present, so a run with them stays offline. The local
+/// files stand in for the active satellites, so other groups are always fetched.
+fn try_load_tle_files(source: &TleSource) -> Result<Option<Vec<TleRecord>>, String> {
+    if !source.files.is_empty() {
+        let mut records = Vec::new();
+        for path in &source.files {
+            records.extend(load_element_set_file(path)?);
         }
+        if records.is_empty() {
+            return Err("No valid TLE records found in the given files".to_string());
+        }
+        return Ok(Some(records));
     }
 
-    // Fall back to network fetch
-    use std::sync::mpsc;
-    use std::thread;
-    use std::time::Duration;
-    
+    if source.groups == [ACTIVE_GROUP] {
+        match try_load_local_tle_data() {
+            Ok(records) => {
+                info!("Successfully loaded {} TLE records from local files", records.len());
+                return Ok(Some(records));
+            }
+            Err(e) => {
+                info!("No local TLE files found or failed to load: {} - Falling back to network", e);
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Fetch Celestrak groups on a background thread, returning the channel the records arrive on
+fn spawn_group_fetch(groups: Vec<String>, format: GpFormat) -> Receiver<Result<Vec<TleRecord>, String>> {
     let (tx, rx) = mpsc::channel();
-    
-    // Spawn a thread for the async operation
-    thread::spawn(move || {
+    std::thread::spawn(move || {
         let rt = tokio::runtime::Runtime::new().unwrap();
         let result = rt.block_on(async {
-            match fetch_tle_data_system().await {
+            match fetch_tle_data_system(groups, format).await {
                 Ok(records) => Ok(records),
                 Err(e) => Err(e.to_string()),
             }
         });
         let _ = tx.send(result);
     });
-    
-    // Wait for result with timeout
-    match rx.recv_timeout(Duration::from_secs(10)) {
-        Ok(Ok(records)) => Ok(records),
-        Ok(Err(e)) => Err(e),
-        Err(_) => Err("Timeout fetching TLE data".to_string()),
-    }
+    rx
+}
+
+/// Load the given element set files on a background thread, returning the channel the records arrive on
+fn spawn_file_load(files: Vec<PathBuf>) -> Receiver<Result<Vec<TleRecord>, String>> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let result = files.iter()
+            .map(|path| load_element_set_file(path))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|records| records.concat());
+        let _ = tx.send(result);
+    });
+    rx
 }
 
 /// Try to load TLE data from local files in assets/tles directory
@@ -142,7 +385,7 @@ fn try_load_local_tle_data() -> Result<Vec<TleRecord>, String> {
         return Err("TLE directory 'assets/tles' does not exist".to_string());
     }
     
-    // Read all .tle files in the directory
+    // Read all element set files (.tle, .json, .csv or .xml) in the directory
     let entries = fs::read_dir(tle_dir)
         .map_err(|e| format!("Failed to read TLE directory: {}", e))?;
     
@@ -153,26 +396,15 @@ fn try_load_local_tle_data() -> Result<Vec<TleRecord>, String> {
         let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
         let path = entry.path();
         
-        // Only process .tle files
-        if let Some(extension) = path.extension() {
-            if extension == "tle" {
-                info!("Loading TLE data from: {}", path.display());
-                
-                let content = fs::read_to_string(&path)
-                    .map_err(|e| format!("Failed to read TLE file {}: {}", path.display(), e))?;
-                
-                let records = parse_tle_data(&content)
-                    .map_err(|e| format!("Failed to parse TLE file {}: {}", path.display(), e))?;
-                
-                info!("Loaded {} TLE records from {}", records.len(), path.display());
-                all_records.extend(records);
-                files_processed += 1;
-            }
+        // Only process element set files
+        if element_set_format(&path).is_some() {
+            all_records.extend(load_element_set_file(&path)?);
+            files_processed += 1;
         }
     }
     
     if files_processed == 0 {
-        return Err("No .tle files found in assets/tles directory".to_string());
+        return Err("No element set files found in assets/tles directory".to_string());
     }
     
     if all_records.is_empty() {
@@ -183,136 +415,346 @@ fn try_load_local_tle_data() -> Result<Vec<TleRecord>, String> {
     Ok(all_records)
 }
 
-/// Create extended test dataset with 100 realistic satellites
-fn create_extended_test_dataset(commands: &mut Commands, tle_cache: &mut ResMut<TleDataCache>) {
-    let test_satellites = vec![
-        // Low Earth Orbit satellites (50 total)
-        create_test_satellite("ISS (ZARYA)", 25544, 408.0, 51.6),
-        create_test_satellite("HUBBLE SPACE TELESCOPE", 20580, 547.0, 28.5),
-        create_test_satellite("TERRA", 25994, 705.0, 98.2),
-        create_test_satellite("AQUA", 27424, 705.0, 98.2),
-        create_test_satellite("NOAA-18", 28654, 854.0, 98.9),
-        create_test_satellite("METOP-A", 29499, 817.0, 98.7),
-        create_test_satellite("WORLDVIEW-1", 32060, 496.0, 97.2),
-        create_test_satellite("KEPLER", 36411, 621.0, 89.0),
-        create_test_satellite("SENTINEL-1A", 39634, 693.0, 98.2),
-        create_test_satellite("LANDSAT-8", 39084, 705.3, 98.2),
-        create_test_satellite("NOAA-19", 33591, 870.0, 98.7),
-        create_test_satellite("METOP-B", 38771, 817.0, 98.7),
-        create_test_satellite("SENTINEL-1B", 41456, 693.0, 98.2),
-        create_test_satellite("SENTINEL-2A", 40697, 786.0, 98.6),
-        create_test_satellite("SENTINEL-2B", 42063, 786.0, 98.6),
-        create_test_satellite("SENTINEL-3A", 41335, 814.5, 98.7),
-        create_test_satellite("SENTINEL-3B", 43437, 814.5, 98.7),
-        create_test_satellite("LANDSAT-7", 25682, 705.0, 98.2),
-        create_test_satellite("SPOT-6", 38755, 694.0, 98.2),
-        create_test_satellite("SPOT-7", 40053, 694.0, 98.2),
-        create_test_satellite("WORLDVIEW-2", 36284, 770.0, 97.2),
-        create_test_satellite("WORLDVIEW-3", 40115, 617.0, 97.9),
-        create_test_satellite("PLEIADES-1A", 38012, 694.0, 98.2),
-        create_test_satellite("PLEIADES-1B", 39019, 694.0, 98.2),
-        create_test_satellite("COSMO-SKYMED 1", 31598, 619.6, 97.9),
-        create_test_satellite("COSMO-SKYMED 2", 32598, 619.6, 97.9),
-        create_test_satellite("RADARSAT-2", 32382, 798.0, 98.6),
-        create_test_satellite("TERRASAR-X", 31698, 514.8, 97.4),
-        create_test_satellite("TANDEM-X", 36605, 514.8, 97.4),
-        create_test_satellite("ICESAT-2", 43613, 496.0, 92.0),
-        create_test_satellite("GRACE-FO 1", 43476, 490.0, 89.0),
-        create_test_satellite("GRACE-FO 2", 43477, 490.0, 89.0),
-        create_test_satellite("SWOT", 52811, 890.6, 77.6),
-        create_test_satellite("ENVISAT", 27386, 790.0, 98.5),
-        create_test_satellite("CRYOSAT-2", 36508, 717.0, 92.0),
-        create_test_satellite("GOCE", 36227, 255.0, 96.7),
-        create_test_satellite("SMOS", 36036, 758.0, 98.4),
-        create_test_satellite("PROBA-2", 36037, 728.0, 98.3),
-        create_test_satellite("SWARM-A", 39451, 460.0, 87.4),
-        create_test_satellite("SWARM-B", 39452, 460.0, 87.4),
-        create_test_satellite("SWARM-C", 39453, 510.0, 88.0),
-        create_test_satellite("AURA", 28376, 705.0, 98.2),
-        create_test_satellite("CALIPSO", 29108, 705.0, 98.2),
-        create_test_satellite("CLOUDSAT", 29107, 705.0, 98.2),
-        create_test_satellite("OCO-2", 40059, 705.0, 98.2),
-        create_test_satellite("SMAP", 40376, 685.0, 98.1),
-        create_test_satellite("JPSS-1 (NOAA-20)", 43013, 824.0, 98.7),
-        create_test_satellite("SUOMI NPP", 37849, 824.0, 98.7),
-        create_test_satellite("DMSP F18", 35951, 850.0, 98.8),
-        create_test_satellite("DMSP F19", 43435, 850.0, 98.8),
-        
-        // Medium Earth Orbit satellites (30 total)
-        create_test_satellite("GPS BIIR-2 (PRN 13)", 24876, 20200.0, 55.0),
-        create_test_satellite("GPS BIIR-10 (PRN 12)", 32260, 20200.0, 55.0),
-        create_test_satellite("GPS BIIF-1 (PRN 25)", 38833, 20200.0, 55.0),
-        create_test_satellite("GPS BIIF-2 (PRN 01)", 39166, 20200.0, 55.0),
-        create_test_satellite("GPS BIIF-3 (PRN 06)", 39533, 20200.0, 55.0),
-        create_test_satellite("GPS BIIF-4 (PRN 03)", 39741, 20200.0, 55.0),
-        create_test_satellite("GPS BIIF-5 (PRN 09)", 40105, 20200.0, 55.0),
-        create_test_satellite("GPS BIIF-6 (PRN 26)", 40294, 20200.0, 55.0),
-        create_test_satellite("GPS BIIF-7 (PRN 08)", 40534, 20200.0, 55.0),
-        create_test_satellite("GPS BIIF-8 (PRN 10)", 40730, 20200.0, 55.0),
-        create_test_satellite("GPS BIIF-9 (PRN 32)", 41019, 20200.0, 55.0),
-        create_test_satellite("GPS BIIF-10 (PRN 02)", 41328, 20200.0, 55.0),
-        create_test_satellite("GALILEO-FOC FM14", 41549, 23222.0, 56.0),
-        create_test_satellite("GALILEO-FOC FM15", 41550, 23222.0, 56.0),
-        create_test_satellite("GALILEO-FOC FM11", 41859, 23222.0, 56.0),
-        create_test_satellite("GALILEO-FOC FM12", 41860, 23222.0, 56.0),
-        create_test_satellite("GALILEO-FOC FM13", 41861, 23222.0, 56.0),
-        create_test_satellite("GALILEO-FOC FM16", 41862, 23222.0, 56.0),
-        create_test_satellite("GLONASS-M 758", 36111, 19130.0, 64.8),
-        create_test_satellite("GLONASS-M 759", 36112, 19130.0, 64.8),
-        create_test_satellite("GLONASS-M 760", 36113, 19130.0, 64.8),
-        create_test_satellite("GLONASS-K1 701", 39155, 19130.0, 64.8),
-        create_test_satellite("GLONASS-K1 702", 41330, 19130.0, 64.8),
-        create_test_satellite("BEIDOU-3 M15", 43581, 21528.0, 55.0),
-        create_test_satellite("BEIDOU-3 M16", 43582, 21528.0, 55.0),
-        create_test_satellite("BEIDOU-3 M13", 43107, 21528.0, 55.0),
-        create_test_satellite("BEIDOU-3 M14", 43108, 21528.0, 55.0),
-        create_test_satellite("IRNSS-1A", 39199, 35786.0, 29.0),
-        create_test_satellite("IRNSS-1B", 40269, 35786.0, 29.0),
-        create_test_satellite("QZSS-1", 37158, 35786.0, 43.0),
-        
-        // High Earth Orbit / GEO satellites (20 total)
-        create_test_satellite("JASON-2", 33105, 1336.0, 66.0),
-        create_test_satellite("JASON-3", 41240, 1336.0, 66.0),
-        create_test_satellite("GOES-16", 41866, 35786.0, 0.1),
-        create_test_satellite("GOES-17", 43226, 35786.0, 0.1),
-        create_test_satellite("GOES-18", 51850, 35786.0, 0.1),
-        create_test_satellite("METEOSAT-11", 38552, 35786.0, 0.1),
-        create_test_satellite("METEOSAT-10", 38771, 35786.0, 0.1),
-        create_test_satellite("HIMAWARI-8", 40267, 35786.0, 0.1),
-        create_test_satellite("HIMAWARI-9", 40268, 35786.0, 0.1),
-        create_test_satellite("INTELSAT 29E", 41308, 35786.0, 0.1),
-        create_test_satellite("INTELSAT 33E", 42432, 35786.0, 0.1),
-        create_test_satellite("INTELSAT 36", 41748, 35786.0, 0.1),
-        create_test_satellite("ASTRA 2E", 38087, 35786.0, 0.1),
-        create_test_satellite("ASTRA 2F", 39020, 35786.0, 0.1),
-        create_test_satellite("ASTRA 2G", 39199, 35786.0, 0.1),
-        create_test_satellite("EUTELSAT 7C", 41855, 35786.0, 0.1),
-        create_test_satellite("EUTELSAT 10A", 40364, 35786.0, 0.1),
-        create_test_satellite("TURKSAT 4A", 39522, 35786.0, 0.1),
-        create_test_satellite("TURKSAT 4B", 40945, 35786.0, 0.1),
-        create_test_satellite("SES-14", 43055, 35786.0, 0.1),
-    ];
+/// Element set format of a file, by its extension
+fn element_set_format(path: &Path) -> Option<GpFormat> {
+    path.extension().and_then(|e| e.to_str()).and_then(GpFormat::from_extension)
+}
+
+/// Load the element sets of a .tle, .json, .csv or .xml file
+pub fn load_element_set_file(path: &Path) -> Result<Vec<TleRecord>, String> {
+    let format = element_set_format(path)
+        .ok_or_else(|| format!("Unknown element set format of {} (expected .tle, .json, .csv or .xml)", path.display()))?;
+    info!("Loading TLE data from: {}", path.display());
     
-    // Store test TLE records in cache
-    for (tle_record, _orbital_state) in &test_satellites {
-        tle_cache.records.push(tle_record.clone());
-    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read TLE file {}: {}", path.display(), e))?;
     
-    // Spawn satellite entities
-    spawn_satellites_from_records(commands, &tle_cache.records);
+    let records = parse_gp_data(&content, format)
+        .map_err(|e| format!("Failed to parse TLE file {}: {}", path.display(), e))?;
     
-    info!("Created test dataset with {} realistic satellites", test_satellites.len());
+    info!("Loaded {} TLE records from {}", records.len(), path.display());
+    Ok(records)
+}
+
+/// System to spawn the next frame's worth of queued cataloged objects
+pub fn tle_spawn_queue_system(
+    mut commands: Commands,
+    mut spawn_queue: ResMut<TleSpawnQueue>,
+    sim_time: Res<SimulationTime>,
+) {
+    if spawn_queue.pending.is_empty() {
+        return;
+    }
+    let count = spawn_queue.pending.len().min(OBJECTS_SPAWNED_PER_FRAME);
+    let records: Vec<TleRecord> = spawn_queue.pending.drain(..count).collect();
+    spawn_satellites_from_records(&mut commands, &records, sim_time.current_unix());
+    if spawn_queue.pending.is_empty() {
+        info!("All {} queued cataloged objects spawned", spawn_queue.queued.len());
+    }
+}
+
+/// System to change the object cap, queueing more of the catalog or dropping what is still queued
+pub fn object_cap_command_system(
+    mut events: EventReader<SearchCommand>,
+    mut source: ResMut<TleSource>,
+    mut spawn_queue: ResMut<TleSpawnQueue>,
+    tle_cache: Res<TleDataCache>,
+) {
+    for command in events.read() {
+        let &SearchCommand::ObjectCap(ObjectCapCommand(cap)) = command else {
+            continue;
+        };
+        source.cap = cap;
+        spawn_queue.limit(cap);
+        let queued = spawn_queue.enqueue(&tle_cache.records, cap);
+        info!("Object cap {}: {} more of {} cataloged objects queued", cap, queued, tle_cache.records.len());
+    }
 }
 
-/// Spawn satellites from TLE records
-fn spawn_satellites_from_records(commands: &mut Commands, records: &[TleRecord]) {
+/// System to check and uncheck Celestrak groups, fetching newly checked ones and saving the choice
+///
+/// Objects of an unchecked group stay until the next start, which no longer fetches it.
+pub fn group_command_system(
+    mut events: EventReader<SearchCommand>,
+    mut source: ResMut<TleSource>,
+    mut settings: ResMut<Settings>,
+) {
+    for command in events.read() {
+        let SearchCommand::Group(command) = command else {
+            continue;
+        };
+        match command {
+            GroupCommand::Checklist => source.checklist = !source.checklist,
+            GroupCommand::Toggle(group) => {
+                if let Some(index) = source.groups.iter().position(|g| g.eq_ignore_ascii_case(group)) {
+                    source.groups.remove(index);
+                    info!("Group {} unchecked; it is no longer fetched from the next start", group);
+                } else {
+                    source.groups.push(group.clone());
+                    info!("Fetching group {} from Celestrak", group);
+                    let rx = spawn_group_fetch(vec![group.clone()], source.format);
+                    source.in_flight.push((group.clone(), Mutex::new(rx)));
+                }
+                settings.tle_groups = source.groups.clone();
+                settings.save();
+            }
+        }
+    }
+}
+
+/// System to merge groups fetched in the background into the catalog and spawn their new objects
+///
+/// Startup groups are spawned up to the cap and checked groups whatever the cap. If every startup group fails and
+/// the catalog is still empty, the test dataset is spawned instead.
+pub fn group_fetch_system(
+    mut commands: Commands,
+    mut source: ResMut<TleSource>,
+    mut tle_cache: ResMut<TleDataCache>,
+    mut spawn_queue: ResMut<TleSpawnQueue>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+) {
+    if source.in_flight.is_empty() {
+        return;
+    }
+    let starting_up = !source.startup_pending.is_empty();
+    let TleSource { in_flight, startup_pending, cap, .. } = &mut *source;
+    in_flight.retain(|(group, in_flight)| {
+        let result = match in_flight.lock().unwrap().try_recv() {
+            Err(TryRecvError::Empty) => return true,
+            Ok(result) => result,
+            Err(TryRecvError::Disconnected) => Err("fetch thread stopped".to_string()),
+        };
+        let cap = if startup_pending.remove(group) { *cap } else { ObjectCap::All };
+        match result {
+            Ok(records) => {
+                let added = add_to_catalog(&mut tle_cache, &mut spawn_queue, records, cap);
+                info!("Group {}: {} new objects added to the catalog", group, added);
+            }
+            Err(e) => warn!("Failed to fetch group {}: {}", group, e),
+        }
+        false
+    });
+
+    if starting_up && source.startup_pending.is_empty() && tle_cache.records.is_empty() {
+        warn!("No Celestrak group could be fetched - Using test dataset instead");
+        spawn_test_dataset(&mut commands, &sim_time, &constants, &mut tle_cache, &mut spawn_queue);
+    }
+}
+
+/// Add the records of objects not already in the catalog and queue them up to `cap`, returning how many were new
+fn add_to_catalog(
+    tle_cache: &mut TleDataCache,
+    spawn_queue: &mut TleSpawnQueue,
+    records: Vec<TleRecord>,
+    cap: ObjectCap,
+) -> usize {
+    let known: HashSet<u32> = tle_cache.records.iter().map(|record| record.norad_id).collect();
+    let new_records: Vec<TleRecord> = merge_records(records).into_iter()
+        .filter(|record| !known.contains(&record.norad_id))
+        .collect();
+    let added = new_records.len();
+    spawn_queue.enqueue(&new_records, cap);
+    tle_cache.records.extend(new_records);
+    tle_cache.last_updated = wall_clock_unix_seconds();
+    added
+}
+
+/// System to re-fetch the element sets every refresh interval and merge them into the catalog
+///
+/// Objects whose element sets are newer than the cached ones take them over, and in live mode are repositioned on
+/// them at once. Objects that appeared since the last fetch are added to the catalog and queued up to the cap.
+/// Files given on the command line are re-read instead of fetching.
+#[allow(clippy::too_many_arguments)]
+pub fn tle_refresh_system(
+    mut refresh: ResMut<TleRefresh>,
+    source: Res<TleSource>,
+    live_mode: Res<LiveMode>,
+    sim_time: Res<SimulationTime>,
+    mut tle_cache: ResMut<TleDataCache>,
+    mut spawn_queue: ResMut<TleSpawnQueue>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+    mut objects: Query<(&mut TleData, &mut OrbitalState, Option<&mut PositionCovariance>, Option<&OptimizedPhysics>)>,
+) {
+    // Scenarios without the catalog keep only their own objects
+    let Some(interval) = refresh.interval.filter(|_| source.catalog) else {
+        return;
+    };
+
+    let Some(in_flight) = &refresh.in_flight else {
+        let now = wall_clock_unix_seconds();
+        if now - refresh.last_refresh >= interval {
+            refresh.last_refresh = now;
+            info!("Refreshing element sets");
+            let rx = if source.files.is_empty() {
+                spawn_group_fetch(source.groups.clone(), source.format)
+            } else {
+                spawn_file_load(source.files.clone())
+            };
+            refresh.in_flight = Some(Mutex::new(rx));
+        }
+        return;
+    };
+    let result = in_flight.lock().unwrap().try_recv();
+    let records = match result {
+        Err(TryRecvError::Empty) => return,
+        Ok(Ok(records)) => records,
+        Ok(Err(e)) => {
+            warn!("Element set refresh failed: {} - Trying again next interval", e);
+            refresh.in_flight = None;
+            return;
+        }
+        Err(TryRecvError::Disconnected) => {
+            warn!("Element set refresh failed: fetch thread stopped - Trying again next interval");
+            refresh.in_flight = None;
+            return;
+        }
+    };
+    refresh.in_flight = None;
+
+    let epoch = |record: &TleRecord| tle_epoch_unix(record.epoch_year, record.epoch_day);
+    let index: HashMap<u32, usize> = tle_cache.records.iter()
+        .enumerate()
+        .map(|(i, record)| (record.norad_id, i))
+        .collect();
+    let mut newer = HashMap::new();
+    let mut appeared = Vec::new();
+    for record in merge_records(records) {
+        match index.get(&record.norad_id) {
+            Some(&i) if epoch(&record) > epoch(&tle_cache.records[i]) => {
+                tle_cache.records[i] = record.clone();
+                newer.insert(record.norad_id, record);
+            }
+            Some(_) => {}
+            None => appeared.push(record),
+        }
+    }
+
+    let unix_seconds = sim_time.current_unix();
+    let mut repositioned = 0;
+    for (mut tle_data, mut state, covariance, optimized) in objects.iter_mut() {
+        let Some(record) = newer.get(&tle_data.norad_id) else {
+            continue;
+        };
+        tle_data.line1 = record.line1.clone();
+        tle_data.line2 = record.line2.clone();
+        tle_data.epoch = record.epoch_day;
+        if let Some(mut covariance) = covariance {
+            *covariance = PositionCovariance::from_tle_age((unix_seconds - epoch(record)) / 86400.0);
+        }
+        if !live_mode.enabled {
+            continue;
+        }
+        if let Ok((position, velocity)) = propagate_tle(record, unix_seconds) {
+            state.position = position;
+            state.velocity = velocity;
+            if let Some(optimized) = optimized {
+                optimized_data.set_state(optimized.index, position, velocity);
+            }
+            repositioned += 1;
+        }
+    }
+
+    refresh.updated += newer.len();
+    let cataloged = appeared.len();
+    tle_cache.records.extend(appeared);
+    tle_cache.last_updated = wall_clock_unix_seconds();
+    let queued = spawn_queue.enqueue(&tle_cache.records, source.cap);
+    info!(
+        "Element set refresh: {} newer element sets ({} objects repositioned), {} new objects cataloged, {} queued (cap: {})",
+        newer.len(), repositioned, cataloged, queued, source.cap
+    );
+}
+
+/// Marker for the group checklist text
+#[derive(Component)]
+pub struct GroupChecklistText;
+
+/// System to create the (initially hidden) group checklist
+pub fn setup_group_checklist_system(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.7, 0.85, 1.0)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(50.0),
+            left: Val::Percent(40.0),
+            ..default()
+        },
+        GroupChecklistText,
+    ));
+}
+
+/// System to list the Celestrak groups with the checked ones marked
+pub fn group_checklist_system(
+    source: Res<TleSource>,
+    tle_cache: Res<TleDataCache>,
+    mut text_query: Query<&mut Text, With<GroupChecklistText>>,
+) {
+    if !source.is_changed() && !tle_cache.is_changed() {
+        return;
+    }
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+    if !source.checklist {
+        text.0.clear();
+        return;
+    }
+
+    let mut lines = vec![format!("Celestrak groups ({} objects cataloged; /fetch N to toggle)", tle_cache.records.len())];
+    let checked = |group: &str| source.groups.iter().any(|g| g.eq_ignore_ascii_case(group));
+    for (number, group) in CELESTRAK_GROUPS.iter().enumerate() {
+        let fetching = source.in_flight.iter().any(|(g, _)| g.as_str() == *group);
+        lines.push(format!(
+            "{:>2}. [{}] {}{}",
+            number + 1, if checked(group) { 'x' } else { ' ' }, group, if fetching { " (fetching)" } else { "" }
+        ));
+    }
+    for group in source.groups.iter().filter(|group| !CELESTRAK_GROUPS.iter().any(|g| g.eq_ignore_ascii_case(group))) {
+        lines.push(format!("    [x] {}", group));
+    }
+    text.0 = lines.join("\n");
+}
+
+/// System to load element set files dropped on the window, adding objects not already in the catalog
+pub fn tle_file_drop_system(
+    mut drops: EventReader<FileDragAndDrop>,
+    mut tle_cache: ResMut<TleDataCache>,
+    mut spawn_queue: ResMut<TleSpawnQueue>,
+) {
+    for drop in drops.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = drop else {
+            continue;
+        };
+        match load_element_set_file(path_buf) {
+            Ok(records) => {
+                // Dropped files are added whatever the cap
+                let added = add_to_catalog(&mut tle_cache, &mut spawn_queue, records, ObjectCap::All);
+                info!("Adding {} new objects from {}", added, path_buf.display());
+            }
+            Err(e) => warn!("Could not load dropped file: {}", e),
+        }
+    }
+}
+
+/// Spawn satellites from TLE records, propagated to a UTC Unix time
+fn spawn_satellites_from_records(commands: &mut Commands, records: &[TleRecord], unix_seconds: f64) {
     let mut spawned_count = 0;
     let mut failed_count = 0;
+    // Element set ages at the spawn time (days), each record being propagated from its own epoch
+    let mut ages = (f64::INFINITY, f64::NEG_INFINITY);
     
     for tle_record in records {
-        match create_satellite_from_tle(tle_record) {
+        match create_satellite_from_tle(tle_record, unix_seconds) {
             Ok(satellite_data) => {
-                spawn_satellite_entity(commands, satellite_data);
+                let age = (unix_seconds - tle_epoch_unix(tle_record.epoch_year, tle_record.epoch_day)) / 86400.0;
+                ages = (ages.0.min(age), ages.1.max(age));
+                spawn_satellite_entity(commands, satellite_data, unix_seconds);
                 spawned_count += 1;
             }
             Err(e) => {
@@ -327,50 +769,26 @@ fn spawn_satellites_from_records(commands: &mut Commands, records: &[TleRecord])
     } else {
         info!("Successfully spawned {} satellites", spawned_count);
     }
+    if spawned_count > 0 {
+        info!(
+            "Propagated element sets from their epochs to {}Z ({:+.1} to {:+.1} days)",
+            unix_to_iso8601(unix_seconds), ages.0, ages.1
+        );
+    }
 }
 
-fn create_test_satellite(name: &str, norad_id: u32, altitude_km: f64, inclination: f64) -> (TleRecord, OrbitalState) {
-    // Create dummy TLE record
-    let tle_record = TleRecord {
-        name: name.to_string(),
-        norad_id,
-        classification: 'U',
-        international_designator: "00000A".to_string(),
-        epoch_year: 23,
-        epoch_day: 1.0,
-        mean_motion_dot: 0.0,
-        mean_motion_ddot: 0.0,
-        bstar: 0.0,
-        inclination,
-        right_ascension: 0.0,
-        eccentricity: 0.001,
-        argument_of_perigee: 0.0,
-        mean_anomaly: 0.0,
-        mean_motion: 15.5, // Rough approximation
-        revolution_number: 1,
-        line1: "".to_string(),
-        line2: "".to_string(),
-    };
-
-    // Create initial orbital state
-    let orbital_radius = 6371.0 + altitude_km; // Earth radius + altitude
-    let orbital_velocity = (3.986004418e14 / (orbital_radius * 1000.0)).sqrt() / 1000.0; // km/s
-
-    let orbital_state = OrbitalState::new(
-        Vec3::new(orbital_radius as f32, 0.0, 0.0),
-        Vec3::new(0.0, orbital_velocity as f32, 0.0),
-        1000.0, // 1000 kg mass
-    );
-
-    (tle_record, orbital_state)
-}
-
-fn spawn_satellite_entity(
+/// Spawn a satellite entity on its initial state, with covariance grown from its element set age
+pub fn spawn_satellite_entity(
     commands: &mut Commands,
     (tle_record, orbital_state): (TleRecord, OrbitalState),
+    unix_seconds: f64,
 ) {
-    commands.spawn((
+    let is_rocket_body = RocketBody::is_rocket_body_name(&tle_record.name);
+    let tle_age_days = (unix_seconds - tle_epoch_unix(tle_record.epoch_year, tle_record.epoch_day)) / 86400.0;
+    let mass = orbital_state.mass;
+    let mut entity = commands.spawn((
         Satellite::new(tle_record.name.clone(), tle_record.norad_id, true),
+        Operator::from_satellite_name(&tle_record.name),
         orbital_state,
         TleData::new(
             tle_record.norad_id,
@@ -379,15 +797,19 @@ fn spawn_satellite_entity(
             tle_record.line2.clone(),
             tle_record.epoch_day,
         ),
-        PhysicsObject::satellite(1000.0),
+        PhysicsObject::satellite(mass),
+        PositionCovariance::from_tle_age(tle_age_days),
+        Health::default(),
         RenderAsSatellite,
     ));
+    if is_rocket_body {
+        entity.insert(RocketBody);
+    }
 }
 
-/// Create satellite from real TLE data using SGP4 conversion
-pub fn create_satellite_from_tle(tle_record: &TleRecord) -> Result<(TleRecord, OrbitalState), String> {
-    // Use SGP4 to convert TLE to position/velocity state vectors
-    let (position, velocity) = tle_to_state_vectors(tle_record)?;
+/// Create satellite from real TLE data, propagated with SGP4 to a UTC Unix time
+pub fn create_satellite_from_tle(tle_record: &TleRecord, unix_seconds: f64) -> Result<(TleRecord, OrbitalState), String> {
+    let (position, velocity) = propagate_tle(tle_record, unix_seconds)?;
     
     // Estimate mass based on satellite type (this is a simplification)
     // In reality, mass would come from satellite databases
@@ -423,42 +845,3 @@ fn estimate_satellite_mass(name: &str) -> f64 {
         1000.0   // Default estimate for unknown satellites
     }
 }
-
-/// System to spawn satellites from live TLE data (for future use)
-pub fn spawn_satellites_from_tle_data(
-    mut commands: Commands,
-    tle_cache: Res<TleDataCache>,
-    existing_sats: Query<&Satellite>,
-) {
-    // Check if we already have satellites spawned
-    if existing_sats.iter().count() > 0 {
-        return; // Already have satellites spawned
-    }
-    
-    debug!("Creating satellites from cached TLE data...");
-    let mut spawned_count = 0;
-    let mut failed_count = 0;
-    
-    // Limit to first 100 satellites for Phase 2 testing
-    // In production, this could be configurable
-    let max_satellites = 100;
-    
-    for tle_record in tle_cache.records.iter().take(max_satellites) {
-        match create_satellite_from_tle(tle_record) {
-            Ok(satellite_data) => {
-                spawn_satellite_entity(&mut commands, satellite_data);
-                spawned_count += 1;
-            }
-            Err(e) => {
-                debug!("Failed to create satellite {}: {}", tle_record.name, e);
-                failed_count += 1;
-            }
-        }
-    }
-    
-    if failed_count > 0 {
-        warn!("Spawned {} satellites from TLE data ({} failed)", spawned_count, failed_count);
-    } else {
-        info!("Successfully spawned {} satellites from TLE data", spawned_count);
-    }
-}
\ No newline at end of file
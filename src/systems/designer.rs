@@ -0,0 +1,343 @@
+// Interactive orbit designer: drag apogee/perigee handles and tilt the orbit plane in the 3D view
+
+use bevy::math::{DQuat, DVec3};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+use crate::systems::search::SearchCommand;
+use crate::systems::selection::{FocusRequest, Selection};
+use crate::utils::*;
+
+/// Maximum distance in pixels between the cursor and a handle for it to be grabbed
+const HANDLE_PICK_RADIUS_PX: f32 = 14.0;
+/// Lowest perigee altitude a handle can be dragged to (km)
+pub const MIN_PERIGEE_ALTITUDE: f64 = REENTRY_ALTITUDE + 50.0;
+/// Default altitude (km) and inclination (degrees) of a designed satellite
+pub const DEFAULT_DESIGN_ALTITUDE: f64 = 500.0;
+pub const DEFAULT_DESIGN_INCLINATION: f64 = 51.6;
+
+/// Draggable handles of the orbit designer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DesignHandle {
+    Perigee,
+    Apogee,
+    /// Tip of the orbit normal; dragging it tilts the plane
+    Plane,
+}
+
+/// Handle being dragged and the orbit shape captured when the drag began
+#[derive(Clone, Copy, Debug)]
+pub struct DesignDrag {
+    pub handle: DesignHandle,
+    pub entity: Entity,
+    pub elements: OrbitalElements,
+}
+
+/// Resource holding the orbit designer's drag state
+#[derive(Resource, Default)]
+pub struct OrbitDesigner {
+    pub dragging: Option<DesignDrag>,
+}
+
+/// Run condition for mouse controls that must not fire while a designer handle is dragged
+pub fn designer_idle(designer: Res<OrbitDesigner>) -> bool {
+    designer.dragging.is_none()
+}
+
+/// Command requesting a new user-spawned satellite on a circular orbit
+#[derive(Clone, Copy, Debug)]
+pub struct DesignRequest {
+    pub altitude: f64,
+    pub inclination: f64,
+}
+
+impl DesignRequest {
+    /// Parse `design [ALT_KM] [INC_DEG]`; returns `None` when the text is not a design command
+    pub fn parse(text: &str) -> Option<Result<Self, String>> {
+        let mut words = text.split_whitespace();
+        if !words.next()?.eq_ignore_ascii_case("design") {
+            return None;
+        }
+        let mut number = |default: f64, what: &str| match words.next() {
+            Some(word) => word.parse::<f64>().map_err(|_| format!("invalid {} {}", what, word)),
+            None => Ok(default),
+        };
+        let altitude = match number(DEFAULT_DESIGN_ALTITUDE, "altitude") {
+            Ok(altitude) if altitude >= MIN_PERIGEE_ALTITUDE => altitude,
+            Ok(altitude) => return Some(Err(format!("altitude {} km is below {} km", altitude, MIN_PERIGEE_ALTITUDE))),
+            Err(e) => return Some(Err(e)),
+        };
+        let inclination = match number(DEFAULT_DESIGN_INCLINATION, "inclination") {
+            Ok(inclination) if (0.0..=180.0).contains(&inclination) => inclination,
+            Ok(inclination) => return Some(Err(format!("inclination {}° outside 0-180°", inclination))),
+            Err(e) => return Some(Err(e)),
+        };
+        Some(Ok(Self { altitude, inclination }))
+    }
+}
+
+/// Marker component for the orbit designer readout
+#[derive(Component)]
+pub struct DesignerText;
+
+/// Handle positions (km) of an orbit: perigee, apogee and the orbit-normal tip
+fn handle_positions(elements: &OrbitalElements, mu_km: f64) -> [(DesignHandle, DVec3); 3] {
+    let (perigee, _) = state_at_true_anomaly(elements, 0.0, mu_km);
+    let (apogee, _) = state_at_true_anomaly(elements, std::f64::consts::PI, mu_km);
+    let normal = orbit_normal(elements);
+    [
+        (DesignHandle::Perigee, perigee),
+        (DesignHandle::Apogee, apogee),
+        (DesignHandle::Plane, normal * elements.semi_major_axis),
+    ]
+}
+
+/// Unit normal of an orbit plane
+fn orbit_normal(elements: &OrbitalElements) -> DVec3 {
+    let (i, raan) = (elements.inclination, elements.raan);
+    DVec3::new(i.sin() * raan.sin(), -i.sin() * raan.cos(), i.cos())
+}
+
+/// Cursor ray in kilometres
+fn cursor_ray(
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    camera_query: &Query<(&Camera, &GlobalTransform)>,
+) -> Option<(DVec3, DVec3)> {
+    let cursor = windows.single().ok()?.cursor_position()?;
+    let (camera, camera_transform) = camera_query.single().ok()?;
+    let ray = camera.viewport_to_world(camera_transform, cursor).ok()?;
+    Some((ray.origin.as_dvec3() * 1000.0, ray.direction.as_dvec3()))
+}
+
+/// System to spawn satellites requested with `design`, select them and fly the camera there
+pub fn design_spawn_system(
+    mut commands: Commands,
+    mut requests: EventReader<SearchCommand>,
+    constants: Res<Constants>,
+    mut focus_requests: EventWriter<FocusRequest>,
+    mut spawned: Local<u32>,
+) {
+    let mu_km = constants.gravitational_parameter / 1e9;
+    for command in requests.read() {
+        let SearchCommand::Design(request) = command else {
+            continue;
+        };
+        *spawned += 1;
+        let (position, velocity) = circular_orbit_state(
+            request.altitude, request.inclination.to_radians(), 0.0, 0.0, constants.earth_radius, mu_km,
+        );
+        let name = format!("DESIGN {}", *spawned);
+        let mass = 500.0;
+        let entity = commands.spawn((
+            Satellite::new(name.clone(), 0, true),
+            Operator::from_satellite_name(&name),
+            OrbitalState::new(position, velocity, mass),
+            PhysicsObject::satellite(mass),
+            Health::default(),
+            RenderAsSatellite,
+            UserSpawned,
+        )).id();
+        focus_requests.write(FocusRequest { entity });
+        info!("Spawned {} at {:.0} km, {:.1}° - drag its handles to reshape the orbit", name, request.altitude, request.inclination);
+    }
+}
+
+/// System to grab, drag and release designer handles of the selected user-spawned object
+#[allow(clippy::too_many_arguments)]
+pub fn designer_drag_system(
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    selection: Res<Selection>,
+    constants: Res<Constants>,
+    mut designer: ResMut<OrbitDesigner>,
+    mut objects: Query<(&mut OrbitalState, Option<&OptimizedPhysics>), With<UserSpawned>>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+) {
+    let mu_km = constants.gravitational_parameter / 1e9;
+
+    if mouse_buttons.just_released(MouseButton::Left) {
+        designer.dragging = None;
+        return;
+    }
+
+    if mouse_buttons.just_pressed(MouseButton::Left) {
+        let Some(entity) = selection.selected else {
+            return;
+        };
+        let Ok((state, _)) = objects.get(entity) else {
+            return;
+        };
+        let Some(elements) = orbital_elements(state.position, state.velocity, mu_km) else {
+            return;
+        };
+        let (Ok(window), Ok((camera, camera_transform))) = (windows.single(), camera_query.single()) else {
+            return;
+        };
+        let Some(cursor) = window.cursor_position() else {
+            return;
+        };
+        // Grab the handle whose projection lies closest to the cursor
+        designer.dragging = handle_positions(&elements, mu_km)
+            .into_iter()
+            .filter_map(|(handle, position)| {
+                let screen = camera.world_to_viewport(camera_transform, (position / 1000.0).as_vec3()).ok()?;
+                let distance = screen.distance(cursor);
+                (distance <= HANDLE_PICK_RADIUS_PX).then_some((handle, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(handle, _)| DesignDrag { handle, entity, elements });
+        return;
+    }
+
+    let Some(drag) = designer.dragging else {
+        return;
+    };
+    let Ok((mut state, optimized)) = objects.get_mut(drag.entity) else {
+        designer.dragging = None;
+        return;
+    };
+    let Some((origin, direction)) = cursor_ray(&windows, &camera_query) else {
+        return;
+    };
+
+    let (position, velocity) = match drag.handle {
+        DesignHandle::Plane => {
+            // Point the normal at where the cursor ray passes the handle sphere, rotating the whole state
+            let radius = drag.elements.semi_major_axis;
+            let along = -origin.dot(direction);
+            let closest = origin + direction * along;
+            let depth = (radius * radius - closest.length_squared()).max(0.0).sqrt();
+            let target = (closest - direction * depth).normalize_or_zero();
+            let normal = state.position.cross(state.velocity).normalize_or_zero();
+            if target == DVec3::ZERO || normal == DVec3::ZERO {
+                return;
+            }
+            let rotation = DQuat::from_rotation_arc(normal, target);
+            (rotation * state.position, rotation * state.velocity)
+        }
+        DesignHandle::Perigee | DesignHandle::Apogee => {
+            // Radius of the point where the cursor ray meets the orbit plane
+            let normal = orbit_normal(&drag.elements);
+            let denominator = direction.dot(normal);
+            if denominator.abs() < 1e-4 {
+                return;
+            }
+            let radius = (origin - direction * origin.dot(normal) / denominator).length();
+            let mut elements = drag.elements;
+            let perigee = elements.semi_major_axis * (1.0 - elements.eccentricity);
+            let apogee = elements.semi_major_axis * (1.0 + elements.eccentricity);
+            let min_perigee = constants.earth_radius + MIN_PERIGEE_ALTITUDE;
+            let (perigee, apogee) = match drag.handle {
+                DesignHandle::Perigee => (radius.clamp(min_perigee, apogee), apogee),
+                _ => (perigee, radius.max(perigee)),
+            };
+            elements.semi_major_axis = (perigee + apogee) / 2.0;
+            elements.eccentricity = (apogee - perigee) / (apogee + perigee);
+            // Keep the object where it is along the orbit, measured from the ascending node
+            let true_anomaly = argument_of_latitude(state.position, state.velocity) - elements.argument_of_perigee;
+            state_at_true_anomaly(&elements, true_anomaly, mu_km)
+        }
+    };
+
+    state.position = position;
+    state.velocity = velocity;
+    if let Some(optimized) = optimized {
+        optimized_data.set_state(optimized.index, position, velocity);
+    }
+}
+
+/// System to draw the selected user-spawned object's orbit and designer handles
+pub fn designer_overlay_system(
+    selection: Res<Selection>,
+    constants: Res<Constants>,
+    designer: Res<OrbitDesigner>,
+    objects: Query<&OrbitalState, With<UserSpawned>>,
+    mut gizmos: Gizmos,
+) {
+    let Some(entity) = selection.selected else {
+        return;
+    };
+    let Ok(state) = objects.get(entity) else {
+        return;
+    };
+    let mu_km = constants.gravitational_parameter / 1e9;
+    let Some(elements) = orbital_elements(state.position, state.velocity, mu_km) else {
+        return;
+    };
+
+    let points = (0..=128).map(|i| {
+        let true_anomaly = std::f64::consts::TAU * i as f64 / 128.0;
+        (state_at_true_anomaly(&elements, true_anomaly, mu_km).0 / 1000.0).as_vec3()
+    });
+    gizmos.linestrip(points, Color::srgba(0.6, 0.9, 1.0, 0.8));
+
+    let dragged = designer.dragging.map(|drag| drag.handle);
+    for (handle, position) in handle_positions(&elements, mu_km) {
+        let position = (position / 1000.0).as_vec3();
+        let color = match handle {
+            DesignHandle::Perigee => Color::srgb(0.3, 1.0, 0.4),
+            DesignHandle::Apogee => Color::srgb(1.0, 0.4, 0.3),
+            DesignHandle::Plane => Color::srgb(0.3, 0.8, 1.0),
+        };
+        if handle == DesignHandle::Plane {
+            gizmos.line(Vec3::ZERO, position, color.with_alpha(0.5));
+        }
+        let radius = if dragged == Some(handle) { 0.25 } else { 0.15 };
+        gizmos.sphere(Isometry3d::from_translation(position), radius, color);
+    }
+}
+
+/// System to create the (initially empty) orbit designer readout
+pub fn setup_designer_text_system(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.6, 0.9, 1.0)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(44.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        DesignerText,
+    ));
+}
+
+/// System to show the live elements of the selected user-spawned object
+pub fn designer_text_system(
+    selection: Res<Selection>,
+    constants: Res<Constants>,
+    objects: Query<(&OrbitalState, &Satellite), With<UserSpawned>>,
+    mut text_query: Query<&mut Text, With<DesignerText>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+    let mu_km = constants.gravitational_parameter / 1e9;
+    let readout = selection.selected
+        .and_then(|entity| objects.get(entity).ok())
+        .and_then(|(state, satellite)| {
+            let elements = orbital_elements(state.position, state.velocity, mu_km)?;
+            let (a, e) = (elements.semi_major_axis, elements.eccentricity);
+            let period = orbital_period(state.position, state.velocity, mu_km).unwrap_or(0.0);
+            Some(format!(
+                "Designing {} (drag perigee/apogee/normal handles)\n\
+                 Perigee {:.0} km  |  Apogee {:.0} km  |  a {:.0} km  |  e {:.4}\n\
+                 Inc {:.2}°  |  RAAN {:.2}°  |  Arg. perigee {:.2}°  |  Period {:.1} min",
+                satellite.name.trim(),
+                a * (1.0 - e) - constants.earth_radius, a * (1.0 + e) - constants.earth_radius, a, e,
+                elements.inclination.to_degrees(), elements.raan.to_degrees(),
+                elements.argument_of_perigee.to_degrees(), period / 60.0,
+            ))
+        })
+        .unwrap_or_default();
+    if text.0 != readout {
+        text.0 = readout;
+    }
+}
@@ -0,0 +1,54 @@
+// Simulation metrics published through Bevy's diagnostics
+
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy::prelude::*;
+use crate::components::*;
+use crate::systems::collision::{CollisionPairs, SpatialOctree};
+use crate::systems::optimized_physics::OptimizedPhysicsData;
+use crate::systems::stress_test::StageTimings;
+
+pub const OBJECT_COUNT: DiagnosticPath = DiagnosticPath::const_new("kessler/objects");
+pub const DEBRIS_COUNT: DiagnosticPath = DiagnosticPath::const_new("kessler/debris");
+pub const COLLISION_CHECKS: DiagnosticPath = DiagnosticPath::const_new("kessler/collision_checks");
+pub const PREFILTERED_PAIRS: DiagnosticPath = DiagnosticPath::const_new("kessler/prefiltered_pairs");
+pub const OCTREE_BUILD_TIME: DiagnosticPath = DiagnosticPath::const_new("kessler/octree_build_ms");
+pub const PHYSICS_STEP_TIME: DiagnosticPath = DiagnosticPath::const_new("kessler/physics_step_ms");
+pub const COLLISION_STAGE_TIME: DiagnosticPath = DiagnosticPath::const_new("kessler/collision_ms");
+
+/// Plugin registering the simulation diagnostics
+pub struct SimulationDiagnosticsPlugin;
+
+impl Plugin for SimulationDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(OBJECT_COUNT).with_suffix(" objects"))
+            .register_diagnostic(Diagnostic::new(DEBRIS_COUNT).with_suffix(" fragments"))
+            .register_diagnostic(Diagnostic::new(COLLISION_CHECKS).with_suffix(" pairs"))
+            .register_diagnostic(Diagnostic::new(PREFILTERED_PAIRS).with_suffix(" pairs"))
+            .register_diagnostic(Diagnostic::new(OCTREE_BUILD_TIME).with_suffix("ms"))
+            .register_diagnostic(Diagnostic::new(PHYSICS_STEP_TIME).with_suffix("ms"))
+            .register_diagnostic(Diagnostic::new(COLLISION_STAGE_TIME).with_suffix("ms"));
+    }
+}
+
+/// System to record this frame's simulation metrics
+pub fn simulation_diagnostics_system(
+    mut diagnostics: Diagnostics,
+    objects: Query<Has<Debris>, With<PhysicsObject>>,
+    collision_pairs: Res<CollisionPairs>,
+    octree: Res<SpatialOctree>,
+    optimized_data: Res<OptimizedPhysicsData>,
+    timings: Res<StageTimings>,
+) {
+    let (total, debris) = objects.iter().fold((0, 0), |(total, debris), is_debris| {
+        (total + 1, debris + is_debris as usize)
+    });
+    diagnostics.add_measurement(&OBJECT_COUNT, || total as f64);
+    diagnostics.add_measurement(&DEBRIS_COUNT, || debris as f64);
+    diagnostics.add_measurement(&COLLISION_CHECKS, || collision_pairs.checks as f64);
+    diagnostics.add_measurement(&PREFILTERED_PAIRS, || collision_pairs.prefiltered as f64);
+    diagnostics.add_measurement(&OCTREE_BUILD_TIME, || octree.stats.build_ms as f64);
+    diagnostics.add_measurement(&PHYSICS_STEP_TIME, || optimized_data.step_ms as f64);
+    if let Some(&ms) = timings.last_ms.get("collision") {
+        diagnostics.add_measurement(&COLLISION_STAGE_TIME, || ms as f64);
+    }
+}
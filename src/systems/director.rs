@@ -0,0 +1,81 @@
+// Director mode: automatic camera cuts to imminent conjunctions
+
+use bevy::prelude::*;
+use crate::resources::*;
+use crate::systems::conjunctions::ConjunctionForecast;
+use crate::systems::rendering::{CameraRig, FlyCamera};
+
+/// Resource controlling the automatic conjunction camera
+#[derive(Resource)]
+pub struct DirectorMode {
+    pub enabled: bool,
+    /// Cut to a conjunction this many simulated seconds before TCA
+    pub lead_time: f64,
+    /// Simulated seconds to hold the shot after TCA
+    pub hold_time: f64,
+    /// Pair currently framed and when the shot ends (simulation time)
+    pub shot: Option<(Entity, Entity, f64)>,
+}
+
+impl Default for DirectorMode {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lead_time: 120.0,
+            hold_time: 30.0,
+            shot: None,
+        }
+    }
+}
+
+/// System to toggle director mode
+pub fn director_control_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut director: ResMut<DirectorMode>,
+    mut rig: ResMut<CameraRig>,
+    fly: Res<FlyCamera>,
+) {
+    // Toggle director mode with 'D' key ('D' strafes while free-flying)
+    if keyboard.just_pressed(KeyCode::KeyD) && !fly.enabled {
+        director.enabled = !director.enabled;
+        if !director.enabled && director.shot.take().is_some() {
+            rig.release();
+        }
+        info!("Director mode {}", if director.enabled { "ENABLED" } else { "DISABLED" });
+    }
+}
+
+/// System to frame the highest-risk conjunction shortly before its TCA
+pub fn director_system(
+    mut director: ResMut<DirectorMode>,
+    mut forecast: ResMut<ConjunctionForecast>,
+    mut rig: ResMut<CameraRig>,
+    sim_time: Res<SimulationTime>,
+) {
+    if !director.enabled {
+        return;
+    }
+    forecast.requested = true;
+
+    // Hold the current shot until the encounter has passed or the user takes over the camera
+    if let Some((primary, secondary, ends_at)) = director.shot {
+        let still_framed = rig.follow == Some(primary) && rig.companion == Some(secondary);
+        if still_framed && sim_time.current <= ends_at {
+            return;
+        }
+        director.shot = None;
+        if still_framed {
+            rig.release();
+        }
+    }
+
+    let Some(conjunction) = forecast.highest_risk_before(sim_time.current + director.lead_time) else {
+        return;
+    };
+    let conjunction = *conjunction;
+
+    rig.frame_pair(conjunction.primary, conjunction.secondary);
+    director.shot = Some((conjunction.primary, conjunction.secondary, conjunction.tca + director.hold_time));
+    info!("Director: framing conjunction in {:.0}s ({:.2} km miss)",
+          conjunction.tca - sim_time.current, conjunction.miss_distance);
+}
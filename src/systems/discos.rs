@@ -0,0 +1,157 @@
+// Optional ESA DISCOS client: catalogued shapes, dimensions and dry masses replace the estimated ones
+
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Mutex;
+use crate::components::*;
+use crate::resources::wall_clock_unix_seconds;
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+use crate::systems::stress_test::StressTestObject;
+use crate::utils::*;
+
+/// Environment variable holding the DISCOSweb API token
+pub const DISCOS_TOKEN_VAR: &str = "DISCOS_TOKEN";
+/// Objects looked up per request, one DISCOSweb page
+pub const DISCOS_BATCH_SIZE: usize = 100;
+/// Wall-clock seconds between requests, within DISCOSweb's limit of 20 a minute
+const DISCOS_REQUEST_INTERVAL: f64 = 5.0;
+
+/// DISCOS descriptions fetched on a background thread
+type DiscosDownload = Mutex<Receiver<Result<Vec<DiscosObject>, String>>>;
+
+/// Resource looking cataloged objects up in DISCOS in batches on a background thread
+#[derive(Resource, Default)]
+pub struct DiscosClient {
+    /// API token; requests are only made with one
+    pub token: Option<String>,
+    /// Objects described by DISCOS so far
+    pub described: usize,
+    /// NORAD IDs already asked for
+    requested: HashSet<u32>,
+    in_flight: Option<DiscosDownload>,
+    last_request: f64,
+}
+
+impl DiscosClient {
+    pub fn new(token: Option<String>) -> Self {
+        Self { token, ..default() }
+    }
+}
+
+impl From<&DiscosObject> for ObjectProperties {
+    fn from(object: &DiscosObject) -> Self {
+        let dimensions = [object.height, object.width, object.depth];
+        let dimensions = dimensions.iter().any(Option::is_some).then(|| {
+            // Round bodies give only a diameter for their missing dimensions
+            dimensions.map(|dimension| dimension.or(object.diameter).unwrap_or(0.0))
+        });
+        Self {
+            shape: object.shape.clone(),
+            dry_mass: object.mass,
+            dimensions: dimensions.or(object.diameter.map(|diameter| [diameter; 3])),
+            span: object.span,
+            cross_section: object.cross_section,
+        }
+    }
+}
+
+/// System to request DISCOS descriptions of the cataloged objects and apply them as they arrive
+///
+/// The dry mass replaces the estimated mass, the largest dimension sets the collision sphere and the average
+/// cross-section the drag area, so collisions and breakups use the catalogued objects.
+#[allow(clippy::type_complexity)]
+pub fn discos_system(
+    mut commands: Commands,
+    mut client: ResMut<DiscosClient>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+    mut satellites: Query<
+        (Entity, &Satellite, &mut OrbitalState, &mut PhysicsObject, Option<&OptimizedPhysics>),
+        (Without<ObjectProperties>, Without<StressTestObject>),
+    >,
+) {
+    let Some(token) = client.token.clone() else {
+        return;
+    };
+
+    if let Some(in_flight) = &client.in_flight {
+        let result = match in_flight.lock().unwrap().try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err("request thread stopped".to_string())),
+        };
+        match result {
+            None => return,
+            Some(Ok(objects)) => {
+                let properties: HashMap<u32, ObjectProperties> = objects.iter()
+                    .map(|object| (object.norad_id, ObjectProperties::from(object)))
+                    .collect();
+                let mut described = 0;
+                for (entity, satellite, mut state, mut physics, optimized) in satellites.iter_mut() {
+                    let Some(properties) = properties.get(&satellite.norad_id) else {
+                        continue;
+                    };
+                    if let Some(mass) = properties.dry_mass {
+                        state.mass = mass;
+                        if let Some(optimized) = optimized {
+                            optimized_data.set_mass(optimized.index, mass);
+                        }
+                    }
+                    if let Some(length) = properties.characteristic_length() {
+                        let radius = length / 2.0;
+                        let cross_section = properties.cross_section.unwrap_or(std::f64::consts::PI * radius * radius);
+                        *physics = PhysicsObject::new(cross_section, physics.drag_coefficient, radius, state.mass);
+                    }
+                    if !properties.shape.is_empty() {
+                        debug!("{} (NORAD {}) is shaped {}", satellite.name, satellite.norad_id, properties.shape);
+                    }
+                    commands.entity(entity).try_insert(properties.clone());
+                    described += 1;
+                }
+                client.described += described;
+                info!("DISCOS described {} more objects ({} in all)", described, client.described);
+                client.in_flight = None;
+            }
+            Some(Err(e)) => {
+                warn!("DISCOS requests stopped: {}", e);
+                client.token = None;
+                client.in_flight = None;
+                return;
+            }
+        }
+    }
+
+    let now = wall_clock_unix_seconds();
+    if now - client.last_request < DISCOS_REQUEST_INTERVAL {
+        return;
+    }
+    let batch: Vec<u32> = satellites.iter()
+        .map(|(_, satellite, ..)| satellite.norad_id)
+        .filter(|norad_id| !client.requested.contains(norad_id))
+        .take(DISCOS_BATCH_SIZE)
+        .collect();
+    if batch.is_empty() {
+        return;
+    }
+    client.requested.extend(&batch);
+    client.last_request = now;
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let result = rt.block_on(async {
+            let response = reqwest::Client::new()
+                .get(discos_objects_url(&batch))
+                .bearer_auth(token)
+                .header("DiscosWeb-Api-Version", DISCOS_API_VERSION)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            let status = response.status();
+            let text = response.text().await.map_err(|e| e.to_string())?;
+            parse_discos_objects(&text).map_err(|e| if status.is_success() { e } else { format!("HTTP {}: {}", status, e) })
+        });
+        let _ = tx.send(result);
+    });
+    client.in_flight = Some(Mutex::new(rx));
+}
@@ -0,0 +1,98 @@
+// Atmospheric drag on low orbits
+
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+use crate::utils::*;
+
+/// System to decelerate objects inside the atmosphere so their orbits decay and eventually reenter
+pub fn atmospheric_drag_system(
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    space_weather: Res<SpaceWeather>,
+    mut objects: Query<(&mut OrbitalState, &PhysicsObject, Option<&OptimizedPhysics>)>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+    mut last_time: Local<Option<f64>>,
+) {
+    let elapsed = last_time.map(|t| sim_time.current - t).unwrap_or(0.0);
+    *last_time = Some(sim_time.current);
+
+    if sim_time.paused || elapsed <= 0.0 {
+        return;
+    }
+
+    let exospheric_temperature = space_weather.exospheric_temperature();
+    for (mut state, physics, optimized) in objects.iter_mut() {
+        let altitude = state.position.length() - constants.earth_radius;
+        if altitude > DRAG_CEILING_KM || physics.ballistic_coefficient() <= 0.0 {
+            continue;
+        }
+        let density = thermosphere_density(altitude, exospheric_temperature);
+        let acceleration = drag_acceleration(state.position, state.velocity, physics.ballistic_coefficient(), density,
+                                             constants.earth_rotation_rate);
+        // Long frames deep in the atmosphere must not reverse the object's motion
+        let delta_v = (acceleration * elapsed).clamp_length_max(state.velocity.length());
+        state.velocity += delta_v;
+        if let Some(optimized) = optimized {
+            optimized_data.change_velocity(optimized.index, delta_v);
+        }
+    }
+}
+
+/// Simulated seconds between lifetime estimates of a debris fragment
+pub const DEBRIS_LIFETIME_REFRESH: f64 = 3600.0;
+
+/// System to estimate each low-perigee fragment's drag lifetime and remove the fragment once it has run out
+///
+/// The estimate is the King-Hele lifetime of a circular orbit at the fragment's perigee, which bounds the
+/// lifetime of an eccentric orbit from below. Fragments are removed without a reentry event, since they are
+/// still in orbit when their time is up, and are tallied in `EnergyAnalytics::decayed_debris`.
+#[allow(clippy::type_complexity)]
+pub fn debris_decay_system(
+    mut commands: Commands,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    space_weather: Res<SpaceWeather>,
+    mut analytics: ResMut<EnergyAnalytics>,
+    debris: Query<(Entity, &OrbitalState, &PhysicsObject, Option<&AltitudeBand>, Option<&SuperParticle>,
+                   Option<&DecayLifetime>), With<Debris>>,
+) {
+    if sim_time.paused {
+        return;
+    }
+    let mu_km = constants.gravitational_parameter / 1e9;
+    let reentry_radius = constants.earth_radius + constants.reentry_altitude;
+    let density = |altitude: f64| space_weather.density(altitude);
+
+    for (entity, state, physics, band, super_particle, lifetime) in debris.iter() {
+        if let Some(lifetime) = lifetime {
+            if sim_time.current >= lifetime.decays_at {
+                analytics.decayed_debris += super_particle.map(|sp| sp.weight).unwrap_or(1) as usize;
+                commands.entity(entity).despawn();
+                continue;
+            }
+            // Time running backwards (a replay or reset) invalidates the estimate
+            if (0.0..DEBRIS_LIFETIME_REFRESH).contains(&(sim_time.current - lifetime.estimated_at)) {
+                continue;
+            }
+        }
+
+        let perigee = band.map(|band| band.perigee)
+            .unwrap_or_else(|| AltitudeBand::from_state(state.position, state.velocity, mu_km).perigee);
+        let decays_at = if perigee - constants.earth_radius > DRAG_CEILING_KM || physics.ballistic_coefficient() <= 0.0 {
+            f64::INFINITY
+        } else {
+            sim_time.current + circular_lifetime(perigee.max(reentry_radius), reentry_radius, constants.earth_radius,
+                                                 physics.ballistic_coefficient(), mu_km, &density)
+        };
+        commands.entity(entity).try_insert(DecayLifetime { decays_at, estimated_at: sim_time.current });
+    }
+}
+
+/// System to move the space weather along the solar cycle as the simulation date advances
+pub fn solar_cycle_system(sim_time: Res<SimulationTime>, mut space_weather: ResMut<SpaceWeather>) {
+    if space_weather.cycle {
+        space_weather.set_if_neq(SpaceWeather::at_cycle_phase(sim_time.current_unix()));
+    }
+}
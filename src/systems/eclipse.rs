@@ -0,0 +1,117 @@
+// Earth shadow (umbra/penumbra) and solar radiation pressure
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+use crate::utils::*;
+
+/// Mean radius of the Sun (km)
+pub const SUN_RADIUS_KM: f64 = 696_000.0;
+/// Solar radiation pressure on an absorbing surface at 1 AU (N/m²)
+pub const SOLAR_PRESSURE: f64 = 4.56e-6;
+/// Radiation pressure coefficient (1 = absorbing, 2 = mirror)
+pub const REFLECTIVITY_COEFFICIENT: f64 = 1.3;
+
+/// Eclipse state of an object at `position` (km) with the Sun at `sun` (km), using a conical shadow
+pub fn shadow_state(position: DVec3, sun: DVec3, earth_radius_km: f64) -> Eclipse {
+    let to_sun = sun - position;
+    let distance = position.length();
+    if distance <= earth_radius_km {
+        return Eclipse { state: EclipseState::Umbra, sunlight: 0.0 };
+    }
+
+    // Apparent radii of the Sun and Earth, and their angular separation, as seen from the object
+    let sun_radius = (SUN_RADIUS_KM / to_sun.length()).asin();
+    let earth_radius = (earth_radius_km / distance).asin();
+    let separation = (-position).angle_between(to_sun);
+
+    let sunlight = if separation >= sun_radius + earth_radius {
+        1.0
+    } else if separation <= earth_radius - sun_radius {
+        0.0
+    } else if separation <= sun_radius - earth_radius {
+        // Annular: the Earth disk lies inside the Sun disk
+        1.0 - (earth_radius * earth_radius) / (sun_radius * sun_radius)
+    } else {
+        // Partial overlap of two disks
+        let x = (separation * separation + sun_radius * sun_radius - earth_radius * earth_radius) / (2.0 * separation);
+        let y = (sun_radius * sun_radius - x * x).max(0.0).sqrt();
+        let overlap = sun_radius * sun_radius * (x / sun_radius).clamp(-1.0, 1.0).acos()
+            + earth_radius * earth_radius * ((separation - x) / earth_radius).clamp(-1.0, 1.0).acos()
+            - separation * y;
+        1.0 - overlap / (std::f64::consts::PI * sun_radius * sun_radius)
+    };
+
+    let state = if sunlight >= 1.0 {
+        EclipseState::Sunlit
+    } else if sunlight <= 0.0 {
+        EclipseState::Umbra
+    } else {
+        EclipseState::Penumbra
+    };
+    Eclipse { state, sunlight: sunlight.clamp(0.0, 1.0) as f32 }
+}
+
+/// Cannonball SRP acceleration (km/s²) pushing an object away from the Sun
+pub fn solar_radiation_pressure(position: DVec3, sun: DVec3, area_m2: f64, mass_kg: f64, sunlight: f32) -> DVec3 {
+    if mass_kg <= 0.0 {
+        return DVec3::ZERO;
+    }
+    let from_sun = position - sun;
+    let distance_au = from_sun.length() / ASTRONOMICAL_UNIT_KM;
+    let pressure = SOLAR_PRESSURE / (distance_au * distance_au);
+    // m/s² to km/s²
+    let magnitude = pressure * REFLECTIVITY_COEFFICIENT * area_m2 / mass_kg * sunlight as f64 / 1000.0;
+    from_sun.normalize_or_zero() * magnitude
+}
+
+/// System to keep every object's eclipse state up to date
+pub fn eclipse_system(
+    mut commands: Commands,
+    constants: Res<Constants>,
+    sim_time: Res<SimulationTime>,
+    mut objects: Query<(Entity, &OrbitalState, Option<&mut Eclipse>)>,
+) {
+    let sun = sun_position(sim_time.current_unix());
+    for (entity, state, eclipse) in objects.iter_mut() {
+        let current = shadow_state(state.position, sun, constants.earth_radius);
+        match eclipse {
+            Some(mut eclipse) => {
+                eclipse.set_if_neq(current);
+            }
+            None => {
+                commands.entity(entity).try_insert(current);
+            }
+        }
+    }
+}
+
+/// System to apply solar radiation pressure to sunlit objects
+pub fn solar_radiation_pressure_system(
+    sim_time: Res<SimulationTime>,
+    mut objects: Query<(&mut OrbitalState, &PhysicsObject, &Eclipse, Option<&OptimizedPhysics>)>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+    mut last_time: Local<Option<f64>>,
+) {
+    let elapsed = last_time.map(|t| sim_time.current - t).unwrap_or(0.0);
+    *last_time = Some(sim_time.current);
+
+    if sim_time.paused || elapsed <= 0.0 {
+        return;
+    }
+
+    let sun = sun_position(sim_time.current_unix());
+    for (mut state, physics, eclipse, optimized) in objects.iter_mut() {
+        if eclipse.sunlight <= 0.0 {
+            continue;
+        }
+        let acceleration = solar_radiation_pressure(state.position, sun, physics.cross_section, state.mass, eclipse.sunlight);
+        let delta_v = acceleration * elapsed;
+        state.velocity += delta_v;
+        if let Some(optimized) = optimized {
+            optimized_data.change_velocity(optimized.index, delta_v);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+// External ephemeris import: STK .e files driving individual objects
+
+use bevy::prelude::*;
+use std::path::{Path, PathBuf};
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+use crate::utils::*;
+
+/// Directory scanned for STK ephemeris files at startup
+pub const EPHEMERIS_DIRECTORY: &str = "assets/ephemerides";
+
+/// Component driving an object's state from an imported ephemeris while inside its span
+#[derive(Component)]
+pub struct ImportedEphemeris {
+    pub ephemeris: StkEphemeris,
+}
+
+/// Resource holding ephemeris files waiting to be attached to objects
+#[derive(Resource, Default)]
+pub struct EphemerisImports {
+    pub pending: Vec<PathBuf>,
+}
+
+impl EphemerisImports {
+    /// Files given on the command line plus any .e files in the ephemeris directory
+    pub fn from_sources(cli_paths: &[PathBuf]) -> Self {
+        let mut pending = cli_paths.to_vec();
+        if let Ok(entries) = std::fs::read_dir(EPHEMERIS_DIRECTORY) {
+            let mut found: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("e")))
+                .collect();
+            found.sort();
+            pending.extend(found);
+        }
+        Self { pending }
+    }
+}
+
+/// NORAD ID encoded at the start of an ephemeris file name, e.g. "25544_iss.e"
+pub fn norad_id_from_path(path: &Path) -> Option<u32> {
+    let stem = path.file_stem()?.to_str()?;
+    let digits: String = stem.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// System to attach pending ephemerides to cataloged objects, spawning new objects for unknown IDs
+pub fn ephemeris_import_system(
+    mut commands: Commands,
+    mut imports: ResMut<EphemerisImports>,
+    sim_time: Res<SimulationTime>,
+    satellites: Query<(Entity, &Satellite)>,
+) {
+    // Wait for the catalog so ephemerides can be matched to existing objects
+    if imports.pending.is_empty() || satellites.is_empty() {
+        return;
+    }
+
+    for path in std::mem::take(&mut imports.pending) {
+        let ephemeris = match std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| parse_stk_ephemeris(&text).map_err(|e| e.to_string()))
+        {
+            Ok(ephemeris) => ephemeris,
+            Err(e) => {
+                warn!("Skipping ephemeris {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let Some((start, end)) = ephemeris.span() else {
+            continue;
+        };
+        info!(
+            "Loaded {} ephemeris samples ({}) from {} covering {}Z to {}Z",
+            ephemeris.samples.len(), ephemeris.coordinate_system, path.display(),
+            unix_to_iso8601(start), unix_to_iso8601(end),
+        );
+
+        let norad_id = norad_id_from_path(&path);
+        let existing = norad_id.and_then(|id| satellites.iter().find(|(_, s)| s.norad_id == id));
+        let component = ImportedEphemeris { ephemeris };
+
+        if let Some((entity, satellite)) = existing {
+            info!("Ephemeris {} now drives {}", path.display(), satellite.name.trim());
+            commands.entity(entity).insert(component);
+            continue;
+        }
+
+        let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("EPHEMERIS").to_uppercase();
+        let (position, velocity) = component.ephemeris
+            .interpolate(sim_time.current_unix())
+            .unwrap_or_else(|| {
+                let (_, position, velocity) = component.ephemeris.samples[0];
+                (position, velocity)
+            });
+        info!("Spawning {} from ephemeris {}", name, path.display());
+        commands.spawn((
+            Satellite::new(name.clone(), norad_id.unwrap_or(0), true),
+            Operator::from_satellite_name(&name),
+            OrbitalState::new(position, velocity, 1000.0),
+            PhysicsObject::satellite(1000.0),
+            Health::default(),
+            RenderAsSatellite,
+            component,
+        ));
+    }
+}
+
+/// System to overwrite propagated states with the imported ephemeris inside its span
+pub fn ephemeris_drive_system(
+    sim_time: Res<SimulationTime>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+    mut driven: Query<(&ImportedEphemeris, &mut OrbitalState, Option<&OptimizedPhysics>)>,
+) {
+    let now = sim_time.current_unix();
+    for (imported, mut orbital_state, optimized) in driven.iter_mut() {
+        // Outside the span the object falls back to the simulator's own propagation
+        let Some((position, velocity)) = imported.ephemeris.interpolate(now) else {
+            continue;
+        };
+        orbital_state.position = position;
+        orbital_state.velocity = velocity;
+        if let Some(optimized) = optimized {
+            optimized_data.set_state(optimized.index, position, velocity);
+        }
+    }
+}
@@ -0,0 +1,573 @@
+// Trajectory export for external analysis tools
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::collision::CollisionEvent;
+use crate::systems::physics::ReentryEvent;
+use crate::systems::selection::Selection;
+use crate::utils::*;
+
+/// Resource controlling trajectory exports
+#[derive(Resource)]
+pub struct ExportConfig {
+    /// Directory export files are written to
+    pub directory: PathBuf,
+    /// Span of exported ephemerides (seconds from the current simulation time)
+    pub span: f64,
+    /// Interval between exported states (seconds)
+    pub step: f64,
+    /// Simulated seconds between catalog checkpoints; none writes catalogs only on request
+    pub checkpoint_interval: Option<f64>,
+    /// Simulation time of the last catalog checkpoint
+    pub last_checkpoint: f64,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("exports"),
+            span: 86400.0,
+            step: 60.0,
+            checkpoint_interval: None,
+            last_checkpoint: 0.0,
+        }
+    }
+}
+
+/// Maximum number of logged impact/reentry locations
+const MAX_EVENT_LOCATIONS: usize = 10_000;
+
+/// Kind of a logged event location
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LocationKind {
+    Reentry,
+    Collision,
+}
+
+impl LocationKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LocationKind::Reentry => "Reentry",
+            LocationKind::Collision => "Collision",
+        }
+    }
+}
+
+/// Where and when an object reentered or collided
+#[derive(Clone, Debug)]
+pub struct EventLocation {
+    pub kind: LocationKind,
+    pub entity: Entity,
+    pub name: String,
+    pub norad_id: Option<u32>,
+    /// Position of the event (km, ECI)
+    pub position: DVec3,
+    /// Unix time of the event
+    pub unix_time: f64,
+}
+
+/// Resource logging impact and reentry locations for ground-track exports
+#[derive(Resource, Default)]
+pub struct EventLocations {
+    pub locations: Vec<EventLocation>,
+}
+
+impl EventLocations {
+    fn push(&mut self, location: EventLocation) {
+        if self.locations.len() >= MAX_EVENT_LOCATIONS {
+            self.locations.remove(0);
+        }
+        self.locations.push(location);
+    }
+
+    /// Logged events involving an object, matched by entity or NORAD ID
+    pub fn for_object(&self, entity: Entity, norad_id: u32) -> impl Iterator<Item = &EventLocation> {
+        self.locations
+            .iter()
+            .filter(move |l| l.entity == entity || (norad_id != 0 && l.norad_id == Some(norad_id)))
+    }
+}
+
+/// Ground-track export formats
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroundTrackFormat {
+    Kml,
+    GeoJson,
+}
+
+impl GroundTrackFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            GroundTrackFormat::Kml => "kml",
+            GroundTrackFormat::GeoJson => "geojson",
+        }
+    }
+}
+
+/// One object's ground track and event locations, ready for export
+pub struct GroundTrack {
+    pub name: String,
+    pub norad_id: u32,
+    /// Track segments of (longitude, latitude, altitude km), split at the antimeridian
+    pub segments: Vec<Vec<(f64, f64, f64)>>,
+    pub events: Vec<EventLocation>,
+}
+
+/// Convert ECI samples into geodetic track segments, splitting where the track crosses ±180°
+pub fn ground_track_segments(states: &[(f64, DVec3, DVec3)], earth_radius_km: f64) -> Vec<Vec<(f64, f64, f64)>> {
+    let mut segments: Vec<Vec<(f64, f64, f64)>> = Vec::new();
+    let mut current: Vec<(f64, f64, f64)> = Vec::new();
+    for (time, position, _) in states {
+        let (latitude, longitude, altitude) = eci_to_geodetic(*position, *time, earth_radius_km);
+        if let Some(&(previous_longitude, _, _)) = current.last() {
+            if (longitude - previous_longitude).abs() > 180.0 {
+                segments.push(std::mem::take(&mut current));
+            }
+        }
+        current.push((longitude, latitude, altitude));
+    }
+    if current.len() > 1 {
+        segments.push(current);
+    }
+    segments.retain(|segment| segment.len() > 1);
+    segments
+}
+
+/// Escape text for inclusion in XML
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Render ground tracks and event locations as a KML document
+pub fn format_kml(tracks: &[GroundTrack], earth_radius_km: f64) -> String {
+    let mut kml = String::new();
+    let _ = writeln!(kml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(kml, r#"<kml xmlns="http://www.opengis.net/kml/2.2">"#);
+    let _ = writeln!(kml, "<Document>");
+    let _ = writeln!(kml, "<name>Kessler ground tracks</name>");
+    for track in tracks {
+        let name = xml_escape(&track.name);
+        let _ = writeln!(kml, "<Placemark>");
+        let _ = writeln!(kml, "<name>{} ({})</name>", name, track.norad_id);
+        let _ = writeln!(kml, "<MultiGeometry>");
+        for segment in &track.segments {
+            let _ = writeln!(kml, "<LineString><tessellate>1</tessellate><coordinates>");
+            for (longitude, latitude, _) in segment {
+                let _ = writeln!(kml, "{:.5},{:.5},0", longitude, latitude);
+            }
+            let _ = writeln!(kml, "</coordinates></LineString>");
+        }
+        let _ = writeln!(kml, "</MultiGeometry>");
+        let _ = writeln!(kml, "</Placemark>");
+
+        for event in &track.events {
+            let (latitude, longitude, altitude) = eci_to_geodetic(event.position, event.unix_time, earth_radius_km);
+            let _ = writeln!(kml, "<Placemark>");
+            let _ = writeln!(kml, "<name>{}: {}</name>", event.kind.label(), xml_escape(&event.name));
+            let _ = writeln!(kml, "<TimeStamp><when>{}Z</when></TimeStamp>", unix_to_iso8601(event.unix_time));
+            let _ = writeln!(kml, "<description>Altitude {:.1} km</description>", altitude);
+            let _ = writeln!(kml, "<Point><coordinates>{:.5},{:.5},0</coordinates></Point>", longitude, latitude);
+            let _ = writeln!(kml, "</Placemark>");
+        }
+    }
+    let _ = writeln!(kml, "</Document>");
+    let _ = writeln!(kml, "</kml>");
+    kml
+}
+
+/// Render ground tracks and event locations as a GeoJSON feature collection
+pub fn format_geojson(tracks: &[GroundTrack], earth_radius_km: f64) -> String {
+    let mut features = Vec::new();
+    for track in tracks {
+        let lines: Vec<Vec<[f64; 2]>> = track.segments
+            .iter()
+            .map(|segment| segment.iter().map(|&(longitude, latitude, _)| [longitude, latitude]).collect())
+            .collect();
+        features.push(serde_json::json!({
+            "type": "Feature",
+            "geometry": { "type": "MultiLineString", "coordinates": lines },
+            "properties": { "name": track.name, "norad_id": track.norad_id, "kind": "ground_track" },
+        }));
+
+        for event in &track.events {
+            let (latitude, longitude, altitude) = eci_to_geodetic(event.position, event.unix_time, earth_radius_km);
+            features.push(serde_json::json!({
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [longitude, latitude] },
+                "properties": {
+                    "name": event.name,
+                    "norad_id": event.norad_id,
+                    "kind": event.kind.label().to_lowercase(),
+                    "time": format!("{}Z", unix_to_iso8601(event.unix_time)),
+                    "altitude_km": altitude,
+                },
+            }));
+        }
+    }
+    let collection = serde_json::json!({ "type": "FeatureCollection", "features": features });
+    serde_json::to_string_pretty(&collection).unwrap_or_default()
+}
+
+/// Sample a two-body trajectory as (unix time, position km, velocity km/s)
+pub fn sample_trajectory(
+    orbital_state: &OrbitalState,
+    start_unix: f64,
+    span: f64,
+    step: f64,
+    mu_km: f64,
+) -> Vec<(f64, DVec3, DVec3)> {
+    let count = (span / step).floor().max(0.0) as usize;
+    (0..=count)
+        .map(|i| {
+            let t = i as f64 * step;
+            let (position, velocity) = propagate_kepler(orbital_state.position, orbital_state.velocity, t, mu_km);
+            (start_unix + t, position, velocity)
+        })
+        .collect()
+}
+
+/// Render a CCSDS OEM 2.0 (KVN) ephemeris message
+pub fn format_oem(object_name: &str, object_id: &str, states: &[(f64, DVec3, DVec3)]) -> String {
+    let mut oem = String::new();
+    let (Some(first), Some(last)) = (states.first(), states.last()) else {
+        return oem;
+    };
+
+    let _ = writeln!(oem, "CCSDS_OEM_VERS = 2.0");
+    let _ = writeln!(oem, "CREATION_DATE = {}", unix_to_iso8601(wall_clock_unix_seconds()));
+    let _ = writeln!(oem, "ORIGINATOR = KESSLER SIMULATOR");
+    let _ = writeln!(oem);
+    let _ = writeln!(oem, "META_START");
+    let _ = writeln!(oem, "OBJECT_NAME = {}", object_name);
+    let _ = writeln!(oem, "OBJECT_ID = {}", object_id);
+    let _ = writeln!(oem, "CENTER_NAME = EARTH");
+    let _ = writeln!(oem, "REF_FRAME = EME2000");
+    let _ = writeln!(oem, "TIME_SYSTEM = UTC");
+    let _ = writeln!(oem, "START_TIME = {}", unix_to_iso8601(first.0));
+    let _ = writeln!(oem, "STOP_TIME = {}", unix_to_iso8601(last.0));
+    let _ = writeln!(oem, "META_STOP");
+    let _ = writeln!(oem);
+    let _ = writeln!(oem, "COMMENT Two-body propagation from the simulator state, units km and km/s");
+    for (time, position, velocity) in states {
+        let _ = writeln!(
+            oem,
+            "{} {:.6} {:.6} {:.6} {:.9} {:.9} {:.9}",
+            unix_to_iso8601(*time),
+            position.x, position.y, position.z,
+            velocity.x, velocity.y, velocity.z,
+        );
+    }
+    oem
+}
+
+/// First catalog number assigned to objects without one (TLE analyst range)
+const SYNTHETIC_NORAD_START: u32 = 80000;
+
+/// One object of an exported element catalog
+pub struct CatalogEntry {
+    pub name: String,
+    pub norad_id: u32,
+    pub international_designator: String,
+    /// SGP4 mean elements at the catalog epoch
+    pub elements: OrbitalElements,
+    /// SGP4 drag term (1/Earth radii)
+    pub bstar: f64,
+}
+
+/// Render a three-line element set from SGP4 mean elements at `epoch_unix`
+///
+/// The mean motion derivatives are left at zero; SGP4 models drag through B* alone.
+pub fn format_tle(entry: &CatalogEntry, epoch_unix: f64, mu_km: f64) -> String {
+    let (year, epoch_day) = tle_epoch(epoch_unix);
+    let elements = &entry.elements;
+    let mut record = TleRecord {
+        name: entry.name.clone(),
+        norad_id: entry.norad_id,
+        classification: 'U',
+        international_designator: entry.international_designator.clone(),
+        epoch_year: year as u32,
+        epoch_day,
+        mean_motion_dot: 0.0,
+        mean_motion_ddot: 0.0,
+        bstar: entry.bstar,
+        inclination: elements.inclination.to_degrees(),
+        right_ascension: elements.raan.to_degrees(),
+        eccentricity: elements.eccentricity,
+        argument_of_perigee: elements.argument_of_perigee.to_degrees(),
+        mean_anomaly: elements.mean_anomaly.to_degrees(),
+        mean_motion: elements.mean_motion_rev_per_day(mu_km),
+        revolution_number: 0,
+        line1: String::new(),
+        line2: String::new(),
+    };
+    record.format_element_lines(0, 999);
+    format!("{}\n{}\n{}\n", record.name, record.line1, record.line2)
+}
+
+/// Render a catalog as CCSDS OMM records in the JSON layout used by CelesTrak
+pub fn format_omm_json(entries: &[CatalogEntry], epoch_unix: f64, mu_km: f64) -> String {
+    let epoch = unix_to_iso8601(epoch_unix);
+    let records: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|entry| {
+            let elements = &entry.elements;
+            serde_json::json!({
+                "OBJECT_NAME": entry.name,
+                "OBJECT_ID": entry.international_designator,
+                "EPOCH": epoch,
+                "MEAN_MOTION": elements.mean_motion_rev_per_day(mu_km),
+                "ECCENTRICITY": elements.eccentricity,
+                "INCLINATION": elements.inclination.to_degrees(),
+                "RA_OF_ASC_NODE": elements.raan.to_degrees(),
+                "ARG_OF_PERICENTER": elements.argument_of_perigee.to_degrees(),
+                "MEAN_ANOMALY": elements.mean_anomaly.to_degrees(),
+                "EPHEMERIS_TYPE": 0,
+                "CLASSIFICATION_TYPE": "U",
+                "NORAD_CAT_ID": entry.norad_id,
+                "ELEMENT_SET_NO": 999,
+                "REV_AT_EPOCH": 0,
+                "BSTAR": entry.bstar,
+                "MEAN_MOTION_DOT": 0.0,
+                "MEAN_MOTION_DDOT": 0.0,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&records).unwrap_or_default()
+}
+
+/// File-system friendly version of an object name
+pub fn export_file_stem(name: &str, norad_id: u32) -> String {
+    let cleaned: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    format!("{}_{}", norad_id, cleaned.trim_matches('_'))
+}
+
+/// Write an export file, creating the export directory if needed
+pub fn write_export(directory: &Path, file_name: &str, contents: &str) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+    let path = directory.join(file_name);
+    std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// System to export the selected object's ephemeris as a CCSDS OEM file with 'X'
+pub fn oem_export_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    config: Res<ExportConfig>,
+    selection: Res<Selection>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    objects: Query<(&OrbitalState, Option<&Satellite>)>,
+) {
+    // Shift+X exports the whole catalog instead
+    if !keyboard.just_pressed(KeyCode::KeyX) || keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        return;
+    }
+    let Some((orbital_state, satellite)) = selection.selected.and_then(|entity| objects.get(entity).ok()) else {
+        info!("OEM export: no object selected");
+        return;
+    };
+
+    let (name, norad_id) = satellite
+        .map(|s| (s.name.trim().to_string(), s.norad_id))
+        .unwrap_or_else(|| ("DEBRIS".to_string(), 0));
+    let mu_km = constants.gravitational_parameter / 1e9;
+    let states = sample_trajectory(orbital_state, sim_time.current_unix(), config.span, config.step, mu_km);
+    let oem = format_oem(&name, &norad_id.to_string(), &states);
+
+    let file_name = format!("{}.oem", export_file_stem(&name, norad_id));
+    match write_export(&config.directory, &file_name, &oem) {
+        Ok(path) => info!("Exported {} states for {} to {}", states.len(), name, path.display()),
+        Err(e) => warn!("OEM export failed: {}", e),
+    }
+}
+
+/// System to log reentry and collision locations for ground-track exports
+pub fn event_location_log_system(
+    mut reentries: EventReader<ReentryEvent>,
+    mut collisions: EventReader<CollisionEvent>,
+    sim_time: Res<SimulationTime>,
+    mut log: ResMut<EventLocations>,
+) {
+    for reentry in reentries.read() {
+        log.push(EventLocation {
+            kind: LocationKind::Reentry,
+            entity: reentry.entity,
+            name: reentry.name.as_deref().unwrap_or("DEBRIS").trim().to_string(),
+            norad_id: reentry.norad_id,
+            position: reentry.position,
+            unix_time: sim_time.unix_at(reentry.time),
+        });
+    }
+    for collision in collisions.read() {
+        for (entity, name, norad_id) in [
+            (collision.entities.0, &collision.names.0, collision.norad_ids.0),
+            (collision.entities.1, &collision.names.1, collision.norad_ids.1),
+        ] {
+            log.push(EventLocation {
+                kind: LocationKind::Collision,
+                entity,
+                name: name.trim().to_string(),
+                norad_id,
+                position: collision.position,
+                unix_time: sim_time.unix_at(collision.time),
+            });
+        }
+    }
+}
+
+/// System to export ground tracks of the selected objects as KML ('K') or GeoJSON ('J')
+pub fn ground_track_export_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    config: Res<ExportConfig>,
+    selection: Res<Selection>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    log: Res<EventLocations>,
+    objects: Query<(&OrbitalState, Option<&Satellite>)>,
+) {
+    let format = if keyboard.just_pressed(KeyCode::KeyK) {
+        GroundTrackFormat::Kml
+    } else if keyboard.just_pressed(KeyCode::KeyJ) {
+        GroundTrackFormat::GeoJson
+    } else {
+        return;
+    };
+
+    let mu_km = constants.gravitational_parameter / 1e9;
+    let earth_radius_km = constants.earth_radius;
+    let tracks: Vec<GroundTrack> = [selection.selected, selection.secondary]
+        .into_iter()
+        .flatten()
+        .filter_map(|entity| objects.get(entity).ok().map(|object| (entity, object)))
+        .map(|(entity, (orbital_state, satellite))| {
+            let (name, norad_id) = satellite
+                .map(|s| (s.name.trim().to_string(), s.norad_id))
+                .unwrap_or_else(|| ("DEBRIS".to_string(), 0));
+            let states = sample_trajectory(orbital_state, sim_time.current_unix(), config.span, config.step, mu_km);
+            GroundTrack {
+                segments: ground_track_segments(&states, earth_radius_km),
+                events: log.for_object(entity, norad_id).cloned().collect(),
+                name,
+                norad_id,
+            }
+        })
+        .collect();
+
+    let Some(first) = tracks.first() else {
+        info!("Ground-track export: no object selected");
+        return;
+    };
+
+    let contents = match format {
+        GroundTrackFormat::Kml => format_kml(&tracks, earth_radius_km),
+        GroundTrackFormat::GeoJson => format_geojson(&tracks, earth_radius_km),
+    };
+    let file_name = format!("{}_track.{}", export_file_stem(&first.name, first.norad_id), format.extension());
+    match write_export(&config.directory, &file_name, &contents) {
+        Ok(path) => info!("Exported {} ground track(s) to {}", tracks.len(), path.display()),
+        Err(e) => warn!("Ground-track export failed: {}", e),
+    }
+}
+
+/// System to write the surviving and generated population as TLE and OMM catalogs with Shift+X
+#[allow(clippy::type_complexity)]
+pub fn catalog_export_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    config: Res<ExportConfig>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    objects: Query<(Entity, &OrbitalState, Option<&Satellite>, Option<&TleData>, Option<&Debris>, Option<&PhysicsObject>)>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyX) || !keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        return;
+    }
+    export_catalog(&config, objects.iter(), sim_time.current_unix(), constants.gravitational_parameter / 1e9);
+}
+
+/// System to checkpoint the population as TLE and OMM catalogs every few simulated hours
+#[allow(clippy::type_complexity)]
+pub fn catalog_checkpoint_system(
+    mut config: ResMut<ExportConfig>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    objects: Query<(Entity, &OrbitalState, Option<&Satellite>, Option<&TleData>, Option<&Debris>, Option<&PhysicsObject>)>,
+) {
+    let Some(interval) = config.checkpoint_interval else {
+        return;
+    };
+    // Live mode pulls a clock that ran ahead back to the wall clock; the interval starts over from there
+    if sim_time.current < config.last_checkpoint {
+        config.last_checkpoint = sim_time.current;
+    }
+    if sim_time.current - config.last_checkpoint < interval {
+        return;
+    }
+    config.last_checkpoint = sim_time.current;
+    export_catalog(&config, objects.iter(), sim_time.current_unix(), constants.gravitational_parameter / 1e9);
+}
+
+/// Write every bound object as a TLE catalog and an OMM JSON catalog, both at `epoch_unix`
+///
+/// Each object's state is fitted with SGP4 mean elements and a B* from its ballistic coefficient, so the catalog
+/// propagates back to the simulated states. Objects without a catalog number, such as fragments, are numbered from
+/// the analyst range in entity order.
+fn export_catalog<'a>(
+    config: &ExportConfig,
+    objects: impl Iterator<Item = (Entity, &'a OrbitalState, Option<&'a Satellite>, Option<&'a TleData>, Option<&'a Debris>, Option<&'a PhysicsObject>)>,
+    epoch_unix: f64,
+    mu_km: f64,
+) {
+    let mut sorted: Vec<_> = objects.collect();
+    sorted.sort_by_key(|(entity, ..)| *entity);
+
+    let mut next_synthetic_id = SYNTHETIC_NORAD_START;
+    let mut unfitted = 0;
+    let mut entries = Vec::new();
+    for (_, orbital_state, satellite, tle_data, debris, physics) in sorted {
+        let bstar = physics.map_or(0.0, PhysicsObject::bstar);
+        let Ok(elements) = fit_mean_elements(orbital_state.position, orbital_state.velocity, epoch_unix, bstar, mu_km) else {
+            unfitted += 1;
+            continue;
+        };
+        let norad_id = match satellite.map(|s| s.norad_id).filter(|&id| id != 0) {
+            Some(id) => id,
+            None => {
+                next_synthetic_id += 1;
+                next_synthetic_id - 1
+            }
+        };
+        let name = match (satellite, debris) {
+            (Some(satellite), _) => satellite.name.trim().to_string(),
+            (None, Some(debris)) => format!("KESSLER DEB G{}", debris.generation),
+            (None, None) => "KESSLER OBJECT".to_string(),
+        };
+        let international_designator = tle_data
+            .and_then(|tle| tle.line1.get(9..17))
+            .map(|designator| designator.trim().to_string())
+            .unwrap_or_default();
+        entries.push(CatalogEntry { name, norad_id, international_designator, elements, bstar });
+    }
+
+    let tle: String = entries.iter().map(|entry| format_tle(entry, epoch_unix, mu_km)).collect();
+    let omm = format_omm_json(&entries, epoch_unix, mu_km);
+    let (year, month, day, hour, minute, second) = unix_to_utc(epoch_unix);
+    let stem = format!("catalog_{:04}{:02}{:02}T{:02}{:02}{:02}", year, month, day, hour, minute, second as u32);
+
+    for (extension, contents) in [("tle", &tle), ("json", &omm)] {
+        match write_export(&config.directory, &format!("{}.{}", stem, extension), contents) {
+            Ok(path) => info!("Exported {} objects to {}", entries.len(), path.display()),
+            Err(e) => warn!("Catalog export failed: {}", e),
+        }
+    }
+    if unfitted > 0 {
+        info!("Skipped {} unbound or decaying objects SGP4 mean elements could not be fitted to", unfitted);
+    }
+}
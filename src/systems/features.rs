@@ -0,0 +1,97 @@
+// Runtime switches for whole simulation subsystems
+
+use bevy::prelude::*;
+use crate::systems::collision::CollisionPairs;
+
+/// Resource enabling or disabling subsystems, e.g. for physics-only studies
+#[derive(Resource, Clone, Debug)]
+pub struct SimulationFeatures {
+    pub collisions: bool,
+    pub debris: bool,
+    pub analytics: bool,
+    pub rendering: bool,
+}
+
+impl Default for SimulationFeatures {
+    fn default() -> Self {
+        Self {
+            collisions: true,
+            debris: true,
+            analytics: true,
+            rendering: true,
+        }
+    }
+}
+
+impl SimulationFeatures {
+    /// Names accepted by `--disable`
+    pub const NAMES: [&'static str; 4] = ["collisions", "debris", "analytics", "rendering"];
+
+    /// Features with the named subsystems switched off
+    pub fn with_disabled(names: &[String]) -> Result<Self, String> {
+        let mut features = Self::default();
+        for name in names {
+            *features.flag(name).ok_or_else(|| format!("unknown feature {} (expected one of {})", name, Self::NAMES.join(", ")))? = false;
+        }
+        Ok(features)
+    }
+
+    fn flag(&mut self, name: &str) -> Option<&mut bool> {
+        match name {
+            "collisions" => Some(&mut self.collisions),
+            "debris" => Some(&mut self.debris),
+            "analytics" => Some(&mut self.analytics),
+            "rendering" => Some(&mut self.rendering),
+            _ => None,
+        }
+    }
+
+    /// Feature name and flag toggled by a function key
+    fn feature_for_key(&mut self, key: KeyCode) -> Option<(&'static str, &mut bool)> {
+        match key {
+            KeyCode::F7 => Some(("Collision detection", &mut self.collisions)),
+            KeyCode::F8 => Some(("Debris generation", &mut self.debris)),
+            KeyCode::F9 => Some(("Analytics", &mut self.analytics)),
+            KeyCode::F10 => Some(("Rendering updates", &mut self.rendering)),
+            _ => None,
+        }
+    }
+}
+
+/// Run condition for collision detection
+pub fn collisions_enabled(features: Res<SimulationFeatures>) -> bool {
+    features.collisions
+}
+
+/// Run condition for debris generation
+pub fn debris_enabled(features: Res<SimulationFeatures>) -> bool {
+    features.debris
+}
+
+/// Run condition for analytics
+pub fn analytics_enabled(features: Res<SimulationFeatures>) -> bool {
+    features.analytics
+}
+
+/// Run condition for rendering updates
+pub fn rendering_enabled(features: Res<SimulationFeatures>) -> bool {
+    features.rendering
+}
+
+/// System to toggle subsystems with F7-F10
+pub fn feature_toggle_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut features: ResMut<SimulationFeatures>,
+    mut collision_pairs: ResMut<CollisionPairs>,
+) {
+    for &key in keyboard.get_just_pressed() {
+        if let Some((name, enabled)) = features.feature_for_key(key) {
+            *enabled = !*enabled;
+            info!("{} {}", name, if *enabled { "enabled" } else { "disabled" });
+        }
+    }
+    // Pairs from the last check must not be processed again once detection stops
+    if !features.collisions {
+        collision_pairs.pairs.clear();
+    }
+}
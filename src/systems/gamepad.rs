@@ -0,0 +1,105 @@
+// Gamepad camera and time controls
+
+use bevy::prelude::*;
+use crate::resources::*;
+use crate::systems::rendering::{CameraRig, FlyCamera};
+use crate::systems::selection::{FocusRequest, Selection};
+
+/// Stick deflection ignored as noise
+const STICK_DEADZONE: f32 = 0.15;
+/// Camera spin at full left-stick deflection (rad/s)
+const STICK_ROTATE_SPEED: f32 = 1.5;
+/// Zoom rate at full right-stick deflection (wheel lines per second)
+const STICK_ZOOM_SPEED: f32 = 6.0;
+
+/// Apply a radial deadzone to a stick reading
+fn deadzone(stick: Vec2) -> Vec2 {
+    if stick.length() < STICK_DEADZONE {
+        Vec2::ZERO
+    } else {
+        stick
+    }
+}
+
+/// Index of the preset nearest to a speed multiplier
+fn nearest_preset(speed: f64) -> usize {
+    SPEED_PRESETS
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| (*a - speed).abs().total_cmp(&(*b - speed).abs()))
+        .map(|(index, _)| index)
+        .unwrap_or(0)
+}
+
+/// System mapping gamepad sticks to the orbit camera
+///
+/// Left stick orbits, right stick Y zooms.
+pub fn gamepad_camera_system(
+    time: Res<Time>,
+    fly: Res<FlyCamera>,
+    gamepads: Query<&Gamepad>,
+    mut rig: ResMut<CameraRig>,
+) {
+    if fly.enabled {
+        return;
+    }
+    let dt = time.delta_secs();
+
+    for gamepad in gamepads.iter() {
+        let orbit = deadzone(gamepad.left_stick());
+        if orbit != Vec2::ZERO {
+            rig.spin = Vec2::new(-orbit.x, orbit.y) * STICK_ROTATE_SPEED;
+        }
+
+        let zoom = deadzone(gamepad.right_stick());
+        if zoom.y != 0.0 {
+            rig.zoom(zoom.y * STICK_ZOOM_SPEED * dt);
+        }
+    }
+}
+
+/// System mapping gamepad buttons to time and focus controls
+///
+/// South pauses/resumes, bumpers step through speed presets, North single-steps,
+/// West focuses the selection and East returns the camera to Earth.
+pub fn gamepad_control_system(
+    gamepads: Query<&Gamepad>,
+    mut sim_time: ResMut<SimulationTime>,
+    selection: Res<Selection>,
+    mut rig: ResMut<CameraRig>,
+    mut focus_requests: EventWriter<FocusRequest>,
+) {
+    for gamepad in gamepads.iter() {
+        if gamepad.just_pressed(GamepadButton::South) {
+            if sim_time.paused {
+                sim_time.resume();
+            } else {
+                sim_time.pause();
+            }
+        }
+
+        let preset = nearest_preset(sim_time.speed_multiplier);
+        if gamepad.just_pressed(GamepadButton::RightTrigger) {
+            let next = (preset + 1).min(SPEED_PRESETS.len() - 1);
+            sim_time.set_speed(SPEED_PRESETS[next]);
+            info!("Time compression {:.0}x", SPEED_PRESETS[next]);
+        }
+        if gamepad.just_pressed(GamepadButton::LeftTrigger) {
+            let previous = preset.saturating_sub(1);
+            sim_time.set_speed(SPEED_PRESETS[previous]);
+            info!("Time compression {:.0}x", SPEED_PRESETS[previous]);
+        }
+
+        if gamepad.just_pressed(GamepadButton::North) {
+            sim_time.request_step();
+        }
+        if gamepad.just_pressed(GamepadButton::West) {
+            if let Some(entity) = selection.selected {
+                focus_requests.write(FocusRequest { entity });
+            }
+        }
+        if gamepad.just_pressed(GamepadButton::East) {
+            rig.release();
+        }
+    }
+}
@@ -0,0 +1,166 @@
+// Debris genealogy: the collision each fragment came from and, through it, the objects that started the chain
+
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::collision::CollisionEvent;
+use crate::systems::selection::Selection;
+use crate::utils::unix_to_iso8601;
+
+/// One of the two objects that met in a collision
+#[derive(Clone, Debug)]
+pub struct LineageParent {
+    pub name: String,
+    pub norad_id: Option<u32>,
+    /// Collision that created this object, when it was itself debris from one
+    pub collision: Option<u32>,
+}
+
+impl LineageParent {
+    fn label(&self) -> String {
+        match (self.collision, self.norad_id) {
+            (Some(collision), _) => format!("fragment of #{}", collision),
+            (None, Some(norad_id)) => format!("{} ({})", self.name.trim(), norad_id),
+            (None, None) => self.name.trim().to_string(),
+        }
+    }
+}
+
+/// A recorded collision and the objects that took part in it
+#[derive(Clone, Debug)]
+pub struct CollisionLineage {
+    /// Simulation time of the collision (seconds)
+    pub time: f64,
+    pub parents: [LineageParent; 2],
+    /// Whether both objects were destroyed
+    pub catastrophic: bool,
+    /// Number of fragments generated
+    pub fragments: u32,
+}
+
+/// Resource recording every collision by ID, so any fragment can be traced back through its parent chain
+#[derive(Resource, Default)]
+pub struct DebrisGenealogy {
+    pub collisions: HashMap<u32, CollisionLineage>,
+}
+
+impl DebrisGenealogy {
+    /// The collision and every earlier collision it descends from, the given one first and each before its parents
+    pub fn chain(&self, collision: u32) -> Vec<(u32, &CollisionLineage)> {
+        let mut chain = Vec::new();
+        let mut visited = HashSet::new();
+        let mut queue = vec![collision];
+        while let Some(id) = queue.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            let Some(lineage) = self.collisions.get(&id) else {
+                continue;
+            };
+            chain.push((id, lineage));
+            // Pushed in reverse so the first parent's ancestors are listed first
+            queue.extend(lineage.parents.iter().rev().filter_map(|parent| parent.collision));
+        }
+        chain
+    }
+
+    /// The original objects a collision descends from, i.e. the colliding objects that were not fragments themselves
+    pub fn roots(&self, collision: u32) -> Vec<&LineageParent> {
+        let mut roots: Vec<&LineageParent> = Vec::new();
+        for (_, lineage) in self.chain(collision) {
+            for parent in &lineage.parents {
+                // Parents whose own collision was never recorded are the furthest back the chain goes
+                let traced = parent.collision.is_some_and(|id| self.collisions.contains_key(&id));
+                if !traced && !roots.iter().any(|r| r.name == parent.name && r.norad_id == parent.norad_id && r.collision == parent.collision) {
+                    roots.push(parent);
+                }
+            }
+        }
+        roots
+    }
+}
+
+/// System to record each collision in the genealogy
+pub fn genealogy_recording_system(mut genealogy: ResMut<DebrisGenealogy>, mut collisions: EventReader<CollisionEvent>) {
+    for collision in collisions.read() {
+        let parent = |name: &str, norad_id: Option<u32>, collision: Option<u32>| LineageParent {
+            name: name.to_string(),
+            norad_id,
+            collision,
+        };
+        genealogy.collisions.insert(collision.id, CollisionLineage {
+            time: collision.time,
+            parents: [
+                parent(&collision.names.0, collision.norad_ids.0, collision.parent_collisions.0),
+                parent(&collision.names.1, collision.norad_ids.1, collision.parent_collisions.1),
+            ],
+            catastrophic: collision.catastrophic,
+            fragments: collision.fragments,
+        });
+    }
+}
+
+/// Marker for the lineage panel text
+#[derive(Component)]
+pub struct GenealogyPanelText;
+
+/// System to create the (initially empty) lineage panel
+pub fn setup_genealogy_panel_system(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.95, 0.75, 0.6)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(420.0),
+            right: Val::Px(10.0),
+            ..default()
+        },
+        GenealogyPanelText,
+    ));
+}
+
+/// System to trace the selected fragment back through its parent collisions to the objects that started the chain
+pub fn genealogy_panel_system(
+    genealogy: Res<DebrisGenealogy>,
+    selection: Res<Selection>,
+    sim_time: Res<SimulationTime>,
+    debris: Query<&Debris>,
+    mut text_query: Query<&mut Text, With<GenealogyPanelText>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+    let Some(fragment) = selection.selected.and_then(|entity| debris.get(entity).ok()) else {
+        if !text.0.is_empty() {
+            text.0.clear();
+        }
+        return;
+    };
+    if !selection.is_changed() && !genealogy.is_changed() && !text.0.is_empty() {
+        return;
+    }
+
+    let Some(collision) = fragment.parent_collision else {
+        text.0 = format!("Lineage (generation {})\ncatalogued debris, no recorded parent", fragment.generation);
+        return;
+    };
+    let mut lines = vec![format!("Lineage (generation {} fragment of collision #{})", fragment.generation, collision)];
+    for (id, lineage) in genealogy.chain(collision) {
+        lines.push(format!(
+            "#{} {}Z: {} + {} -> {} fragments{}",
+            id, unix_to_iso8601(sim_time.unix_at(lineage.time)),
+            lineage.parents[0].label(), lineage.parents[1].label(), lineage.fragments,
+            if lineage.catastrophic { "" } else { " (ejecta)" }
+        ));
+    }
+    let roots: Vec<String> = genealogy.roots(collision).iter().map(|root| root.label()).collect();
+    if !roots.is_empty() {
+        lines.push(format!("Roots: {}", roots.join(", ")));
+    }
+    text.0 = lines.join("\n");
+}
@@ -0,0 +1,185 @@
+// GEO longitude slots, station-keeping boxes and drift alarms
+
+use bevy::math::{DQuat, DVec3};
+use bevy::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::rendering::LayerVisibility;
+use crate::utils::*;
+
+/// Geostationary orbit radius (km)
+pub const GEO_RADIUS_KM: f64 = 42164.0;
+/// Radial half-height of the protected GEO region (km)
+pub const GEO_REGION_HALF_HEIGHT: f64 = 200.0;
+/// Largest latitude (degrees) still counted as GEO; derelicts librate up to ~15°
+pub const GEO_MAX_LATITUDE: f64 = 15.0;
+/// Width of a longitude slot (degrees)
+pub const GEO_SLOT_WIDTH_DEG: f64 = 0.5;
+/// Half-width of a station-keeping box in longitude and latitude (degrees)
+pub const STATION_KEEPING_HALF_WIDTH_DEG: f64 = 0.1;
+
+/// Component holding the nominal longitude a GEO satellite is kept at
+#[derive(Component, Clone, Copy, Debug)]
+pub struct StationKeepingBox {
+    pub longitude: f64,
+}
+
+impl StationKeepingBox {
+    /// Whether a position (degrees) lies inside the box
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        longitude_difference(longitude, self.longitude).abs() <= STATION_KEEPING_HALF_WIDTH_DEG
+            && latitude.abs() <= STATION_KEEPING_HALF_WIDTH_DEG
+    }
+}
+
+/// Resource tracking occupied GEO slots and derelicts drifting through them
+#[derive(Resource, Default)]
+pub struct GeoSlots {
+    /// Keeper of each occupied slot
+    pub occupied: BTreeMap<i32, Entity>,
+    /// Occupied slot each drifting derelict is currently passing through
+    pub intruders: HashMap<Entity, i32>,
+    pub alarms_raised: u32,
+}
+
+/// Slot a longitude (degrees) falls into; slot 0 is centered on the Greenwich meridian
+pub fn geo_slot(longitude: f64) -> i32 {
+    let slots = (360.0 / GEO_SLOT_WIDTH_DEG) as i32;
+    // 180°E and 180°W are the same slot
+    ((longitude / GEO_SLOT_WIDTH_DEG).round() as i32 + slots / 2).rem_euclid(slots) - slots / 2
+}
+
+/// Longitude difference a - b wrapped into [-180, 180)
+pub fn longitude_difference(a: f64, b: f64) -> f64 {
+    (a - b + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Latitude and longitude (degrees) of an object inside the GEO region
+pub fn geo_position(position: DVec3, unix_seconds: f64, earth_radius_km: f64) -> Option<(f64, f64)> {
+    if (position.length() - GEO_RADIUS_KM).abs() > GEO_REGION_HALF_HEIGHT {
+        return None;
+    }
+    let (latitude, longitude, _) = eci_to_geodetic(position, unix_seconds, earth_radius_km);
+    (latitude.abs() <= GEO_MAX_LATITUDE).then_some((latitude, longitude))
+}
+
+/// Render-space point on the GEO sphere at an Earth-fixed latitude/longitude (degrees)
+fn geo_render_point(latitude: f64, longitude: f64, rotation: DQuat) -> Vec3 {
+    (rotation * geodetic_to_ecef(latitude, longitude, 0.0, GEO_RADIUS_KM) / 1000.0).as_vec3()
+}
+
+/// System to give GEO satellites station-keeping boxes and raise alarms for derelicts crossing occupied slots
+#[allow(clippy::type_complexity)]
+pub fn geo_slot_tracking_system(
+    mut commands: Commands,
+    constants: Res<Constants>,
+    sim_time: Res<SimulationTime>,
+    mut slots: ResMut<GeoSlots>,
+    objects: Query<(
+        Entity,
+        &OrbitalState,
+        Option<&Satellite>,
+        Option<&StationKeepingBox>,
+        (Has<Derelict>, Has<Debris>, Has<RocketBody>),
+    )>,
+) {
+    let unix = sim_time.current_unix();
+    let mut occupied = BTreeMap::new();
+    let mut drifting = Vec::new();
+
+    for (entity, state, satellite, station_box, (is_derelict, is_debris, is_rocket_body)) in objects.iter() {
+        let Some((_, longitude)) = geo_position(state.position, unix, constants.earth_radius) else {
+            continue;
+        };
+        let controlled = satellite.is_some_and(|s| s.active) && !is_derelict && !is_debris && !is_rocket_body;
+        if controlled {
+            // The slot a satellite is first seen in becomes its assigned box
+            let station_box = station_box.copied().unwrap_or_else(|| {
+                let station_box = StationKeepingBox { longitude };
+                commands.entity(entity).try_insert(station_box);
+                station_box
+            });
+            occupied.insert(geo_slot(station_box.longitude), entity);
+        } else {
+            drifting.push((entity, longitude, satellite.map(|s| s.name.clone())));
+        }
+    }
+
+    let mut intruders = HashMap::new();
+    // New intrusions per slot: count and the first derelict's name, so fragment clouds raise one alarm
+    let mut new_intrusions: BTreeMap<i32, (usize, String)> = BTreeMap::new();
+    for (entity, longitude, name) in drifting {
+        let slot = geo_slot(longitude);
+        if !occupied.contains_key(&slot) {
+            continue;
+        }
+        intruders.insert(entity, slot);
+        if slots.intruders.get(&entity) != Some(&slot) {
+            let intrusion = new_intrusions.entry(slot)
+                .or_insert_with(|| (0, name.unwrap_or_else(|| "debris".to_string())));
+            intrusion.0 += 1;
+        }
+    }
+
+    for (slot, (count, name)) in new_intrusions {
+        slots.alarms_raised += 1;
+        let keeper_name = objects.get(occupied[&slot]).ok()
+            .and_then(|(_, _, satellite, ..)| satellite.map(|s| s.name.clone()))
+            .unwrap_or_default();
+        let others = if count > 1 { format!(" and {} more", count - 1) } else { String::new() };
+        warn!("GEO drift alarm: derelict {}{} entering slot {:.1}° occupied by {}",
+              name, others, slot as f64 * GEO_SLOT_WIDTH_DEG, keeper_name);
+    }
+
+    slots.occupied = occupied;
+    slots.intruders = intruders;
+}
+
+/// System to draw the GEO belt, occupied slots and station-keeping boxes
+pub fn geo_overlay_system(
+    layers: Res<LayerVisibility>,
+    constants: Res<Constants>,
+    sim_time: Res<SimulationTime>,
+    slots: Res<GeoSlots>,
+    boxes: Query<(&OrbitalState, &StationKeepingBox)>,
+    mut gizmos: Gizmos,
+) {
+    if !layers.geo_slots {
+        return;
+    }
+    let unix = sim_time.current_unix();
+    let rotation = earth_rotation(unix);
+    let belt_color = Color::srgba(0.6, 0.6, 0.8, 0.4);
+    let alarm_slots: Vec<i32> = slots.intruders.values().copied().collect();
+
+    // Belt with a tick every 10° of longitude
+    gizmos.linestrip((0..=360).map(|lon| geo_render_point(0.0, lon as f64, rotation)), belt_color);
+    for lon in (0..360).step_by(10) {
+        gizmos.line(geo_render_point(-1.0, lon as f64, rotation), geo_render_point(1.0, lon as f64, rotation), belt_color);
+    }
+
+    // Occupied slot boundaries, highlighted while a derelict drifts through
+    for &slot in slots.occupied.keys() {
+        let center = slot as f64 * GEO_SLOT_WIDTH_DEG;
+        let color = if alarm_slots.contains(&slot) {
+            Color::srgb(1.0, 0.3, 0.1)
+        } else {
+            Color::srgba(0.9, 0.9, 0.3, 0.6)
+        };
+        for edge in [center - GEO_SLOT_WIDTH_DEG / 2.0, center + GEO_SLOT_WIDTH_DEG / 2.0] {
+            gizmos.line(geo_render_point(-0.5, edge, rotation), geo_render_point(0.5, edge, rotation), color);
+        }
+    }
+
+    // Station-keeping boxes: green while the satellite is inside, red when it has left
+    let half = STATION_KEEPING_HALF_WIDTH_DEG;
+    for (state, station_box) in boxes.iter() {
+        let inside = geo_position(state.position, unix, constants.earth_radius)
+            .is_some_and(|(latitude, longitude)| station_box.contains(latitude, longitude));
+        let color = if inside { Color::srgb(0.2, 1.0, 0.4) } else { Color::srgb(1.0, 0.2, 0.2) };
+        let lon = station_box.longitude;
+        let corners = [(-half, lon - half), (-half, lon + half), (half, lon + half), (half, lon - half), (-half, lon - half)];
+        gizmos.linestrip(corners.map(|(lat, lon)| geo_render_point(lat, lon, rotation)), color);
+    }
+}
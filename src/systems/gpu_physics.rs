@@ -10,7 +10,7 @@ use bytemuck::{Pod, Zeroable};
 use crate::components::*;
 use crate::resources::*;
 
-/// GPU-compatible orbital state data structure
+/// GPU-compatible orbital state data structure, narrowed to f32 for the compute shader
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
 pub struct GpuOrbitalState {
@@ -23,15 +23,15 @@ pub struct GpuOrbitalState {
 impl GpuOrbitalState {
     pub fn from_orbital_state(state: &OrbitalState) -> Self {
         Self {
-            position: [state.position.x, state.position.y, state.position.z, state.mass as f32],
-            velocity: [state.velocity.x, state.velocity.y, state.velocity.z, 0.0],
+            position: [state.position.x as f32, state.position.y as f32, state.position.z as f32, state.mass as f32],
+            velocity: [state.velocity.x as f32, state.velocity.y as f32, state.velocity.z as f32, 0.0],
         }
     }
 
     pub fn to_orbital_state(&self) -> OrbitalState {
         OrbitalState::new(
-            Vec3::new(self.position[0], self.position[1], self.position[2]),
-            Vec3::new(self.velocity[0], self.velocity[1], self.velocity[2]),
+            Vec3::new(self.position[0], self.position[1], self.position[2]).as_dvec3(),
+            Vec3::new(self.velocity[0], self.velocity[1], self.velocity[2]).as_dvec3(),
             self.position[3] as f64,
         )
     }
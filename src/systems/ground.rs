@@ -0,0 +1,127 @@
+// Live ground track and ground-station contacts of the selected object, kept in the Earth-fixed frame
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::rendering::LayerVisibility;
+use crate::systems::selection::Selection;
+use crate::utils::{earth_rotation, eci_to_ecef, elevation_angle, geodetic_to_ecef};
+
+/// Simulated seconds between ground-track samples
+pub const GROUND_TRACE_SPACING: f64 = 30.0;
+/// Ground-track samples kept, about one and a half LEO orbits at the default spacing
+pub const GROUND_TRACE_LENGTH: usize = 300;
+/// Elevation above the local horizon a station needs to be in contact (degrees)
+pub const STATION_ELEVATION_MASK: f64 = 10.0;
+
+/// A ground station at a spherical-Earth latitude/longitude (degrees)
+pub struct GroundStation {
+    pub name: &'static str,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Tracking stations screened for contacts with the selected object
+pub const GROUND_STATIONS: [GroundStation; 6] = [
+    GroundStation { name: "Svalbard", latitude: 78.23, longitude: 15.39 },
+    GroundStation { name: "Fairbanks", latitude: 64.86, longitude: -147.85 },
+    GroundStation { name: "Wallops", latitude: 37.94, longitude: -75.46 },
+    GroundStation { name: "Kourou", latitude: 5.25, longitude: -52.80 },
+    GroundStation { name: "Hartebeesthoek", latitude: -25.89, longitude: 27.69 },
+    GroundStation { name: "Canberra", latitude: -35.40, longitude: 148.98 },
+];
+
+/// Resource holding the selected object's recent sub-satellite points and the stations in contact with it
+#[derive(Resource, Default)]
+pub struct GroundTrace {
+    /// Object the trace belongs to
+    pub target: Option<Entity>,
+    /// Sub-satellite points on the surface (Earth-fixed, km), oldest first
+    pub points: VecDeque<DVec3>,
+    /// Simulation time of the latest sample
+    pub last_sample: f64,
+    /// Indices into `GROUND_STATIONS` of the stations currently in contact
+    pub contacts: Vec<usize>,
+}
+
+/// System to sample the selected object's ground track and report station acquisitions and losses of signal
+pub fn ground_trace_system(
+    mut trace: ResMut<GroundTrace>,
+    selection: Res<Selection>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    objects: Query<(&OrbitalState, Option<&Satellite>)>,
+) {
+    let selected = selection.selected.filter(|&entity| objects.contains(entity));
+    if selected != trace.target {
+        *trace = GroundTrace { target: selected, ..default() };
+    }
+    let Some((state, satellite)) = selected.and_then(|entity| objects.get(entity).ok()) else {
+        return;
+    };
+
+    let unix = sim_time.current_unix();
+    let position = eci_to_ecef(state.position, unix);
+    // Time running backwards (a replay or reset) starts a new trace
+    if sim_time.current < trace.last_sample {
+        trace.points.clear();
+    }
+    if trace.points.is_empty() || sim_time.current - trace.last_sample >= GROUND_TRACE_SPACING {
+        trace.points.push_back(position.normalize_or_zero() * constants.earth_radius);
+        if trace.points.len() > GROUND_TRACE_LENGTH {
+            trace.points.pop_front();
+        }
+        trace.last_sample = sim_time.current;
+    }
+
+    let name = satellite.map(|s| s.name.trim().to_string()).unwrap_or_else(|| "Object".to_string());
+    let contacts: Vec<usize> = GROUND_STATIONS.iter().enumerate()
+        .filter(|(_, station)| {
+            let site = geodetic_to_ecef(station.latitude, station.longitude, 0.0, constants.earth_radius);
+            elevation_angle(site, position) >= STATION_ELEVATION_MASK
+        })
+        .map(|(index, _)| index)
+        .collect();
+    for &index in contacts.iter().filter(|index| !trace.contacts.contains(index)) {
+        info!("{} acquired by {} at {}", name, GROUND_STATIONS[index].name, sim_time.current_iso8601());
+    }
+    for &index in trace.contacts.iter().filter(|index| !contacts.contains(index)) {
+        info!("{} lost by {} at {}", name, GROUND_STATIONS[index].name, sim_time.current_iso8601());
+    }
+    trace.contacts = contacts;
+}
+
+/// System to draw the ground track on the turning Earth and the links to stations in contact
+pub fn ground_trace_drawing_system(
+    trace: Res<GroundTrace>,
+    layers: Res<LayerVisibility>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    objects: Query<&OrbitalState>,
+    mut gizmos: Gizmos,
+) {
+    if !layers.trails {
+        return;
+    }
+    let Some(state) = trace.target.and_then(|entity| objects.get(entity).ok()) else {
+        return;
+    };
+
+    // Lifted slightly off the surface so the Earth mesh does not hide it
+    let rotation = earth_rotation(sim_time.current_unix());
+    let to_render = |point: DVec3| (rotation * point * 1.002 / 1000.0).as_vec3();
+    gizmos.linestrip(trace.points.iter().map(|&point| to_render(point)), Color::srgba(1.0, 0.8, 0.2, 0.8));
+
+    let object = (state.position / 1000.0).as_vec3();
+    for (index, station) in GROUND_STATIONS.iter().enumerate() {
+        let site = geodetic_to_ecef(station.latitude, station.longitude, 0.0, constants.earth_radius);
+        let in_contact = trace.contacts.contains(&index);
+        let color = if in_contact { Color::srgb(0.2, 1.0, 0.4) } else { Color::srgba(0.7, 0.7, 0.7, 0.5) };
+        gizmos.line(to_render(site), to_render(site * 1.03), color);
+        if in_contact {
+            gizmos.line(to_render(site), object, color);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+// Uniform spatial hash grid broad-phase: only occupied cells are stored, so empty space costs nothing
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Smallest grid cell (km); cells grow to the largest search radius of the step
+pub const HASH_GRID_MIN_CELL_SIZE: f64 = 10.0;
+
+/// Resource holding objects bucketed by the grid cell they occupy
+#[derive(Resource)]
+pub struct HashGridBroadPhase {
+    /// Edge length of a grid cell in the current build (km)
+    pub cell_size: f64,
+    cells: HashMap<[i64; 3], Vec<Entity>>,
+    /// Emptied buckets kept for reuse, so rebuilding does not reallocate them
+    spare: Vec<Vec<Entity>>,
+}
+
+impl Default for HashGridBroadPhase {
+    fn default() -> Self {
+        Self {
+            cell_size: HASH_GRID_MIN_CELL_SIZE,
+            cells: HashMap::new(),
+            spare: Vec::new(),
+        }
+    }
+}
+
+impl HashGridBroadPhase {
+    /// Re-bucket objects into cells at least `max_radius` wide, so any query only touches neighbouring cells
+    pub fn rebuild(&mut self, objects: impl Iterator<Item = (Entity, DVec3)>, max_radius: f64) {
+        self.cell_size = max_radius.max(HASH_GRID_MIN_CELL_SIZE);
+        for (_, mut bucket) in self.cells.drain() {
+            bucket.clear();
+            self.spare.push(bucket);
+        }
+        for (entity, position) in objects {
+            let cell = cell_of(position, self.cell_size);
+            let spare = &mut self.spare;
+            self.cells.entry(cell).or_insert_with(|| spare.pop().unwrap_or_default()).push(entity);
+        }
+    }
+
+    /// Get all objects in cells overlapping a sphere
+    pub fn query_sphere(&self, center: DVec3, radius: f64, results: &mut Vec<Entity>) {
+        let reach = (radius / self.cell_size).ceil().max(1.0) as i64;
+        let cell = cell_of(center, self.cell_size);
+        for dz in -reach..=reach {
+            for dy in -reach..=reach {
+                for dx in -reach..=reach {
+                    if let Some(bucket) = self.cells.get(&[cell[0] + dx, cell[1] + dy, cell[2] + dz]) {
+                        results.extend_from_slice(bucket);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn cell_of(position: DVec3, cell_size: f64) -> [i64; 3] {
+    let cell = (position / cell_size).floor();
+    [cell.x as i64, cell.y as i64, cell.z as i64]
+}
@@ -0,0 +1,173 @@
+// Satellite health and degradation model
+
+use bevy::prelude::*;
+use rand::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+
+/// Seconds in a Julian year, used to convert flux rates
+const SECONDS_PER_YEAR: f64 = 365.25 * 86400.0;
+/// Seconds a full battery carries the satellite's load without solar input
+const BATTERY_ENDURANCE: f64 = 7200.0;
+/// Solar array output in full sunlight as a multiple of the load, for a nominal power subsystem
+const ARRAY_MARGIN: f64 = 2.5;
+
+/// What caused a damaging impact
+#[derive(Clone, Copy, Debug)]
+pub enum ImpactSource {
+    /// Sub-catalog debris too small to be tracked as an entity
+    SmallDebris,
+    /// Non-catastrophic collision with a tracked object
+    Collision,
+}
+
+/// Event raised when an object is hit without being destroyed
+#[derive(Event)]
+pub struct ImpactEvent {
+    pub target: Entity,
+    /// Fraction of subsystem condition lost (0.0 - 1.0)
+    pub severity: f32,
+    pub source: ImpactSource,
+}
+
+/// Resource to control the small-debris degradation model
+#[derive(Resource)]
+pub struct DegradationConfig {
+    pub enabled: bool,
+    /// Peak flux of damaging sub-catalog particles in impacts/m²/year
+    pub peak_flux: f64,
+    /// Altitude of peak flux in km
+    pub peak_altitude: f64,
+    /// Width of the flux band in km
+    pub band_width: f64,
+    /// Range of condition lost per small-debris impact
+    pub min_severity: f32,
+    pub max_severity: f32,
+}
+
+impl Default for DegradationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            peak_flux: 1.0e-3,   // Roughly ORDEM-level flux for >1mm particles
+            peak_altitude: 850.0,
+            band_width: 350.0,
+            min_severity: 0.05,
+            max_severity: 0.5,
+        }
+    }
+}
+
+impl DegradationConfig {
+    /// Damaging particle flux (impacts/m²/year) at the given altitude above the surface
+    pub fn flux_at_altitude(&self, altitude_km: f64) -> f64 {
+        if altitude_km <= 0.0 {
+            return 0.0;
+        }
+        let normalized = (altitude_km - self.peak_altitude) / self.band_width;
+        self.peak_flux * (-normalized * normalized).exp()
+    }
+}
+
+/// System to sample small-debris impacts on satellites with a Health component
+pub fn small_debris_flux_system(
+    config: Res<DegradationConfig>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    query: Query<(Entity, &OrbitalState, &PhysicsObject), With<Health>>,
+    mut impacts: EventWriter<ImpactEvent>,
+    mut last_time: Local<Option<f64>>,
+) {
+    let elapsed = last_time.map(|t| sim_time.current - t).unwrap_or(0.0);
+    *last_time = Some(sim_time.current);
+
+    if !config.enabled || sim_time.paused || elapsed <= 0.0 {
+        return;
+    }
+
+    let elapsed_years = elapsed / SECONDS_PER_YEAR;
+    let mut rng = thread_rng();
+
+    for (entity, orbital_state, physics_object) in query.iter() {
+        let altitude = orbital_state.altitude() - constants.earth_radius;
+        let expected_hits = config.flux_at_altitude(altitude) * physics_object.cross_section * elapsed_years;
+        let hit_probability = 1.0 - (-expected_hits).exp();
+
+        if rng.gen::<f64>() < hit_probability {
+            impacts.write(ImpactEvent {
+                target: entity,
+                severity: rng.gen_range(config.min_severity..=config.max_severity),
+                source: ImpactSource::SmallDebris,
+            });
+        }
+    }
+}
+
+/// System to apply impact damage and convert crippled satellites into derelicts
+pub fn impact_damage_system(
+    mut impacts: EventReader<ImpactEvent>,
+    mut query: Query<(&mut Health, Option<&mut Satellite>)>,
+) {
+    let mut rng = thread_rng();
+
+    for impact in impacts.read() {
+        let Ok((mut health, satellite)) = query.get_mut(impact.target) else {
+            continue;
+        };
+
+        let subsystem = if rng.gen_bool(0.5) {
+            Subsystem::Power
+        } else {
+            Subsystem::Propulsion
+        };
+
+        if health.degrade(subsystem, impact.severity) {
+            info!("{:?} impact on {:?} caused {:?} failure", impact.source, impact.target, subsystem);
+        } else {
+            debug!("{:?} impact on {:?}: {:?} degraded by {:.0}% (power {:.2}, propulsion {:.2})",
+                   impact.source, impact.target, subsystem, impact.severity * 100.0,
+                   health.power, health.propulsion);
+        }
+
+        // Without propulsion the satellite can no longer maneuver and becomes a derelict
+        if let Some(mut satellite) = satellite {
+            if satellite.active && health.is_crippled() {
+                satellite.active = false;
+                warn!("{} can no longer maneuver and is now a derelict (power {:.2}, propulsion {:.2})",
+                      satellite.name, health.power, health.propulsion);
+            }
+        }
+    }
+}
+
+/// System to charge batteries in sunlight and drain them in eclipse, browning out satellites whose arrays are too degraded
+pub fn power_budget_system(
+    sim_time: Res<SimulationTime>,
+    mut query: Query<(&mut Health, &Eclipse, Option<&Satellite>)>,
+    mut last_time: Local<Option<f64>>,
+) {
+    let elapsed = last_time.map(|t| sim_time.current - t).unwrap_or(0.0);
+    *last_time = Some(sim_time.current);
+
+    if sim_time.paused || elapsed <= 0.0 {
+        return;
+    }
+
+    for (mut health, eclipse, satellite) in query.iter_mut() {
+        if health.is_crippled() {
+            continue;
+        }
+        let generation = ARRAY_MARGIN * health.power as f64 * eclipse.sunlight as f64;
+        let charge = (health.battery as f64 + (generation - 1.0) * elapsed / BATTERY_ENDURANCE).clamp(0.0, 1.0) as f32;
+        if charge == health.battery {
+            continue;
+        }
+        let name = satellite.map_or("Satellite", |s| s.name.trim());
+        if health.battery > 0.0 && charge <= 0.0 {
+            warn!("{} battery depleted ({:?}, power condition {:.2})", name, eclipse.state, health.power);
+        } else if health.battery <= 0.0 && charge > 0.0 {
+            info!("{} recovered power in sunlight", name);
+        }
+        health.battery = charge;
+    }
+}
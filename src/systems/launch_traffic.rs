@@ -0,0 +1,196 @@
+// Launch traffic model: new satellites injected over simulated years from a launches-per-year profile
+
+use bevy::prelude::*;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use crate::components::*;
+use crate::resources::*;
+use crate::utils::*;
+
+const SECONDS_PER_YEAR: f64 = 365.25 * 86400.0;
+
+/// Launch activity into one altitude/inclination band
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchBand {
+    pub name: String,
+    /// Altitude band (km) of the circular insertion orbits
+    pub altitude: (f64, f64),
+    /// Inclination center and half-width (degrees)
+    pub inclination: (f64, f64),
+    pub launches_per_year: f64,
+    pub satellites_per_launch: u32,
+    /// Mass of each satellite (kg)
+    pub mass: f64,
+    /// Mass (kg) of the upper stage left in the insertion orbit, if any
+    pub rocket_body_mass: Option<f64>,
+}
+
+impl Default for LaunchBand {
+    fn default() -> Self {
+        Self {
+            name: "LAUNCH".to_string(),
+            altitude: (500.0, 600.0),
+            inclination: (53.0, 0.0),
+            launches_per_year: 1.0,
+            satellites_per_launch: 1,
+            mass: 500.0,
+            rocket_body_mass: None,
+        }
+    }
+}
+
+/// Launches-per-year profile, loaded from a JSON file or built in
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchProfile {
+    pub bands: Vec<LaunchBand>,
+    /// Yearly growth of every band's launch rate (0.05 is 5% more launches each year)
+    pub growth: f64,
+}
+
+impl LaunchProfile {
+    /// Load a profile file, or the built-in profile for "default"
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if path.as_os_str() == "default" {
+            return Ok(Self::recent_activity());
+        }
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read launch profile {}: {}", path.display(), e))?;
+        let profile: Self = serde_json::from_str(&contents)
+            .map_err(|e| format!("Invalid launch profile {}: {}", path.display(), e))?;
+        if let Some(band) = profile.bands.iter().find(|band| band.altitude.0 > band.altitude.1 || band.altitude.0 < 0.0) {
+            return Err(format!("Invalid launch profile {}: bad altitude band in {}", path.display(), band.name));
+        }
+        Ok(profile)
+    }
+
+    /// Rough launch activity of the early 2020s by orbit regime
+    pub fn recent_activity() -> Self {
+        let band = |name: &str, altitude, inclination, launches_per_year, satellites_per_launch, mass, rocket_body_mass| LaunchBand {
+            name: name.to_string(),
+            altitude,
+            inclination,
+            launches_per_year,
+            satellites_per_launch,
+            mass,
+            rocket_body_mass,
+        };
+        Self {
+            bands: vec![
+                band("MEGA", (530.0, 570.0), (53.0, 0.5), 60.0, 22, 800.0, None),
+                band("SSO", (500.0, 600.0), (97.6, 0.3), 12.0, 10, 150.0, Some(4000.0)),
+                band("LEO", (400.0, 1200.0), (50.0, 40.0), 20.0, 2, 1000.0, Some(2000.0)),
+                band("GNSS", (19000.0, 23500.0), (55.0, 1.0), 6.0, 1, 1500.0, None),
+                band("GEO", (35776.0, 35796.0), (0.05, 0.05), 20.0, 1, 4000.0, None),
+            ],
+            growth: 0.0,
+        }
+    }
+}
+
+/// Resource holding the launch profile and the launches it has made so far
+#[derive(Resource, Default)]
+pub struct LaunchTraffic {
+    pub profile: Option<LaunchProfile>,
+    pub launches: usize,
+    pub satellites: usize,
+    /// Fractional launches owed to each band
+    due: Vec<f64>,
+    /// Simulation time the traffic started and last ran (UTC Unix)
+    started: Option<f64>,
+    last: f64,
+}
+
+impl LaunchTraffic {
+    pub fn new(profile: Option<LaunchProfile>) -> Self {
+        Self { profile, ..default() }
+    }
+}
+
+/// Spawn one launch's satellites, spaced evenly around a random plane in the band, and its upper stage
+fn spawn_launch(commands: &mut Commands, band: &LaunchBand, designator: &str, constants: &Constants, rng: &mut impl Rng) -> u32 {
+    let altitude = rng.gen_range(band.altitude.0..=band.altitude.1);
+    let (center, half_width) = band.inclination;
+    let inclination = (center + rng.gen_range(-1.0..=1.0) * half_width).clamp(0.0, 180.0).to_radians();
+    let raan = rng.gen_range(0.0..std::f64::consts::TAU);
+    let phase = rng.gen_range(0.0..std::f64::consts::TAU);
+    let mu_km = constants.gravitational_parameter / 1e9;
+    let count = band.satellites_per_launch.max(1);
+
+    for index in 0..count {
+        let argument_of_latitude = phase + std::f64::consts::TAU * index as f64 / count as f64;
+        let (position, velocity) = circular_orbit_state(altitude, inclination, raan, argument_of_latitude, constants.earth_radius, mu_km);
+        let name = format!("{} {} #{}", band.name, designator, index + 1);
+        commands.spawn((
+            Satellite::new(name.clone(), 0, true),
+            Operator::from_satellite_name(&name),
+            OrbitalState::new(position, velocity, band.mass),
+            PhysicsObject::satellite(band.mass),
+            Health::default(),
+            RenderAsSatellite,
+        ));
+    }
+    if let Some(mass) = band.rocket_body_mass {
+        // Half an orbit behind the satellites it released
+        let (position, velocity) = circular_orbit_state(
+            altitude, inclination, raan, phase + std::f64::consts::PI, constants.earth_radius, mu_km,
+        );
+        let name = format!("{} {} R/B", band.name, designator);
+        commands.spawn((
+            Satellite::new(name.clone(), 0, false),
+            Operator::from_satellite_name(&name),
+            OrbitalState::new(position, velocity, mass),
+            PhysicsObject::satellite(mass),
+            Health::default(),
+            RenderAsSatellite,
+            RocketBody,
+        ));
+    }
+    count
+}
+
+/// System to make the launches the profile calls for as simulated time passes
+///
+/// Each band accrues its launch rate, raised by the profile's growth for every year since the traffic started, and
+/// launches whenever a whole launch is due. Each launch goes into a circular orbit at a random altitude, inclination
+/// and RAAN within its band.
+pub fn launch_traffic_system(
+    mut commands: Commands,
+    mut traffic: ResMut<LaunchTraffic>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+) {
+    let traffic = &mut *traffic;
+    let Some(profile) = &traffic.profile else {
+        return;
+    };
+    let now = sim_time.current_unix();
+    let started = *traffic.started.get_or_insert(now);
+    let elapsed = now - traffic.last;
+    traffic.last = now;
+    // The first frame, and jumps back in time when a scenario is loaded, only set the clock
+    if now == started || elapsed <= 0.0 {
+        return;
+    }
+    traffic.due.resize(profile.bands.len(), 0.0);
+
+    let growth = (1.0 + profile.growth).max(0.0).powf((now - started) / SECONDS_PER_YEAR);
+    let (year, ..) = unix_to_utc(now);
+    let mut rng = thread_rng();
+    for (band, due) in profile.bands.iter().zip(traffic.due.iter_mut()) {
+        *due += band.launches_per_year.max(0.0) * growth * elapsed / SECONDS_PER_YEAR;
+        while *due >= 1.0 {
+            *due -= 1.0;
+            traffic.launches += 1;
+            let designator = format!("{}-{:04}", year, traffic.launches);
+            let satellites = spawn_launch(&mut commands, band, &designator, &constants, &mut rng);
+            traffic.satellites += satellites as usize;
+            info!(
+                "Launch {}: {} satellites into {} ({} launches, {} satellites so far)",
+                designator, satellites, band.name, traffic.launches, traffic.satellites
+            );
+        }
+    }
+}
@@ -0,0 +1,105 @@
+// Live mode: simulation epoch synchronized to wall-clock UTC
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+use crate::components::*;
+use crate::resources::*;
+use crate::utils::*;
+use crate::systems::data::TleDataCache;
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+
+/// Resource controlling real-time situational-awareness mode
+#[derive(Resource)]
+pub struct LiveMode {
+    pub enabled: bool,
+    /// Wall-clock seconds between SGP4 repositioning passes
+    pub update_interval: f64,
+    /// Speed multiplier to restore when live mode is switched off
+    pub saved_speed: f64,
+    /// Wall-clock time of the last repositioning pass (Unix seconds)
+    pub last_update: f64,
+}
+
+impl Default for LiveMode {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            update_interval: 1.0,
+            saved_speed: 1.0,
+            last_update: 0.0,
+        }
+    }
+}
+
+/// System to toggle live mode
+pub fn live_mode_control_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut live_mode: ResMut<LiveMode>,
+    mut sim_time: ResMut<SimulationTime>,
+) {
+    // Toggle live mode with 'L' key
+    if keyboard.just_pressed(KeyCode::KeyL) {
+        live_mode.enabled = !live_mode.enabled;
+        if live_mode.enabled {
+            live_mode.saved_speed = sim_time.speed_multiplier;
+            live_mode.last_update = 0.0;
+            sim_time.set_speed(1.0);
+            sim_time.resume();
+            info!("Live mode ENABLED - tracking wall-clock UTC");
+        } else {
+            sim_time.set_speed(live_mode.saved_speed);
+            info!("Live mode DISABLED - restored {:.0}x time compression", live_mode.saved_speed);
+        }
+    }
+}
+
+/// System to keep the simulation clock on wall-clock time and reposition cataloged objects via SGP4
+pub fn live_mode_sync_system(
+    mut live_mode: ResMut<LiveMode>,
+    mut sim_time: ResMut<SimulationTime>,
+    tle_cache: Res<TleDataCache>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+    mut tle_query: Query<(&TleData, &mut OrbitalState, Option<&OptimizedPhysics>)>,
+) {
+    if !live_mode.enabled {
+        return;
+    }
+
+    // Live mode always runs in real time
+    let now = wall_clock_unix_seconds();
+    sim_time.current = utc_to_tai(now) - utc_to_tai(sim_time.epoch_unix);
+    sim_time.speed_multiplier = 1.0;
+    sim_time.paused = false;
+
+    if now - live_mode.last_update < live_mode.update_interval {
+        return;
+    }
+    live_mode.last_update = now;
+
+    let records: HashMap<u32, &TleRecord> = tle_cache.records
+        .iter()
+        .map(|record| (record.norad_id, record))
+        .collect();
+
+    let mut updated = 0;
+    for (tle_data, mut orbital_state, optimized) in tle_query.iter_mut() {
+        let Some(record) = records.get(&tle_data.norad_id) else {
+            continue;
+        };
+        match propagate_to_current_time(record) {
+            Ok((position, velocity)) => {
+                orbital_state.position = position;
+                orbital_state.velocity = velocity;
+                if let Some(optimized) = optimized {
+                    optimized_data.set_state(optimized.index, position, velocity);
+                }
+                updated += 1;
+            }
+            Err(e) => {
+                debug!("Live propagation failed for {}: {}", tle_data.name, e);
+            }
+        }
+    }
+
+    trace!("Live mode repositioned {} cataloged objects", updated);
+}
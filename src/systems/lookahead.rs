@@ -0,0 +1,328 @@
+// Look-ahead conjunction prediction: every object is flown forward on its conic to list the coming close approaches
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::thread::JoinHandle;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::conjunctions::linear_closest_approach;
+use crate::systems::hash_grid::HashGridBroadPhase;
+use crate::systems::search::SearchCommand;
+use crate::systems::selection::{FocusRequest, Selection};
+use crate::utils::{propagate_kepler, unix_to_iso8601};
+
+/// Default look-ahead (hours)
+pub const DEFAULT_LOOKAHEAD_HOURS: f64 = 6.0;
+/// Longest look-ahead accepted (hours)
+pub const MAX_LOOKAHEAD_HOURS: f64 = 72.0;
+/// Simulated seconds between predicted states
+pub const LOOKAHEAD_STEP: f64 = 60.0;
+/// Predicted miss distance below which an approach is listed (km)
+pub const LOOKAHEAD_MISS_DISTANCE: f64 = 10.0;
+/// Approaches shown in the panel and the log
+pub const LOOKAHEAD_REPORTED: usize = 10;
+/// Simulated seconds after which a prediction is repeated from the current states
+pub const LOOKAHEAD_REFRESH: f64 = 1800.0;
+
+/// A close approach predicted by the look-ahead
+#[derive(Clone, Copy, Debug)]
+pub struct PredictedApproach {
+    /// Satellite whose screening found the approach
+    pub primary: Entity,
+    pub secondary: Entity,
+    /// Simulation time of closest approach (seconds)
+    pub tca: f64,
+    /// Predicted miss distance (km)
+    pub miss_distance: f64,
+    /// Relative speed at closest approach (km/s)
+    pub relative_speed: f64,
+}
+
+/// Resource holding the latest look-ahead prediction and the one being computed
+#[derive(Resource)]
+pub struct LookAhead {
+    /// Whether predictions run and are listed in the panel and the log
+    pub enabled: bool,
+    /// Whether a consumer needs predictions this frame even though they are not listed
+    pub requested: bool,
+    /// Prediction span (simulated seconds)
+    pub horizon: f64,
+    /// Closest approach of each pair within the horizon, soonest first
+    pub approaches: Vec<PredictedApproach>,
+    /// Simulation time the latest prediction started from
+    pub predicted_at: Option<f64>,
+    /// Start time and worker of a prediction in progress
+    pending: Option<(f64, JoinHandle<Vec<PredictedApproach>>)>,
+    /// Start a new prediction without waiting for the refresh interval
+    rerun: bool,
+}
+
+impl Default for LookAhead {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            requested: false,
+            horizon: DEFAULT_LOOKAHEAD_HOURS * 3600.0,
+            approaches: Vec::new(),
+            predicted_at: None,
+            pending: None,
+            rerun: false,
+        }
+    }
+}
+
+impl LookAhead {
+    pub fn with_hours(hours: f64) -> Self {
+        Self {
+            enabled: true,
+            horizon: hours.clamp(LOOKAHEAD_STEP / 3600.0, MAX_LOOKAHEAD_HOURS) * 3600.0,
+            ..default()
+        }
+    }
+
+    /// Approaches whose closest approach is still ahead of `now`
+    pub fn upcoming(&self, now: f64) -> impl Iterator<Item = &PredictedApproach> {
+        self.approaches.iter().filter(move |approach| approach.tca >= now)
+    }
+}
+
+/// Command to run the look-ahead or to select one of its predicted approaches
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LookAheadCommand {
+    /// Predict now, optionally over a new horizon (simulated seconds)
+    Run { horizon: Option<f64> },
+    /// Select and follow the pair of the upcoming approach at this 1-based rank
+    Show(usize),
+}
+
+impl LookAheadCommand {
+    /// Parse `lookahead [HOURS]` or `lookahead show N`; returns `None` when the text is not a look-ahead command
+    pub fn parse(text: &str) -> Option<Result<Self, String>> {
+        let mut words = text.split_whitespace();
+        if !words.next()?.eq_ignore_ascii_case("lookahead") {
+            return None;
+        }
+        let command = match (words.next(), words.next()) {
+            (None, _) => Ok(LookAheadCommand::Run { horizon: None }),
+            (Some(word), Some(rank)) if word.eq_ignore_ascii_case("show") => rank
+                .parse::<usize>()
+                .ok()
+                .filter(|rank| *rank >= 1)
+                .map(LookAheadCommand::Show)
+                .ok_or_else(|| format!("invalid rank {}", rank)),
+            (Some(word), None) => match word.parse::<f64>() {
+                Ok(hours) if hours > 0.0 && hours <= MAX_LOOKAHEAD_HOURS => Ok(LookAheadCommand::Run { horizon: Some(hours * 3600.0) }),
+                Ok(hours) => Err(format!("look-ahead {} h outside 0-{} h", hours, MAX_LOOKAHEAD_HOURS)),
+                Err(_) => Err(format!("invalid look-ahead {}", word)),
+            },
+            (Some(word), Some(_)) => Err(format!("unknown look-ahead command {}", word)),
+        };
+        Some(command)
+    }
+}
+
+/// Closest approach of each satellite pair and satellite-object pair within `horizon` seconds
+///
+/// States are stepped along their conics every `LOOKAHEAD_STEP` seconds; at each step objects within reach
+/// of a satellite are found with a hash grid and the encounter is refined assuming straight-line relative
+/// motion for half a step either side. Times are seconds from the given states.
+fn predict_approaches(objects: Vec<(Entity, DVec3, DVec3, bool)>, horizon: f64, mu: f64) -> Vec<PredictedApproach> {
+    use rayon::prelude::*;
+
+    let index: HashMap<Entity, usize> = objects.iter().enumerate().map(|(i, object)| (object.0, i)).collect();
+    let mut states: Vec<(DVec3, DVec3)> = objects.iter().map(|object| (object.1, object.2)).collect();
+    let max_speed = states.iter().map(|state| state.1.length()).fold(0.0, f64::max);
+    // Two objects closing head-on at the highest speed cover this much in half a step each
+    let reach = LOOKAHEAD_MISS_DISTANCE + max_speed * LOOKAHEAD_STEP;
+    let half_step = LOOKAHEAD_STEP / 2.0;
+
+    let mut grid = HashGridBroadPhase::default();
+    let mut nearby = Vec::new();
+    let mut closest: HashMap<(Entity, Entity), PredictedApproach> = HashMap::new();
+    let samples = (horizon / LOOKAHEAD_STEP).ceil() as usize;
+    for sample in 0..=samples {
+        let time = sample as f64 * LOOKAHEAD_STEP;
+        if sample > 0 {
+            states.par_iter_mut().for_each(|state| *state = propagate_kepler(state.0, state.1, LOOKAHEAD_STEP, mu));
+        }
+        grid.rebuild(objects.iter().zip(&states).map(|(object, state)| (object.0, state.0)), reach);
+
+        for (i, &(entity, _, _, satellite)) in objects.iter().enumerate() {
+            if !satellite {
+                continue;
+            }
+            let (position, velocity) = states[i];
+            nearby.clear();
+            grid.query_sphere(position, reach, &mut nearby);
+            for &other in &nearby {
+                let Some(&j) = index.get(&other) else {
+                    continue;
+                };
+                // Report each satellite pair once
+                if j == i || (objects[j].3 && j < i) {
+                    continue;
+                }
+                let relative_position = states[j].0 - position;
+                let relative_velocity = states[j].1 - velocity;
+                let (tca, miss) = linear_closest_approach(relative_position, relative_velocity);
+                // Minima outside this step's half-width belong to a neighbouring step
+                if tca.abs() > half_step || time + tca < 0.0 || miss > LOOKAHEAD_MISS_DISTANCE {
+                    continue;
+                }
+                let approach = PredictedApproach {
+                    primary: entity,
+                    secondary: other,
+                    tca: time + tca,
+                    miss_distance: miss,
+                    relative_speed: relative_velocity.length(),
+                };
+                closest.entry((entity, other))
+                    .and_modify(|best| if miss < best.miss_distance { *best = approach })
+                    .or_insert(approach);
+            }
+        }
+    }
+
+    let mut approaches: Vec<PredictedApproach> = closest.into_values().collect();
+    approaches.sort_by(|a, b| a.tca.total_cmp(&b.tca));
+    approaches
+}
+
+/// System to start look-ahead predictions on a worker thread and collect their results
+pub fn lookahead_system(
+    mut lookahead: ResMut<LookAhead>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    objects: Query<(Entity, &OrbitalState, Has<Satellite>)>,
+    satellites: Query<&Satellite>,
+) {
+    if lookahead.pending.as_ref().is_some_and(|(_, worker)| worker.is_finished()) {
+        let (started_at, worker) = lookahead.pending.take().expect("pending prediction");
+        match worker.join() {
+            Ok(mut approaches) => {
+                for approach in approaches.iter_mut() {
+                    approach.tca += started_at;
+                }
+                if lookahead.enabled {
+                    let name = |entity: Entity| satellites.get(entity).map(|s| s.name.trim().to_string()).unwrap_or_else(|_| "Debris".to_string());
+                    info!("Look-ahead: {} approaches under {:.0} km in the next {:.1} h",
+                          approaches.len(), LOOKAHEAD_MISS_DISTANCE, lookahead.horizon / 3600.0);
+                    for approach in approaches.iter().take(LOOKAHEAD_REPORTED) {
+                        info!("  {} / {} at {}Z: miss {:.2} km at {:.2} km/s",
+                              name(approach.primary), name(approach.secondary),
+                              unix_to_iso8601(sim_time.unix_at(approach.tca)), approach.miss_distance, approach.relative_speed);
+                    }
+                }
+                lookahead.approaches = approaches;
+                lookahead.predicted_at = Some(started_at);
+            }
+            Err(_) => warn!("Look-ahead prediction failed"),
+        }
+    }
+
+    // Consumers re-request every frame they need predictions
+    let requested = std::mem::take(&mut lookahead.requested);
+    if !(lookahead.enabled || requested) || lookahead.pending.is_some() {
+        return;
+    }
+    // Time running backwards (a replay or reset) invalidates the prediction
+    let stale = lookahead.predicted_at.is_none_or(|at| sim_time.current < at || sim_time.current - at >= LOOKAHEAD_REFRESH);
+    if !stale && !lookahead.rerun {
+        return;
+    }
+    lookahead.rerun = false;
+
+    let snapshot: Vec<_> = objects.iter()
+        .map(|(entity, state, satellite)| (entity, state.position, state.velocity, satellite))
+        .collect();
+    let (horizon, mu_km) = (lookahead.horizon, constants.gravitational_parameter / 1e9);
+    let worker = std::thread::spawn(move || predict_approaches(snapshot, horizon, mu_km));
+    lookahead.pending = Some((sim_time.current, worker));
+}
+
+/// System to handle look-ahead commands from the search box
+pub fn lookahead_command_system(
+    mut commands: EventReader<SearchCommand>,
+    mut lookahead: ResMut<LookAhead>,
+    sim_time: Res<SimulationTime>,
+    mut selection: ResMut<Selection>,
+    mut focus_requests: EventWriter<FocusRequest>,
+) {
+    for command in commands.read() {
+        let SearchCommand::LookAhead(command) = command else {
+            continue;
+        };
+        match *command {
+            LookAheadCommand::Run { horizon } => {
+                lookahead.enabled = true;
+                lookahead.rerun = true;
+                if let Some(horizon) = horizon {
+                    lookahead.horizon = horizon;
+                }
+                info!("Look-ahead: predicting {:.1} h ahead", lookahead.horizon / 3600.0);
+            }
+            LookAheadCommand::Show(rank) => match lookahead.upcoming(sim_time.current).nth(rank - 1) {
+                Some(approach) => {
+                    selection.selected = Some(approach.primary);
+                    selection.secondary = Some(approach.secondary);
+                    focus_requests.write(FocusRequest { entity: approach.primary });
+                }
+                None => info!("Look-ahead: no upcoming approach #{}", rank),
+            },
+        }
+    }
+}
+
+/// Marker component for the look-ahead panel text
+#[derive(Component)]
+pub struct LookAheadPanelText;
+
+/// System to create the (initially empty) look-ahead panel
+pub fn setup_lookahead_panel_system(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.75, 0.4)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(60.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        LookAheadPanelText,
+    ));
+}
+
+/// System to list the soonest predicted approaches with their countdowns
+pub fn lookahead_panel_system(
+    lookahead: Res<LookAhead>,
+    sim_time: Res<SimulationTime>,
+    satellites: Query<&Satellite>,
+    mut text_query: Query<&mut Text, With<LookAheadPanelText>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+    if !lookahead.enabled {
+        if !text.0.is_empty() {
+            text.0.clear();
+        }
+        return;
+    }
+
+    let name = |entity: Entity| satellites.get(entity).map(|s| s.name.trim().to_string()).unwrap_or_else(|_| "Debris".to_string());
+    let status = if lookahead.pending.is_some() { "  (updating)" } else { "" };
+    let mut lines = vec![format!("Look-ahead {:.1} h, coarse Kepler{}", lookahead.horizon / 3600.0, status)];
+    for (rank, approach) in lookahead.upcoming(sim_time.current).take(LOOKAHEAD_REPORTED).enumerate() {
+        lines.push(format!(
+            "{}. {} / {} in {:.0} min: {:.2} km at {:.1} km/s",
+            rank + 1, name(approach.primary), name(approach.secondary),
+            (approach.tca - sim_time.current) / 60.0, approach.miss_distance, approach.relative_speed
+        ));
+    }
+    text.0 = lines.join("\n");
+}
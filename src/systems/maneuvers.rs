@@ -0,0 +1,213 @@
+// Rendezvous transfer planning and execution of scheduled burns
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::designer::MIN_PERIGEE_ALTITUDE;
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+use crate::systems::search::SearchCommand;
+use crate::systems::selection::Selection;
+use crate::utils::*;
+
+/// Default distance (km) the chaser arrives behind its target
+pub const DEFAULT_STANDOFF_KM: f64 = 5.0;
+/// Plane differences (degrees) above which the planner warns that it leaves them uncorrected
+const PLANE_WARNING_DEG: f64 = 0.5;
+
+/// Command requesting a rendezvous transfer of the selected object to the secondary selection
+#[derive(Clone, Copy, Debug)]
+pub struct TransferRequest {
+    /// Distance the chaser arrives behind the target (km)
+    pub standoff: f64,
+}
+
+impl TransferRequest {
+    /// Parse `transfer [STANDOFF_KM]`; returns `None` when the text is not a transfer request
+    pub fn parse(text: &str) -> Option<Result<Self, String>> {
+        let mut words = text.split_whitespace();
+        if !words.next()?.eq_ignore_ascii_case("transfer") {
+            return None;
+        }
+        let standoff = match words.next() {
+            Some(word) => match word.parse::<f64>() {
+                Ok(standoff) if standoff >= 0.0 => standoff,
+                _ => return Some(Err(format!("invalid standoff {}", word))),
+            },
+            None => DEFAULT_STANDOFF_KM,
+        };
+        Some(Ok(Self { standoff }))
+    }
+}
+
+/// Command requesting a burn of the selected object
+#[derive(Clone, Copy, Debug)]
+pub struct BurnRequest {
+    /// Velocity change along the radial, transverse and normal directions (km/s)
+    pub delta_v: DVec3,
+    /// Simulated seconds from now until the burn
+    pub delay: f64,
+}
+
+impl BurnRequest {
+    /// Parse `burn R T N [DELAY_S]` with the components in m/s; returns `None` when the text is not a burn
+    pub fn parse(text: &str) -> Option<Result<Self, String>> {
+        let mut words = text.split_whitespace();
+        if !words.next()?.eq_ignore_ascii_case("burn") {
+            return None;
+        }
+        let values: Result<Vec<f64>, String> = words
+            .map(|word| word.parse::<f64>().map_err(|_| format!("invalid number {}", word)))
+            .collect();
+        match values {
+            Ok(values) => match values.as_slice() {
+                [r, t, n] => Some(Ok(Self { delta_v: DVec3::new(*r, *t, *n) / 1000.0, delay: 0.0 })),
+                [r, t, n, delay] if *delay >= 0.0 => {
+                    Some(Ok(Self { delta_v: DVec3::new(*r, *t, *n) / 1000.0, delay: *delay }))
+                }
+                _ => Some(Err("expected burn R T N [DELAY_S]".to_string())),
+            },
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Add a burn to an object's maneuver plan in execution order, giving it a plan if it has none
+pub fn schedule_burn(commands: &mut Commands, entity: Entity, plan: Option<Mut<ManeuverPlan>>, burn: PlannedBurn) {
+    match plan {
+        Some(mut plan) => {
+            let index = plan.burns.partition_point(|b| b.execute_at <= burn.execute_at);
+            plan.burns.insert(index, burn);
+        }
+        None => {
+            commands.entity(entity).try_insert(ManeuverPlan { burns: [burn].into() });
+        }
+    }
+}
+
+/// System to add a requested burn to the selected satellite's maneuver plan
+pub fn burn_scheduling_system(
+    mut commands: Commands,
+    mut requests: EventReader<SearchCommand>,
+    selection: Res<Selection>,
+    sim_time: Res<SimulationTime>,
+    mut objects: Query<(Option<&Satellite>, Option<&Health>, Option<&mut ManeuverPlan>)>,
+) {
+    for command in requests.read() {
+        let SearchCommand::Burn(request) = command else {
+            continue;
+        };
+        let Some((satellite, health, plan)) = selection.selected.and_then(|entity| objects.get_mut(entity).ok()) else {
+            info!("Burn needs a selected satellite");
+            continue;
+        };
+        let Some(satellite) = satellite.filter(|s| s.active && health.is_none_or(|h| h.has_propulsion())) else {
+            info!("Burn: the selected object cannot maneuver");
+            continue;
+        };
+
+        let burn = PlannedBurn { execute_at: sim_time.current + request.delay, delta_v: request.delta_v };
+        info!("{} scheduled {:.2} m/s burn (R {:.2}, T {:.2}, N {:.2}) at {}Z", satellite.name.trim(),
+              request.delta_v.length() * 1000.0, request.delta_v.x * 1000.0, request.delta_v.y * 1000.0,
+              request.delta_v.z * 1000.0, unix_to_iso8601(sim_time.unix_at(burn.execute_at)));
+        if let Some(entity) = selection.selected {
+            schedule_burn(&mut commands, entity, plan, burn);
+        }
+    }
+}
+
+/// System to plan a Hohmann/phasing rendezvous for the selected pair and schedule it as a maneuver plan
+pub fn transfer_planning_system(
+    mut commands: Commands,
+    mut requests: EventReader<SearchCommand>,
+    selection: Res<Selection>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    objects: Query<(&OrbitalState, Option<&Satellite>, Option<&Health>)>,
+) {
+    let request = requests.read().filter_map(|command| match command {
+        SearchCommand::Transfer(request) => Some(*request),
+        _ => None,
+    }).last();
+    let Some(request) = request else {
+        return;
+    };
+    let (Some(chaser), Some(target)) = (selection.selected, selection.secondary) else {
+        info!("Transfer needs a chaser and a target: select one and Shift+click another");
+        return;
+    };
+    let (Ok((chaser_state, chaser_satellite, health)), Ok((target_state, target_satellite, _))) =
+        (objects.get(chaser), objects.get(target)) else {
+        return;
+    };
+    let Some(chaser_satellite) = chaser_satellite.filter(|s| s.active && health.is_none_or(|h| h.has_propulsion())) else {
+        info!("Transfer: the selected object cannot maneuver");
+        return;
+    };
+
+    let target_name = target_satellite.map(|s| s.name.trim().to_string()).unwrap_or_else(|| "Debris".to_string());
+    let mu_km = constants.gravitational_parameter / 1e9;
+    let Some(plan) = plan_transfer(
+        (chaser_state.position, chaser_state.velocity),
+        (target_state.position, target_state.velocity),
+        request.standoff,
+        constants.earth_radius + MIN_PERIGEE_ALTITUDE,
+        mu_km,
+    ) else {
+        info!("Transfer: no rendezvous found from {} to {}", chaser_satellite.name.trim(), target_name);
+        return;
+    };
+
+    let kind = match plan.kind {
+        TransferKind::Hohmann => "Hohmann".to_string(),
+        TransferKind::Phasing { revolutions } => format!("phasing ({} rev)", revolutions),
+    };
+    info!("Transfer {} -> {}: {} transfer, {} burns, {:.1} m/s, arriving {:.1} km behind at {}Z (in {:.1} h)",
+          chaser_satellite.name.trim(), target_name, kind, plan.burns.len(), plan.total_delta_v() * 1000.0,
+          request.standoff, unix_to_iso8601(sim_time.unix_at(sim_time.current + plan.duration)), plan.duration / 3600.0);
+    if plan.plane_angle.to_degrees() > PLANE_WARNING_DEG {
+        warn!("Transfer: orbit planes differ by {:.1}°, which the plan does not correct", plan.plane_angle.to_degrees());
+    }
+
+    commands.entity(chaser).try_insert(ManeuverPlan {
+        burns: plan.burns.iter().map(|burn| PlannedBurn {
+            execute_at: sim_time.current + burn.offset,
+            // Planned for circular orbits, where the direction of motion is transverse
+            delta_v: DVec3::new(0.0, burn.delta_v, 0.0),
+        }).collect(),
+    });
+}
+
+/// System to execute scheduled burns once their time has come
+#[allow(clippy::type_complexity)]
+pub fn maneuver_execution_system(
+    mut commands: Commands,
+    sim_time: Res<SimulationTime>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+    mut objects: Query<(Entity, &mut OrbitalState, &mut ManeuverPlan, Option<&Satellite>, Option<&Health>, Option<&OptimizedPhysics>)>,
+) {
+    for (entity, mut state, mut plan, satellite, health, optimized) in objects.iter_mut() {
+        let name = satellite.map(|s| s.name.trim().to_string()).unwrap_or_else(|| "Object".to_string());
+        if health.is_some_and(|h| !h.has_propulsion()) {
+            warn!("{} lost propulsion, cancelling {} remaining burns", name, plan.burns.len());
+            commands.entity(entity).remove::<ManeuverPlan>();
+            continue;
+        }
+
+        while plan.burns.front().is_some_and(|burn| burn.execute_at <= sim_time.current) {
+            let Some(burn) = plan.burns.pop_front() else {
+                break;
+            };
+            let delta_v = rtn_to_eci(state.position, state.velocity, burn.delta_v);
+            state.velocity += delta_v;
+            if let Some(optimized) = optimized {
+                optimized_data.change_velocity(optimized.index, delta_v);
+            }
+            info!("{} executed {:.2} m/s burn ({} remaining)", name, burn.delta_v.length() * 1000.0, plan.burns.len());
+        }
+
+        if plan.burns.is_empty() {
+            commands.entity(entity).remove::<ManeuverPlan>();
+        }
+    }
+}
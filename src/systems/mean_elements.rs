@@ -0,0 +1,81 @@
+// Analytic mean-element propagation for objects with nothing imminent
+
+use bevy::prelude::*;
+use std::collections::HashSet;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::conjunctions::ConjunctionForecast;
+
+/// Resource controlling which objects are propagated analytically
+///
+/// While enabled, every object follows its conic with the secular J2 drift, and only objects in a
+/// predicted conjunction or with a burn coming up are integrated numerically under the full force model.
+#[derive(Resource)]
+pub struct MeanElementPropagation {
+    pub enabled: bool,
+    /// Objects with a burn scheduled within this many simulated seconds are integrated numerically
+    pub maneuver_window: f64,
+}
+
+impl Default for MeanElementPropagation {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            maneuver_window: 600.0,
+        }
+    }
+}
+
+/// System to pick each object's propagation mode from the conjunction forecast and scheduled burns
+///
+/// The forecast screens around satellites, so debris-on-debris encounters stay analytic; collisions are
+/// still detected from the analytic positions.
+#[allow(clippy::type_complexity)]
+pub fn propagation_mode_system(
+    mut commands: Commands,
+    mean_elements: Res<MeanElementPropagation>,
+    sim_time: Res<SimulationTime>,
+    mut forecast: ResMut<ConjunctionForecast>,
+    mut objects: Query<(Entity, Option<&mut Propagation>, Option<&ManeuverPlan>), With<OrbitalState>>,
+    mut reported: Local<Option<usize>>,
+) {
+    if !mean_elements.enabled || sim_time.paused {
+        return;
+    }
+    forecast.requested = true;
+
+    let imminent: HashSet<Entity> = forecast.upcoming.iter()
+        .flat_map(|conjunction| [conjunction.primary, conjunction.secondary])
+        .collect();
+    let burn_horizon = sim_time.current + mean_elements.maneuver_window;
+
+    let (mut analytic, mut numerical) = (0, 0);
+    for (entity, propagation, plan) in objects.iter_mut() {
+        let maneuvering = plan.is_some_and(|plan| plan.burns.front().is_some_and(|burn| burn.execute_at <= burn_horizon));
+        let mode = if maneuvering || imminent.contains(&entity) {
+            numerical += 1;
+            Propagation::Numerical
+        } else {
+            analytic += 1;
+            Propagation::MeanElements
+        };
+
+        match propagation {
+            Some(mut propagation) => {
+                if *propagation != mode {
+                    *propagation = mode;
+                }
+            }
+            // The object may be destroyed before the command applies
+            None => {
+                commands.entity(entity).try_insert(mode);
+            }
+        }
+    }
+
+    // Report only when objects wake up or go back to sleep
+    if *reported != Some(numerical) {
+        *reported = Some(numerical);
+        info!("Mean-element propagation: {} objects analytic, {} integrated numerically", analytic, numerical);
+    }
+}
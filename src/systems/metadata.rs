@@ -0,0 +1,205 @@
+// Owner/country metadata and physical properties from SATCAT, and operator/owner filters
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Mutex;
+use crate::components::*;
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+use crate::systems::stress_test::StressTestObject;
+use crate::utils::*;
+
+/// Local SATCAT copy, preferred over the network
+pub const SATCAT_PATH: &str = "assets/satcat.csv";
+/// CelesTrak SATCAT download
+pub const SATCAT_URL: &str = "https://celestrak.org/pub/satcat.csv";
+
+/// Owner code given to objects missing from SATCAT
+pub const UNKNOWN_OWNER: &str = "UNK";
+
+/// SATCAT entries downloaded on a background thread
+type SatcatDownload = Mutex<Receiver<Result<HashMap<u32, SatcatEntry>, String>>>;
+
+/// Resource holding SATCAT entries, loaded from disk or downloaded in the background
+#[derive(Resource, Default)]
+pub struct SatcatCatalog {
+    pub entries: HashMap<u32, SatcatEntry>,
+    /// Set once loading finished, successfully or not
+    pub loaded: bool,
+    download: Option<SatcatDownload>,
+}
+
+/// System to load SATCAT from `assets/satcat.csv`, or start downloading it from CelesTrak
+pub fn load_satcat_system(mut catalog: ResMut<SatcatCatalog>) {
+    if Path::new(SATCAT_PATH).exists() {
+        match std::fs::read_to_string(SATCAT_PATH).map_err(|e| e.to_string()).and_then(|text| parse_satcat_csv(&text)) {
+            Ok(entries) => {
+                info!("Loaded {} SATCAT entries from {}", entries.len(), SATCAT_PATH);
+                catalog.entries = entries;
+            }
+            Err(e) => warn!("Failed to load {}: {}", SATCAT_PATH, e),
+        }
+        catalog.loaded = true;
+        return;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let result = rt.block_on(async {
+            let response = reqwest::get(SATCAT_URL).await.map_err(|e| e.to_string())?;
+            let text = response.text().await.map_err(|e| e.to_string())?;
+            parse_satcat_csv(&text)
+        });
+        let _ = tx.send(result);
+    });
+    catalog.download = Some(Mutex::new(rx));
+}
+
+/// System to tag satellites with their SATCAT owner and derelict status, and size them from its physical data
+///
+/// Objects with a catalogued size or radar cross-section get a collision sphere of that diameter; masses come
+/// from the catalog, or for debris from their size, and otherwise stay the name-based estimate.
+#[allow(clippy::type_complexity)]
+pub fn satcat_ingest_system(
+    mut commands: Commands,
+    mut catalog: ResMut<SatcatCatalog>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+    mut satellites: Query<
+        (Entity, &Satellite, &mut OrbitalState, &mut PhysicsObject, Option<&OptimizedPhysics>, Has<ObjectProperties>),
+        (Without<Owner>, Without<StressTestObject>),
+    >,
+) {
+    if let Some(download) = &catalog.download {
+        let result = match download.lock().unwrap().try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err("download thread stopped".to_string()),
+        };
+        match result {
+            Ok(entries) => {
+                info!("Downloaded {} SATCAT entries", entries.len());
+                catalog.entries = entries;
+            }
+            Err(e) => warn!("Failed to download SATCAT: {} - owners will be unknown", e),
+        }
+        catalog.download = None;
+        catalog.loaded = true;
+    }
+    if !catalog.loaded {
+        return;
+    }
+
+    let (mut sized, mut weighed) = (0, 0);
+    for (entity, satellite, mut state, mut physics, optimized, described) in satellites.iter_mut() {
+        let entry = catalog.entries.get(&satellite.norad_id);
+        let code = entry.map_or(UNKNOWN_OWNER, |entry| entry.owner.as_str());
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert(Owner { code: code.to_string() });
+        if entry.is_some_and(SatcatEntry::is_derelict) {
+            entity_commands.insert(Derelict);
+        }
+
+        // DISCOS descriptions are more complete than SATCAT's
+        let Some(entry) = entry.filter(|_| !described) else {
+            continue;
+        };
+        if let Some(mass) = entry.estimated_mass() {
+            state.mass = mass;
+            if let Some(optimized) = optimized {
+                optimized_data.set_mass(optimized.index, mass);
+            }
+            weighed += 1;
+        }
+        if let Some(length) = entry.characteristic_length() {
+            *physics = PhysicsObject::sized(state.mass, length / 2.0, physics.drag_coefficient);
+            sized += 1;
+        } else if entry.mass.is_some() {
+            *physics = PhysicsObject::satellite(state.mass);
+        }
+    }
+    if sized + weighed > 0 {
+        info!("SATCAT sized {} objects and gave {} their mass", sized, weighed);
+    }
+}
+
+/// Resource restricting the shown population by operator, owner, derelict and eclipse status
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct PopulationFilter {
+    pub operator: Option<String>,
+    pub owner: Option<String>,
+    /// `Some(true)` shows only derelicts, `Some(false)` only operational objects
+    pub derelict: Option<bool>,
+    /// `Some(true)` shows only objects in the Earth's shadow, `Some(false)` only sunlit ones
+    pub eclipsed: Option<bool>,
+}
+
+impl PopulationFilter {
+    /// Whether a search query is a filter expression rather than an object name
+    pub fn is_expression(query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.contains(':') || matches!(query.as_str(), "all" | "derelict" | "active" | "eclipsed" | "sunlit")
+    }
+
+    /// Parse a comma-separated filter, e.g. `operator:SpaceX` or `owner:CIS,derelict`; `all` clears it
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let mut filter = Self::default();
+        for term in expression.split(',').map(str::trim).filter(|term| !term.is_empty()) {
+            match term.split_once(':') {
+                Some((key, value)) if key.eq_ignore_ascii_case("operator") => filter.operator = Some(value.trim().to_string()),
+                Some((key, value)) if key.eq_ignore_ascii_case("owner") => filter.owner = Some(value.trim().to_string()),
+                None if term.eq_ignore_ascii_case("derelict") => filter.derelict = Some(true),
+                None if term.eq_ignore_ascii_case("active") => filter.derelict = Some(false),
+                None if term.eq_ignore_ascii_case("eclipsed") => filter.eclipsed = Some(true),
+                None if term.eq_ignore_ascii_case("sunlit") => filter.eclipsed = Some(false),
+                None if term.eq_ignore_ascii_case("all") => filter = Self::default(),
+                _ => return Err(format!("unknown filter term {} (expected operator:NAME, owner:CODE, derelict, active, eclipsed, sunlit or all)", term)),
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Whether the filter hides anything
+    pub fn is_active(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Whether an object passes the filter
+    pub fn matches(&self, operator: Option<&Operator>, owner: Option<&Owner>, derelict: bool, eclipsed: bool) -> bool {
+        let operator_ok = self.operator.as_ref()
+            .is_none_or(|wanted| operator.is_some_and(|operator| operator.name.eq_ignore_ascii_case(wanted)));
+        let owner_ok = self.owner.as_ref()
+            .is_none_or(|wanted| owner.is_some_and(|owner| owner.code.eq_ignore_ascii_case(wanted)));
+        let derelict_ok = self.derelict.is_none_or(|wanted| wanted == derelict);
+        let eclipsed_ok = self.eclipsed.is_none_or(|wanted| wanted == eclipsed);
+        operator_ok && owner_ok && derelict_ok && eclipsed_ok
+    }
+}
+
+impl std::fmt::Display for PopulationFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut terms = Vec::new();
+        if let Some(operator) = &self.operator {
+            terms.push(format!("operator:{}", operator));
+        }
+        if let Some(owner) = &self.owner {
+            terms.push(format!("owner:{}", owner));
+        }
+        match self.derelict {
+            Some(true) => terms.push("derelict".to_string()),
+            Some(false) => terms.push("active".to_string()),
+            None => {}
+        }
+        match self.eclipsed {
+            Some(true) => terms.push("eclipsed".to_string()),
+            Some(false) => terms.push("sunlit".to_string()),
+            None => {}
+        }
+        if terms.is_empty() {
+            write!(f, "all")
+        } else {
+            write!(f, "{}", terms.join(","))
+        }
+    }
+}
@@ -1,17 +1,103 @@
+pub mod archive;
+pub mod avoidance;
+pub mod background;
+pub mod cdm;
 pub mod data;
+pub mod designer;
+pub mod diagnostics;
+pub mod discos;
+pub mod director;
+pub mod drag;
+pub mod eclipse;
+pub mod ephemeris;
+pub mod export;
+pub mod features;
+pub mod gamepad;
+pub mod genealogy;
+pub mod geo;
+pub mod ground;
+pub mod hash_grid;
 pub mod physics;
+pub mod protection;
 // pub mod gpu_physics; // Disabled - complex Bevy render API
 pub mod optimized_physics;
 pub mod collision;
+pub mod colors;
+pub mod conjunctions;
+pub mod constellation;
 pub mod analytics;
+pub mod health;
+pub mod launch_traffic;
+pub mod live;
+pub mod lookahead;
+pub mod maneuvers;
+pub mod mean_elements;
+pub mod metadata;
+pub mod morton;
+pub mod publisher;
 pub mod rendering;
+pub mod scenario;
+pub mod search;
+pub mod selection;
+pub mod slow_motion;
+pub mod sso;
 pub mod stress_test;
+pub mod super_particles;
+pub mod telemetry;
+pub mod touch;
+pub mod traffic;
+pub mod ui;
+pub mod validation;
+pub mod watchlist;
 
+pub use archive::*;
+pub use avoidance::*;
+pub use background::*;
+pub use cdm::*;
 pub use data::*;
+pub use designer::*;
+pub use diagnostics::*;
+pub use discos::*;
+pub use director::*;
+pub use drag::*;
+pub use eclipse::*;
+pub use ephemeris::*;
+pub use export::*;
+pub use features::*;
+pub use gamepad::*;
+pub use genealogy::*;
+pub use geo::*;
+pub use ground::*;
+pub use hash_grid::*;
 pub use physics::*;
+pub use protection::*;
 // pub use gpu_physics::*; // Disabled
 pub use optimized_physics::*;
 pub use collision::*;
+pub use colors::*;
+pub use conjunctions::*;
+pub use constellation::*;
 pub use analytics::*;
+pub use health::*;
+pub use launch_traffic::*;
+pub use live::*;
+pub use lookahead::*;
+pub use maneuvers::*;
+pub use mean_elements::*;
+pub use metadata::*;
+pub use morton::*;
+pub use publisher::*;
 pub use rendering::*;
-pub use stress_test::*;
\ No newline at end of file
+pub use scenario::*;
+pub use search::*;
+pub use selection::*;
+pub use slow_motion::*;
+pub use sso::*;
+pub use stress_test::*;
+pub use super_particles::*;
+pub use telemetry::*;
+pub use touch::*;
+pub use traffic::*;
+pub use ui::*;
+pub use validation::*;
+pub use watchlist::*;
\ No newline at end of file
@@ -0,0 +1,83 @@
+// Morton-code sorted sweep broad-phase: a cache-friendly alternative to the octree
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+/// Smallest grid cell (km); cells grow to the largest search radius of the step
+pub const MORTON_MIN_CELL_SIZE: f64 = 10.0;
+/// Bits per axis of a Morton code
+const MORTON_AXIS_BITS: u32 = 21;
+/// Cell offset moving ECI coordinates into the unsigned code range
+const MORTON_AXIS_OFFSET: i64 = 1 << (MORTON_AXIS_BITS - 1);
+
+/// Resource holding objects sorted by the Morton code of their grid cell
+#[derive(Resource)]
+pub struct MortonBroadPhase {
+    /// Edge length of a grid cell in the current build (km)
+    pub cell_size: f64,
+    entries: Vec<(u64, Entity)>,
+}
+
+impl Default for MortonBroadPhase {
+    fn default() -> Self {
+        Self {
+            cell_size: MORTON_MIN_CELL_SIZE,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl MortonBroadPhase {
+    /// Re-sort objects into cells at least `max_radius` wide, so any query only touches neighbouring cells
+    pub fn rebuild(&mut self, objects: impl Iterator<Item = (Entity, DVec3)>, max_radius: f64) {
+        self.cell_size = max_radius.max(MORTON_MIN_CELL_SIZE);
+        self.entries.clear();
+        for (entity, position) in objects {
+            self.entries.push((morton_code(self.cell_of(position)), entity));
+        }
+        self.entries.sort_unstable_by_key(|&(code, _)| code);
+    }
+
+    /// Get all objects in cells overlapping a sphere
+    pub fn query_sphere(&self, center: DVec3, radius: f64, results: &mut Vec<Entity>) {
+        let reach = (radius / self.cell_size).ceil().max(1.0) as i64;
+        let cell = self.cell_of(center);
+        for dz in -reach..=reach {
+            for dy in -reach..=reach {
+                for dx in -reach..=reach {
+                    let code = morton_code([cell[0] + dx, cell[1] + dy, cell[2] + dz]);
+                    let start = self.entries.partition_point(|&(c, _)| c < code);
+                    results.extend(
+                        self.entries[start..]
+                            .iter()
+                            .take_while(|&&(c, _)| c == code)
+                            .map(|&(_, entity)| entity),
+                    );
+                }
+            }
+        }
+    }
+
+    fn cell_of(&self, position: DVec3) -> [i64; 3] {
+        let cell = (position / self.cell_size).floor();
+        [cell.x as i64, cell.y as i64, cell.z as i64]
+    }
+}
+
+/// Interleave the bits of a cell's coordinates
+fn morton_code(cell: [i64; 3]) -> u64 {
+    let max = (1i64 << MORTON_AXIS_BITS) - 1;
+    let axis = |value: i64| spread_bits((value + MORTON_AXIS_OFFSET).clamp(0, max) as u64);
+    axis(cell[0]) | (axis(cell[1]) << 1) | (axis(cell[2]) << 2)
+}
+
+/// Spread the low 21 bits of a value so two zero bits separate each of them
+fn spread_bits(value: u64) -> u64 {
+    let mut x = value & 0x1f_ffff;
+    x = (x | (x << 32)) & 0x001f_0000_0000_ffff;
+    x = (x | (x << 16)) & 0x001f_0000_ff00_00ff;
+    x = (x | (x << 8)) & 0x100f_00f0_0f00_f00f;
+    x = (x | (x << 4)) & 0x10c3_0c30_c30c_30c3;
+    x = (x | (x << 2)) & 0x1249_2492_4924_9249;
+    x
+}
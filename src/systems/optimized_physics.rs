@@ -1,21 +1,61 @@
+use bevy::math::DVec3;
 use bevy::prelude::*;
+use std::thread::JoinHandle;
+use std::time::Instant;
 use crate::components::*;
 use crate::resources::*;
+use crate::utils::{gmst_radians, integrate, integrate_adaptive, propagate_kepler_j2};
 
 /// Optimized orbital state using aligned data structures for better cache performance
 #[repr(C, align(32))] // 32-byte alignment for SIMD operations
 #[derive(Clone, Copy)]
 pub struct OptimizedOrbitalState {
-    pub position: [f32; 4],  // x, y, z, mass (aligned for SIMD)
-    pub velocity: [f32; 4],  // vx, vy, vz, padding
+    pub position: [f64; 4],  // x, y, z, mass (aligned for SIMD)
+    pub velocity: [f64; 4],  // vx, vy, vz, last adaptive step (s, 0 until the first)
+}
+
+/// Integration applied to one step of the optimized pipeline
+#[derive(Clone, Copy)]
+enum IntegrationStep {
+    Integrate { integrator: IntegratorConfig, mu_km: f64, perturbations: Perturbations, dt: f64, steps: u32 },
+    Kepler { mu_km: f64, j2: f64, reference_radius: f64, dt: f64 },
+}
+
+/// Accelerations beyond the central term for one frame of substeps
+#[derive(Clone, Copy)]
+struct Perturbations {
+    geopotential: Geopotential,
+    third_bodies: ThirdBodies,
+    /// The first substep sees the Earth at `earth_angle`, turning at `rotation_rate` (rad/s)
+    earth_angle: f64,
+    rotation_rate: f64,
+    /// Secular J2 drift and its reference radius for objects on mean elements
+    secular_j2: f64,
+    reference_radius: f64,
 }
 
 /// Resource to hold optimized physics data
+///
+/// Double-buffered: `states` holds the latest completed step and is read by the apply pass, while the
+/// next step is integrated into a second buffer on a worker thread and swapped in on the following frame.
 #[derive(Resource)]
 pub struct OptimizedPhysicsData {
     pub states: Vec<OptimizedOrbitalState>,
     pub entity_map: Vec<Entity>,
     pub dirty: bool,
+    /// Worker-thread integration time of the latest step (ms)
+    pub step_ms: f32,
+    /// Buffer the next step is integrated into
+    back: Vec<OptimizedOrbitalState>,
+    /// Step being integrated on the worker thread
+    pending: Option<JoinHandle<(Vec<OptimizedOrbitalState>, f32)>>,
+    /// Edits made while a step was in flight, reapplied to its result: a new state (index, Some(position),
+    /// velocity) or a velocity change (index, None, delta-v)
+    overrides: Vec<(usize, Option<DVec3>, DVec3)>,
+    /// Mass edits made while a step was in flight: (index, mass)
+    mass_overrides: Vec<(usize, f64)>,
+    /// Whether each object follows its mean elements instead of being integrated, refreshed every step
+    mean_elements: Vec<bool>,
 }
 
 impl Default for OptimizedPhysicsData {
@@ -24,10 +64,113 @@ impl Default for OptimizedPhysicsData {
             states: Vec::new(),
             entity_map: Vec::new(),
             dirty: true,
+            step_ms: 0.0,
+            back: Vec::new(),
+            pending: None,
+            overrides: Vec::new(),
+            mass_overrides: Vec::new(),
+            mean_elements: Vec::new(),
         }
     }
 }
 
+impl OptimizedPhysicsData {
+    /// Add a velocity change made outside the optimized pipeline, such as a burn or a frame of drag
+    ///
+    /// The change is added to the step in flight as well rather than replacing its velocity, so the
+    /// gravity integrated over that step is kept.
+    pub fn change_velocity(&mut self, index: usize, delta_v: DVec3) {
+        if let Some(state) = self.states.get_mut(index) {
+            let velocity = DVec3::from_slice(&state.velocity[..3]) + delta_v;
+            state.velocity[..3].copy_from_slice(&velocity.to_array());
+            if self.pending.is_some() {
+                self.overrides.push((index, None, delta_v));
+            }
+        }
+    }
+
+    /// Overwrite the cached position and velocity of an object driven outside the optimized pipeline
+    pub fn set_state(&mut self, index: usize, position: DVec3, velocity: DVec3) {
+        if let Some(state) = self.states.get_mut(index) {
+            state.position[..3].copy_from_slice(&position.to_array());
+            state.velocity[..3].copy_from_slice(&velocity.to_array());
+            if self.pending.is_some() {
+                self.overrides.push((index, Some(position), velocity));
+            }
+        }
+    }
+
+    /// Overwrite the cached mass of an object whose physical properties were revised
+    pub fn set_mass(&mut self, index: usize, mass: f64) {
+        if let Some(state) = self.states.get_mut(index) {
+            state.position[3] = mass;
+            if self.pending.is_some() {
+                self.mass_overrides.push((index, mass));
+            }
+        }
+    }
+
+    /// Wait for the in-flight step and swap its result in as the current states
+    pub fn finish_step(&mut self) {
+        let Some(handle) = self.pending.take() else {
+            return;
+        };
+        let overrides = std::mem::take(&mut self.overrides);
+        let mass_overrides = std::mem::take(&mut self.mass_overrides);
+        let mut result = match handle.join() {
+            Ok((result, step_ms)) => {
+                self.step_ms = step_ms;
+                result
+            }
+            Err(_) => {
+                warn!("Optimized physics worker panicked - step dropped");
+                return;
+            }
+        };
+        if result.len() != self.states.len() {
+            return;
+        }
+
+        for (index, position, velocity) in overrides {
+            let state = &mut result[index];
+            let velocity = match position {
+                Some(position) => {
+                    state.position[..3].copy_from_slice(&position.to_array());
+                    velocity
+                }
+                None => DVec3::from_slice(&state.velocity[..3]) + velocity,
+            };
+            state.velocity[..3].copy_from_slice(&velocity.to_array());
+        }
+        for (index, mass) in mass_overrides {
+            result[index].position[3] = mass;
+        }
+        std::mem::swap(&mut self.states, &mut result);
+        self.back = result;
+        self.dirty = true;
+    }
+
+    /// Start integrating the next step from the current states on a worker thread
+    fn begin_step(&mut self, step: IntegrationStep) {
+        let mut next = std::mem::take(&mut self.back);
+        next.clear();
+        next.extend_from_slice(&self.states);
+        let mean_elements = self.mean_elements.clone();
+        self.pending = Some(std::thread::spawn(move || {
+            let started = Instant::now();
+            match step {
+                IntegrationStep::Integrate { integrator, mu_km, perturbations, dt, steps } => {
+                    compute_physics_parallel(&mut next, &mean_elements, integrator, mu_km, &perturbations, dt, steps)
+                }
+                IntegrationStep::Kepler { mu_km, j2, reference_radius, dt } => {
+                    compute_kepler_parallel(&mut next, mu_km, j2, reference_radius, dt)
+                }
+            }
+            (next, started.elapsed().as_secs_f32() * 1000.0)
+        }));
+    }
+}
+
 /// Component to mark entities for optimized physics processing
 #[derive(Component)]
 pub struct OptimizedPhysics {
@@ -35,32 +178,41 @@ pub struct OptimizedPhysics {
 }
 
 /// System to prepare optimized physics data
+#[allow(clippy::type_complexity)]
 pub fn prepare_optimized_physics_system(
     mut optimized_data: ResMut<OptimizedPhysicsData>,
-    orbital_query: Query<(Entity, &OrbitalState), (With<PhysicsObject>, Without<OptimizedPhysics>)>,
+    new_objects: Query<(), (With<OrbitalState>, With<PhysicsObject>, Without<OptimizedPhysics>)>,
+    mut orbital_query: Query<(Entity, &OrbitalState, Option<&mut OptimizedPhysics>), With<PhysicsObject>>,
     mut commands: Commands,
 ) {
     // Check if we have new entities to optimize
-    if orbital_query.is_empty() {
+    if new_objects.is_empty() {
         return;
     }
 
-    info!("Preparing {} objects for optimized physics", orbital_query.iter().count());
+    info!("Preparing {} objects for optimized physics", new_objects.iter().count());
+
+    // The in-flight step refers to the old layout
+    optimized_data.finish_step();
 
-    // Clear and rebuild optimized data
-    optimized_data.states.clear();
-    optimized_data.entity_map.clear();
+    // Rebuild optimized data for every object; already registered ones keep their latest integrated state
+    let previous_states = std::mem::take(&mut optimized_data.states);
+    let previous_entities = std::mem::take(&mut optimized_data.entity_map);
 
-    for (entity, orbital_state) in orbital_query.iter() {
+    for (entity, orbital_state, optimized) in orbital_query.iter_mut() {
         let index = optimized_data.states.len();
-        
+
+        let previous = optimized.as_ref()
+            .map(|optimized| optimized.index)
+            .filter(|&previous| previous_entities.get(previous) == Some(&entity))
+            .and_then(|previous| previous_states.get(previous).copied());
         // Convert to optimized format
-        let optimized_state = OptimizedOrbitalState {
+        let optimized_state = previous.unwrap_or(OptimizedOrbitalState {
             position: [
                 orbital_state.position.x,
                 orbital_state.position.y,
                 orbital_state.position.z,
-                orbital_state.mass as f32,
+                orbital_state.mass,
             ],
             velocity: [
                 orbital_state.velocity.x,
@@ -68,13 +220,18 @@ pub fn prepare_optimized_physics_system(
                 orbital_state.velocity.z,
                 0.0,
             ],
-        };
+        });
 
         optimized_data.states.push(optimized_state);
         optimized_data.entity_map.push(entity);
 
-        // Mark entity as optimized
-        commands.entity(entity).insert(OptimizedPhysics { index });
+        match optimized {
+            Some(mut optimized) => optimized.index = index,
+            // Mark entity as optimized (it may be despawned by a collision or reentry before the command applies)
+            None => {
+                commands.entity(entity).try_insert(OptimizedPhysics { index });
+            }
+        }
     }
 
     optimized_data.dirty = true;
@@ -82,125 +239,176 @@ pub fn prepare_optimized_physics_system(
 }
 
 /// High-performance parallel physics system using SIMD and multithreading
+///
+/// Collects the step integrated since last frame and starts the next one, so integration overlaps
+/// with applying and rendering the current step. Results therefore trail the simulation clock by one step.
 pub fn optimized_physics_system(
     mut optimized_data: ResMut<OptimizedPhysicsData>,
     constants: Res<Constants>,
     sim_time: Res<SimulationTime>,
+    governor: Res<TimestepGovernor>,
+    integrator: Res<IntegratorConfig>,
+    time: Res<Time>,
+    propagation_query: Query<&Propagation>,
 ) {
-    // Don't run if paused or no objects
-    if sim_time.paused || optimized_data.states.is_empty() {
+    optimized_data.finish_step();
+
+    // Don't run if paused (unless single-stepping) or no objects
+    if !sim_time.should_integrate() || optimized_data.states.is_empty() {
         return;
     }
 
-    let gm = constants.gravitational_parameter as f32;
-    let dt = sim_time.timestep as f32;
+    // Coarse time compression, or more substeps than the governor allows, follows each conic analytically
+    let step = match governor.plan(&sim_time, time.delta_secs()) {
+        StepPlan::Kepler { dt } => IntegrationStep::Kepler {
+            mu_km: constants.gravitational_parameter / 1e9,
+            j2: constants.secular_j2(),
+            reference_radius: constants.equatorial_radius,
+            dt,
+        },
+        StepPlan::Integrate { count, dt } => IntegrationStep::Integrate {
+            integrator: *integrator,
+            mu_km: constants.gravitational_parameter / 1e9,
+            perturbations: Perturbations {
+                geopotential: constants.geopotential(),
+                // The Sun and Moon barely move over one frame
+                third_bodies: constants.third_bodies(sim_time.current_unix()),
+                earth_angle: gmst_radians(sim_time.current_unix()),
+                rotation_rate: constants.earth_rotation_rate,
+                secular_j2: constants.secular_j2(),
+                reference_radius: constants.equatorial_radius,
+            },
+            dt,
+            steps: count,
+        },
+    };
 
-    // Compute physics directly using parallel processing
-    compute_physics_parallel(&mut optimized_data.states, gm, dt);
-    optimized_data.dirty = true;
+    let data = &mut *optimized_data;
+    data.mean_elements.clear();
+    data.mean_elements.extend(data.entity_map.iter().map(|&entity| {
+        propagation_query.get(entity).is_ok_and(|propagation| *propagation == Propagation::MeanElements)
+    }));
+    data.begin_step(step);
 }
 
 /// Parallel physics computation using chunked processing
 fn compute_physics_parallel(
     states: &mut [OptimizedOrbitalState],
-    gm: f32,
-    dt: f32,
+    mean_elements: &[bool],
+    integrator: IntegratorConfig,
+    mu_km: f64,
+    perturbations: &Perturbations,
+    dt: f64,
+    steps: u32,
 ) {
     use rayon::prelude::*;
 
     // Process physics in parallel chunks
-    states.par_iter_mut().for_each(|state| {
-        compute_orbital_physics_simd(state, gm, dt);
+    states.par_iter_mut().enumerate().for_each(|(index, state)| {
+        if mean_elements.get(index).copied().unwrap_or(false) {
+            propagate_mean_elements(state, mu_km, perturbations, dt * steps as f64);
+        } else {
+            compute_orbital_physics_simd(state, integrator, mu_km, perturbations, dt, steps);
+        }
     });
 }
 
-/// SIMD-optimized orbital physics computation for a single object
-#[inline(always)]
-fn compute_orbital_physics_simd(state: &mut OptimizedOrbitalState, gm: f32, dt: f32) {
-    // Load position and velocity
-    let pos = [state.position[0], state.position[1], state.position[2]];
-    let vel = [state.velocity[0], state.velocity[1], state.velocity[2]];
-    let mass = state.position[3];
+/// Parallel analytic Kepler propagation for coarse time compression
+fn compute_kepler_parallel(
+    states: &mut [OptimizedOrbitalState],
+    mu_km: f64,
+    j2: f64,
+    reference_radius: f64,
+    dt: f64,
+) {
+    use rayon::prelude::*;
 
-    // Skip invalid objects
-    if mass <= 0.0 {
+    states.par_iter_mut().for_each(|state| {
+        if state.position[3] <= 0.0 {
+            return;
+        }
+        let position = DVec3::from_slice(&state.position[..3]);
+        let velocity = DVec3::from_slice(&state.velocity[..3]);
+        let (position, velocity) = propagate_kepler_j2(position, velocity, dt, mu_km, j2, reference_radius);
+        state.position[..3].copy_from_slice(&position.to_array());
+        state.velocity[..3].copy_from_slice(&velocity.to_array());
+    });
+}
+
+/// Analytic propagation of a single object on mean elements over a whole frame
+fn propagate_mean_elements(state: &mut OptimizedOrbitalState, mu_km: f64, perturbations: &Perturbations, dt: f64) {
+    if state.position[3] <= 0.0 {
         return;
     }
+    let position = DVec3::from_slice(&state.position[..3]);
+    let velocity = DVec3::from_slice(&state.velocity[..3]);
+    let (position, velocity) = propagate_kepler_j2(position, velocity, dt, mu_km, perturbations.secular_j2,
+                                                   perturbations.reference_radius);
+    state.position[..3].copy_from_slice(&position.to_array());
+    state.velocity[..3].copy_from_slice(&velocity.to_array());
+}
 
-    // Calculate distance from Earth center (in km)
-    let r_mag_km = (pos[0] * pos[0] + pos[1] * pos[1] + pos[2] * pos[2]).sqrt();
-    
-    if r_mag_km <= 0.0 {
+/// Orbital physics computation for a single object over a frame of substeps
+#[inline(always)]
+fn compute_orbital_physics_simd(
+    state: &mut OptimizedOrbitalState,
+    integrator: IntegratorConfig,
+    mu_km: f64,
+    perturbations: &Perturbations,
+    dt: f64,
+    steps: u32,
+) {
+    // Skip invalid objects
+    if state.position[3] <= 0.0 {
         return;
     }
 
-    // MATCH ORIGINAL PHYSICS: Use r² instead of r³ (the original has this "bug" but it works)
-    // Convert to meters for gravitational calculation
-    let r_mag_m = r_mag_km * 1000.0;
-    
-    // Use original formula: a = -GM/r² * r̂ (matches original physics.rs line 38)
-    let acc_magnitude = -gm / (r_mag_m * r_mag_m);  // Note: r² not r³, matching original
-    
-    // Unit vector components
-    let r_unit_x = pos[0] / r_mag_km;
-    let r_unit_y = pos[1] / r_mag_km;
-    let r_unit_z = pos[2] / r_mag_km;
-    
-    // Acceleration in km/s² (matches original physics.rs lines 46-49)
-    let acc_km_s2 = acc_magnitude / 1000.0;
-    let acc_x = r_unit_x * acc_km_s2;
-    let acc_y = r_unit_y * acc_km_s2;
-    let acc_z = r_unit_z * acc_km_s2;
-
-    // Euler integration
-    let new_vel_x = vel[0] + acc_x * dt;
-    let new_vel_y = vel[1] + acc_y * dt;
-    let new_vel_z = vel[2] + acc_z * dt;
+    let position = DVec3::from_slice(&state.position[..3]);
+    let velocity = DVec3::from_slice(&state.velocity[..3]);
 
-    let new_pos_x = pos[0] + new_vel_x * dt;
-    let new_pos_y = pos[1] + new_vel_y * dt;
-    let new_pos_z = pos[2] + new_vel_z * dt;
+    // Central term a = -μ r̂ / r², plus the harmonics of the force model with the Earth turning under
+    // the substeps, and the Sun and Moon
+    let acceleration = |position: DVec3, elapsed: f64| {
+        let r2 = position.length_squared();
+        if r2 <= 0.0 {
+            return DVec3::ZERO;
+        }
+        -position * (mu_km / (r2 * r2.sqrt()))
+            + perturbations.geopotential.acceleration(position, perturbations.earth_angle + perturbations.rotation_rate * elapsed)
+            + perturbations.third_bodies.acceleration(position)
+    };
+    let (position, velocity) = match integrator.method {
+        // The adaptive step carries over between frames instead of restarting from the substep length
+        Integrator::DormandPrince => {
+            let mut step = if state.velocity[3] > 0.0 { state.velocity[3] } else { dt };
+            let result = integrate_adaptive(position, velocity, dt * steps as f64, &mut step, integrator.tolerance, acceleration);
+            state.velocity[3] = step;
+            result
+        }
+        _ => integrate(integrator, position, velocity, dt, steps, acceleration),
+    };
 
     // Store results
-    state.position[0] = new_pos_x;
-    state.position[1] = new_pos_y;
-    state.position[2] = new_pos_z;
-    
-    state.velocity[0] = new_vel_x;
-    state.velocity[1] = new_vel_y;
-    state.velocity[2] = new_vel_z;
+    state.position[..3].copy_from_slice(&position.to_array());
+    state.velocity[..3].copy_from_slice(&velocity.to_array());
 }
 
 /// System to apply optimized results back to ECS components
 pub fn apply_optimized_physics_system(
     optimized_data: Res<OptimizedPhysicsData>,
     mut orbital_query: Query<&mut OrbitalState, With<OptimizedPhysics>>,
-    optimized_query: Query<&OptimizedPhysics>,
 ) {
     if !optimized_data.dirty {
         return;
     }
 
     // Apply results back to ECS components
-    for (optimized_physics, &entity) in optimized_query.iter().zip(optimized_data.entity_map.iter()) {
+    for (state, &entity) in optimized_data.states.iter().zip(optimized_data.entity_map.iter()) {
         if let Ok(mut orbital_state) = orbital_query.get_mut(entity) {
-            let index = optimized_physics.index;
-            if index < optimized_data.states.len() {
-                let state = &optimized_data.states[index];
-                
-                // Update ECS component
-                orbital_state.position = Vec3::new(
-                    state.position[0],
-                    state.position[1],
-                    state.position[2],
-                );
-                orbital_state.velocity = Vec3::new(
-                    state.velocity[0],
-                    state.velocity[1],
-                    state.velocity[2],
-                );
-                orbital_state.mass = state.position[3] as f64;
-            }
+            // Update ECS component
+            orbital_state.position = DVec3::from_slice(&state.position[..3]);
+            orbital_state.velocity = DVec3::from_slice(&state.velocity[..3]);
+            orbital_state.mass = state.position[3];
         }
     }
 }
@@ -1,74 +1,139 @@
+use bevy::math::DVec3;
 use bevy::prelude::*;
 use crate::components::*;
 use crate::resources::*;
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+use crate::systems::search::SearchBox;
+use crate::utils::{gmst_radians, integrate, propagate_kepler_j2};
 
-/// Main physics system implementing 2-body orbital mechanics
+/// Event emitted when an object reenters the atmosphere and is removed
+#[derive(Event, Clone, Debug)]
+pub struct ReentryEvent {
+    pub entity: Entity,
+    pub name: Option<String>,
+    pub norad_id: Option<u32>,
+    /// Position at reentry (km, ECI)
+    pub position: DVec3,
+    /// Velocity at reentry (km/s, ECI)
+    pub velocity: DVec3,
+    /// Simulation time of reentry (seconds)
+    pub time: f64,
+    /// Mass of the object (kg)
+    pub mass: f64,
+    /// Whether the object was debris, and how many fragments it stood for
+    pub debris: bool,
+    pub weight: u32,
+}
+
+/// System to advance the simulation clock by the frame's real time and the time compression
+pub fn simulation_clock_system(mut sim_time: ResMut<SimulationTime>, time: Res<Time>) {
+    sim_time.advance(time.delta_secs());
+}
+
+/// Run condition for the legacy physics pipeline
+pub fn legacy_physics_active(backend: Res<PhysicsBackend>) -> bool {
+    *backend == PhysicsBackend::Legacy
+}
+
+/// Run condition for the optimized physics pipeline
+pub fn optimized_physics_active(backend: Res<PhysicsBackend>) -> bool {
+    *backend == PhysicsBackend::Optimized
+}
+
+/// System to switch the physics backend with F12, handing the latest states over to the other pipeline
+pub fn physics_backend_control_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut backend: ResMut<PhysicsBackend>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+    mut orbital_query: Query<(Entity, &mut OrbitalState, Option<&OptimizedPhysics>)>,
+) {
+    if !keyboard.just_pressed(KeyCode::F12) {
+        return;
+    }
+
+    optimized_data.finish_step();
+    match *backend {
+        // The legacy pipeline continues from the step that was in flight
+        PhysicsBackend::Optimized => {
+            for (state, &entity) in optimized_data.states.iter().zip(optimized_data.entity_map.iter()) {
+                if let Ok((_, mut orbital_state, _)) = orbital_query.get_mut(entity) {
+                    orbital_state.position = DVec3::from_slice(&state.position[..3]);
+                    orbital_state.velocity = DVec3::from_slice(&state.velocity[..3]);
+                }
+            }
+        }
+        // The buffer still holds the states from when the legacy pipeline took over
+        PhysicsBackend::Legacy => {
+            for (entity, orbital_state, optimized) in orbital_query.iter() {
+                let Some(optimized) = optimized.filter(|o| optimized_data.entity_map.get(o.index) == Some(&entity)) else {
+                    continue;
+                };
+                optimized_data.set_state(optimized.index, orbital_state.position, orbital_state.velocity);
+            }
+        }
+    }
+    *backend = backend.toggled();
+    info!("Physics backend: {}", backend.name());
+}
+
+/// Legacy physics system implementing 2-body orbital mechanics with the geopotential of the selected force model
+/// and lunisolar perturbations
 pub fn physics_system(
-    mut orbital_query: Query<&mut OrbitalState>,
+    mut orbital_query: Query<(&mut OrbitalState, Option<&Propagation>)>,
     constants: Res<Constants>,
-    mut sim_time: ResMut<SimulationTime>,
+    sim_time: Res<SimulationTime>,
+    governor: Res<TimestepGovernor>,
+    integrator: Res<IntegratorConfig>,
     time: Res<Time>,
 ) {
-    // Update simulation time
-    sim_time.advance(time.delta_secs());
-
-    // Don't run physics if paused
-    if sim_time.paused {
+    // Don't run physics if paused (unless single-stepping)
+    if !sim_time.should_integrate() {
         return;
     }
 
-    let dt = sim_time.timestep;
-    let gm = constants.gravitational_parameter;
+    let mu_km = constants.gravitational_parameter / 1e9; // m³/s² to km³/s²
 
-    for mut orbital_state in orbital_query.iter_mut() {
-        // Work with f64 precision for physics calculations
-        let pos_x = orbital_state.position.x as f64;
-        let pos_y = orbital_state.position.y as f64;
-        let pos_z = orbital_state.position.z as f64;
-        
-        let vel_x = orbital_state.velocity.x as f64;
-        let vel_y = orbital_state.velocity.y as f64;
-        let vel_z = orbital_state.velocity.z as f64;
+    // Numerical integration is meaningless at week/month-per-second compression, or when the governor would
+    // need too many substeps; follow the conic instead
+    let (steps, dt) = match governor.plan(&sim_time, time.delta_secs()) {
+        StepPlan::Integrate { count, dt } => (count, dt),
+        StepPlan::Kepler { dt } => {
+            for (mut orbital_state, _) in orbital_query.iter_mut() {
+                let (position, velocity) = propagate_kepler_j2(orbital_state.position, orbital_state.velocity, dt, mu_km,
+                                                               constants.secular_j2(), constants.equatorial_radius);
+                orbital_state.position = position;
+                orbital_state.velocity = velocity;
+            }
+            return;
+        }
+    };
 
-        // Calculate gravitational acceleration: a = -GM * r / |r|³
-        let r_magnitude_km = (pos_x * pos_x + pos_y * pos_y + pos_z * pos_z).sqrt();
-        let r_magnitude_m = r_magnitude_km * 1000.0; // Convert km to m
-        
-        if r_magnitude_m > 0.0 {
-            let acc_magnitude = -gm / (r_magnitude_m * r_magnitude_m);
-            
-            // Unit vector components
-            let r_unit_x = pos_x / r_magnitude_km;
-            let r_unit_y = pos_y / r_magnitude_km;
-            let r_unit_z = pos_z / r_magnitude_km;
-            
-            // Acceleration in km/s²
-            let acc_km_s2 = acc_magnitude / 1000.0;
-            let acc_x = r_unit_x * acc_km_s2;
-            let acc_y = r_unit_y * acc_km_s2;
-            let acc_z = r_unit_z * acc_km_s2;
-
-            // Simple Euler integration
-            let new_vel_x = vel_x + acc_x * dt;
-            let new_vel_y = vel_y + acc_y * dt;
-            let new_vel_z = vel_z + acc_z * dt;
-            
-            let new_pos_x = pos_x + new_vel_x * dt;
-            let new_pos_y = pos_y + new_vel_y * dt;
-            let new_pos_z = pos_z + new_vel_z * dt;
-
-            // Update orbital state
-            orbital_state.velocity = Vec3::new(
-                new_vel_x as f32,
-                new_vel_y as f32,
-                new_vel_z as f32,
-            );
-            orbital_state.position = Vec3::new(
-                new_pos_x as f32,
-                new_pos_y as f32,
-                new_pos_z as f32,
-            );
+    let geopotential = constants.geopotential();
+    let earth_angle = gmst_radians(sim_time.current_unix());
+    // The Sun and Moon barely move over one frame
+    let third_bodies = constants.third_bodies(sim_time.current_unix());
+
+    // Gravitational acceleration in km/s²: -μ r̂ / r², the harmonics of the force model with the Earth
+    // turning under the substeps, and the Sun and Moon
+    let acceleration = |position: DVec3, elapsed: f64| {
+        let r2 = position.length_squared();
+        if r2 <= 0.0 {
+            return DVec3::ZERO;
         }
+        -position * (mu_km / (r2 * r2.sqrt()))
+            + geopotential.acceleration(position, earth_angle + constants.earth_rotation_rate * elapsed)
+            + third_bodies.acceleration(position)
+    };
+
+    for (mut orbital_state, propagation) in orbital_query.iter_mut() {
+        let (position, velocity) = match propagation {
+            Some(Propagation::MeanElements) => propagate_kepler_j2(orbital_state.position, orbital_state.velocity,
+                                                                   dt * steps as f64, mu_km, constants.secular_j2(),
+                                                                   constants.equatorial_radius),
+            _ => integrate(*integrator, orbital_state.position, orbital_state.velocity, dt, steps, acceleration),
+        };
+        orbital_state.position = position;
+        orbital_state.velocity = velocity;
     }
 }
 
@@ -76,7 +141,21 @@ pub fn physics_system(
 pub fn time_control_system(
     mut sim_time: ResMut<SimulationTime>,
     keyboard: Res<ButtonInput<KeyCode>>,
+    search: Res<SearchBox>,
+    mut was_coarse: Local<bool>,
 ) {
+    // A step requested last frame runs exactly one physics step this frame
+    sim_time.stepping = std::mem::take(&mut sim_time.step_requested);
+    if sim_time.stepping {
+        let timestep = sim_time.timestep;
+        sim_time.current += timestep;
+    }
+
+    // Keys are going into the search box
+    if search.active {
+        return;
+    }
+
     if keyboard.just_pressed(KeyCode::Space) {
         if sim_time.paused {
             sim_time.resume();
@@ -97,6 +176,129 @@ pub fn time_control_system(
     if keyboard.just_pressed(KeyCode::Digit4) {
         sim_time.set_speed(86400.0); // 1 day per second
     }
+    if keyboard.just_pressed(KeyCode::Digit9) {
+        sim_time.set_speed(7.0 * 86400.0); // 1 week per second
+    }
+    if keyboard.just_pressed(KeyCode::Digit0) {
+        sim_time.set_speed(30.0 * 86400.0); // 1 month per second
+    }
+
+    if sim_time.is_changed() && sim_time.is_coarse() != *was_coarse {
+        *was_coarse = sim_time.is_coarse();
+        if *was_coarse {
+            info!(
+                "{:.0}x compression: switching to Kepler propagation, collisions checked every {} frames",
+                sim_time.speed_multiplier, sim_time.collision_stride()
+            );
+        } else {
+            info!("{:.0}x compression: switching back to numerical integration", sim_time.speed_multiplier);
+        }
+    }
+
+    // Halve/double the physics timestep with '[' and ']' keys
+    if keyboard.just_pressed(KeyCode::BracketLeft) {
+        adjust_timestep(&mut sim_time, 0.5);
+    }
+    if keyboard.just_pressed(KeyCode::BracketRight) {
+        adjust_timestep(&mut sim_time, 2.0);
+    }
+
+    // Advance a single physics step while paused with '.' key
+    if keyboard.just_pressed(KeyCode::Period) {
+        sim_time.request_step();
+    }
+}
+
+/// Scale the physics timestep and warn when it leaves the stable range for LEO
+pub fn adjust_timestep(sim_time: &mut SimulationTime, factor: f64) {
+    let timestep = sim_time.timestep * factor;
+    sim_time.set_timestep(timestep);
+
+    match sim_time.stability_warning() {
+        Some(warning) => warn!("Timestep set to {:.1}s - {}", sim_time.timestep, warning),
+        None => info!("Timestep set to {:.1}s", sim_time.timestep),
+    }
+}
+
+/// System to measure the fastest orbital angular rate for the timestep governor and log changes of regime
+pub fn timestep_governor_system(
+    mut governor: ResMut<TimestepGovernor>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    time: Res<Time>,
+    orbital_query: Query<(&OrbitalState, Option<&Propagation>)>,
+    mut reported: Local<(f64, bool, bool)>,
+) {
+    if !governor.enabled || !sim_time.should_integrate() {
+        return;
+    }
+    // Objects on mean elements are not integrated and so neither limit the step nor count towards the budget
+    let integrated = || orbital_query.iter()
+        .filter(|(_, propagation)| *propagation != Some(&Propagation::MeanElements))
+        .map(|(state, _)| state);
+    // Objects below the surface are about to be removed as reentered and would dominate the rate
+    let surface = constants.earth_radius;
+    governor.max_angular_rate = integrated()
+        .filter(|state| state.position.length() > surface)
+        .map(|state| state.position.cross(state.velocity).length() / state.position.length_squared())
+        .fold(0.0, f64::max);
+    governor.object_count = integrated().count();
+
+    // Coarse propagation is announced by the time controls
+    if sim_time.is_coarse() {
+        return;
+    }
+    // Report each regime once per time compression; frame-time jitter moves the substep count around
+    if reported.0 != sim_time.speed_multiplier {
+        *reported = (sim_time.speed_multiplier, false, false);
+    }
+    match governor.plan(&sim_time, time.delta_secs()) {
+        StepPlan::Integrate { count, dt } if count > 1 && !reported.1 => {
+            reported.1 = true;
+            info!("{:.0}x compression: {} substeps of {:.1}s keep orbital steps under {:.1}°",
+                  sim_time.speed_multiplier, count, dt, governor.max_step_angle.to_degrees());
+        }
+        StepPlan::Kepler { dt } if !reported.2 => {
+            reported.2 = true;
+            warn!("{:.0}x compression: more than {} substeps per frame needed for {} objects, switching to Kepler \
+                   propagation ({:.0}s per frame, collisions are only checked between frames)",
+                  sim_time.speed_multiplier, governor.substep_limit(), governor.object_count, dt);
+        }
+        _ => {}
+    }
+}
+
+/// System to remove objects that have dropped into the atmosphere
+#[allow(clippy::type_complexity)]
+pub fn reentry_system(
+    mut commands: Commands,
+    constants: Res<Constants>,
+    sim_time: Res<SimulationTime>,
+    orbital_query: Query<(Entity, &OrbitalState, Option<&Satellite>, Has<Debris>, Option<&SuperParticle>)>,
+    mut reentry_events: EventWriter<ReentryEvent>,
+) {
+    let reentry_radius = constants.earth_radius + constants.reentry_altitude;
+
+    for (entity, orbital_state, satellite, debris, super_particle) in orbital_query.iter() {
+        if orbital_state.altitude() >= reentry_radius {
+            continue;
+        }
+        if let Some(satellite) = satellite {
+            info!("{} reentered at T+{:.0}s", satellite.name, sim_time.current);
+        }
+        reentry_events.write(ReentryEvent {
+            entity,
+            name: satellite.map(|s| s.name.clone()),
+            norad_id: satellite.map(|s| s.norad_id),
+            position: orbital_state.position,
+            velocity: orbital_state.velocity,
+            time: sim_time.current,
+            mass: orbital_state.mass,
+            debris,
+            weight: super_particle.map(|sp| sp.weight).unwrap_or(1),
+        });
+        commands.entity(entity).despawn();
+    }
 }
 
 /// Debug system to print orbital information summary
@@ -117,11 +319,8 @@ pub fn debug_orbital_system(
             // Only show detailed info for first few satellites in debug builds
             #[cfg(debug_assertions)]
             {
-                let mut count = 0;
-                for (orbital_state, satellite) in orbital_query.iter() {
-                    if count >= 3 { // Limit to first 3 satellites
-                        break;
-                    }
+                // Limit to first 3 satellites
+                for (orbital_state, satellite) in orbital_query.iter().take(3) {
                     let altitude = orbital_state.altitude() - constants.earth_radius;
                     let speed = orbital_state.speed();
                     let energy = orbital_state.total_energy(constants.gravitational_parameter);
@@ -130,7 +329,6 @@ pub fn debug_orbital_system(
                         "{}: Alt={:.1}km, Speed={:.2}km/s, Energy={:.2e}J",
                         satellite.name, altitude, speed, energy
                     );
-                    count += 1;
                 }
                 if total_satellites > 3 {
                     trace!("... and {} more satellites", total_satellites - 3);
@@ -0,0 +1,202 @@
+// Crewed-asset protection: pizza-box screening volumes and cumulative collision risk
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use crate::components::*;
+use crate::systems::collision::SpatialOctree;
+use crate::systems::conjunctions::linear_closest_approach;
+
+/// Crewed stations protected by default (NORAD ID, name)
+pub const PROTECTED_STATIONS: [(u32, &str); 2] = [(25544, "ISS"), (48274, "CSS")];
+/// Combined 1-sigma position uncertainty used for collision probability (km)
+pub const POSITION_SIGMA_KM: f64 = 1.0;
+/// Probability above which a penetration is raised as an alert (NASA's maneuver threshold)
+pub const RISK_ALERT_PROBABILITY: f64 = 1e-4;
+
+/// Component marking a protected asset with its radial, along-track and cross-track screening half-extents (km)
+#[derive(Component, Clone, Debug)]
+pub struct ProtectedAsset {
+    pub name: String,
+    pub radial: f64,
+    pub along_track: f64,
+    pub cross_track: f64,
+    /// Hard-body radius of the asset (km)
+    pub hard_body_radius: f64,
+}
+
+impl ProtectedAsset {
+    /// Crewed-station pizza box: ±2 km radial, ±25 km along- and cross-track
+    pub fn crewed_station(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            radial: 2.0,
+            along_track: 25.0,
+            cross_track: 25.0,
+            hard_body_radius: 0.06,
+        }
+    }
+
+    /// Radius of the sphere enclosing the box
+    pub fn bounding_radius(&self) -> f64 {
+        DVec3::new(self.radial, self.along_track, self.cross_track).length()
+    }
+
+    /// Whether a relative position lies inside the box around an asset at `position` moving with `velocity`
+    pub fn contains(&self, position: DVec3, velocity: DVec3, relative_position: DVec3) -> bool {
+        let radial = position.normalize_or_zero();
+        let cross = position.cross(velocity).normalize_or_zero();
+        let along = cross.cross(radial);
+        relative_position.dot(radial).abs() <= self.radial
+            && relative_position.dot(along).abs() <= self.along_track
+            && relative_position.dot(cross).abs() <= self.cross_track
+    }
+}
+
+/// Collision probability of an encounter with miss distance `miss` and combined hard-body radius (km),
+/// for an isotropic Gaussian uncertainty much larger than the hard body
+pub fn encounter_probability(miss: f64, hard_body_radius: f64, sigma: f64) -> f64 {
+    let variance = sigma * sigma;
+    (hard_body_radius * hard_body_radius / (2.0 * variance) * (-miss * miss / (2.0 * variance)).exp()).min(1.0)
+}
+
+/// Risk accumulated against one protected asset
+#[derive(Clone, Debug, Default)]
+pub struct AssetRisk {
+    pub name: String,
+    pub penetrations: u32,
+    /// Product of (1 - Pc) over all encounters so far
+    pub survival: f64,
+    pub worst_miss: Option<f64>,
+    /// Objects currently inside the screening volume
+    pub intruders: HashSet<Entity>,
+}
+
+impl AssetRisk {
+    /// Cumulative collision probability over all encounters
+    pub fn cumulative_probability(&self) -> f64 {
+        1.0 - self.survival
+    }
+}
+
+/// Resource tracking penetrations and cumulative risk per protected asset
+#[derive(Resource, Default)]
+pub struct ProtectionMonitor {
+    pub assets: BTreeMap<Entity, AssetRisk>,
+}
+
+/// Marker component for the risk panel text
+#[derive(Component)]
+pub struct RiskPanelText;
+
+/// System to mark crewed stations as protected assets when they are cataloged
+pub fn protected_asset_tagging_system(
+    mut commands: Commands,
+    satellites: Query<(Entity, &Satellite), Added<Satellite>>,
+) {
+    for (entity, satellite) in satellites.iter() {
+        if let Some((_, name)) = PROTECTED_STATIONS.iter().find(|(norad_id, _)| *norad_id == satellite.norad_id) {
+            info!("Protecting {} ({}) with a crewed-station screening volume", name, satellite.name.trim());
+            commands.entity(entity).insert(ProtectedAsset::crewed_station(*name));
+        }
+    }
+}
+
+/// System to detect objects entering protected volumes and accumulate their collision probability
+pub fn protection_screening_system(
+    mut monitor: ResMut<ProtectionMonitor>,
+    octree: Res<SpatialOctree>,
+    assets: Query<(Entity, &ProtectedAsset, &OrbitalState)>,
+    objects: Query<(&OrbitalState, Option<&Satellite>, Option<&PhysicsObject>)>,
+    mut nearby_objects: Local<Vec<Entity>>,
+) {
+    monitor.assets.retain(|entity, _| assets.contains(*entity));
+
+    for (entity, asset, asset_state) in assets.iter() {
+        let risk = monitor.assets.entry(entity).or_insert_with(|| AssetRisk {
+            name: asset.name.clone(),
+            survival: 1.0,
+            ..default()
+        });
+
+        nearby_objects.clear();
+        octree.root.query_sphere(asset_state.position, asset.bounding_radius(), &mut nearby_objects);
+
+        let mut inside = HashSet::new();
+        for &other in nearby_objects.iter() {
+            if other == entity {
+                continue;
+            }
+            let Ok((state, satellite, physics)) = objects.get(other) else {
+                continue;
+            };
+            let relative_position = state.position - asset_state.position;
+            if !asset.contains(asset_state.position, asset_state.velocity, relative_position) {
+                continue;
+            }
+            inside.insert(other);
+            if risk.intruders.contains(&other) {
+                continue;
+            }
+
+            // New penetration: score the encounter once from its predicted straight-line miss
+            let (tca, miss) = linear_closest_approach(relative_position, state.velocity - asset_state.velocity);
+            let hard_body = asset.hard_body_radius + physics.map_or(0.0, |p| p.collision_radius / 1000.0);
+            let probability = encounter_probability(miss, hard_body, POSITION_SIGMA_KM);
+            risk.penetrations += 1;
+            risk.survival *= 1.0 - probability;
+            risk.worst_miss = Some(risk.worst_miss.map_or(miss, |worst| worst.min(miss)));
+
+            let name = satellite.map(|s| s.name.trim().to_string()).unwrap_or_else(|| "Debris".to_string());
+            if probability >= RISK_ALERT_PROBABILITY {
+                warn!("{} ALERT: {} inside screening volume, miss {:.2} km in {:.0}s, Pc {:.1e}",
+                      asset.name, name, miss, tca.max(0.0), probability);
+            } else {
+                info!("{} screening volume penetrated by {} (miss {:.2} km, Pc {:.1e})", asset.name, name, miss, probability);
+            }
+        }
+        risk.intruders = inside;
+    }
+}
+
+/// System to create the (initially empty) risk panel
+pub fn setup_risk_panel_system(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.85, 0.9, 1.0)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..default()
+        },
+        RiskPanelText,
+    ));
+}
+
+/// System to show penetrations and cumulative collision probability of each protected asset
+pub fn risk_panel_system(
+    monitor: Res<ProtectionMonitor>,
+    mut text_query: Query<(&mut Text, &mut TextColor), With<RiskPanelText>>,
+) {
+    if !monitor.is_changed() {
+        return;
+    }
+    let Ok((mut text, mut color)) = text_query.single_mut() else {
+        return;
+    };
+
+    let lines: Vec<String> = monitor.assets.values().map(|risk| {
+        let worst = risk.worst_miss.map_or("-".to_string(), |miss| format!("{:.2} km", miss));
+        format!("{}: {} in volume  |  {} penetrations  |  closest {}  |  cumulative Pc {:.2e}",
+                risk.name, risk.intruders.len(), risk.penetrations, worst, risk.cumulative_probability())
+    }).collect();
+    text.0 = lines.join("\n");
+
+    let alert = monitor.assets.values().any(|risk| !risk.intruders.is_empty());
+    color.0 = if alert { Color::srgb(1.0, 0.4, 0.3) } else { Color::srgb(0.85, 0.9, 1.0) };
+}
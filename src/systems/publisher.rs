@@ -0,0 +1,200 @@
+// Optional publishing of collision, conjunction and reentry events to MQTT or Kafka
+
+use bevy::prelude::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::collision::CollisionEvent;
+use crate::systems::conjunctions::ConjunctionForecast;
+use crate::systems::physics::ReentryEvent;
+use crate::utils::*;
+
+/// MQTT keep-alive interval (seconds)
+const MQTT_KEEP_ALIVE: u16 = 60;
+
+/// An event queued for the publisher thread
+pub struct PublishedEvent {
+    /// Event kind: "collision", "conjunction" or "reentry"
+    pub kind: &'static str,
+    pub payload: Value,
+}
+
+/// Resource forwarding simulation events to a background publisher thread
+#[derive(Resource, Default)]
+pub struct EventPublisher {
+    sender: Option<Sender<PublishedEvent>>,
+    /// TCA of each object pair's last published conjunction
+    published_conjunctions: HashMap<(Entity, Entity), f64>,
+}
+
+impl EventPublisher {
+    /// Start a publisher thread for the target
+    pub fn start(target: PublishTarget) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        info!("Publishing events to {}", target);
+        std::thread::spawn(move || match target {
+            PublishTarget::Mqtt { host, port, topic } => run_mqtt_publisher(&host, port, &topic, receiver),
+            PublishTarget::Kafka { host, port, topic } => run_kafka_publisher(&host, port, &topic, receiver),
+        });
+        Self {
+            sender: Some(sender),
+            published_conjunctions: HashMap::new(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    fn send(&mut self, kind: &'static str, payload: Value) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        if sender.send(PublishedEvent { kind, payload }).is_err() {
+            warn!("Event publisher stopped - no further events will be published");
+            self.sender = None;
+        }
+    }
+}
+
+/// Publisher thread loop for an MQTT broker, reconnecting after failures
+fn run_mqtt_publisher(host: &str, port: u16, topic: &str, receiver: Receiver<PublishedEvent>) {
+    let client_id = format!("kessler-{}", std::process::id());
+    let mut client: Option<MqttClient> = None;
+
+    loop {
+        match receiver.recv_timeout(Duration::from_secs(MQTT_KEEP_ALIVE as u64 / 4)) {
+            Ok(event) => {
+                if client.is_none() {
+                    client = MqttClient::connect(host, port, &client_id, MQTT_KEEP_ALIVE)
+                        .map_err(|e| warn!("MQTT connection to {}:{} failed: {}", host, port, e))
+                        .ok();
+                }
+                if let Some(connected) = client.as_mut() {
+                    let event_topic = format!("{}/{}", topic, event.kind);
+                    if let Err(e) = connected.publish(&event_topic, event.payload.to_string().as_bytes()) {
+                        warn!("MQTT publish failed: {}", e);
+                        client = None;
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                if client.as_mut().is_some_and(|connected| connected.keep_alive().is_err()) {
+                    client = None;
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+/// Publisher thread loop for a Kafka topic behind a REST proxy
+fn run_kafka_publisher(host: &str, port: u16, topic: &str, receiver: Receiver<PublishedEvent>) {
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            warn!("Kafka publisher could not start: {}", e);
+            return;
+        }
+    };
+    let client = reqwest::Client::new();
+    let url = format!("http://{}:{}/topics/{}", host, port, topic);
+
+    while let Ok(event) = receiver.recv() {
+        let body = json!({ "records": [{ "key": event.kind, "value": event.payload }] });
+        let result = runtime.block_on(
+            client
+                .post(&url)
+                .header("Content-Type", "application/vnd.kafka.json.v2+json")
+                .body(body.to_string())
+                .send(),
+        );
+        match result {
+            Ok(response) if !response.status().is_success() => {
+                warn!("Kafka REST proxy rejected event: HTTP {}", response.status());
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Kafka publish failed: {}", e),
+        }
+    }
+}
+
+/// JSON description of an object for event payloads
+fn object_json(entity: Entity, satellites: &Query<&Satellite>) -> Value {
+    match satellites.get(entity) {
+        Ok(satellite) => json!({ "name": satellite.name.trim(), "norad_id": satellite.norad_id }),
+        Err(_) => json!({ "name": "DEBRIS", "norad_id": null }),
+    }
+}
+
+/// System to publish collision and reentry events
+pub fn publish_events_system(
+    mut publisher: ResMut<EventPublisher>,
+    mut collisions: EventReader<CollisionEvent>,
+    mut reentries: EventReader<ReentryEvent>,
+    sim_time: Res<SimulationTime>,
+) {
+    if !publisher.enabled() {
+        collisions.clear();
+        reentries.clear();
+        return;
+    }
+
+    for collision in collisions.read() {
+        publisher.send("collision", json!({
+            "time": format!("{}Z", unix_to_iso8601(sim_time.unix_at(collision.time))),
+            "objects": [
+                { "name": collision.names.0.trim(), "norad_id": collision.norad_ids.0 },
+                { "name": collision.names.1.trim(), "norad_id": collision.norad_ids.1 },
+            ],
+            "position_km": collision.position.to_array(),
+            "relative_speed_km_s": collision.relative_speed,
+            "catastrophic": collision.catastrophic,
+            "fragments": collision.fragments,
+        }));
+    }
+    for reentry in reentries.read() {
+        publisher.send("reentry", json!({
+            "time": format!("{}Z", unix_to_iso8601(sim_time.unix_at(reentry.time))),
+            "object": { "name": reentry.name.as_deref().unwrap_or("DEBRIS").trim(), "norad_id": reentry.norad_id },
+            "position_km": reentry.position.to_array(),
+        }));
+    }
+}
+
+/// System to keep the conjunction forecast running and publish each newly predicted conjunction once
+pub fn publish_conjunctions_system(
+    mut publisher: ResMut<EventPublisher>,
+    mut forecast: ResMut<ConjunctionForecast>,
+    sim_time: Res<SimulationTime>,
+    satellites: Query<&Satellite>,
+) {
+    if !publisher.enabled() {
+        return;
+    }
+    forecast.requested = true;
+
+    // Forget encounters that have passed so later approaches of the same pair are published
+    let window = forecast.window;
+    publisher.published_conjunctions.retain(|_, tca| *tca > sim_time.current - window);
+
+    for conjunction in &forecast.upcoming {
+        let pair = (conjunction.primary, conjunction.secondary);
+        if publisher.published_conjunctions.contains_key(&pair) {
+            continue;
+        }
+        publisher.published_conjunctions.insert(pair, conjunction.tca);
+        publisher.send("conjunction", json!({
+            "tca": format!("{}Z", unix_to_iso8601(sim_time.unix_at(conjunction.tca))),
+            "objects": [
+                object_json(conjunction.primary, &satellites),
+                object_json(conjunction.secondary, &satellites),
+            ],
+            "miss_distance_km": conjunction.miss_distance,
+            "probability": conjunction.probability,
+        }));
+    }
+}
@@ -1,42 +1,344 @@
 use bevy::prelude::*;
 use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
 use bevy::math::primitives::Sphere;
+use bevy::math::DVec3;
+use bevy::window::PrimaryWindow;
+use std::collections::VecDeque;
 use crate::components::*;
 use crate::resources::*;
+use crate::systems::colors::{ObjectCategory, ObjectMaterials};
+use crate::systems::constellation::ConstellationGroups;
+use crate::systems::metadata::PopulationFilter;
+use crate::systems::physics::ReentryEvent;
+use crate::utils::{earth_rotation, propagate_kepler};
+use crate::systems::stress_test::StressTestObject;
+
+/// Marker component for the textured Earth mesh, turned with the Earth-fixed frame
+#[derive(Component)]
+pub struct Earth;
 
 /// Marker component to track objects that have been rendered
 #[derive(Component)]
 pub struct RenderedObject;
 
+/// Maximum number of points kept in an orbit trail
+const TRAIL_MAX_POINTS: usize = 400;
+/// Minimum spacing between recorded trail points (render units)
+const TRAIL_MIN_SPACING: f32 = 0.05;
+/// On-screen motion (pixels) below which rendered positions are left untouched
+const MIN_RENDER_MOTION_PIXELS: f32 = 1.0;
+/// Real seconds a reentry streak stays on screen
+const REENTRY_STREAK_SECS: f32 = 2.5;
+/// Simulated seconds of approach shown behind the reentry point
+const REENTRY_STREAK_TAIL: f64 = 60.0;
+/// Longest simulated descent traced ahead of the reentry point (seconds)
+const REENTRY_STREAK_MAX_DESCENT: f64 = 900.0;
+
+/// Component recording the recent rendered path of an object
+#[derive(Component, Default)]
+pub struct Trail {
+    pub points: VecDeque<Vec3>,
+}
+
+/// Component for a screen-space text label attached to an object
+#[derive(Component)]
+pub struct ObjectLabel {
+    pub target: Entity,
+}
+
+/// Resource controlling which classes of objects are drawn
+#[derive(Resource)]
+pub struct LayerVisibility {
+    pub satellites: bool,
+    pub debris: bool,
+    pub rocket_bodies: bool,
+    pub stress_test: bool,
+    pub trails: bool,
+    pub labels: bool,
+    pub geo_slots: bool,
+}
+
+impl Default for LayerVisibility {
+    fn default() -> Self {
+        Self {
+            satellites: true,
+            debris: true,
+            rocket_bodies: true,
+            stress_test: true,
+            trails: true,
+            labels: true,
+            geo_slots: false,
+        }
+    }
+}
+
+impl LayerVisibility {
+    /// Layer name and flag toggled by a function key
+    fn layer_for_key(&mut self, key: KeyCode) -> Option<(&'static str, &mut bool)> {
+        match key {
+            KeyCode::F1 => Some(("Satellites", &mut self.satellites)),
+            KeyCode::F2 => Some(("Debris", &mut self.debris)),
+            KeyCode::F3 => Some(("Rocket bodies", &mut self.rocket_bodies)),
+            KeyCode::F4 => Some(("Stress test objects", &mut self.stress_test)),
+            KeyCode::F5 => Some(("Trails", &mut self.trails)),
+            KeyCode::F6 => Some(("Labels", &mut self.labels)),
+            KeyCode::F11 => Some(("GEO slots", &mut self.geo_slots)),
+            _ => None,
+        }
+    }
+}
+
+/// Distance from Earth center (render units) the camera starts at
+pub const DEFAULT_CAMERA_DISTANCE: f32 = 15.0;
+/// Distance (render units) used to frame a focused object
+pub const FOCUS_CAMERA_DISTANCE: f32 = 0.6;
+/// Rate at which the pivot and distance converge on their goals (1/s)
+const CAMERA_SMOOTHING: f32 = 4.0;
+/// Rate at which rotation momentum dies out after releasing the mouse (1/s)
+const ROTATION_DAMPING: f32 = 6.0;
+/// Radians of rotation per pixel of mouse drag
+const ROTATE_SENSITIVITY: f32 = 0.005;
+/// Pan distance per pixel of mouse drag, as a fraction of the camera distance
+const PAN_SENSITIVITY: f32 = 0.0015;
+/// Earth's radius in render units
+const EARTH_RENDER_RADIUS: f32 = 6.371;
+
+/// Resource describing what the camera orbits and how far away it sits
+#[derive(Resource)]
+pub struct CameraRig {
+    /// Point the camera currently orbits (render units)
+    pub pivot: Vec3,
+    /// Point the pivot eases towards when not following an object (render units)
+    pub target_pivot: Vec3,
+    /// Current distance from the pivot (render units)
+    pub distance: f32,
+    /// Distance the camera is easing towards
+    pub target_distance: f32,
+    /// Rotation momentum (yaw, pitch) in radians per second
+    pub spin: Vec2,
+    /// Object the camera follows, or None to orbit `target_pivot`
+    pub follow: Option<Entity>,
+    /// Second object kept in frame alongside the followed one
+    pub companion: Option<Entity>,
+}
+
+impl Default for CameraRig {
+    fn default() -> Self {
+        Self {
+            pivot: Vec3::ZERO,
+            target_pivot: Vec3::ZERO,
+            distance: DEFAULT_CAMERA_DISTANCE,
+            target_distance: DEFAULT_CAMERA_DISTANCE,
+            spin: Vec2::ZERO,
+            follow: None,
+            companion: None,
+        }
+    }
+}
+
+impl CameraRig {
+    /// Fly to and follow an object
+    pub fn focus(&mut self, entity: Entity) {
+        self.follow = Some(entity);
+        self.companion = None;
+        self.target_distance = FOCUS_CAMERA_DISTANCE;
+    }
+
+    /// Orbit around an object without changing the zoom
+    pub fn orbit(&mut self, entity: Entity) {
+        self.follow = Some(entity);
+        self.companion = None;
+    }
+
+    /// Fly to and keep two objects in frame
+    pub fn frame_pair(&mut self, primary: Entity, secondary: Entity) {
+        self.follow = Some(primary);
+        self.companion = Some(secondary);
+    }
+
+    /// Fly back to orbiting Earth
+    pub fn release(&mut self) {
+        self.follow = None;
+        self.companion = None;
+        self.target_pivot = Vec3::ZERO;
+        self.target_distance = DEFAULT_CAMERA_DISTANCE;
+    }
+
+    /// Stop following and orbit the current pivot point instead
+    pub fn detach(&mut self) {
+        if self.follow.is_some() {
+            self.follow = None;
+            self.companion = None;
+            self.target_pivot = self.pivot;
+        }
+    }
+
+    /// Whether the camera orbits something other than Earth's center
+    fn off_center(&self) -> bool {
+        self.follow.is_some() || self.target_pivot != Vec3::ZERO
+    }
+
+    /// Zoom limits for the current pivot
+    fn zoom_range(&self) -> (f32, f32) {
+        if self.off_center() {
+            (0.1, 100.0)
+        } else {
+            (8.0, 100.0)
+        }
+    }
+
+    /// Move towards (positive) or away from the pivot, proportionally when away from Earth's center
+    pub fn zoom(&mut self, amount: f32) {
+        let (min_distance, max_distance) = self.zoom_range();
+        let new_distance = if self.off_center() {
+            self.target_distance * (1.0 - amount * 0.2)
+        } else {
+            self.target_distance - amount
+        };
+        self.target_distance = new_distance.clamp(min_distance, max_distance); // Min/max zoom distances
+    }
+}
+
+/// Resource for the keyboard free-fly camera
+#[derive(Resource)]
+pub struct FlyCamera {
+    pub enabled: bool,
+    /// Flight speed as a fraction of the current altitude above Earth's surface per second
+    pub speed_factor: f32,
+    /// Speed multiplier while Shift is held
+    pub boost: f32,
+}
+
+impl Default for FlyCamera {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            speed_factor: 0.5,
+            boost: 4.0,
+        }
+    }
+}
+
+/// Run condition for the orbit camera systems
+pub fn orbit_camera_active(fly: Res<FlyCamera>) -> bool {
+    !fly.enabled
+}
+
+/// System to toggle the free-fly camera with 'V' and adjust its speed with '-' / '='
+pub fn fly_camera_control_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut fly: ResMut<FlyCamera>,
+    mut rig: ResMut<CameraRig>,
+    camera_query: Query<&Transform, With<Camera3d>>,
+) {
+    if keyboard.just_pressed(KeyCode::KeyV) {
+        fly.enabled = !fly.enabled;
+        rig.spin = Vec2::ZERO;
+        if fly.enabled {
+            rig.follow = None;
+            rig.companion = None;
+        } else if let Ok(camera_transform) = camera_query.single() {
+            // Resume orbiting the point straight ahead of the camera
+            let forward = camera_transform.rotation * Vec3::NEG_Z;
+            rig.pivot = camera_transform.translation + forward * rig.distance;
+            rig.target_pivot = rig.pivot;
+            rig.target_distance = rig.distance;
+        }
+        info!("Free-fly camera {}", if fly.enabled { "ENABLED" } else { "DISABLED" });
+    }
+
+    if fly.enabled {
+        if keyboard.just_pressed(KeyCode::Equal) {
+            fly.speed_factor = (fly.speed_factor * 2.0).min(8.0);
+            info!("Fly speed {:.2}x altitude/s", fly.speed_factor);
+        }
+        if keyboard.just_pressed(KeyCode::Minus) {
+            fly.speed_factor = (fly.speed_factor * 0.5).max(0.01);
+            info!("Fly speed {:.2}x altitude/s", fly.speed_factor);
+        }
+    }
+}
+
+/// System to move the free-fly camera with WASD (plane) and QE (down/up), looking around with left-drag
+pub fn fly_camera_system(
+    time: Res<Time>,
+    fly: Res<FlyCamera>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+    mut camera_query: Query<&mut Transform, With<Camera3d>>,
+) {
+    if !fly.enabled {
+        return;
+    }
+    let Ok(mut camera_transform) = camera_query.single_mut() else {
+        return;
+    };
+    let dt = time.delta_secs();
+
+    // Look around in place
+    let drag: Vec2 = mouse_motion_events.read().map(|event| event.delta).sum();
+    if mouse_buttons.pressed(MouseButton::Left) && drag != Vec2::ZERO {
+        let yaw = Quat::from_rotation_y(-drag.x * ROTATE_SENSITIVITY);
+        let right = camera_transform.rotation * Vec3::X;
+        let pitch = Quat::from_axis_angle(right, -drag.y * ROTATE_SENSITIVITY);
+        camera_transform.rotation = (pitch * yaw * camera_transform.rotation).normalize();
+    }
+
+    let mut direction = Vec3::ZERO;
+    for (key, axis) in [
+        (KeyCode::KeyW, Vec3::NEG_Z),
+        (KeyCode::KeyS, Vec3::Z),
+        (KeyCode::KeyA, Vec3::NEG_X),
+        (KeyCode::KeyD, Vec3::X),
+        (KeyCode::KeyQ, Vec3::NEG_Y),
+        (KeyCode::KeyE, Vec3::Y),
+    ] {
+        if keyboard.pressed(key) {
+            direction += axis;
+        }
+    }
+    if direction == Vec3::ZERO {
+        return;
+    }
+
+    // Slow down near Earth so debris clouds can be explored from inside
+    let altitude = (camera_transform.translation.length() - EARTH_RENDER_RADIUS).max(0.05);
+    let boost = if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) { fly.boost } else { 1.0 };
+    let velocity = camera_transform.rotation * direction.normalize() * altitude * fly.speed_factor * boost;
+    camera_transform.translation += velocity * dt;
+}
+
 /// System for handling mouse camera controls
 pub fn camera_control_system(
+    time: Res<Time>,
     mut mouse_motion_events: EventReader<MouseMotion>,
     mut mouse_wheel_events: EventReader<MouseWheel>,
     mouse_buttons: Res<ButtonInput<MouseButton>>,
-    mut camera_query: Query<&mut Transform, With<Camera3d>>,
+    mut rig: ResMut<CameraRig>,
+    camera_query: Query<&Transform, With<Camera3d>>,
 ) {
-    let Ok(mut camera_transform) = camera_query.single_mut() else {
+    let Ok(camera_transform) = camera_query.single() else {
         return;
     };
+    let dt = time.delta_secs().max(1e-4);
+    let drag: Vec2 = mouse_motion_events.read().map(|event| event.delta).sum();
 
-    // Mouse rotation
+    // Left-drag rotation sets the spin directly; it carries on and decays once released
     if mouse_buttons.pressed(MouseButton::Left) {
-        for event in mouse_motion_events.read() {
-            let delta = event.delta;
-            
-            // Horizontal rotation (around Y axis)
-            camera_transform.rotate_around(
-                Vec3::ZERO,
-                Quat::from_rotation_y(-delta.x * 0.005),
-            );
-            
-            // Vertical rotation (around local X axis)
-            let right = camera_transform.rotation * Vec3::X;
-            camera_transform.rotate_around(
-                Vec3::ZERO,
-                Quat::from_axis_angle(right, -delta.y * 0.005),
-            );
-        }
+        rig.spin = -drag * ROTATE_SENSITIVITY / dt;
+    } else {
+        let decay = (-ROTATION_DAMPING * dt).exp();
+        rig.spin *= decay;
+    }
+
+    // Right-drag pans the pivot in the view plane, leaving any followed object behind
+    if mouse_buttons.pressed(MouseButton::Right) && drag != Vec2::ZERO {
+        rig.detach();
+        let right = camera_transform.rotation * Vec3::X;
+        let up = camera_transform.rotation * Vec3::Y;
+        let offset = (-drag.x * right + drag.y * up) * rig.distance * PAN_SENSITIVITY;
+        rig.target_pivot += offset;
     }
 
     // Mouse zoom
@@ -45,71 +347,407 @@ pub fn camera_control_system(
             MouseScrollUnit::Line => event.y * 0.5,
             MouseScrollUnit::Pixel => event.y * 0.01,
         };
-        
-        // Move camera towards/away from center
-        let direction = camera_transform.translation.normalize();
-        let new_distance = (camera_transform.translation.length() - scroll_amount)
-            .clamp(8.0, 100.0); // Min/max zoom distances
-        
-        camera_transform.translation = direction * new_distance;
+
+        rig.zoom(scroll_amount);
+    }
+}
+
+/// System to ease the camera onto its pivot, tracking the followed object and applying spin
+pub fn camera_follow_system(
+    time: Res<Time>,
+    mut rig: ResMut<CameraRig>,
+    transforms: Query<&Transform, (With<RenderedObject>, Without<Camera3d>)>,
+    mut camera_query: Query<&mut Transform, With<Camera3d>>,
+) {
+    let Ok(mut camera_transform) = camera_query.single_mut() else {
+        return;
+    };
+    let dt = time.delta_secs();
+
+    // Fall back to Earth if the followed object no longer exists
+    let mut goal = match rig.follow {
+        Some(entity) => match transforms.get(entity) {
+            Ok(transform) => transform.translation,
+            Err(_) => {
+                rig.release();
+                Vec3::ZERO
+            }
+        },
+        None => rig.target_pivot,
+    };
+
+    // Frame both objects of a pair around their midpoint
+    if let Some(companion) = rig.companion {
+        match transforms.get(companion) {
+            Ok(transform) => {
+                let separation = goal.distance(transform.translation);
+                goal = (goal + transform.translation) / 2.0;
+                rig.target_distance = (separation * 2.0).max(FOCUS_CAMERA_DISTANCE);
+            }
+            Err(_) => rig.companion = None,
+        }
+    }
+
+    let blend = 1.0 - (-CAMERA_SMOOTHING * dt).exp();
+    rig.pivot = rig.pivot.lerp(goal, blend);
+    rig.distance += (rig.target_distance - rig.distance) * blend;
+
+    // Yaw around the world Y axis, then pitch around the camera's right axis
+    if rig.spin != Vec2::ZERO {
+        let yaw = Quat::from_rotation_y(rig.spin.x * dt);
+        let right = camera_transform.rotation * Vec3::X;
+        let pitch = Quat::from_axis_angle(right, rig.spin.y * dt);
+        camera_transform.rotation = (pitch * yaw * camera_transform.rotation).normalize();
     }
+
+    // Keep the current viewing direction, looking at the pivot
+    let back = camera_transform.rotation * Vec3::Z;
+    camera_transform.translation = rig.pivot + back * rig.distance;
 }
 
 /// System to render satellites as small spheres
+#[allow(clippy::type_complexity)]
 pub fn satellite_rendering_system(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    satellites_without_mesh: Query<(Entity, &OrbitalState, &Satellite), (With<RenderAsSatellite>, Without<RenderedObject>)>,
+    object_materials: Res<ObjectMaterials>,
+    satellites_without_mesh: Query<
+        (Entity, &OrbitalState, &Satellite, Has<StressTestObject>, Has<RocketBody>),
+        (With<RenderAsSatellite>, Without<RenderedObject>),
+    >,
 ) {
-    for (entity, orbital_state, _satellite) in satellites_without_mesh.iter() {
+    for (entity, orbital_state, _satellite, is_stress_test, is_rocket_body) in satellites_without_mesh.iter() {
         // Scale down the position to make satellites visible - divide by 1000 to convert km to render units
-        let scaled_position = orbital_state.position / 1000.0;
+        let scaled_position = (orbital_state.position / 1000.0).as_vec3();
         
         // Create a visible sphere to represent the satellite
         let mesh = meshes.add(Sphere::new(0.05).mesh().ico(5).unwrap());
-        let material = materials.add(StandardMaterial {
-            base_color: Color::srgb(0.0, 1.0, 0.0), // Green color
-            ..default()
-        });
+        let category = ObjectCategory::classify(is_stress_test, is_rocket_body, false);
+        let material = object_materials.get(category);
         
-        commands.entity(entity)
-            .insert(Mesh3d(mesh))
-            .insert(MeshMaterial3d(material))
-            .insert(Transform::from_translation(scaled_position))
-            .insert(RenderedObject);
+        // The object may reenter or be destroyed before the commands apply
+        commands.entity(entity).try_insert((
+            Mesh3d(mesh),
+            MeshMaterial3d(material),
+            Transform::from_translation(scaled_position),
+            RenderedObject,
+        ));
     }
 }
 
 /// System to render debris as small points
+#[allow(clippy::type_complexity)]
 pub fn debris_rendering_system(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    object_materials: Res<ObjectMaterials>,
     debris_query: Query<(Entity, &OrbitalState, &Debris), (With<RenderAsDebris>, Without<RenderedObject>)>,
+    mut debris_mesh: Local<Option<Handle<Mesh>>>,
 ) {
+    // Fragments share one tiny sphere so they are drawn as a single instanced batch
+    let mesh = debris_mesh
+        .get_or_insert_with(|| meshes.add(Sphere::new(2.0).mesh().ico(3).unwrap()))
+        .clone();
+
     for (entity, orbital_state, _debris) in debris_query.iter() {
-        // Create tiny sphere for debris
-        let mesh = meshes.add(Sphere::new(2.0).mesh().ico(3).unwrap());
-        let material = materials.add(StandardMaterial {
-            base_color: Color::srgb(1.0, 0.0, 0.0), // Red color
-            ..default()
-        });
+        let material = object_materials.get(ObjectCategory::Debris);
         
-        commands.entity(entity)
-            .insert(Mesh3d(mesh))
-            .insert(MeshMaterial3d(material))
-            .insert(Transform::from_translation(orbital_state.position))
-            .insert(RenderedObject);
+        commands.entity(entity).try_insert((
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material),
+            Transform::from_translation(orbital_state.position.as_vec3()),
+            RenderedObject,
+        ));
+    }
+}
+
+/// Resource counting debris fragments drawn and culled in the latest frame
+#[derive(Resource, Default)]
+pub struct DebrisCulling {
+    pub visible: usize,
+    /// Fragments inside the view frustum but hidden behind the Earth
+    pub occluded: usize,
+}
+
+/// System to drop debris hidden behind the Earth from rendering
+///
+/// Runs after Bevy's frustum culling, so fragments outside the view are already excluded;
+/// occluded fragments are then marked hidden and left out of the instance buffer.
+#[allow(clippy::type_complexity)]
+pub fn debris_occlusion_culling_system(
+    cameras: Query<&GlobalTransform, With<Camera3d>>,
+    mut culling: ResMut<DebrisCulling>,
+    mut debris: Query<(&GlobalTransform, &mut ViewVisibility), (With<Debris>, With<RenderedObject>)>,
+) {
+    let Ok(camera) = cameras.single() else {
+        return;
+    };
+    let eye = camera.translation();
+
+    let (mut visible, mut occluded) = (0, 0);
+    for (transform, mut view_visibility) in debris.iter_mut() {
+        if !view_visibility.get() {
+            continue;
+        }
+        if earth_occludes(eye, transform.translation()) {
+            *view_visibility = ViewVisibility::HIDDEN;
+            occluded += 1;
+        } else {
+            visible += 1;
+        }
+    }
+    culling.visible = visible;
+    culling.occluded = occluded;
+}
+
+/// Whether the Earth sphere blocks the line of sight between two points (render units)
+fn earth_occludes(eye: Vec3, target: Vec3) -> bool {
+    let sight = target - eye;
+    let length_squared = sight.length_squared();
+    if length_squared <= f32::EPSILON {
+        return false;
     }
+    // Closest point of the sight line to the Earth centre must lie between eye and target
+    let t = -eye.dot(sight) / length_squared;
+    t > 0.0 && t < 1.0 && (eye + sight * t).length_squared() < EARTH_RENDER_RADIUS * EARTH_RENDER_RADIUS
 }
 
 /// System to update positions of rendered objects
+#[allow(clippy::type_complexity)]
 pub fn update_positions_system(
+    cameras: Query<(&GlobalTransform, &Projection), With<Camera3d>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
     mut query: Query<(&mut Transform, &OrbitalState), (With<RenderedObject>, Changed<OrbitalState>)>,
 ) {
+    // Size of one pixel per render unit of distance from the camera; zero updates everything
+    let (camera_position, pixel_scale) = match (cameras.single(), windows.single()) {
+        (Ok((camera, Projection::Perspective(perspective))), Ok(window)) if window.height() > 0.0 => (
+            camera.translation(),
+            2.0 * (perspective.fov / 2.0).tan() / window.height() * MIN_RENDER_MOTION_PIXELS,
+        ),
+        _ => (Vec3::ZERO, 0.0),
+    };
+
     for (mut transform, orbital_state) in query.iter_mut() {
         // Scale down position to match rendering scale (km to render units)
-        transform.translation = orbital_state.position / 1000.0;
+        let position = (orbital_state.position / 1000.0).as_vec3();
+
+        // Slow or distant objects (e.g. GEO) keep their transform, so change detection skips them downstream
+        let threshold = camera_position.distance(position) * pixel_scale;
+        if transform.translation.distance_squared(position) < threshold * threshold {
+            continue;
+        }
+        transform.translation = position;
+    }
+}
+/// System to toggle render layers with F1-F6 and F11
+pub fn layer_toggle_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut layers: ResMut<LayerVisibility>,
+) {
+    for &key in keyboard.get_just_pressed() {
+        if let Some((name, shown)) = layers.layer_for_key(key) {
+            *shown = !*shown;
+            info!("{} layer {}", name, if *shown { "shown" } else { "hidden" });
+        }
+    }
+}
+
+/// System to apply layer visibility to rendered objects without touching their physics
+#[allow(clippy::type_complexity)]
+pub fn layer_visibility_system(
+    layers: Res<LayerVisibility>,
+    filter: Res<PopulationFilter>,
+    groups: Res<ConstellationGroups>,
+    mut objects: Query<
+        (
+            Ref<RenderedObject>,
+            &mut Visibility,
+            Option<&Operator>,
+            Option<Ref<Owner>>,
+            Option<&Constellation>,
+            Option<Ref<Eclipse>>,
+            (Has<StressTestObject>, Has<RocketBody>, Has<Debris>, Has<Derelict>),
+        ),
+        Without<ObjectLabel>,
+    >,
+) {
+    let refresh_all = layers.is_changed() || filter.is_changed() || groups.is_changed();
+
+    for (rendered, mut visibility, operator, owner, constellation, eclipse, (is_stress_test, is_rocket_body, is_debris, is_derelict)) in objects.iter_mut() {
+        // Owners arrive after the object is first drawn once SATCAT has loaded
+        let owner_added = filter.is_active() && owner.as_ref().is_some_and(|owner| owner.is_added());
+        // Eclipse filters follow objects as they cross the terminator
+        let eclipse_changed = filter.eclipsed.is_some() && eclipse.as_ref().is_some_and(|eclipse| eclipse.is_changed());
+        if !refresh_all && !rendered.is_added() && !owner_added && !eclipse_changed {
+            continue;
+        }
+        let layer_shown = match ObjectCategory::classify(is_stress_test, is_rocket_body, is_debris) {
+            ObjectCategory::StressTest => layers.stress_test,
+            ObjectCategory::RocketBody => layers.rocket_bodies,
+            ObjectCategory::Debris => layers.debris,
+            ObjectCategory::Satellite => layers.satellites,
+        };
+        let group_hidden = constellation.is_some_and(|c| groups.hidden.contains(&c.name));
+        let shown = layer_shown && !group_hidden && filter.matches(operator, owner.as_deref(), is_derelict, eclipse.is_some_and(|eclipse| eclipse.is_eclipsed()));
+        visibility.set_if_neq(if shown { Visibility::Inherited } else { Visibility::Hidden });
+    }
+}
+
+/// System to record trail points for objects that carry a trail
+#[allow(clippy::type_complexity)]
+pub fn trail_recording_system(
+    mut trails: Query<(&mut Trail, &Transform), (With<RenderedObject>, Changed<Transform>)>,
+) {
+    for (mut trail, transform) in trails.iter_mut() {
+        let position = transform.translation;
+        if trail.points.back().is_some_and(|last| last.distance(position) < TRAIL_MIN_SPACING) {
+            continue;
+        }
+        trail.points.push_back(position);
+        if trail.points.len() > TRAIL_MAX_POINTS {
+            trail.points.pop_front();
+        }
+    }
+}
+
+/// System to turn the Earth mesh at the sidereal rate, keeping its texture on the Earth-fixed frame
+pub fn earth_rotation_system(sim_time: Res<SimulationTime>, mut earth_query: Query<&mut Transform, With<Earth>>) {
+    // The UV sphere's poles lie on Z like the inertial frame's; its texture starts at 180°W on the +X side
+    let rotation = earth_rotation(sim_time.current_unix()) * bevy::math::DQuat::from_rotation_z(std::f64::consts::PI);
+    for mut transform in earth_query.iter_mut() {
+        transform.rotation = rotation.as_quat();
+    }
+}
+
+/// System to draw recorded trails
+pub fn trail_drawing_system(
+    layers: Res<LayerVisibility>,
+    trails: Query<(&Trail, &Transform)>,
+    mut gizmos: Gizmos,
+) {
+    if !layers.trails {
+        return;
+    }
+    for (trail, transform) in trails.iter() {
+        let points = trail.points.iter().copied().chain(std::iter::once(transform.translation));
+        gizmos.linestrip(points, Color::srgba(0.4, 0.8, 1.0, 0.7));
+    }
+}
+
+/// A glowing streak traced along an object's final trajectory after reentry
+pub struct ReentryStreak {
+    /// Path from shortly before reentry down to the surface (render units)
+    pub points: Vec<Vec3>,
+    /// Index of the reentry point within `points`
+    pub reentry_index: usize,
+    /// Real seconds since the streak appeared
+    pub age: f32,
+}
+
+/// Resource holding the reentry streaks currently on screen
+#[derive(Resource, Default)]
+pub struct ReentryStreaks {
+    pub streaks: Vec<ReentryStreak>,
+}
+
+/// Final trajectory of a reentering object: a short tail of its approach, then its ballistic descent to the surface (km)
+pub fn reentry_path(position: DVec3, velocity: DVec3, earth_radius: f64, mu_km: f64) -> (Vec<DVec3>, usize) {
+    const STEPS: usize = 12;
+    let tail: Vec<DVec3> = (1..=STEPS).rev()
+        .map(|i| propagate_kepler(position, velocity, -REENTRY_STREAK_TAIL * i as f64 / STEPS as f64, mu_km).0)
+        .collect();
+    let reentry_index = tail.len();
+
+    let mut points = tail;
+    points.push(position);
+    let step = REENTRY_STREAK_MAX_DESCENT / (4 * STEPS) as f64;
+    for i in 1..=4 * STEPS {
+        let (point, _) = propagate_kepler(position, velocity, step * i as f64, mu_km);
+        if point.length() <= earth_radius {
+            // Stop on the surface along the last segment
+            points.push(point.normalize_or_zero() * earth_radius);
+            break;
+        }
+        points.push(point);
     }
-}
\ No newline at end of file
+    (points, reentry_index)
+}
+
+/// System to start a streak for every reentry
+pub fn reentry_streak_spawn_system(
+    mut reentries: EventReader<ReentryEvent>,
+    constants: Res<Constants>,
+    mut streaks: ResMut<ReentryStreaks>,
+) {
+    let mu_km = constants.gravitational_parameter / 1e9;
+    for reentry in reentries.read() {
+        let (points, reentry_index) = reentry_path(reentry.position, reentry.velocity, constants.earth_radius, mu_km);
+        streaks.streaks.push(ReentryStreak {
+            points: points.into_iter().map(|point| (point / 1000.0).as_vec3()).collect(),
+            reentry_index,
+            age: 0.0,
+        });
+    }
+}
+
+/// System to draw reentry streaks: a glowing head runs down the final trajectory toward the limb, then fades out
+pub fn reentry_streak_drawing_system(
+    time: Res<Time>,
+    mut streaks: ResMut<ReentryStreaks>,
+    mut gizmos: Gizmos,
+) {
+    let dt = time.delta_secs();
+    streaks.streaks.retain_mut(|streak| {
+        streak.age += dt;
+        streak.age < REENTRY_STREAK_SECS
+    });
+
+    for streak in &streaks.streaks {
+        let progress = (streak.age / (0.6 * REENTRY_STREAK_SECS)).min(1.0);
+        let fade = 1.0 - ((streak.age - 0.6 * REENTRY_STREAK_SECS) / (0.4 * REENTRY_STREAK_SECS)).clamp(0.0, 1.0);
+        let descent = streak.points.len() - 1 - streak.reentry_index;
+        let head = streak.reentry_index + (progress * descent as f32).ceil() as usize;
+        let visible = &streak.points[..=head.min(streak.points.len() - 1)];
+
+        // Dull red tail brightening to white-hot toward the head
+        let count = visible.len().max(2) as f32;
+        gizmos.linestrip_gradient(visible.iter().enumerate().map(|(i, &point)| {
+            let heat = i as f32 / (count - 1.0);
+            (point, Color::srgba(1.0, 0.3 + 0.6 * heat, 0.1 + 0.6 * heat * heat, (0.2 + 0.8 * heat) * fade))
+        }));
+        if let Some(&tip) = visible.last() {
+            gizmos.sphere(Isometry3d::from_translation(tip), 0.04, Color::srgba(1.0, 0.9, 0.6, fade));
+        }
+    }
+}
+
+/// System to keep object labels next to their targets on screen
+pub fn label_position_system(
+    mut commands: Commands,
+    layers: Res<LayerVisibility>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    targets: Query<&GlobalTransform, Without<ObjectLabel>>,
+    mut labels: Query<(Entity, &ObjectLabel, &mut Node, &mut Visibility)>,
+) {
+    let Ok((camera, camera_transform)) = camera_query.single() else {
+        return;
+    };
+
+    for (label_entity, label, mut node, mut visibility) in labels.iter_mut() {
+        let Ok(target) = targets.get(label.target) else {
+            commands.entity(label_entity).despawn();
+            continue;
+        };
+        match camera.world_to_viewport(camera_transform, target.translation()) {
+            Ok(screen) if layers.labels => {
+                node.left = Val::Px(screen.x + 8.0);
+                node.top = Val::Px(screen.y - 8.0);
+                visibility.set_if_neq(Visibility::Inherited);
+            }
+            _ => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+}
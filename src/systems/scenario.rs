@@ -0,0 +1,566 @@
+// Scenario files: a declarative starting population, constants overrides, scheduled events and run length
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::path::Path;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::collision::{fragment_masses, fragment_velocities};
+use crate::systems::data::{create_satellite_from_tle, spawn_satellite_entity, TleDataCache, TleSource, TleSpawnQueue};
+use crate::systems::maneuvers::schedule_burn;
+use crate::systems::search::SearchCommand;
+use crate::utils::*;
+
+/// Scenario built into the binary and spawned when no element sets can be loaded
+pub const TEST_DATASET_SCENARIO: &str = include_str!("../../assets/scenarios/test_dataset.json");
+/// Historical breakups built into the binary: the parent orbits reconstructed from the documented altitude,
+/// inclination, time and place of each event, and fragment clouds the size of the tracked ones
+pub const SCENARIO_PRESETS: [(&str, &str); 3] = [
+    ("fengyun-1c", include_str!("../../assets/scenarios/fengyun_1c_2007.json")),
+    ("iridium-cosmos", include_str!("../../assets/scenarios/iridium_cosmos_2009.json")),
+    ("nudol", include_str!("../../assets/scenarios/nudol_2021.json")),
+];
+/// Fragments of a scheduled breakup when the scenario does not say
+const DEFAULT_BREAKUP_FRAGMENTS: u32 = 50;
+/// Largest speed fragments of a scheduled breakup are kicked by (km/s)
+const BREAKUP_KICK_SPEED: f64 = 0.1;
+
+/// Mean orbital elements of a scenario object (km and degrees)
+///
+/// `altitude` above the mean Earth radius may stand in for the semi-major axis of a circular orbit.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ScenarioElements {
+    pub semi_major_axis: Option<f64>,
+    pub altitude: Option<f64>,
+    pub eccentricity: f64,
+    pub inclination: f64,
+    pub raan: f64,
+    pub argument_of_perigee: f64,
+    pub mean_anomaly: f64,
+}
+
+impl ScenarioElements {
+    /// Element set at `epoch_unix` without element lines, so it is propagated on its mean elements
+    pub fn record(&self, name: &str, norad_id: u32, epoch_unix: f64, constants: &Constants) -> Result<TleRecord, String> {
+        let semi_major_axis = self.semi_major_axis
+            .or(self.altitude.map(|altitude| constants.earth_radius + altitude))
+            .ok_or_else(|| format!("{} needs a semi_major_axis or an altitude", name))?;
+        if !(0.0..1.0).contains(&self.eccentricity) || semi_major_axis * (1.0 - self.eccentricity) <= constants.earth_radius {
+            return Err(format!("{} has no orbit above the Earth's surface", name));
+        }
+        let elements = OrbitalElements {
+            semi_major_axis,
+            eccentricity: self.eccentricity,
+            inclination: self.inclination.to_radians(),
+            raan: self.raan.to_radians(),
+            argument_of_perigee: self.argument_of_perigee.to_radians(),
+            mean_anomaly: self.mean_anomaly.to_radians(),
+        };
+        let (epoch_year, epoch_day) = tle_epoch(epoch_unix);
+        let epoch_year = epoch_year as u32;
+        Ok(TleRecord {
+            name: name.to_string(),
+            norad_id,
+            classification: 'U',
+            international_designator: String::new(),
+            epoch_year,
+            epoch_day,
+            mean_motion_dot: 0.0,
+            mean_motion_ddot: 0.0,
+            bstar: 0.0,
+            inclination: self.inclination,
+            right_ascension: self.raan.rem_euclid(360.0),
+            eccentricity: self.eccentricity,
+            argument_of_perigee: self.argument_of_perigee.rem_euclid(360.0),
+            mean_anomaly: self.mean_anomaly.rem_euclid(360.0),
+            mean_motion: elements.mean_motion_rev_per_day(constants.gravitational_parameter / 1e9),
+            revolution_number: 0,
+            line1: String::new(),
+            line2: String::new(),
+        })
+    }
+}
+
+/// A named satellite given by the two lines of a TLE or by mean elements
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ScenarioSatellite {
+    pub name: String,
+    pub norad_id: u32,
+    pub tle: Option<[String; 2]>,
+    pub elements: Option<ScenarioElements>,
+    /// Mass (kg); estimated from the name when left out
+    pub mass: Option<f64>,
+}
+
+impl ScenarioSatellite {
+    fn record(&self, epoch_unix: f64, constants: &Constants) -> Result<TleRecord, String> {
+        match (&self.tle, &self.elements) {
+            (Some([line1, line2]), _) => TleRecord::from_tle_lines(&self.name, line1, line2)
+                .map_err(|e| format!("{}: {}", self.name, e)),
+            (None, Some(elements)) => elements.record(&self.name, self.norad_id, epoch_unix, constants),
+            (None, None) => Err(format!("{} needs a tle or elements", self.name)),
+        }
+    }
+}
+
+/// Cloud of fragments spreading from one point of an orbit
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ScenarioDebrisCloud {
+    pub name: String,
+    /// Orbit of the cloud's centre, at its mean anomaly
+    pub elements: ScenarioElements,
+    pub count: u32,
+    /// Mass of all the fragments together (kg)
+    pub mass: f64,
+    /// Largest speed a fragment is kicked by (m/s)
+    pub spread: f64,
+}
+
+impl Default for ScenarioDebrisCloud {
+    fn default() -> Self {
+        Self {
+            name: "Debris cloud".to_string(),
+            elements: ScenarioElements::default(),
+            count: 100,
+            mass: 1000.0,
+            spread: 100.0,
+        }
+    }
+}
+
+/// What a scheduled event does to the satellite it names
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScenarioAction {
+    /// Break the satellite up into fragments
+    Breakup {
+        object: String,
+        #[serde(default)]
+        fragments: Option<u32>,
+        /// Largest speed a fragment is kicked by (m/s)
+        #[serde(default)]
+        spread: Option<f64>,
+    },
+    /// Burn the satellite with a radial, transverse and normal delta-v (m/s)
+    Burn { object: String, delta_v: [f64; 3] },
+}
+
+/// An action taken at a simulation time
+#[derive(Deserialize, Clone, Debug)]
+pub struct ScenarioEvent {
+    /// Simulated seconds from the start of the scenario
+    pub at: f64,
+    #[serde(flatten)]
+    pub action: ScenarioAction,
+}
+
+/// Constants a scenario may set; command-line options take precedence
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ScenarioConstants {
+    pub reentry_altitude: Option<f64>,
+    pub force_model: Option<ForceModel>,
+    pub third_body: Option<bool>,
+}
+
+/// A scenario file in JSON or TOML, e.g.
+/// `{"start": "2024-06-01T00:00:00Z", "duration": 86400, "satellites": [{"name": "SAT-1", "elements": {"altitude": 550, "inclination": 53}}], "events": [{"at": 3600, "type": "breakup", "object": "SAT-1"}]}`
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Scenario {
+    pub name: String,
+    pub description: String,
+    /// Simulation start (ISO 8601 UTC)
+    pub start: Option<String>,
+    /// Simulated seconds to run before the app quits
+    pub duration: Option<f64>,
+    /// Whether the TLE catalog is loaded alongside the scenario's own objects
+    pub catalog: bool,
+    pub constants: ScenarioConstants,
+    pub satellites: Vec<ScenarioSatellite>,
+    pub debris_clouds: Vec<ScenarioDebrisCloud>,
+    pub events: Vec<ScenarioEvent>,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            name: "Scenario".to_string(),
+            description: String::new(),
+            start: None,
+            duration: None,
+            catalog: true,
+            constants: ScenarioConstants::default(),
+            satellites: Vec::new(),
+            debris_clouds: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+}
+
+impl Scenario {
+    /// Read a scenario file, parsed as TOML for a `.toml` extension and as JSON otherwise
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path).map_err(|e| format!("Failed to read scenario {}: {}", path.display(), e))?;
+        let is_toml = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("toml"));
+        let scenario = if is_toml { Self::parse_toml(&contents) } else { Self::parse(&contents) };
+        scenario.map_err(|e| format!("Invalid scenario {}: {}", path.display(), e))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        serde_json::from_str::<Scenario>(text).map_err(|e| e.to_string())?.validated()
+    }
+
+    /// Parse a TOML scenario; it has the same fields as the JSON form
+    pub fn parse_toml(text: &str) -> Result<Self, String> {
+        let document: toml_edit::DocumentMut = text.parse().map_err(|e: toml_edit::TomlError| e.to_string())?;
+        serde_json::from_value::<Scenario>(toml_table_to_json(document.as_table().iter()))
+            .map_err(|e| e.to_string())?
+            .validated()
+    }
+
+    fn validated(self) -> Result<Self, String> {
+        if let Some(start) = self.start.as_deref().filter(|start| parse_iso8601(start).is_none()) {
+            return Err(format!("invalid start {}", start));
+        }
+        Ok(self)
+    }
+
+    /// Built-in preset by name or by its number in the preset menu
+    pub fn preset(name: &str) -> Result<Self, String> {
+        let index = match name.parse::<usize>() {
+            Ok(number) => number.checked_sub(1).filter(|&index| index < SCENARIO_PRESETS.len()),
+            Err(_) => SCENARIO_PRESETS.iter().position(|(preset, _)| preset.eq_ignore_ascii_case(name)),
+        };
+        let Some(index) = index else {
+            let names: Vec<&str> = SCENARIO_PRESETS.iter().map(|(preset, _)| *preset).collect();
+            return Err(format!("unknown scenario preset {} (expected one of {})", name, names.join(", ")));
+        };
+        Self::parse(SCENARIO_PRESETS[index].1).map_err(|e| format!("Invalid scenario preset {}: {}", name, e))
+    }
+
+    /// Simulation start as a UTC Unix time
+    pub fn start_unix(&self) -> Option<f64> {
+        self.start.as_deref().and_then(parse_iso8601)
+    }
+}
+
+fn toml_table_to_json<'a>(entries: impl IntoIterator<Item = (&'a str, &'a toml_edit::Item)>) -> serde_json::Value {
+    serde_json::Value::Object(
+        entries.into_iter().filter_map(|(key, item)| toml_item_to_json(item).map(|value| (key.to_string(), value))).collect(),
+    )
+}
+
+fn toml_item_to_json(item: &toml_edit::Item) -> Option<serde_json::Value> {
+    match item {
+        toml_edit::Item::None => None,
+        toml_edit::Item::Value(value) => Some(toml_value_to_json(value)),
+        toml_edit::Item::Table(table) => Some(toml_table_to_json(table.iter())),
+        toml_edit::Item::ArrayOfTables(tables) => {
+            Some(serde_json::Value::Array(tables.iter().map(|table| toml_table_to_json(table.iter())).collect()))
+        }
+    }
+}
+
+fn toml_value_to_json(value: &toml_edit::Value) -> serde_json::Value {
+    match value {
+        toml_edit::Value::String(s) => serde_json::Value::from(s.value().as_str()),
+        toml_edit::Value::Integer(i) => serde_json::Value::from(*i.value()),
+        toml_edit::Value::Float(f) => serde_json::Value::from(*f.value()),
+        toml_edit::Value::Boolean(b) => serde_json::Value::from(*b.value()),
+        // Dates are kept as their ISO 8601 text, which is how `start` is written in JSON
+        toml_edit::Value::Datetime(d) => serde_json::Value::from(d.value().to_string()),
+        toml_edit::Value::Array(array) => serde_json::Value::Array(array.iter().map(toml_value_to_json).collect()),
+        toml_edit::Value::InlineTable(table) => serde_json::Value::Object(
+            table.iter().map(|(key, value)| (key.to_string(), toml_value_to_json(value))).collect(),
+        ),
+    }
+}
+
+/// Spawn a scenario's satellites and debris clouds at the current simulation time, cataloging the satellites
+pub fn spawn_scenario(
+    commands: &mut Commands,
+    scenario: &Scenario,
+    sim_time: &SimulationTime,
+    constants: &Constants,
+    tle_cache: &mut TleDataCache,
+    spawn_queue: &mut TleSpawnQueue,
+) -> usize {
+    let unix_seconds = sim_time.current_unix();
+    let mut spawned = 0;
+    for satellite in &scenario.satellites {
+        let created = satellite.record(unix_seconds, constants)
+            .and_then(|record| create_satellite_from_tle(&record, unix_seconds));
+        match created {
+            Ok((record, mut state)) => {
+                if let Some(mass) = satellite.mass {
+                    state.mass = mass;
+                }
+                spawn_queue.queued.insert(record.norad_id);
+                tle_cache.records.push(record.clone());
+                spawn_satellite_entity(commands, (record, state), unix_seconds);
+                spawned += 1;
+            }
+            Err(e) => warn!("Skipping scenario satellite: {}", e),
+        }
+    }
+
+    for cloud in &scenario.debris_clouds {
+        let centre = cloud.elements.record(&cloud.name, 0, unix_seconds, constants)
+            .and_then(|record| propagate_tle(&record, unix_seconds));
+        let (position, velocity) = match centre {
+            Ok(state) => state,
+            Err(e) => {
+                warn!("Skipping scenario debris cloud: {}", e);
+                continue;
+            }
+        };
+        // Fragments are kicked by a tenth to a half of the speed passed, so twice the spread tops out at the spread
+        let masses = fragment_masses(cloud.mass, cloud.count);
+        let velocities = fragment_velocities(&masses, velocity, cloud.spread / 1000.0 * 2.0);
+        for (&mass, &velocity) in masses.iter().zip(&velocities) {
+            commands.spawn((
+                Debris::new(None, 1),
+                OrbitalState::new(position, velocity, mass),
+                PhysicsObject::debris(mass),
+                RenderAsDebris,
+            ));
+        }
+        spawned += masses.len();
+    }
+
+    info!("Scenario {}: spawned {} objects", scenario.name, spawned);
+    spawned
+}
+
+/// Resource holding the scenario being run
+#[derive(Resource, Default)]
+pub struct ActiveScenario {
+    pub scenario: Option<Scenario>,
+    /// Whether its objects have been spawned
+    pub spawned: bool,
+    /// Whether its duration has run out
+    pub finished: bool,
+    /// Simulation time its objects were spawned at, which its event times count from
+    pub started_at: f64,
+    /// Whether the preset menu is shown
+    pub menu: bool,
+    /// Events still to run, earliest first
+    pending: VecDeque<ScenarioEvent>,
+}
+
+impl ActiveScenario {
+    pub fn new(scenario: Scenario) -> Self {
+        let mut events = scenario.events.clone();
+        events.sort_by(|a, b| a.at.total_cmp(&b.at));
+        Self { scenario: Some(scenario), pending: events.into(), ..default() }
+    }
+}
+
+/// System to spawn the scenario's objects on the first frame
+pub fn scenario_spawn_system(
+    mut commands: Commands,
+    mut active: ResMut<ActiveScenario>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    mut tle_cache: ResMut<TleDataCache>,
+    mut spawn_queue: ResMut<TleSpawnQueue>,
+) {
+    if active.spawned {
+        return;
+    }
+    active.spawned = true;
+    active.started_at = sim_time.current;
+    if let Some(scenario) = &active.scenario {
+        spawn_scenario(&mut commands, scenario, &sim_time, &constants, &mut tle_cache, &mut spawn_queue);
+    }
+}
+
+/// System to run scheduled scenario events as the simulation clock reaches them and to quit once the
+/// scenario's duration has passed
+pub fn scenario_event_system(
+    mut commands: Commands,
+    mut active: ResMut<ActiveScenario>,
+    sim_time: Res<SimulationTime>,
+    mut satellites: Query<(Entity, &Satellite, &OrbitalState, Option<&mut ManeuverPlan>)>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let elapsed = sim_time.current - active.started_at;
+    while active.spawned && active.pending.front().is_some_and(|event| event.at <= elapsed) {
+        let Some(event) = active.pending.pop_front() else {
+            break;
+        };
+        let (ScenarioAction::Breakup { object, .. } | ScenarioAction::Burn { object, .. }) = &event.action;
+        let Some((entity, satellite, state, plan)) = satellites.iter_mut()
+            .find(|(_, satellite, ..)| satellite.name.trim().eq_ignore_ascii_case(object.trim()))
+        else {
+            warn!("Scenario event at T+{:.0}s: no object named {}", event.at, object);
+            continue;
+        };
+
+        match &event.action {
+            ScenarioAction::Breakup { fragments, spread, .. } => {
+                let masses = fragment_masses(state.mass, fragments.unwrap_or(DEFAULT_BREAKUP_FRAGMENTS));
+                let kick = spread.map_or(BREAKUP_KICK_SPEED, |spread| spread / 1000.0);
+                let velocities = fragment_velocities(&masses, state.velocity, kick * 2.0);
+                for (&mass, &velocity) in masses.iter().zip(&velocities) {
+                    commands.spawn((
+                        Debris::new(None, 1),
+                        OrbitalState::new(state.position, velocity, mass),
+                        PhysicsObject::debris(mass),
+                        RenderAsDebris,
+                    ));
+                }
+                commands.entity(entity).despawn();
+                warn!("Scenario breakup of {} at T+{:.0}s: {} fragments", satellite.name.trim(), elapsed, masses.len());
+            }
+            ScenarioAction::Burn { delta_v, .. } => {
+                let burn = PlannedBurn { execute_at: sim_time.current, delta_v: DVec3::from_array(*delta_v) / 1000.0 };
+                schedule_burn(&mut commands, entity, plan, burn);
+                info!("Scenario burn of {} at T+{:.0}s: {:.1} m/s", satellite.name.trim(), elapsed, burn.delta_v.length() * 1000.0);
+            }
+        }
+    }
+
+    let duration = active.scenario.as_ref().and_then(|scenario| scenario.duration);
+    if let Some(duration) = duration.filter(|&duration| active.spawned && !active.finished && elapsed >= duration) {
+        active.finished = true;
+        info!("Scenario complete after {:.0} simulated seconds", duration);
+        exit.write(AppExit::Success);
+    }
+}
+
+/// Command showing the preset menu or running a scenario preset
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScenarioCommand {
+    /// Show or hide the preset menu
+    Menu,
+    /// Replace the simulated objects with a preset's, by name or number
+    Run(String),
+}
+
+impl ScenarioCommand {
+    /// Parse `presets`, or `preset NAME` with a preset name or its number in the menu;
+    /// returns `None` when the text is not a scenario command
+    pub fn parse(text: &str) -> Option<Result<Self, String>> {
+        let mut words = text.split_whitespace();
+        let verb = words.next()?;
+        if verb.eq_ignore_ascii_case("presets") {
+            return Some(Ok(ScenarioCommand::Menu));
+        }
+        if !verb.eq_ignore_ascii_case("preset") {
+            return None;
+        }
+        Some(match words.next() {
+            Some(name) => Ok(ScenarioCommand::Run(name.to_string())),
+            None => Err("expected a scenario preset".to_string()),
+        })
+    }
+}
+
+/// System to toggle the preset menu and to run presets
+///
+/// Running a preset removes every simulated object and empties the catalog, then moves the simulation clock to the
+/// preset's start; its objects are spawned on the next pass of the spawn system.
+#[allow(clippy::too_many_arguments)]
+pub fn scenario_command_system(
+    mut commands: Commands,
+    mut events: EventReader<SearchCommand>,
+    mut active: ResMut<ActiveScenario>,
+    mut sim_time: ResMut<SimulationTime>,
+    mut source: ResMut<TleSource>,
+    mut tle_cache: ResMut<TleDataCache>,
+    mut spawn_queue: ResMut<TleSpawnQueue>,
+    objects: Query<Entity, With<OrbitalState>>,
+) {
+    for command in events.read() {
+        let SearchCommand::Scenario(command) = command else {
+            continue;
+        };
+        let name = match command {
+            ScenarioCommand::Menu => {
+                active.menu = !active.menu;
+                continue;
+            }
+            ScenarioCommand::Run(name) => name,
+        };
+        let scenario = match Scenario::preset(name) {
+            Ok(scenario) => scenario,
+            Err(e) => {
+                warn!("{}", e);
+                continue;
+            }
+        };
+
+        for entity in objects.iter() {
+            commands.entity(entity).despawn();
+        }
+        tle_cache.records.clear();
+        spawn_queue.pending.clear();
+        spawn_queue.queued.clear();
+        source.catalog = scenario.catalog;
+        if let Some(start) = scenario.start_unix() {
+            // The clock keeps counting up, so only its epoch moves
+            sim_time.epoch_unix = start - sim_time.current;
+        }
+        info!("Running scenario preset {}", scenario.name);
+        let menu = active.menu;
+        *active = ActiveScenario { menu, ..ActiveScenario::new(scenario) };
+    }
+}
+
+/// Marker for the preset menu text
+#[derive(Component)]
+pub struct ScenarioMenuText;
+
+/// System to create the (initially hidden) preset menu
+pub fn setup_scenario_menu_system(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.7, 0.85, 1.0)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(50.0),
+            left: Val::Percent(65.0),
+            ..default()
+        },
+        ScenarioMenuText,
+    ));
+}
+
+/// System to list the scenario presets with the running one marked
+pub fn scenario_menu_system(
+    active: Res<ActiveScenario>,
+    mut text_query: Query<&mut Text, With<ScenarioMenuText>>,
+) {
+    if !active.is_changed() {
+        return;
+    }
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+    if !active.menu {
+        text.0.clear();
+        return;
+    }
+
+    let running = active.scenario.as_ref().map(|scenario| scenario.name.as_str());
+    let mut lines = vec!["Scenario presets (/preset N to run)".to_string()];
+    for (number, (preset, contents)) in SCENARIO_PRESETS.iter().enumerate() {
+        let title = Scenario::parse(contents).map(|scenario| scenario.name).unwrap_or_default();
+        lines.push(format!(
+            "{:>2}. {} {}: {}",
+            number + 1, if running == Some(title.as_str()) { '>' } else { ' ' }, preset, title
+        ));
+    }
+    text.0 = lines.join("\n");
+}
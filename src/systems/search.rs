@@ -0,0 +1,202 @@
+// Jump-to-object search box
+
+use bevy::prelude::*;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use crate::components::*;
+use crate::systems::conjunctions::ApproachQuery;
+use crate::systems::maneuvers::{BurnRequest, TransferRequest};
+use crate::systems::constellation::ConstellationCommand;
+use crate::systems::data::{GroupCommand, ObjectCapCommand};
+use crate::systems::designer::DesignRequest;
+use crate::systems::lookahead::LookAheadCommand;
+use crate::systems::metadata::PopulationFilter;
+use crate::systems::scenario::ScenarioCommand;
+use crate::systems::selection::FocusRequest;
+use crate::systems::watchlist::WatchCommand;
+
+/// Resource holding the state of the search box
+#[derive(Resource, Default)]
+pub struct SearchBox {
+    pub active: bool,
+    pub query: String,
+}
+
+/// Marker component for the search box text
+#[derive(Component)]
+pub struct SearchBoxText;
+
+/// Event carrying a command typed into the search box
+#[derive(Event, Clone, Debug)]
+pub enum SearchCommand {
+    Design(DesignRequest),
+    Approach(ApproachQuery),
+    Transfer(TransferRequest),
+    Burn(BurnRequest),
+    LookAhead(LookAheadCommand),
+    Watch(WatchCommand),
+    ObjectCap(ObjectCapCommand),
+    Scenario(ScenarioCommand),
+    Group(GroupCommand),
+    Constellation(ConstellationCommand),
+}
+
+/// Parse a search box command; None when the query is a filter expression or an object to find
+pub fn parse_command(query: &str) -> Option<Result<SearchCommand, String>> {
+    DesignRequest::parse(query).map(|parsed| parsed.map(SearchCommand::Design))
+        .or_else(|| ApproachQuery::parse(query).map(|parsed| parsed.map(SearchCommand::Approach)))
+        .or_else(|| TransferRequest::parse(query).map(|parsed| parsed.map(SearchCommand::Transfer)))
+        .or_else(|| BurnRequest::parse(query).map(|parsed| parsed.map(SearchCommand::Burn)))
+        .or_else(|| LookAheadCommand::parse(query).map(|parsed| parsed.map(SearchCommand::LookAhead)))
+        .or_else(|| WatchCommand::parse(query).map(|parsed| parsed.map(SearchCommand::Watch)))
+        .or_else(|| ObjectCapCommand::parse(query).map(|parsed| parsed.map(SearchCommand::ObjectCap)))
+        .or_else(|| ScenarioCommand::parse(query).map(|parsed| parsed.map(SearchCommand::Scenario)))
+        .or_else(|| GroupCommand::parse(query).map(|parsed| parsed.map(SearchCommand::Group)))
+        .or_else(|| ConstellationCommand::parse(query).map(|parsed| parsed.map(SearchCommand::Constellation)))
+}
+
+/// Run condition for keyboard shortcuts that must not fire while typing a search
+pub fn search_inactive(search: Res<SearchBox>) -> bool {
+    !search.active
+}
+
+/// Find the best satellite match for a NORAD ID or (partial, case-insensitive) name
+pub fn find_satellite<'a>(
+    query: &str,
+    satellites: impl Iterator<Item = (Entity, &'a Satellite)>,
+) -> Option<(Entity, &'a Satellite)> {
+    let query = query.trim();
+    if query.is_empty() {
+        return None;
+    }
+
+    let norad_id = query.parse::<u32>().ok();
+    let needle = query.to_lowercase();
+
+    // Rank: NORAD ID or exact name, then name prefix, then substring
+    let mut best: Option<(u8, Entity, &Satellite)> = None;
+    for (entity, satellite) in satellites {
+        let name = satellite.name.trim().to_lowercase();
+        let rank = if norad_id == Some(satellite.norad_id) || name == needle {
+            0
+        } else if name.starts_with(&needle) {
+            1
+        } else if name.contains(&needle) {
+            2
+        } else {
+            continue;
+        };
+        if best.is_none_or(|(best_rank, _, _)| rank < best_rank) {
+            best = Some((rank, entity, satellite));
+        }
+    }
+
+    best.map(|(_, entity, satellite)| (entity, satellite))
+}
+
+/// System to create the (initially hidden) search box
+pub fn setup_search_box_system(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.95, 0.6)),
+        BackgroundColor(Color::srgba(0.05, 0.05, 0.1, 0.85)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Percent(40.0),
+            padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+            display: Display::None,
+            ..default()
+        },
+        SearchBoxText,
+    ));
+}
+
+/// System to open the search box with '/', collect typed text and jump to the match on Enter
+/// (filter expressions such as `operator:SpaceX` or `owner:CIS,derelict` set the population filter instead,
+/// commands such as `hide Starlink` act on constellations, `approach [KM] [HOURS]` queries the selected pair,
+/// `design [ALT] [INC]` spawns a satellite for the orbit designer, `burn R T N [DELAY_S]` schedules a burn of the
+/// selection, `lookahead [HOURS]` / `lookahead show N` run and browse the look-ahead prediction, `watch`
+/// toggles the watch on the selection, `cap N|all` sets how many cataloged objects are spawned, `groups` /
+/// `fetch GROUP` show the Celestrak group checklist and check or uncheck a group and `presets` / `preset NAME`
+/// show the scenario preset menu and run a preset)
+pub fn search_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut key_events: EventReader<KeyboardInput>,
+    mut search: ResMut<SearchBox>,
+    satellites: Query<(Entity, &Satellite)>,
+    mut focus_requests: EventWriter<FocusRequest>,
+    mut filter: ResMut<PopulationFilter>,
+    mut search_commands: EventWriter<SearchCommand>,
+) {
+    if !search.active {
+        if keyboard.just_pressed(KeyCode::Slash) {
+            search.active = true;
+            search.query.clear();
+        }
+        // Drain events so the '/' that opened the box is not typed into it
+        key_events.clear();
+        return;
+    }
+
+    for event in key_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => {
+                match parse_command(&search.query) {
+                    Some(Ok(command)) => {
+                        search_commands.write(command);
+                    }
+                    Some(Err(e)) => info!("Search '{}': {}", search.query, e),
+                    None if PopulationFilter::is_expression(&search.query) => match PopulationFilter::parse(&search.query) {
+                        Ok(parsed) => {
+                            info!("Population filter: {}", parsed);
+                            filter.set_if_neq(parsed);
+                        }
+                        Err(e) => info!("Search '{}': {}", search.query, e),
+                    },
+                    None => match find_satellite(&search.query, satellites.iter()) {
+                        Some((entity, satellite)) => {
+                            info!("Search '{}': jumping to {} (NORAD {})", search.query, satellite.name, satellite.norad_id);
+                            focus_requests.write(FocusRequest { entity });
+                        }
+                        None => info!("Search '{}': no matching object", search.query),
+                    },
+                }
+                search.active = false;
+            }
+            Key::Escape => search.active = false,
+            Key::Backspace => {
+                search.query.pop();
+            }
+            Key::Space => search.query.push(' '),
+            Key::Character(text) => search.query.push_str(text),
+            _ => {}
+        }
+        if !search.active {
+            break;
+        }
+    }
+}
+
+/// System to show the search box contents
+pub fn search_box_display_system(
+    search: Res<SearchBox>,
+    mut text_query: Query<(&mut Text, &mut Node), With<SearchBoxText>>,
+) {
+    if !search.is_changed() {
+        return;
+    }
+    let Ok((mut text, mut node)) = text_query.single_mut() else {
+        return;
+    };
+
+    node.display = if search.active { Display::Flex } else { Display::None };
+    text.0 = format!("Find (NORAD ID, name or filter): {}_", search.query);
+}
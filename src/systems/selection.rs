@@ -0,0 +1,250 @@
+// Object selection by clicking in the viewport
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+use crate::systems::rendering::{CameraRig, ObjectLabel, RenderedObject, Trail};
+use crate::systems::watchlist::Watched;
+
+/// Maximum distance in pixels between the cursor and an object for it to be picked
+const PICK_RADIUS_PX: f32 = 12.0;
+/// Maximum time between clicks to count as a double-click (seconds)
+const DOUBLE_CLICK_SECS: f64 = 0.4;
+/// Depth below the reentry altitude of the perigee targeted by a manual deorbit burn (km)
+pub const DEORBIT_PERIGEE_MARGIN: f64 = 50.0;
+
+/// Resource holding the currently selected object
+#[derive(Resource, Default)]
+pub struct Selection {
+    pub selected: Option<Entity>,
+    /// Secondary object for distance measurement (Shift+click)
+    pub secondary: Option<Entity>,
+}
+
+/// Event requesting that the camera fly to and follow an object
+#[derive(Event)]
+pub struct FocusRequest {
+    pub entity: Entity,
+}
+
+/// System to select satellites by clicking them; double-click focuses the camera, Shift+click picks a secondary
+#[allow(clippy::too_many_arguments)]
+pub fn selection_system(
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    objects: Query<(Entity, &GlobalTransform, &Satellite), With<RenderedObject>>,
+    mut selection: ResMut<Selection>,
+    mut focus_requests: EventWriter<FocusRequest>,
+    mut last_click: Local<Option<(f64, Entity)>>,
+) {
+    if !mouse_buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera_query.single() else {
+        return;
+    };
+
+    // Pick the object whose projection lies closest to the cursor
+    let mut best: Option<(Entity, f32, &Satellite)> = None;
+    for (entity, transform, satellite) in objects.iter() {
+        let Ok(screen) = camera.world_to_viewport(camera_transform, transform.translation()) else {
+            continue;
+        };
+        let distance = screen.distance(cursor);
+        if distance <= PICK_RADIUS_PX && best.is_none_or(|(_, d, _)| distance < d) {
+            best = Some((entity, distance, satellite));
+        }
+    }
+
+    let Some((entity, _, satellite)) = best else {
+        return;
+    };
+
+    if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        if selection.selected != Some(entity) && selection.secondary != Some(entity) {
+            selection.secondary = Some(entity);
+            info!("Measuring against {} (NORAD {})", satellite.name, satellite.norad_id);
+        }
+        return;
+    }
+
+    let now = time.elapsed_secs_f64();
+    let double_click = matches!(*last_click, Some((t, e)) if e == entity && now - t <= DOUBLE_CLICK_SECS);
+    *last_click = Some((now, entity));
+
+    if selection.selected != Some(entity) {
+        selection.selected = Some(entity);
+        if selection.secondary == Some(entity) {
+            selection.secondary = None;
+        }
+        info!("Selected {} (NORAD {})", satellite.name, satellite.norad_id);
+    }
+    if double_click {
+        focus_requests.write(FocusRequest { entity });
+    }
+}
+
+/// System to focus the camera on the selection with 'F', orbit it with 'G' and release it with Escape
+pub fn focus_control_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut selection: ResMut<Selection>,
+    mut focus_requests: EventWriter<FocusRequest>,
+    mut rig: ResMut<CameraRig>,
+) {
+    if keyboard.just_pressed(KeyCode::KeyF) {
+        if let Some(entity) = selection.selected {
+            focus_requests.write(FocusRequest { entity });
+        }
+    }
+    if keyboard.just_pressed(KeyCode::KeyG) {
+        if let Some(entity) = selection.selected {
+            rig.orbit(entity);
+        }
+    }
+    if keyboard.just_pressed(KeyCode::Escape) {
+        selection.selected = None;
+        selection.secondary = None;
+        rig.release();
+    }
+}
+
+/// System to apply focus requests to the camera rig
+pub fn camera_focus_system(
+    mut focus_requests: EventReader<FocusRequest>,
+    mut rig: ResMut<CameraRig>,
+    mut selection: ResMut<Selection>,
+) {
+    if let Some(request) = focus_requests.read().last() {
+        selection.selected = Some(request.entity);
+        if selection.secondary == Some(request.entity) {
+            selection.secondary = None;
+        }
+        rig.focus(request.entity);
+    }
+}
+
+/// Spawn a screen-space name label that follows an object
+pub fn spawn_object_label(commands: &mut Commands, target: Entity, name: String) {
+    commands.spawn((
+        Text::new(name),
+        TextFont {
+            font_size: 12.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.9, 0.95, 1.0)),
+        Node {
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        ObjectLabel { target },
+    ));
+}
+
+/// System to give the selected object a trail and label, removing them from the previous one
+/// (watched objects keep theirs)
+pub fn selection_decoration_system(
+    mut commands: Commands,
+    selection: Res<Selection>,
+    satellites: Query<&Satellite>,
+    watched: Query<(), With<Watched>>,
+    labels: Query<(Entity, &ObjectLabel)>,
+    mut previous: Local<Option<Entity>>,
+) {
+    if !selection.is_changed() || selection.selected == *previous {
+        return;
+    }
+
+    if let Some(old) = previous.take().filter(|old| !watched.contains(*old)) {
+        if let Ok(mut entity) = commands.get_entity(old) {
+            entity.remove::<Trail>();
+        }
+        for (label_entity, label) in labels.iter() {
+            if label.target == old {
+                commands.entity(label_entity).despawn();
+            }
+        }
+    }
+
+    if let Some(new) = selection.selected.filter(|new| !watched.contains(*new)) {
+        let name = satellites.get(new).map(|s| s.name.clone()).unwrap_or_else(|_| "Debris".to_string());
+        commands.entity(new).insert(Trail::default());
+        spawn_object_label(&mut commands, new, name);
+    }
+    *previous = selection.selected;
+}
+
+/// Velocity after a horizontal burn that leaves the current point as apoapsis with the given periapsis radius
+pub fn deorbit_velocity(position: DVec3, velocity: DVec3, perigee_radius_km: f64, mu_km: f64) -> DVec3 {
+    let radial = position.normalize_or_zero();
+    let radial_speed = velocity.dot(radial);
+    let horizontal = (velocity - radial * radial_speed).normalize_or_zero();
+
+    let r = position.length();
+    let rp = perigee_radius_km.min(r);
+    let apoapsis_speed = (mu_km * 2.0 * rp / (r * (r + rp))).sqrt();
+
+    radial * radial_speed + horizontal * apoapsis_speed
+}
+
+/// System to remove the selected object with Delete, or deorbit it with 'O'
+pub fn object_action_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    constants: Res<Constants>,
+    mut selection: ResMut<Selection>,
+    mut orbital_query: Query<(&mut OrbitalState, Option<&Satellite>, Option<&OptimizedPhysics>)>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+) {
+    let Some(entity) = selection.selected else {
+        return;
+    };
+    let Ok((mut orbital_state, satellite, optimized)) = orbital_query.get_mut(entity) else {
+        return;
+    };
+    let name = satellite.map(|s| s.name.clone()).unwrap_or_else(|| "Debris".to_string());
+
+    if keyboard.just_pressed(KeyCode::Delete) {
+        info!("Removed {} from the simulation", name);
+        commands.entity(entity).despawn();
+        selection.selected = None;
+    } else if keyboard.just_pressed(KeyCode::KeyO) {
+        let mu_km = constants.gravitational_parameter / 1e9;
+        let perigee_altitude = constants.reentry_altitude - DEORBIT_PERIGEE_MARGIN;
+        let perigee_radius = constants.earth_radius + perigee_altitude;
+        let delta_v = deorbit_velocity(orbital_state.position, orbital_state.velocity, perigee_radius, mu_km)
+            - orbital_state.velocity;
+
+        orbital_state.velocity += delta_v;
+        if let Some(optimized) = optimized {
+            optimized_data.change_velocity(optimized.index, delta_v);
+        }
+        info!("{} performed {:.1} m/s deorbit burn (perigee {:.0} km)", name, delta_v.length() * 1000.0, perigee_altitude);
+    }
+}
+
+/// System to draw a line between the primary and secondary selections
+pub fn measurement_line_system(
+    selection: Res<Selection>,
+    transforms: Query<&GlobalTransform, With<RenderedObject>>,
+    mut gizmos: Gizmos,
+) {
+    let (Some(primary), Some(secondary)) = (selection.selected, selection.secondary) else {
+        return;
+    };
+    let Ok([a, b]) = transforms.get_many([primary, secondary]) else {
+        return;
+    };
+    gizmos.line(a.translation(), b.translation(), Color::srgba(1.0, 0.9, 0.3, 0.8));
+}
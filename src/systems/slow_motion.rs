@@ -0,0 +1,83 @@
+// Automatic slow motion around predicted conjunctions
+
+use bevy::prelude::*;
+use crate::resources::*;
+use crate::systems::conjunctions::ConjunctionForecast;
+
+/// Resource controlling automatic slow motion near collisions
+#[derive(Resource)]
+pub struct SlowMotion {
+    pub enabled: bool,
+    /// Trigger when a conjunction's time of closest approach is within this many simulated seconds
+    pub trigger_window: f64,
+    /// Speed multiplier used while slowed down
+    pub slow_speed: f64,
+    /// Simulated seconds to stay slowed after the closest approach
+    pub linger: f64,
+    /// Speed to restore once the encounter has passed (set while slowed down)
+    pub saved_speed: Option<f64>,
+    /// Simulation time after which normal speed is restored
+    pub active_until: f64,
+}
+
+impl Default for SlowMotion {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            trigger_window: 300.0,
+            slow_speed: 10.0,
+            linger: 60.0,
+            saved_speed: None,
+            active_until: 0.0,
+        }
+    }
+}
+
+/// System to toggle automatic slow motion
+pub fn slow_motion_control_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut slow_motion: ResMut<SlowMotion>,
+    mut sim_time: ResMut<SimulationTime>,
+) {
+    // Toggle automatic slow motion with 'M' key
+    if keyboard.just_pressed(KeyCode::KeyM) {
+        slow_motion.enabled = !slow_motion.enabled;
+        if !slow_motion.enabled {
+            if let Some(speed) = slow_motion.saved_speed.take() {
+                sim_time.set_speed(speed);
+            }
+        }
+        info!("Automatic slow motion {}", if slow_motion.enabled { "ENABLED" } else { "DISABLED" });
+    }
+}
+
+/// System to drop time compression while a predicted conjunction is imminent
+pub fn slow_motion_system(
+    mut slow_motion: ResMut<SlowMotion>,
+    mut sim_time: ResMut<SimulationTime>,
+    mut forecast: ResMut<ConjunctionForecast>,
+) {
+    if !slow_motion.enabled || sim_time.paused {
+        return;
+    }
+    forecast.requested = true;
+
+    let nearest_tca = forecast.next()
+        .map(|conjunction| conjunction.tca - sim_time.current)
+        .filter(|&tca| tca <= slow_motion.trigger_window);
+
+    if let Some(tca) = nearest_tca {
+        slow_motion.active_until = slow_motion.active_until.max(sim_time.current + tca + slow_motion.linger);
+        if slow_motion.saved_speed.is_none() && sim_time.speed_multiplier > slow_motion.slow_speed {
+            slow_motion.saved_speed = Some(sim_time.speed_multiplier);
+            let slow_speed = slow_motion.slow_speed;
+            sim_time.set_speed(slow_speed);
+            info!("Conjunction in {:.0}s: slowing to {:.0}x", tca, slow_speed);
+        }
+    } else if sim_time.current > slow_motion.active_until {
+        if let Some(speed) = slow_motion.saved_speed.take() {
+            sim_time.set_speed(speed);
+            info!("Conjunction passed: restoring {:.0}x", speed);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+// Sun-synchronous satellites spawned from altitude and local time of ascending node
+
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::utils::*;
+
+/// Resource holding SSO planes waiting to be populated
+#[derive(Resource, Default)]
+pub struct SsoSpawns {
+    pub pending: Vec<SsoPlane>,
+}
+
+/// Spawn satellites evenly spaced around a sun-synchronous plane at the current simulation epoch
+pub fn spawn_sso_plane(commands: &mut Commands, plane: SsoPlane, unix_seconds: f64, constants: &Constants) -> usize {
+    let inclination = sun_synchronous_inclination(plane.altitude);
+    let raan = raan_for_ltan(plane.ltan_hours, unix_seconds);
+    let mu_km = constants.gravitational_parameter / 1e9;
+    let mass = 500.0;
+    let (hours, minutes) = (plane.ltan_hours.trunc() as u32, (plane.ltan_hours.fract() * 60.0).round() as u32);
+
+    for index in 0..plane.count {
+        let argument_of_latitude = std::f64::consts::TAU * index as f64 / plane.count as f64;
+        let (position, velocity) = circular_orbit_state(
+            plane.altitude, inclination.to_radians(), raan, argument_of_latitude,
+            constants.earth_radius, mu_km,
+        );
+        let name = format!("SSO {:.0}KM {:02}{:02} #{}", plane.altitude, hours, minutes, index + 1);
+        commands.spawn((
+            Satellite::new(name.clone(), 0, true),
+            Operator::from_satellite_name(&name),
+            OrbitalState::new(position, velocity, mass),
+            PhysicsObject::satellite(mass),
+            Health::default(),
+            RenderAsSatellite,
+            UserSpawned,
+        ));
+    }
+
+    info!("Spawned {} satellites in a {:.0} km sun-synchronous plane: LTAN {:02}:{:02}, inclination {:.2}°, RAAN {:.2}°",
+          plane.count, plane.altitude, hours, minutes, inclination, raan.to_degrees());
+    plane.count
+}
+
+/// System to populate requested SSO planes
+pub fn sso_spawn_system(
+    mut commands: Commands,
+    mut spawns: ResMut<SsoSpawns>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+) {
+    for plane in std::mem::take(&mut spawns.pending) {
+        spawn_sso_plane(&mut commands, plane, sim_time.current_unix(), &constants);
+    }
+}
@@ -1,7 +1,18 @@
+use bevy::ecs::archetype::Archetypes;
+use bevy::ecs::component::Components;
+use bevy::ecs::entity::Entities;
+use bevy::math::DVec3;
 use bevy::prelude::*;
 use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Instant;
 use crate::components::*;
-use crate::resources::*;
+use crate::resources::BroadPhase;
+use crate::utils::{sun_synchronous_inclination, CliOptions};
+use crate::systems::collision::{SmartSieve, SpatialOctree};
+use crate::systems::export::{write_export, ExportConfig};
 
 /// Component to mark stress test objects
 #[derive(Component)]
@@ -16,6 +27,7 @@ impl StressTestObject {
 }
 
 /// Orbital types for satellite distribution
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Copy, Debug)]
 pub enum OrbitType {
     LEO,  // Low Earth Orbit: 160-2000km
@@ -24,13 +36,167 @@ pub enum OrbitType {
 }
 
 impl OrbitType {
-    fn altitude_range(&self) -> (f32, f32) {
+    fn altitude_range(&self) -> (f64, f64) {
         match self {
             OrbitType::LEO => (160.0, 2000.0),
             OrbitType::MEO => (2000.0, 35786.0),
             OrbitType::GEO => (35786.0, 35786.0), // Fixed altitude for GEO
         }
     }
+
+    /// Orbit class of an altitude (km)
+    fn from_altitude(altitude: f64) -> Self {
+        if altitude < 2000.0 {
+            OrbitType::LEO
+        } else if altitude < 35000.0 {
+            OrbitType::MEO
+        } else {
+            OrbitType::GEO
+        }
+    }
+}
+
+/// Altitude bins (km) and relative weights of the empirical LEO-heavy profile
+const EMPIRICAL_ALTITUDE_BINS: [(f64, f64, f64); 12] = [
+    (200.0, 400.0, 4.0),
+    (400.0, 500.0, 10.0),
+    (500.0, 600.0, 26.0), // Starlink shells
+    (600.0, 700.0, 10.0),
+    (700.0, 800.0, 13.0), // Fengyun/Iridium debris
+    (800.0, 900.0, 11.0), // Cosmos-Iridium debris, SSO imagers
+    (900.0, 1000.0, 6.0),
+    (1000.0, 1300.0, 6.0), // OneWeb
+    (1300.0, 2000.0, 4.0),
+    (19000.0, 24000.0, 3.0), // GNSS
+    (35766.0, 35806.0, 5.0), // GEO
+    (2000.0, 35000.0, 2.0), // GTO remnants and the rest of MEO
+];
+
+/// Inclinations (degrees), spread and weights of common LEO families
+const EMPIRICAL_INCLINATIONS: [(f64, f64, f64); 7] = [
+    (53.0, 0.5, 30.0),  // Starlink
+    (97.8, 1.0, 25.0),  // Sun-synchronous
+    (51.6, 0.3, 6.0),   // ISS
+    (86.4, 0.3, 6.0),   // Iridium
+    (87.9, 0.3, 5.0),   // OneWeb
+    (70.0, 10.0, 18.0), // Soviet-era and polar-ish
+    (20.0, 15.0, 10.0), // Low-inclination launches
+];
+
+/// Altitude/inclination presets for stress-test populations
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpawnDistribution {
+    /// Original per-class bands driven by the LEO/MEO/GEO targets
+    Bands,
+    /// Empirical catalog-like profile dominated by LEO
+    LeoEmpirical,
+    /// Sun-synchronous band between 500 and 900 km
+    SunSynchronous,
+    /// Geostationary ring with a few drifting objects
+    GeoRing,
+}
+
+impl SpawnDistribution {
+    pub const ALL: [SpawnDistribution; 4] = [
+        SpawnDistribution::Bands,
+        SpawnDistribution::LeoEmpirical,
+        SpawnDistribution::SunSynchronous,
+        SpawnDistribution::GeoRing,
+    ];
+
+    /// Next preset in the cycle
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&d| d == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Preset from its name as used on the command line ("bands", "leo", "sso", "geo")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "bands" => Some(SpawnDistribution::Bands),
+            "leo" | "leoempirical" => Some(SpawnDistribution::LeoEmpirical),
+            "sso" | "sunsynchronous" => Some(SpawnDistribution::SunSynchronous),
+            "geo" | "georing" => Some(SpawnDistribution::GeoRing),
+            _ => None,
+        }
+    }
+
+    /// Sample an (altitude km, inclination degrees) pair; None for the per-class bands
+    fn sample(&self, rng: &mut impl Rng) -> Option<(f64, f64)> {
+        match self {
+            SpawnDistribution::Bands => None,
+            SpawnDistribution::LeoEmpirical => {
+                let total: f64 = EMPIRICAL_ALTITUDE_BINS.iter().map(|bin| bin.2).sum();
+                let mut pick = rng.gen_range(0.0..total);
+                let (low, high, _) = *EMPIRICAL_ALTITUDE_BINS
+                    .iter()
+                    .find(|bin| {
+                        pick -= bin.2;
+                        pick < 0.0
+                    })
+                    .unwrap_or(&EMPIRICAL_ALTITUDE_BINS[0]);
+                let altitude = rng.gen_range(low..high);
+                let inclination = match OrbitType::from_altitude(altitude) {
+                    OrbitType::LEO => {
+                        let total: f64 = EMPIRICAL_INCLINATIONS.iter().map(|family| family.2).sum();
+                        let mut pick = rng.gen_range(0.0..total);
+                        let (center, spread, _) = *EMPIRICAL_INCLINATIONS
+                            .iter()
+                            .find(|family| {
+                                pick -= family.2;
+                                pick < 0.0
+                            })
+                            .unwrap_or(&EMPIRICAL_INCLINATIONS[0]);
+                        (center + rng.gen_range(-spread..=spread)).clamp(0.0, 180.0)
+                    }
+                    OrbitType::MEO => rng.gen_range(54.0..65.0),
+                    OrbitType::GEO => rng.gen_range(0.0..2.0),
+                };
+                Some((altitude, inclination))
+            }
+            SpawnDistribution::SunSynchronous => {
+                let altitude = rng.gen_range(500.0..900.0);
+                Some((altitude, sun_synchronous_inclination(altitude)))
+            }
+            SpawnDistribution::GeoRing => {
+                // Most objects are station-kept; the rest drift with growing inclination
+                if rng.gen_bool(0.85) {
+                    Some((rng.gen_range(35766.0..35806.0), rng.gen_range(0.0..0.1)))
+                } else {
+                    Some((rng.gen_range(35586.0..36086.0), rng.gen_range(0.0..15.0)))
+                }
+            }
+        }
+    }
+}
+
+/// Mass ranges (kg) of spawned objects per orbit class
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct MassRanges {
+    pub leo: (f64, f64),
+    pub meo: (f64, f64),
+    pub geo: (f64, f64),
+}
+
+impl Default for MassRanges {
+    fn default() -> Self {
+        Self {
+            leo: (100.0, 2000.0),  // Small to medium satellites
+            meo: (500.0, 3000.0),  // GPS-like satellites
+            geo: (2000.0, 8000.0), // Large geostationary satellites
+        }
+    }
+}
+
+impl MassRanges {
+    fn for_orbit(&self, orbit_type: OrbitType) -> (f64, f64) {
+        match orbit_type {
+            OrbitType::LEO => self.leo,
+            OrbitType::MEO => self.meo,
+            OrbitType::GEO => self.geo,
+        }
+    }
 }
 
 /// Resource to control stress test parameters
@@ -48,6 +214,9 @@ pub struct StressTestConfig {
     pub current_leo: usize,
     pub current_meo: usize,
     pub current_geo: usize,
+    /// Altitude/inclination preset used for new objects
+    pub distribution: SpawnDistribution,
+    pub masses: MassRanges,
 }
 
 impl Default for StressTestConfig {
@@ -64,10 +233,121 @@ impl Default for StressTestConfig {
             current_leo: 0,
             current_meo: 0,
             current_geo: 0,
+            distribution: SpawnDistribution::Bands,
+            masses: MassRanges::default(),
         }
     }
 }
 
+impl StressTestConfig {
+    /// Set the total target, split 80% LEO / 10% MEO / 10% GEO
+    pub fn set_target(&mut self, total: usize) {
+        self.target_objects = total;
+        self.target_leo = total * 8 / 10;
+        self.target_meo = total / 10;
+        self.target_geo = total - self.target_leo - self.target_meo;
+    }
+}
+
+/// Sweep section of a stress-test scenario file
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct SweepScenario {
+    pub targets: Option<Vec<usize>>,
+    pub hold_secs: Option<f32>,
+    pub settle_secs: Option<f32>,
+    /// Start the sweep as soon as the app runs
+    pub run: bool,
+    /// Quit once the report is written
+    pub exit_when_done: bool,
+}
+
+/// Stress-test scenario file, e.g.
+/// `{"target_objects": 2000, "distribution": "LeoEmpirical", "sweep": {"targets": [500, 1000], "run": true}}`
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct StressTestScenario {
+    pub enabled: Option<bool>,
+    pub target_objects: Option<usize>,
+    pub spawn_rate: Option<usize>,
+    pub distribution: Option<SpawnDistribution>,
+    pub masses: Option<MassRanges>,
+    pub sweep: Option<SweepScenario>,
+}
+
+impl StressTestScenario {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&contents).map_err(|e| e.to_string())
+    }
+}
+
+/// Build the stress-test configuration and sweep from the scenario file and command-line overrides
+pub fn configure_stress_test(cli: &CliOptions) -> (StressTestConfig, StressSweep) {
+    let mut config = StressTestConfig::default();
+    let mut sweep = StressSweep::default();
+
+    let scenario = match &cli.stress_config {
+        Some(path) => StressTestScenario::load(path).unwrap_or_else(|e| {
+            warn!("Ignoring stress-test scenario {}: {}", path.display(), e);
+            StressTestScenario::default()
+        }),
+        None => StressTestScenario::default(),
+    };
+    if let Some(enabled) = scenario.enabled {
+        config.enabled = enabled;
+    }
+    if let Some(total) = scenario.target_objects {
+        config.set_target(total);
+    }
+    if let Some(rate) = scenario.spawn_rate {
+        config.spawn_rate = rate;
+    }
+    if let Some(distribution) = scenario.distribution {
+        config.distribution = distribution;
+    }
+    if let Some(masses) = scenario.masses {
+        config.masses = masses;
+    }
+    let mut run_sweep = false;
+    if let Some(sweep_scenario) = scenario.sweep {
+        if let Some(targets) = sweep_scenario.targets {
+            sweep.targets = targets;
+        }
+        if let Some(hold) = sweep_scenario.hold_secs {
+            sweep.hold_secs = hold.max(0.0);
+        }
+        if let Some(settle) = sweep_scenario.settle_secs {
+            sweep.settle_secs = settle.max(0.0);
+        }
+        run_sweep = sweep_scenario.run;
+        sweep.exit_when_done = sweep_scenario.exit_when_done;
+    }
+
+    if let Some(total) = cli.stress_objects {
+        config.set_target(total);
+        config.enabled = true;
+    }
+    if let Some(rate) = cli.stress_rate {
+        config.spawn_rate = rate.max(1);
+    }
+    if let Some(name) = &cli.stress_distribution {
+        match SpawnDistribution::from_name(name) {
+            Some(distribution) => config.distribution = distribution,
+            None => warn!("Unknown stress-test distribution {} - using {:?}", name, config.distribution),
+        }
+    }
+
+    // Headless runs have nobody to close the window, so sweeps quit when done
+    run_sweep |= cli.stress_sweep;
+    sweep.exit_when_done |= cli.headless;
+    if run_sweep {
+        sweep.start(&mut config);
+    }
+
+    (config, sweep)
+}
+
 /// System to create stress test objects for performance testing
 pub fn stress_test_spawn_system(
     mut commands: Commands,
@@ -75,8 +355,9 @@ pub fn stress_test_spawn_system(
     keyboard: Res<ButtonInput<KeyCode>>,
     existing_objects: Query<&StressTestObject>,
 ) {
-    // Toggle stress test with 'T' key
-    if keyboard.just_pressed(KeyCode::KeyT) {
+    // Toggle stress test with 'T' key (Shift+T runs a sweep)
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if keyboard.just_pressed(KeyCode::KeyT) && !shift {
         config.enabled = !config.enabled;
         if config.enabled {
             info!("Stress test ENABLED - spawning {} satellites (LEO: {}, MEO: {}, GEO: {})",
@@ -87,37 +368,24 @@ pub fn stress_test_spawn_system(
     }
 
     // Adjust target object count - keep the same distribution ratios
-    if keyboard.just_pressed(KeyCode::Digit5) {
-        config.target_objects = 500;
-        config.target_leo = 400;  // 80%
-        config.target_meo = 50;   // 10%
-        config.target_geo = 50;   // 10%
-        info!("Target objects: {} (LEO: {}, MEO: {}, GEO: {})",
-              config.target_objects, config.target_leo, config.target_meo, config.target_geo);
-    }
-    if keyboard.just_pressed(KeyCode::Digit6) {
-        config.target_objects = 1000;
-        config.target_leo = 800;  // 80%
-        config.target_meo = 100;  // 10%
-        config.target_geo = 100;  // 10%
-        info!("Target objects: {} (LEO: {}, MEO: {}, GEO: {})",
-              config.target_objects, config.target_leo, config.target_meo, config.target_geo);
-    }
-    if keyboard.just_pressed(KeyCode::Digit7) {
-        config.target_objects = 2000;
-        config.target_leo = 1600; // 80%
-        config.target_meo = 200;  // 10%
-        config.target_geo = 200;  // 10%
-        info!("Target objects: {} (LEO: {}, MEO: {}, GEO: {})",
-              config.target_objects, config.target_leo, config.target_meo, config.target_geo);
-    }
-    if keyboard.just_pressed(KeyCode::Digit8) {
-        config.target_objects = 5000;
-        config.target_leo = 4000; // 80%
-        config.target_meo = 500;  // 10%
-        config.target_geo = 500;  // 10%
-        info!("Target objects: {} (LEO: {}, MEO: {}, GEO: {})",
-              config.target_objects, config.target_leo, config.target_meo, config.target_geo);
+    for (key, total) in [
+        (KeyCode::Digit5, 500),
+        (KeyCode::Digit6, 1000),
+        (KeyCode::Digit7, 2000),
+        (KeyCode::Digit8, 5000),
+    ] {
+        if keyboard.just_pressed(key) {
+            config.set_target(total);
+            info!("Target objects: {} (LEO: {}, MEO: {}, GEO: {})",
+                  config.target_objects, config.target_leo, config.target_meo, config.target_geo);
+        }
+    }
+
+    // Cycle altitude/inclination presets with 'B'
+    if keyboard.just_pressed(KeyCode::KeyB) {
+        config.distribution = config.distribution.next();
+        info!("Stress test distribution: {:?} (applies to newly spawned objects, 'C' clears existing ones)",
+              config.distribution);
     }
 
     if !config.enabled {
@@ -141,12 +409,29 @@ pub fn stress_test_spawn_system(
 
     // Spawn objects if we haven't reached targets
     let mut spawned = 0;
+
+    // Presets fill the total target; only the original bands use per-class targets
+    if config.distribution != SpawnDistribution::Bands {
+        let to_spawn = config.target_objects.saturating_sub(config.current_objects).min(config.spawn_rate);
+        let mut rng = thread_rng();
+        for _ in 0..to_spawn {
+            if let Some((altitude, inclination)) = config.distribution.sample(&mut rng) {
+                spawn_satellite_at(&mut commands, OrbitType::from_altitude(altitude), altitude, inclination.to_radians(), config.masses);
+                spawned += 1;
+            }
+        }
+        if spawned > 0 {
+            info!("Spawned {} {:?} satellites - {}/{}",
+                  spawned, config.distribution, config.current_objects + spawned, config.target_objects);
+        }
+        return;
+    }
     
     // Spawn LEO satellites
     if config.current_leo < config.target_leo {
         let to_spawn = (config.target_leo - config.current_leo).min(config.spawn_rate / 3);
         for _ in 0..to_spawn {
-            spawn_orbital_satellite(&mut commands, OrbitType::LEO);
+            spawn_orbital_satellite(&mut commands, OrbitType::LEO, config.masses);
             spawned += 1;
         }
     }
@@ -155,7 +440,7 @@ pub fn stress_test_spawn_system(
     if config.current_meo < config.target_meo {
         let to_spawn = (config.target_meo - config.current_meo).min(config.spawn_rate / 3);
         for _ in 0..to_spawn {
-            spawn_orbital_satellite(&mut commands, OrbitType::MEO);
+            spawn_orbital_satellite(&mut commands, OrbitType::MEO, config.masses);
             spawned += 1;
         }
     }
@@ -164,7 +449,7 @@ pub fn stress_test_spawn_system(
     if config.current_geo < config.target_geo {
         let to_spawn = (config.target_geo - config.current_geo).min(config.spawn_rate / 3);
         for _ in 0..to_spawn {
-            spawn_orbital_satellite(&mut commands, OrbitType::GEO);
+            spawn_orbital_satellite(&mut commands, OrbitType::GEO, config.masses);
             spawned += 1;
         }
     }
@@ -177,7 +462,7 @@ pub fn stress_test_spawn_system(
 }
 
 /// Create a satellite in the specified orbital type
-fn spawn_orbital_satellite(commands: &mut Commands, orbit_type: OrbitType) {
+fn spawn_orbital_satellite(commands: &mut Commands, orbit_type: OrbitType, masses: MassRanges) {
     let mut rng = thread_rng();
     
     // Get altitude range for this orbit type
@@ -190,27 +475,33 @@ fn spawn_orbital_satellite(commands: &mut Commands, orbit_type: OrbitType) {
         rng.gen_range(min_alt..max_alt)
     };
     
-    let earth_radius = 6371.0; // km
-    let orbital_radius = earth_radius + altitude;
-    
     // Orbital inclination based on orbit type
     let inclination = match orbit_type {
-        OrbitType::LEO => rng.gen_range(0.0..180.0_f32).to_radians(), // Any inclination
-        OrbitType::MEO => rng.gen_range(55.0..65.0_f32).to_radians(), // Common MEO inclinations
+        OrbitType::LEO => rng.gen_range(0.0..180.0_f64).to_radians(), // Any inclination
+        OrbitType::MEO => rng.gen_range(55.0..65.0_f64).to_radians(), // Common MEO inclinations
         OrbitType::GEO => 0.0, // Equatorial orbit
     };
+
+    spawn_satellite_at(commands, orbit_type, altitude, inclination, masses);
+}
+
+/// Create a circular-orbit satellite at an altitude (km) and inclination (radians) with random phase
+fn spawn_satellite_at(commands: &mut Commands, orbit_type: OrbitType, altitude: f64, inclination: f64, masses: MassRanges) {
+    let mut rng = thread_rng();
+    let earth_radius = 6371.0; // km
+    let orbital_radius = earth_radius + altitude;
     
     // Random right ascension of ascending node
-    let raan = rng.gen_range(0.0..360.0_f32).to_radians();
+    let raan = rng.gen_range(0.0..360.0_f64).to_radians();
     
     // Random argument of perigee
-    let arg_perigee = rng.gen_range(0.0..360.0_f32).to_radians();
+    let arg_perigee = rng.gen_range(0.0..360.0_f64).to_radians();
     
     // Random true anomaly (position in orbit)
-    let true_anomaly = rng.gen_range(0.0..360.0_f32).to_radians();
+    let true_anomaly = rng.gen_range(0.0..360.0_f64).to_radians();
     
     // Calculate position in orbital plane
-    let r_orbital = Vec3::new(
+    let r_orbital = DVec3::new(
         orbital_radius * true_anomaly.cos(),
         orbital_radius * true_anomaly.sin(),
         0.0,
@@ -224,7 +515,7 @@ fn spawn_orbital_satellite(commands: &mut Commands, orbit_type: OrbitType) {
     let orbital_speed = (gm / (orbital_radius * 1000.0)).sqrt() / 1000.0; // km/s
     
     // Velocity perpendicular to position in orbital plane
-    let v_orbital = Vec3::new(
+    let v_orbital = DVec3::new(
         -orbital_speed * true_anomaly.sin(),
         orbital_speed * true_anomaly.cos(),
         0.0,
@@ -233,31 +524,30 @@ fn spawn_orbital_satellite(commands: &mut Commands, orbit_type: OrbitType) {
     let velocity = apply_orbital_rotations(v_orbital, inclination, raan, arg_perigee);
     
     // Satellite mass based on orbit type
-    let mass = match orbit_type {
-        OrbitType::LEO => rng.gen_range(100.0..2000.0), // Small to medium satellites
-        OrbitType::MEO => rng.gen_range(500.0..3000.0), // GPS-like satellites
-        OrbitType::GEO => rng.gen_range(2000.0..8000.0), // Large geostationary satellites
-    };
+    let (min_mass, max_mass) = masses.for_orbit(orbit_type);
+    let mass = rng.gen_range(min_mass..=max_mass.max(min_mass));
     
     // Spawn the satellite entity
     commands.spawn((
         OrbitalState::new(position, velocity, mass),
         PhysicsObject::satellite(mass),
         Satellite::new(format!("{:?} Satellite", orbit_type), 0, true),
+        Operator::new("Stress Test"),
+        Health::default(),
         StressTestObject::new(orbit_type),
         RenderAsSatellite, // Render as green satellite
     ));
 }
 
 /// Apply orbital rotations to convert from orbital plane to Earth-fixed coordinates
-fn apply_orbital_rotations(vec: Vec3, inclination: f32, raan: f32, arg_perigee: f32) -> Vec3 {
+fn apply_orbital_rotations(vec: DVec3, inclination: f64, raan: f64, arg_perigee: f64) -> DVec3 {
     // Rotation matrices for orbital mechanics
     // This is a simplified version - full implementation would use proper rotation matrices
     
     // Rotate by argument of perigee (in orbital plane)
     let cos_w = arg_perigee.cos();
     let sin_w = arg_perigee.sin();
-    let rotated_w = Vec3::new(
+    let rotated_w = DVec3::new(
         vec.x * cos_w - vec.y * sin_w,
         vec.x * sin_w + vec.y * cos_w,
         vec.z,
@@ -266,7 +556,7 @@ fn apply_orbital_rotations(vec: Vec3, inclination: f32, raan: f32, arg_perigee:
     // Rotate by inclination
     let cos_i = inclination.cos();
     let sin_i = inclination.sin();
-    let rotated_i = Vec3::new(
+    let rotated_i = DVec3::new(
         rotated_w.x,
         rotated_w.y * cos_i - rotated_w.z * sin_i,
         rotated_w.y * sin_i + rotated_w.z * cos_i,
@@ -275,13 +565,83 @@ fn apply_orbital_rotations(vec: Vec3, inclination: f32, raan: f32, arg_perigee:
     // Rotate by RAAN (right ascension of ascending node)
     let cos_raan = raan.cos();
     let sin_raan = raan.sin();
-    Vec3::new(
+    DVec3::new(
         rotated_i.x * cos_raan - rotated_i.y * sin_raan,
         rotated_i.x * sin_raan + rotated_i.y * cos_raan,
         rotated_i.z,
     )
 }
 
+/// A narrow altitude/inclination shell targeted by burst spawns
+#[derive(Clone, Copy, Debug)]
+pub struct BurstShell {
+    pub name: &'static str,
+    /// Shell altitude (km)
+    pub altitude: f64,
+    /// Half-width of the altitude band (km)
+    pub altitude_spread: f64,
+    /// Inclination (degrees)
+    pub inclination: f64,
+    /// Half-width of the inclination band (degrees)
+    pub inclination_spread: f64,
+}
+
+/// Shells selectable with Shift+N
+pub const BURST_SHELLS: [BurstShell; 4] = [
+    BurstShell { name: "Starlink 550 km", altitude: 550.0, altitude_spread: 5.0, inclination: 53.0, inclination_spread: 0.2 },
+    BurstShell { name: "Sun-synchronous 800 km", altitude: 800.0, altitude_spread: 10.0, inclination: 98.6, inclination_spread: 0.3 },
+    BurstShell { name: "ISS 420 km", altitude: 420.0, altitude_spread: 5.0, inclination: 51.6, inclination_spread: 0.2 },
+    BurstShell { name: "GEO belt", altitude: 35786.0, altitude_spread: 20.0, inclination: 0.0, inclination_spread: 0.1 },
+];
+
+/// Resource controlling burst injection of objects into one shell
+#[derive(Resource)]
+pub struct BurstSpawn {
+    /// Objects injected per burst
+    pub count: usize,
+    pub shell: BurstShell,
+}
+
+impl Default for BurstSpawn {
+    fn default() -> Self {
+        Self {
+            count: 500,
+            shell: BURST_SHELLS[0],
+        }
+    }
+}
+
+/// System to inject a burst of objects with 'N'; Shift+N cycles the target shell
+pub fn burst_spawn_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut burst: ResMut<BurstSpawn>,
+    config: Res<StressTestConfig>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyN) {
+        return;
+    }
+
+    if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        let index = BURST_SHELLS.iter().position(|shell| shell.name == burst.shell.name).unwrap_or(0);
+        burst.shell = BURST_SHELLS[(index + 1) % BURST_SHELLS.len()];
+        info!("Burst shell: {} ({:.0} ± {:.0} km, {:.1}° ± {:.1}°)",
+              burst.shell.name, burst.shell.altitude, burst.shell.altitude_spread,
+              burst.shell.inclination, burst.shell.inclination_spread);
+        return;
+    }
+
+    let mut rng = thread_rng();
+    let shell = burst.shell;
+    for _ in 0..burst.count {
+        let altitude = shell.altitude + rng.gen_range(-shell.altitude_spread..=shell.altitude_spread);
+        let inclination = (shell.inclination + rng.gen_range(-shell.inclination_spread..=shell.inclination_spread))
+            .clamp(0.0, 180.0);
+        spawn_satellite_at(&mut commands, OrbitType::from_altitude(altitude), altitude, inclination.to_radians(), config.masses);
+    }
+    info!("Burst: injected {} objects into the {} shell", burst.count, shell.name);
+}
+
 /// System to clean up stress test objects
 pub fn stress_test_cleanup_system(
     mut commands: Commands,
@@ -301,11 +661,68 @@ pub fn stress_test_cleanup_system(
     }
 }
 
+/// Number of archetypes listed in performance reports
+const REPORTED_ARCHETYPES: usize = 5;
+
+/// Resident memory of the process in MiB (Linux only)
+fn process_memory_mib() -> Option<f64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: f64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib / 1024.0)
+}
+
+/// Type name without module path or generic parameters
+fn short_type_name(name: &str) -> &str {
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Describe the most populated archetypes by this crate's components
+fn archetype_summary(archetypes: &Archetypes, components: &Components) -> String {
+    let mut populated: Vec<_> = archetypes.iter().filter(|archetype| !archetype.is_empty()).collect();
+    populated.sort_unstable_by_key(|archetype| std::cmp::Reverse(archetype.len()));
+
+    populated
+        .iter()
+        .take(REPORTED_ARCHETYPES)
+        .map(|archetype| {
+            let names: Vec<_> = archetype
+                .components()
+                .filter_map(|id| components.get_name(id))
+                .collect();
+            // Engine components (Transform, Visibility, ...) are shared by most archetypes
+            let own: Vec<_> = names
+                .iter()
+                .filter(|name| name.starts_with(env!("CARGO_CRATE_NAME")))
+                .map(|name| short_type_name(name))
+                .collect();
+            let label = if own.is_empty() {
+                names.iter().map(|name| short_type_name(name)).take(3).collect::<Vec<_>>().join("+")
+            } else {
+                own.join("+")
+            };
+            format!("{} x [{}]", archetype.len(), label)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Performance comparison system
+#[allow(clippy::too_many_arguments)]
 pub fn performance_comparison_system(
     stress_config: Res<StressTestConfig>,
     time: Res<Time>,
+    entities: &Entities,
+    archetypes: &Archetypes,
+    components: &Components,
+    meshes: Res<Assets<Mesh>>,
+    materials: Res<Assets<StandardMaterial>>,
+    octree: Res<SpatialOctree>,
+    broad_phase: Res<BroadPhase>,
+    sieve: Res<SmartSieve>,
     mut last_report: Local<f32>,
+    mut last_assets: Local<(usize, usize)>,
 ) {
     let current_time = time.elapsed_secs();
     
@@ -318,6 +735,25 @@ pub fn performance_comparison_system(
         
         info!("PERFORMANCE: {} objects @ {:.1} FPS ({:.2}ms/frame)", 
               stress_config.current_objects, fps, frame_time_ms);
+
+        // Memory, entity and asset statistics - steadily growing asset counts point to leaks
+        let memory = process_memory_mib().map_or("n/a".to_string(), |mib| format!("{:.1} MiB", mib));
+        let (mesh_count, material_count) = (meshes.len(), materials.len());
+        info!("MEMORY: {} resident, {} entities in {} archetypes, {} meshes ({:+}), {} materials ({:+})",
+              memory, entities.len(), archetypes.len(),
+              mesh_count, mesh_count as i64 - last_assets.0 as i64,
+              material_count, material_count as i64 - last_assets.1 as i64);
+        info!("ARCHETYPES: {}", archetype_summary(archetypes, components));
+        info!("OCTREE: {} nodes, depth {}/{}, max occupancy {} (capacity {}), {} re-inserted in {:.2}ms",
+              octree.stats.nodes, octree.stats.deepest, octree.settings.max_depth,
+              octree.stats.max_occupancy, octree.settings.max_objects_per_node,
+              octree.stats.reinserted, octree.stats.build_ms);
+        if *broad_phase == BroadPhase::SmartSieve {
+            let rejected = sieve.stats;
+            info!("SIEVE: rejected {} by x, {} by y/z, {} by radius, {} by relative velocity",
+                  rejected.coordinate_x, rejected.coordinate_yz, rejected.radius, rejected.relative_velocity);
+        }
+        *last_assets = (mesh_count, material_count);
         
         // Performance thresholds
         if fps < 30.0 {
@@ -326,4 +762,260 @@ pub fn performance_comparison_system(
             info!("Excellent performance: {} objects running at {:.1} FPS", stress_config.current_objects, fps);
         }
     }
-}
\ No newline at end of file
+}
+// Stage timing
+
+/// Resource holding wall-clock durations of instrumented pipeline stages
+#[derive(Resource, Default)]
+pub struct StageTimings {
+    started: HashMap<&'static str, Instant>,
+    /// Duration of each stage in the most recent frame (ms)
+    pub last_ms: HashMap<&'static str, f32>,
+}
+
+impl StageTimings {
+    /// Stages measured by the timer systems, in report order
+    pub const STAGES: [&'static str; 2] = ["physics", "collision"];
+}
+
+/// Timer system marking the start of a stage
+pub fn stage_timer_begin(stage: &'static str) -> impl FnMut(ResMut<StageTimings>) {
+    move |mut timings: ResMut<StageTimings>| {
+        timings.started.insert(stage, Instant::now());
+    }
+}
+
+/// Timer system marking the end of a stage
+pub fn stage_timer_end(stage: &'static str) -> impl FnMut(ResMut<StageTimings>) {
+    move |mut timings: ResMut<StageTimings>| {
+        if let Some(started) = timings.started.remove(stage) {
+            timings.last_ms.insert(stage, started.elapsed().as_secs_f32() * 1000.0);
+        }
+    }
+}
+
+// Automated sweep
+
+/// Measured performance at one sweep target
+#[derive(Clone, Debug)]
+pub struct SweepResult {
+    pub target: usize,
+    /// Objects in the simulation while measuring
+    pub objects: usize,
+    pub mean_fps: f32,
+    /// 95th percentile frame time (ms)
+    pub p95_frame_ms: f32,
+    /// Mean duration of each instrumented stage (ms)
+    pub stage_ms: Vec<(&'static str, f32)>,
+}
+
+/// Phase of the running sweep
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SweepPhase {
+    /// Waiting for the spawner to reach the target
+    Filling,
+    /// Letting the frame rate settle after spawning
+    Settling { since: f32 },
+    /// Collecting samples
+    Measuring { since: f32 },
+}
+
+/// Resource driving an automated sweep over stress-test target counts
+#[derive(Resource)]
+pub struct StressSweep {
+    /// Target counts visited in ascending order
+    pub targets: Vec<usize>,
+    /// Seconds each target is measured for
+    pub hold_secs: f32,
+    /// Seconds to wait after spawning before measuring
+    pub settle_secs: f32,
+    pub results: Vec<SweepResult>,
+    step: Option<usize>,
+    phase: SweepPhase,
+    /// Send `AppExit` once the report is written
+    pub exit_when_done: bool,
+    frame_ms: Vec<f32>,
+    stage_ms: HashMap<&'static str, Vec<f32>>,
+}
+
+impl Default for StressSweep {
+    fn default() -> Self {
+        Self {
+            targets: vec![500, 1000, 2000, 5000],
+            hold_secs: 10.0,
+            settle_secs: 2.0,
+            results: Vec::new(),
+            step: None,
+            phase: SweepPhase::Filling,
+            exit_when_done: false,
+            frame_ms: Vec::new(),
+            stage_ms: HashMap::new(),
+        }
+    }
+}
+
+impl StressSweep {
+    pub fn is_running(&self) -> bool {
+        self.step.is_some()
+    }
+
+    /// Begin a sweep from the first target
+    pub fn start(&mut self, config: &mut StressTestConfig) {
+        self.targets.sort_unstable();
+        self.results.clear();
+        self.step = None;
+        self.advance(config);
+    }
+
+    /// Move on to the next target, returning false when the sweep is finished
+    fn advance(&mut self, config: &mut StressTestConfig) -> bool {
+        let next = self.step.map_or(0, |step| step + 1);
+        self.frame_ms.clear();
+        self.stage_ms.clear();
+        self.phase = SweepPhase::Filling;
+        match self.targets.get(next) {
+            Some(&target) => {
+                self.step = Some(next);
+                config.set_target(target);
+                config.enabled = true;
+                info!("Stress sweep: step {}/{} - {} objects", next + 1, self.targets.len(), target);
+                true
+            }
+            None => {
+                self.step = None;
+                false
+            }
+        }
+    }
+
+    /// Summarize the samples collected for the current target
+    fn finish_step(&self, config: &StressTestConfig) -> Option<SweepResult> {
+        let target = self.targets.get(self.step?)?;
+        if self.frame_ms.is_empty() {
+            return None;
+        }
+        let mean_ms = self.frame_ms.iter().sum::<f32>() / self.frame_ms.len() as f32;
+        let mut sorted = self.frame_ms.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let p95 = sorted[((sorted.len() - 1) as f32 * 0.95).round() as usize];
+        let stage_ms = StageTimings::STAGES
+            .iter()
+            .map(|&stage| {
+                let samples = self.stage_ms.get(stage).map(Vec::as_slice).unwrap_or_default();
+                let mean = if samples.is_empty() { 0.0 } else { samples.iter().sum::<f32>() / samples.len() as f32 };
+                (stage, mean)
+            })
+            .collect();
+
+        Some(SweepResult {
+            target: *target,
+            objects: config.current_objects,
+            mean_fps: 1000.0 / mean_ms.max(1e-3),
+            p95_frame_ms: p95,
+            stage_ms,
+        })
+    }
+}
+
+/// Render sweep results as a Markdown report
+pub fn format_sweep_report(sweep: &StressSweep, broad_phase: BroadPhase) -> String {
+    let mut report = String::from("# Stress test sweep\n\n");
+    report.push_str(&format!(
+        "Each target was measured for {:.0} s after a {:.0} s settle period, finding collision candidates with the {} broad-phase.\n\n",
+        sweep.hold_secs, sweep.settle_secs, broad_phase.name(),
+    ));
+    report.push_str("| Target | Objects | Mean FPS | p95 frame (ms) |");
+    for stage in StageTimings::STAGES {
+        report.push_str(&format!(" {} (ms) |", stage));
+    }
+    report.push_str("\n|---|---|---|---|");
+    for _ in StageTimings::STAGES {
+        report.push_str("---|");
+    }
+    report.push('\n');
+    for result in &sweep.results {
+        report.push_str(&format!(
+            "| {} | {} | {:.1} | {:.2} |",
+            result.target, result.objects, result.mean_fps, result.p95_frame_ms,
+        ));
+        for (_, ms) in &result.stage_ms {
+            report.push_str(&format!(" {:.2} |", ms));
+        }
+        report.push('\n');
+    }
+    report
+}
+
+/// System to start or abort a sweep with Shift+T
+pub fn stress_sweep_control_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut sweep: ResMut<StressSweep>,
+    mut config: ResMut<StressTestConfig>,
+) {
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if !(shift && keyboard.just_pressed(KeyCode::KeyT)) {
+        return;
+    }
+    if sweep.is_running() {
+        sweep.step = None;
+        info!("Stress sweep aborted");
+    } else {
+        sweep.start(&mut config);
+    }
+}
+
+/// System to step the sweep through its targets, sample frame and stage timings and write the report
+pub fn stress_sweep_system(
+    time: Res<Time>,
+    timings: Res<StageTimings>,
+    export_config: Res<ExportConfig>,
+    broad_phase: Res<BroadPhase>,
+    mut sweep: ResMut<StressSweep>,
+    mut config: ResMut<StressTestConfig>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if !sweep.is_running() {
+        return;
+    }
+    let now = time.elapsed_secs();
+
+    match sweep.phase {
+        SweepPhase::Filling => {
+            if config.current_objects >= config.target_objects {
+                sweep.phase = SweepPhase::Settling { since: now };
+            }
+        }
+        SweepPhase::Settling { since } => {
+            if now - since >= sweep.settle_secs {
+                sweep.phase = SweepPhase::Measuring { since: now };
+            }
+        }
+        SweepPhase::Measuring { since } => {
+            sweep.frame_ms.push(time.delta_secs() * 1000.0);
+            for stage in StageTimings::STAGES {
+                if let Some(&ms) = timings.last_ms.get(stage) {
+                    sweep.stage_ms.entry(stage).or_default().push(ms);
+                }
+            }
+            if now - since < sweep.hold_secs {
+                return;
+            }
+
+            if let Some(result) = sweep.finish_step(&config) {
+                info!("Stress sweep: {} objects - {:.1} FPS (p95 {:.2} ms)",
+                      result.objects, result.mean_fps, result.p95_frame_ms);
+                sweep.results.push(result);
+            }
+            if !sweep.advance(&mut config) {
+                let report = format_sweep_report(&sweep, *broad_phase);
+                match write_export(&export_config.directory, "stress_sweep.md", &report) {
+                    Ok(path) => info!("Stress sweep complete - report written to {}", path.display()),
+                    Err(e) => warn!("Failed to write stress sweep report: {}", e),
+                }
+                if sweep.exit_when_done {
+                    exit.write(AppExit::Success);
+                }
+            }
+        }
+    }
+}
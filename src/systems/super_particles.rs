@@ -0,0 +1,138 @@
+// Super-particle aggregation for very large fragment populations
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use crate::components::*;
+use crate::systems::optimized_physics::{OptimizedPhysics, OptimizedPhysicsData};
+
+/// Resource to control when and how small fragments are aggregated
+#[derive(Resource)]
+pub struct SuperParticleConfig {
+    pub enabled: bool,
+    /// Fragment entity count above which aggregation starts
+    pub fragment_cap: usize,
+    /// Only fragments lighter than this (kg) are aggregated
+    pub max_fragment_mass: f64,
+    /// Maximum number of fragments represented by one super particle
+    pub max_weight: u32,
+    /// Size of the position cells used to group fragments (km)
+    pub position_cell: f64,
+    /// Size of the velocity cells used to group fragments (km/s)
+    pub velocity_cell: f64,
+    /// Upper bound on the spread of a super particle's fragment cloud (km)
+    pub max_spread: f64,
+}
+
+impl Default for SuperParticleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            fragment_cap: 20000,
+            max_fragment_mass: 10.0,
+            max_weight: 100,
+            position_cell: 50.0,
+            velocity_cell: 0.05,
+            max_spread: 25.0,
+        }
+    }
+}
+
+/// Accumulated members of one aggregation cell
+#[derive(Default)]
+struct AggregationCell {
+    members: Vec<Entity>,
+    weight: u32,
+    mass: f64,
+    weighted_position: DVec3,
+    momentum: DVec3,
+}
+
+/// System to merge small fragments into weighted super particles once the population exceeds the cap
+#[allow(clippy::type_complexity)]
+pub fn super_particle_aggregation_system(
+    mut commands: Commands,
+    config: Res<SuperParticleConfig>,
+    mut optimized_data: ResMut<OptimizedPhysicsData>,
+    mut debris_query: Query<(Entity, &mut OrbitalState, &Debris, Option<&SuperParticle>, Option<&OptimizedPhysics>)>,
+) {
+    if !config.enabled {
+        return;
+    }
+
+    let fragment_entities = debris_query.iter().count();
+    if fragment_entities <= config.fragment_cap {
+        return;
+    }
+
+    // Group small fragments sharing a position and velocity cell, i.e. a similar orbit
+    let mut cells: HashMap<(IVec3, IVec3), AggregationCell> = HashMap::new();
+    for (entity, orbital_state, _debris, super_particle, _) in debris_query.iter() {
+        let weight = super_particle.map(|sp| sp.weight).unwrap_or(1);
+        let fragment_mass = orbital_state.mass / weight as f64;
+        if fragment_mass > config.max_fragment_mass || weight >= config.max_weight {
+            continue;
+        }
+
+        let key = (
+            (orbital_state.position / config.position_cell).floor().as_ivec3(),
+            (orbital_state.velocity / config.velocity_cell).floor().as_ivec3(),
+        );
+        let cell = cells.entry(key).or_default();
+        if cell.weight + weight > config.max_weight {
+            continue;
+        }
+        cell.members.push(entity);
+        cell.weight += weight;
+        cell.mass += orbital_state.mass;
+        cell.weighted_position += orbital_state.position * orbital_state.mass;
+        cell.momentum += orbital_state.velocity * orbital_state.mass;
+    }
+
+    let mut merged_entities = 0;
+    let mut created = 0;
+
+    for cell in cells.into_values() {
+        if cell.members.len() < 2 || cell.mass <= 0.0 {
+            continue;
+        }
+
+        let centroid = cell.weighted_position / cell.mass;
+        let velocity = cell.momentum / cell.mass;
+
+        // Spread of the cloud is the RMS distance of the merged fragments from the centroid
+        let mut spread_sq = 0.0;
+        for &member in &cell.members {
+            if let Ok((_, state, _, existing, _)) = debris_query.get(member) {
+                let own_spread = existing.map(|sp| sp.spread).unwrap_or(0.0);
+                spread_sq += state.position.distance_squared(centroid) + own_spread * own_spread;
+            }
+        }
+        let spread = (spread_sq / cell.members.len() as f64).sqrt().min(config.max_spread);
+
+        // Reuse the first member as the representative and remove the rest
+        let representative = cell.members[0];
+        if let Ok((_, mut state, _, _, optimized)) = debris_query.get_mut(representative) {
+            state.position = centroid;
+            state.velocity = velocity;
+            state.mass = cell.mass;
+            if let Some(optimized) = optimized {
+                optimized_data.set_state(optimized.index, centroid, velocity);
+                optimized_data.set_mass(optimized.index, cell.mass);
+            }
+        }
+        commands.entity(representative).insert(SuperParticle::new(cell.weight, spread));
+
+        for &member in &cell.members[1..] {
+            commands.entity(member).despawn();
+        }
+
+        merged_entities += cell.members.len();
+        created += 1;
+    }
+
+    if created > 0 {
+        info!("Aggregated {} fragment entities into {} super particles ({} fragment entities before)",
+              merged_entities, created, fragment_entities);
+    }
+}
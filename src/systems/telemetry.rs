@@ -0,0 +1,163 @@
+// Telemetry CSV streaming for watched objects
+
+use bevy::prelude::*;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::export::{export_file_stem, ExportConfig};
+use crate::systems::selection::Selection;
+use crate::utils::*;
+
+/// Column header of telemetry files
+const TELEMETRY_HEADER: &str = "utc,sim_time_s,x_km,y_km,z_km,vx_km_s,vy_km_s,vz_km_s,altitude_km,latitude_deg,longitude_deg,\
+semi_major_axis_km,eccentricity,inclination_deg,raan_deg,arg_perigee_deg,mean_anomaly_deg";
+
+/// Resource controlling telemetry output
+#[derive(Resource)]
+pub struct TelemetryConfig {
+    /// Simulated seconds between rows
+    pub cadence: f64,
+    /// NORAD IDs to start watching once the catalog has loaded
+    pub pending: Vec<u32>,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            cadence: 60.0,
+            pending: Vec::new(),
+        }
+    }
+}
+
+/// Component streaming an object's state to a CSV file
+#[derive(Component)]
+pub struct Telemetry {
+    pub path: PathBuf,
+    writer: BufWriter<File>,
+    /// Simulation time of the next row (seconds)
+    next_sample: f64,
+}
+
+impl Telemetry {
+    /// Open (or append to) the telemetry file of an object
+    pub fn open(config: &ExportConfig, name: &str, norad_id: u32) -> Result<Self, String> {
+        let directory = config.directory.join("telemetry");
+        std::fs::create_dir_all(&directory).map_err(|e| e.to_string())?;
+        let path = directory.join(format!("{}.csv", export_file_stem(name, norad_id)));
+
+        let file = OpenOptions::new().create(true).append(true).open(&path).map_err(|e| e.to_string())?;
+        let is_new = file.metadata().map(|m| m.len() == 0).unwrap_or(true);
+        let mut writer = BufWriter::new(file);
+        if is_new {
+            writeln!(writer, "{}", TELEMETRY_HEADER).map_err(|e| e.to_string())?;
+        }
+
+        Ok(Self { path, writer, next_sample: f64::NEG_INFINITY })
+    }
+}
+
+/// Format one telemetry row
+fn telemetry_row(orbital_state: &OrbitalState, sim_time: &SimulationTime, constants: &Constants) -> String {
+    let unix = sim_time.current_unix();
+    let (position, velocity) = (orbital_state.position, orbital_state.velocity);
+    let (latitude, longitude, altitude) = eci_to_geodetic(position, unix, constants.earth_radius);
+    let elements = orbital_elements(position, velocity, constants.gravitational_parameter / 1e9)
+        .map(|el| format!(
+            "{:.3},{:.7},{:.4},{:.4},{:.4},{:.4}",
+            el.semi_major_axis, el.eccentricity,
+            el.inclination.to_degrees(), el.raan.to_degrees(),
+            el.argument_of_perigee.to_degrees(), el.mean_anomaly.to_degrees(),
+        ))
+        .unwrap_or_else(|| ",,,,,".to_string());
+
+    format!(
+        "{}Z,{:.3},{:.3},{:.3},{:.3},{:.6},{:.6},{:.6},{:.3},{:.4},{:.4},{}",
+        unix_to_iso8601(unix), sim_time.current,
+        position.x, position.y, position.z,
+        velocity.x, velocity.y, velocity.z,
+        altitude, latitude, longitude, elements,
+    )
+}
+
+/// Attach telemetry to an object, logging failures
+pub fn start_telemetry(commands: &mut Commands, config: &ExportConfig, entity: Entity, name: &str, norad_id: u32) {
+    match Telemetry::open(config, name, norad_id) {
+        Ok(telemetry) => {
+            info!("Telemetry for {} streaming to {}", name, telemetry.path.display());
+            commands.entity(entity).insert(telemetry);
+        }
+        Err(e) => warn!("Could not start telemetry for {}: {}", name, e),
+    }
+}
+
+/// System to toggle telemetry for the selected object with 'I'
+pub fn telemetry_control_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    selection: Res<Selection>,
+    export_config: Res<ExportConfig>,
+    objects: Query<(Option<&Satellite>, Has<Telemetry>)>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyI) {
+        return;
+    }
+    let Some((entity, (satellite, watched))) = selection.selected.and_then(|e| objects.get(e).ok().map(|o| (e, o))) else {
+        info!("Telemetry: no object selected");
+        return;
+    };
+
+    let (name, norad_id) = satellite
+        .map(|s| (s.name.trim().to_string(), s.norad_id))
+        .unwrap_or_else(|| ("DEBRIS".to_string(), entity.index()));
+    if watched {
+        commands.entity(entity).remove::<Telemetry>();
+        info!("Telemetry for {} stopped", name);
+    } else {
+        start_telemetry(&mut commands, &export_config, entity, &name, norad_id);
+    }
+}
+
+/// System to start telemetry for objects requested on the command line once the catalog has loaded
+pub fn telemetry_watch_system(
+    mut commands: Commands,
+    mut config: ResMut<TelemetryConfig>,
+    export_config: Res<ExportConfig>,
+    satellites: Query<(Entity, &Satellite)>,
+) {
+    if config.pending.is_empty() || satellites.is_empty() {
+        return;
+    }
+    for norad_id in std::mem::take(&mut config.pending) {
+        match satellites.iter().find(|(_, s)| s.norad_id == norad_id) {
+            Some((entity, satellite)) => {
+                start_telemetry(&mut commands, &export_config, entity, satellite.name.trim(), norad_id);
+            }
+            None => warn!("Telemetry: no object with NORAD ID {}", norad_id),
+        }
+    }
+}
+
+/// System to append a telemetry row for each watched object at the configured cadence
+pub fn telemetry_recording_system(
+    config: Res<TelemetryConfig>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    mut watched: Query<(&OrbitalState, &mut Telemetry)>,
+) {
+    for (orbital_state, mut telemetry) in watched.iter_mut() {
+        if sim_time.current < telemetry.next_sample {
+            continue;
+        }
+        telemetry.next_sample = sim_time.current + config.cadence;
+
+        let row = telemetry_row(orbital_state, &sim_time, &constants);
+        // Flush every row so files can be tailed and plotted during a run
+        let result = writeln!(telemetry.writer, "{}", row).and_then(|_| telemetry.writer.flush());
+        if let Err(e) = result {
+            warn!("Telemetry write to {} failed: {}", telemetry.path.display(), e);
+        }
+    }
+}
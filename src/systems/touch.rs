@@ -0,0 +1,79 @@
+// Trackpad gestures and touch-screen camera controls
+
+use bevy::prelude::*;
+use bevy::input::gestures::{DoubleTapGesture, PinchGesture, RotationGesture};
+use crate::systems::rendering::CameraRig;
+use crate::systems::selection::{FocusRequest, Selection};
+
+/// Zoom amount (wheel lines) per unit of trackpad pinch
+const PINCH_ZOOM_SCALE: f32 = 10.0;
+/// Zoom amount (wheel lines) per pixel of change in two-finger spread
+const TOUCH_ZOOM_SCALE: f32 = 0.02;
+/// Radians of camera rotation per pixel of one-finger drag
+const TOUCH_ROTATE_SENSITIVITY: f32 = 0.005;
+
+/// System mapping trackpad pinch/rotate gestures to the orbit camera; double-tap focuses the selection
+pub fn trackpad_gesture_system(
+    time: Res<Time>,
+    mut pinch_events: EventReader<PinchGesture>,
+    mut rotation_events: EventReader<RotationGesture>,
+    mut double_tap_events: EventReader<DoubleTapGesture>,
+    selection: Res<Selection>,
+    mut focus_requests: EventWriter<FocusRequest>,
+    mut rig: ResMut<CameraRig>,
+) {
+    let dt = time.delta_secs().max(1e-4);
+
+    for PinchGesture(delta) in pinch_events.read() {
+        rig.zoom(delta * PINCH_ZOOM_SCALE);
+    }
+
+    let twist: f32 = rotation_events.read().map(|RotationGesture(delta)| *delta).sum();
+    if twist != 0.0 {
+        rig.spin = Vec2::new(twist / dt, 0.0);
+    }
+
+    if double_tap_events.read().count() > 0 {
+        if let Some(entity) = selection.selected {
+            focus_requests.write(FocusRequest { entity });
+        }
+    }
+}
+
+/// System mapping touch input to the orbit camera
+///
+/// One finger orbits, two fingers pinch to zoom and twist to rotate.
+pub fn touch_camera_system(
+    time: Res<Time>,
+    touches: Res<Touches>,
+    mut rig: ResMut<CameraRig>,
+) {
+    let dt = time.delta_secs().max(1e-4);
+    let active: Vec<_> = touches.iter().collect();
+
+    match active.as_slice() {
+        [touch] => {
+            let delta = touch.delta();
+            if delta != Vec2::ZERO {
+                rig.spin = -delta * TOUCH_ROTATE_SENSITIVITY / dt;
+            }
+        }
+        [first, second, ..] => {
+            let previous = second.previous_position() - first.previous_position();
+            let current = second.position() - first.position();
+
+            // Spreading the fingers zooms in
+            let spread = current.length() - previous.length();
+            if spread != 0.0 {
+                rig.zoom(spread * TOUCH_ZOOM_SCALE);
+            }
+
+            // Twisting yaws the camera
+            let twist = previous.angle_to(current);
+            if twist.is_finite() && twist != 0.0 {
+                rig.spin = Vec2::new(twist / dt, 0.0);
+            }
+        }
+        [] => {}
+    }
+}
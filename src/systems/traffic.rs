@@ -0,0 +1,363 @@
+// Space traffic coordination rules engine
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::lookahead::LookAhead;
+use crate::systems::maneuvers::schedule_burn;
+
+/// Conjunction-response rules applied to a single operator's satellites
+#[derive(Clone, Debug)]
+pub struct OperatorRules {
+    /// Whether this operator performs avoidance maneuvers at all
+    pub maneuvers: bool,
+    /// Miss distance below which a close approach triggers a response (km)
+    pub screening_distance: f64,
+    /// Time between detection and the burn being executed (seconds)
+    pub coordination_delay: f64,
+    /// Along-track delta-v applied by an avoidance burn (km/s)
+    pub avoidance_delta_v: f64,
+}
+
+impl Default for OperatorRules {
+    fn default() -> Self {
+        Self {
+            maneuvers: true,
+            screening_distance: 5.0,
+            coordination_delay: 3600.0,
+            avoidance_delta_v: 0.0005, // 0.5 m/s
+        }
+    }
+}
+
+/// Resource holding the active space traffic coordination rules
+#[derive(Resource)]
+pub struct TrafficRules {
+    pub regime: StmRegime,
+    pub right_of_way: RightOfWay,
+    /// Rules used for operators without an explicit entry
+    pub default_rules: OperatorRules,
+    /// Per-operator overrides keyed by operator name
+    pub operator_rules: HashMap<String, OperatorRules>,
+    /// Per-constellation overrides keyed by constellation name, taking precedence over operator rules
+    pub constellation_rules: HashMap<String, OperatorRules>,
+    /// Closest approaches of the same pair less than this far apart count as one encounter (seconds)
+    pub response_cooldown: f64,
+}
+
+impl Default for TrafficRules {
+    fn default() -> Self {
+        Self::from_regime(StmRegime::default())
+    }
+}
+
+impl TrafficRules {
+    /// Build the rule set for a policy regime
+    pub fn from_regime(regime: StmRegime) -> Self {
+        let mut operator_rules = HashMap::new();
+
+        let (right_of_way, default_rules) = match regime {
+            StmRegime::Unregulated => (RightOfWay::NoManeuver, OperatorRules {
+                maneuvers: false,
+                ..default()
+            }),
+            StmRegime::SelfManaged => (RightOfWay::BothManeuver, OperatorRules {
+                coordination_delay: 2.0 * 3600.0,
+                ..default()
+            }),
+            StmRegime::Coordinated => {
+                // Large constellation operators run automated screening with tighter delays
+                operator_rules.insert("SpaceX".to_string(), OperatorRules {
+                    screening_distance: 10.0,
+                    coordination_delay: 600.0,
+                    ..default()
+                });
+                (RightOfWay::NewerYields, OperatorRules {
+                    coordination_delay: 1800.0,
+                    ..default()
+                })
+            }
+        };
+
+        Self {
+            regime,
+            right_of_way,
+            default_rules,
+            operator_rules,
+            constellation_rules: HashMap::new(),
+            response_cooldown: 3600.0,
+        }
+    }
+
+    /// Rules for a satellite's constellation or operator, falling back to the defaults
+    pub fn rules_for(&self, operator: Option<&Operator>, constellation: Option<&Constellation>) -> &OperatorRules {
+        constellation
+            .and_then(|c| self.constellation_rules.get(&c.name))
+            .or_else(|| operator.and_then(|op| self.operator_rules.get(&op.name)))
+            .unwrap_or(&self.default_rules)
+    }
+
+    /// One side of an encounter with the given miss distance (km) as these rules see it
+    pub fn party(&self, entity: Entity, mass: f64, object: PartyComponents, miss_distance: f64) -> Party<'_> {
+        let (satellite, health, operator, constellation) = object;
+        let rules = self.rules_for(operator, constellation);
+        // Debris and other uncontrolled objects neither screen nor maneuver
+        let screens = satellite.is_some() && miss_distance <= rules.screening_distance;
+        Party {
+            entity,
+            screens,
+            can_maneuver: screens
+                && rules.maneuvers
+                && satellite.is_some_and(|s| s.active)
+                && health.is_none_or(|h| h.has_propulsion()),
+            norad_id: satellite.map_or(0, |s| s.norad_id),
+            mass,
+            rules,
+        }
+    }
+}
+
+/// Components of an encounter's object that decide its part in the response
+pub type PartyComponents<'a> = (Option<&'a Satellite>, Option<&'a Health>, Option<&'a Operator>, Option<&'a Constellation>);
+
+/// One side of a screened close approach
+pub struct Party<'a> {
+    pub entity: Entity,
+    /// Whether the object's operator screens at this miss distance
+    pub screens: bool,
+    pub can_maneuver: bool,
+    pub norad_id: u32,
+    pub mass: f64,
+    pub rules: &'a OperatorRules,
+}
+
+/// Decide which parties respond to a close approach under the given right-of-way rule
+pub fn select_responders<'a>(right_of_way: RightOfWay, a: &'a Party<'a>, b: &'a Party<'a>) -> Vec<&'a Party<'a>> {
+    let yielding = match (a.can_maneuver, b.can_maneuver) {
+        (false, false) => return Vec::new(),
+        // When only one side can move it has to respond regardless of right of way
+        (true, false) => a,
+        (false, true) => b,
+        (true, true) => match right_of_way {
+            RightOfWay::NoManeuver => return Vec::new(),
+            RightOfWay::BothManeuver => return vec![a, b],
+            RightOfWay::NewerYields => if a.norad_id >= b.norad_id { a } else { b },
+            RightOfWay::LighterYields => if a.mass <= b.mass { a } else { b },
+        },
+    };
+
+    if right_of_way == RightOfWay::NoManeuver {
+        Vec::new()
+    } else {
+        vec![yielding]
+    }
+}
+
+/// Key of an encounter between two objects, whichever of them screened it
+pub fn encounter_pair(a: Entity, b: Entity) -> (Entity, Entity) {
+    if a.index() < b.index() { (a, b) } else { (b, a) }
+}
+
+/// A scheduled avoidance response waiting for its coordination delay to elapse
+#[derive(Clone, Debug)]
+pub struct PendingResponse {
+    pub maneuvering: Entity,
+    pub execute_at: f64,
+    /// Time of closest approach of the encounter responded to
+    pub tca: f64,
+    pub delta_v: f64,
+}
+
+/// Resource tracking coordination state and outcomes for the current regime
+///
+/// Scheduled responses and autonomous avoidance share it, so an encounter is responded to once and every
+/// avoidance burn is tallied in one place.
+#[derive(Resource, Default)]
+pub struct TrafficCoordination {
+    pub pending: Vec<PendingResponse>,
+    /// Closest approach time of the encounter each pair was last screened for
+    pub screened: HashMap<(Entity, Entity), f64>,
+    pub conjunctions_screened: u32,
+    pub maneuvers_executed: u32,
+    /// Responses whose coordination delay ran past the closest approach
+    pub responses_too_late: u32,
+    /// Total avoidance delta-v expended per operator (m/s)
+    pub delta_v_by_operator: HashMap<String, f64>,
+}
+
+impl TrafficCoordination {
+    /// Whether the pair's encounter at `tca` has already been screened
+    pub fn already_screened(&self, pair: (Entity, Entity), tca: f64, same_encounter: f64) -> bool {
+        self.screened.get(&pair).is_some_and(|&screened_tca| (screened_tca - tca).abs() < same_encounter)
+    }
+
+    /// Record an encounter as screened
+    pub fn screen(&mut self, pair: (Entity, Entity), tca: f64) {
+        self.screened.insert(pair, tca);
+        self.conjunctions_screened += 1;
+    }
+
+    /// Satellites with a response waiting, which are not asked to respond again
+    pub fn busy(&self) -> HashSet<Entity> {
+        self.pending.iter().map(|response| response.maneuvering).collect()
+    }
+
+    /// Tally an avoidance burn of `delta_v` (km/s) against its operator
+    pub fn record_maneuver(&mut self, operator: Option<&Operator>, delta_v: f64) {
+        let operator_name = operator.map(|op| op.name.clone()).unwrap_or_else(|| "Unknown".to_string());
+        *self.delta_v_by_operator.entry(operator_name).or_insert(0.0) += delta_v.abs() * 1000.0;
+        self.maneuvers_executed += 1;
+    }
+
+    /// Total avoidance delta-v expended (m/s)
+    pub fn total_delta_v(&self) -> f64 {
+        self.delta_v_by_operator.values().sum()
+    }
+}
+
+/// System to switch between STM policy regimes at runtime
+pub fn traffic_rules_control_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut rules: ResMut<TrafficRules>,
+    mut coordination: ResMut<TrafficCoordination>,
+) {
+    // Cycle policy regimes with 'R' key
+    if keyboard.just_pressed(KeyCode::KeyR) {
+        // Constellation policies are set explicitly and survive regime changes
+        let constellation_rules = std::mem::take(&mut rules.constellation_rules);
+        *rules = TrafficRules::from_regime(rules.regime.next());
+        rules.constellation_rules = constellation_rules;
+        *coordination = TrafficCoordination::default();
+        info!("STM regime: {:?} (right of way: {:?}, default screening {:.1}km, delay {:.0}s)",
+              rules.regime, rules.right_of_way,
+              rules.default_rules.screening_distance, rules.default_rules.coordination_delay);
+    }
+}
+
+/// System to screen the look-ahead's predicted closest approaches and schedule responses according to the rules
+///
+/// A response is scheduled a coordination delay after the encounter is first predicted; when that is not
+/// before the time of closest approach the operators coordinated too late and nobody maneuvers.
+#[allow(clippy::type_complexity)]
+pub fn traffic_screening_system(
+    rules: Res<TrafficRules>,
+    mut coordination: ResMut<TrafficCoordination>,
+    mut lookahead: ResMut<LookAhead>,
+    sim_time: Res<SimulationTime>,
+    objects: Query<(&OrbitalState, Option<&Satellite>, Option<&Health>, Option<&Operator>, Option<&Constellation>)>,
+) {
+    if rules.right_of_way == RightOfWay::NoManeuver {
+        return;
+    }
+    lookahead.requested = true;
+    if sim_time.paused {
+        return;
+    }
+
+    let now = sim_time.current;
+    let same_encounter = rules.response_cooldown;
+    coordination.screened.retain(|_, tca| *tca > now - same_encounter);
+    let mut busy = coordination.busy();
+
+    for approach in lookahead.upcoming(now) {
+        let pair = encounter_pair(approach.primary, approach.secondary);
+        if coordination.already_screened(pair, approach.tca, same_encounter) {
+            continue;
+        }
+        let (Ok((state, satellite, health, operator, constellation)), Ok((other_state, other_satellite, other_health, other_operator, other_constellation))) =
+            (objects.get(approach.primary), objects.get(approach.secondary)) else {
+            continue;
+        };
+        let mut parties = [
+            rules.party(approach.primary, state.mass, (satellite, health, operator, constellation), approach.miss_distance),
+            rules.party(approach.secondary, other_state.mass, (other_satellite, other_health, other_operator, other_constellation), approach.miss_distance),
+        ];
+        if !parties.iter().any(|party| party.screens) {
+            continue;
+        }
+        coordination.screen(pair, approach.tca);
+        for party in parties.iter_mut() {
+            party.can_maneuver &= !busy.contains(&party.entity);
+        }
+
+        for responder in select_responders(rules.right_of_way, &parties[0], &parties[1]) {
+            let execute_at = now + responder.rules.coordination_delay;
+            if execute_at >= approach.tca {
+                coordination.responses_too_late += 1;
+                debug!("{:?} cannot respond to the {:.2}km approach in {:.0}s after a {:.0}s coordination delay",
+                       responder.entity, approach.miss_distance, approach.tca - now, responder.rules.coordination_delay);
+                continue;
+            }
+            debug!("Scheduling avoidance for {:?} ({:.2}km approach in {:.0}s, delay {:.0}s)",
+                   responder.entity, approach.miss_distance, approach.tca - now, responder.rules.coordination_delay);
+            coordination.pending.push(PendingResponse {
+                maneuvering: responder.entity,
+                execute_at,
+                tca: approach.tca,
+                delta_v: responder.rules.avoidance_delta_v,
+            });
+            busy.insert(responder.entity);
+        }
+    }
+}
+
+/// System to hand scheduled avoidance responses to the maneuver subsystem once their coordination delay has elapsed
+#[allow(clippy::type_complexity)]
+pub fn traffic_response_system(
+    mut commands: Commands,
+    mut coordination: ResMut<TrafficCoordination>,
+    sim_time: Res<SimulationTime>,
+    mut satellites: Query<(&Satellite, Option<&Health>, Option<&Operator>, Option<&mut ManeuverPlan>)>,
+) {
+    if coordination.pending.is_empty() {
+        return;
+    }
+
+    let (due, waiting): (Vec<_>, Vec<_>) = coordination.pending
+        .drain(..)
+        .partition(|response| response.execute_at <= sim_time.current);
+    coordination.pending = waiting;
+
+    for response in due {
+        let Ok((satellite, health, operator, plan)) = satellites.get_mut(response.maneuvering) else {
+            continue;
+        };
+        if !satellite.active || health.is_some_and(|h| !h.has_propulsion()) {
+            continue;
+        }
+        // The clock can jump past the encounter, e.g. when a replay or scenario resets it
+        if sim_time.current >= response.tca {
+            coordination.responses_too_late += 1;
+            debug!("{} avoidance skipped: encounter passed during coordination", satellite.name);
+            continue;
+        }
+
+        // Along-track burn raises the orbit slightly and shifts the arrival time at the conjunction
+        let burn = PlannedBurn { execute_at: sim_time.current, delta_v: DVec3::new(0.0, response.delta_v, 0.0) };
+        schedule_burn(&mut commands, response.maneuvering, plan, burn);
+        coordination.record_maneuver(operator, response.delta_v);
+
+        info!("{} scheduled {:.2} m/s avoidance maneuver, {:.0}s before closest approach",
+              satellite.name, response.delta_v * 1000.0, response.tca - sim_time.current);
+    }
+}
+
+/// Debug system to summarize coordination outcomes for the active regime
+pub fn debug_traffic_system(
+    rules: Res<TrafficRules>,
+    coordination: Res<TrafficCoordination>,
+    sim_time: Res<SimulationTime>,
+    mut last_print: Local<f64>,
+) {
+    if sim_time.current - *last_print > 3600.0 {
+        *last_print = sim_time.current;
+
+        if coordination.conjunctions_screened > 0 {
+            info!("STM {:?}: {} conjunctions screened, {} maneuvers ({:.1} m/s total), {} too late, {} pending",
+                  rules.regime, coordination.conjunctions_screened, coordination.maneuvers_executed,
+                  coordination.total_delta_v(), coordination.responses_too_late, coordination.pending.len());
+        }
+    }
+}
@@ -0,0 +1,233 @@
+// On-screen HUD and control buttons
+
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::conjunctions::ApproachReport;
+use crate::systems::physics::adjust_timestep;
+use crate::systems::selection::Selection;
+use crate::utils::{closest_approach, orbital_period};
+
+/// Longest look-ahead when searching for the next closest approach (seconds)
+const MEASUREMENT_HORIZON: f64 = 86400.0;
+/// Real seconds between closest-approach recomputations
+const MEASUREMENT_REFRESH_SECS: f32 = 0.25;
+
+/// Marker component for the status text in the top-left corner
+#[derive(Component)]
+pub struct HudStatusText;
+
+/// Marker component for the distance measurement text in the top-right corner
+#[derive(Component)]
+pub struct HudMeasurementText;
+
+/// Actions triggered by HUD buttons
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HudButton {
+    StepFrame,
+    TimestepDown,
+    TimestepUp,
+}
+
+impl HudButton {
+    /// Buttons in the order they appear on the button bar
+    pub const ALL: [HudButton; 3] = [HudButton::StepFrame, HudButton::TimestepDown, HudButton::TimestepUp];
+
+    fn label(&self) -> &'static str {
+        match self {
+            HudButton::StepFrame => "Step >|",
+            HudButton::TimestepDown => "dt /2",
+            HudButton::TimestepUp => "dt x2",
+        }
+    }
+}
+
+const BUTTON_COLOR: Color = Color::srgba(0.15, 0.15, 0.2, 0.8);
+const BUTTON_HOVER_COLOR: Color = Color::srgba(0.25, 0.25, 0.35, 0.9);
+const BUTTON_PRESSED_COLOR: Color = Color::srgba(0.35, 0.45, 0.6, 1.0);
+
+/// System to create the HUD overlay
+pub fn setup_hud_system(mut commands: Commands) {
+    // Status text
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.85, 0.9, 1.0)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        HudStatusText,
+    ));
+
+    // Distance measurement readout
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.9, 0.3)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..default()
+        },
+        HudMeasurementText,
+    ));
+
+    // Button bar
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            column_gap: Val::Px(6.0),
+            ..default()
+        })
+        .with_children(|bar| {
+            for button in HudButton::ALL {
+                bar.spawn((
+                    Button,
+                    Node {
+                        padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                        ..default()
+                    },
+                    BackgroundColor(BUTTON_COLOR),
+                    button,
+                ))
+                .with_child((
+                    Text::new(button.label()),
+                    TextFont {
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+            }
+        });
+}
+
+/// System to refresh the HUD status text
+pub fn hud_status_system(
+    sim_time: Res<SimulationTime>,
+    governor: Res<TimestepGovernor>,
+    integrator: Res<IntegratorConfig>,
+    backend: Res<PhysicsBackend>,
+    time: Res<Time>,
+    mut text_query: Query<&mut Text, With<HudStatusText>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+
+    let state = if sim_time.paused { "PAUSED" } else { "RUNNING" };
+    let propagator = match governor.plan(&sim_time, time.delta_secs()) {
+        StepPlan::Integrate { count, dt } if count > 1 => format!("{} {}x{:.1}s", integrator.method.name(), count, dt),
+        StepPlan::Integrate { .. } => integrator.method.name().to_string(),
+        StepPlan::Kepler { .. } => "Kepler".to_string(),
+    };
+    text.0 = format!(
+        "{} (JD {:.5})  |  T+{:.0}s  |  {:.0}x  |  dt {:.1}s  |  {} {}  |  {}",
+        sim_time.current_iso8601(), sim_time.current_julian_date(), sim_time.current, sim_time.speed_multiplier, sim_time.timestep, backend.name(), propagator,
+        state
+    );
+    // Euler stability only matters while it is the active integrator and nothing limits its steps
+    if let Some(warning) = sim_time.stability_warning().filter(|_| !sim_time.is_coarse() && !governor.enabled) {
+        text.0.push_str(&format!("\nWARNING: {}", warning));
+    }
+}
+
+/// System to show separation, relative velocity and next closest approach of the two selected objects
+#[allow(clippy::too_many_arguments)]
+pub fn hud_measurement_system(
+    time: Res<Time>,
+    sim_time: Res<SimulationTime>,
+    selection: Res<Selection>,
+    constants: Res<Constants>,
+    approach: Res<ApproachReport>,
+    objects: Query<(&OrbitalState, Option<&Satellite>)>,
+    mut text_query: Query<&mut Text, With<HudMeasurementText>>,
+    mut since_refresh: Local<f32>,
+    // Simulation time of the closest approach and its miss distance
+    mut cached_tca: Local<Option<(f64, f64)>>,
+) {
+    let Ok(mut text) = text_query.single_mut() else {
+        return;
+    };
+
+    let (Some(primary), Some(secondary)) = (selection.selected, selection.secondary) else {
+        if !text.0.is_empty() {
+            text.0.clear();
+        }
+        *cached_tca = None;
+        return;
+    };
+    let (Ok((a, a_satellite)), Ok((b, b_satellite))) = (objects.get(primary), objects.get(secondary)) else {
+        text.0.clear();
+        return;
+    };
+
+    let mu_km = constants.gravitational_parameter / 1e9;
+    *since_refresh += time.delta_secs();
+    if cached_tca.is_none() || selection.is_changed() || *since_refresh >= MEASUREMENT_REFRESH_SECS {
+        *since_refresh = 0.0;
+        let horizon = orbital_period(a.position, a.velocity, mu_km)
+            .into_iter()
+            .chain(orbital_period(b.position, b.velocity, mu_km))
+            .fold(0.0, f64::max)
+            .clamp(600.0, MEASUREMENT_HORIZON);
+        let (tca, miss) = closest_approach((a.position, a.velocity), (b.position, b.velocity), horizon, mu_km);
+        *cached_tca = Some((sim_time.current + tca, miss));
+    }
+
+    let name = |satellite: Option<&Satellite>| satellite.map(|s| s.name.trim().to_string()).unwrap_or_else(|| "Debris".to_string());
+    let separation = a.position.distance(b.position);
+    let relative_speed = (a.velocity - b.velocity).length();
+    let mut readout = format!(
+        "{} -> {}\nSeparation {:.1} km  |  Rel. velocity {:.3} km/s",
+        name(a_satellite), name(b_satellite), separation, relative_speed
+    );
+    if let Some((tca, miss)) = *cached_tca {
+        let time_to_go = (tca - sim_time.current).max(0.0);
+        readout.push_str(&format!("\nNext closest approach in {:.0}s at {:.1} km", time_to_go, miss));
+    }
+    if let (true, Some(query)) = (approach.is_for(primary, secondary), approach.query) {
+        match approach.found {
+            Some(found) if found.tca >= sim_time.current => readout.push_str(&format!(
+                "\nUnder {:.1} km in {:.0}s: miss {:.3} km at {:.3} km/s",
+                query.distance, found.tca - sim_time.current, found.miss_distance, found.relative_speed
+            )),
+            Some(_) => {}
+            None => readout.push_str(&format!("\nNo approach under {:.1} km within {:.1} h", query.distance, query.horizon / 3600.0)),
+        }
+    }
+    text.0 = readout;
+}
+
+/// System to handle HUD button presses
+pub fn hud_button_system(
+    mut buttons: Query<(&Interaction, &HudButton, &mut BackgroundColor), Changed<Interaction>>,
+    mut sim_time: ResMut<SimulationTime>,
+) {
+    for (interaction, button, mut background) in buttons.iter_mut() {
+        match interaction {
+            Interaction::Pressed => {
+                background.0 = BUTTON_PRESSED_COLOR;
+                match button {
+                    HudButton::StepFrame => sim_time.request_step(),
+                    HudButton::TimestepDown => adjust_timestep(&mut sim_time, 0.5),
+                    HudButton::TimestepUp => adjust_timestep(&mut sim_time, 2.0),
+                }
+            }
+            Interaction::Hovered => background.0 = BUTTON_HOVER_COLOR,
+            Interaction::None => background.0 = BUTTON_COLOR,
+        }
+    }
+}
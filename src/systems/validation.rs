@@ -0,0 +1,181 @@
+// Drag-decay validation of reference objects against King-Hele lifetime estimates
+
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::utils::*;
+
+/// Number of reference objects followed at a time
+pub const DECAY_REFERENCE_COUNT: usize = 5;
+/// Simulated seconds over which each decay measurement is taken
+pub const DECAY_VALIDATION_WINDOW: f64 = 86400.0;
+/// Altitude band (km) reference objects are drawn from, where drag dominates their lifetime
+const REFERENCE_ALTITUDES: (f64, f64) = (200.0, 700.0);
+/// Largest eccentricity for the circular-orbit formulas to apply
+const REFERENCE_MAX_ECCENTRICITY: f64 = 0.01;
+/// Simulated decay within this factor of the analytic rate passes
+const DECAY_TOLERANCE: f64 = 2.0;
+/// Change in semi-major axis over one window (km) beyond anything drag could cause
+const MAX_WINDOW_CHANGE_KM: f64 = 50.0;
+/// Lifetimes beyond this many years are reported as such
+const MAX_REPORTED_YEARS: f64 = 1000.0;
+
+/// A reference object and the start of its current measurement window
+#[derive(Clone, Debug)]
+pub struct DecayReference {
+    pub entity: Entity,
+    pub name: String,
+    /// Simulated time and semi-major axis (km) when the object became a reference
+    pub followed_since: f64,
+    pub initial_radius: f64,
+    pub start_time: f64,
+    /// Semi-major axis at the start of the window (km)
+    pub start_radius: f64,
+    /// Drag coefficient × area / mass (m²/kg)
+    pub area_to_mass: f64,
+}
+
+/// Resource driving the decay validation
+#[derive(Resource, Default)]
+pub struct DecayValidation {
+    pub enabled: bool,
+    pub references: Vec<DecayReference>,
+    /// Measurements outside the tolerance so far
+    pub failures: u32,
+}
+
+/// Duration in the largest sensible unit
+fn format_lifetime(seconds: f64) -> String {
+    let years = seconds / (365.25 * 86400.0);
+    if !seconds.is_finite() {
+        "never".to_string()
+    } else if years > MAX_REPORTED_YEARS {
+        format!("> {:.0} y", MAX_REPORTED_YEARS)
+    } else if seconds >= 365.25 * 86400.0 {
+        format!("{:.1} y", years)
+    } else {
+        format!("{:.1} d", seconds / 86400.0)
+    }
+}
+
+/// System to compare the simulated decay of reference objects with King-Hele estimates once per window
+pub fn decay_validation_system(
+    mut validation: ResMut<DecayValidation>,
+    sim_time: Res<SimulationTime>,
+    constants: Res<Constants>,
+    space_weather: Res<SpaceWeather>,
+    objects: Query<(Entity, &OrbitalState, &PhysicsObject, &Satellite)>,
+) {
+    if !validation.enabled {
+        return;
+    }
+    let mu_km = constants.gravitational_parameter / 1e9;
+    let reentry_radius = constants.earth_radius + constants.reentry_altitude;
+    let density = |altitude: f64| space_weather.density(altitude);
+    let semi_major_axis = |state: &OrbitalState| {
+        orbital_elements(state.position, state.velocity, mu_km)
+            .filter(|elements| elements.eccentricity <= REFERENCE_MAX_ECCENTRICITY)
+            .map(|elements| elements.semi_major_axis)
+    };
+
+    let mut failures = 0;
+    validation.references.retain(|reference| {
+        let alive = objects.contains(reference.entity);
+        if !alive {
+            let analytic_lifetime = circular_lifetime(reference.initial_radius, reentry_radius, constants.earth_radius,
+                                                      reference.area_to_mass, mu_km, &density);
+            let simulated_lifetime = sim_time.current - reference.followed_since;
+            let message = format!("Decay check: {} reentered {} after it was followed from {:.0} km, King-Hele lifetime {}",
+                                  reference.name, format_lifetime(simulated_lifetime),
+                                  reference.initial_radius - constants.earth_radius, format_lifetime(analytic_lifetime));
+            if simulated_lifetime * DECAY_TOLERANCE >= analytic_lifetime {
+                info!("{}", message);
+            } else {
+                warn!("{} - outside {}x tolerance, check the drag model and timestep before long runs", message, DECAY_TOLERANCE);
+                failures += 1;
+            }
+        }
+        alive
+    });
+
+    // Spread the references across the drag-dominated band, lowest to highest
+    if validation.references.is_empty() {
+        let mut candidates: Vec<_> = objects.iter()
+            .filter_map(|(entity, state, physics, satellite)| {
+                let radius = semi_major_axis(state)?;
+                let altitude = radius - constants.earth_radius;
+                (altitude >= REFERENCE_ALTITUDES.0 && altitude <= REFERENCE_ALTITUDES.1 && physics.area_to_mass > 0.0)
+                    .then(|| (radius, entity, physics.ballistic_coefficient(), satellite))
+            })
+            .collect();
+        if candidates.is_empty() {
+            validation.failures += failures;
+            return;
+        }
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let count = DECAY_REFERENCE_COUNT.min(candidates.len());
+        for i in 0..count {
+            let (radius, entity, area_to_mass, satellite) = candidates[i * (candidates.len() - 1) / (count - 1).max(1)];
+            validation.references.push(DecayReference {
+                entity,
+                name: satellite.name.trim().to_string(),
+                followed_since: sim_time.current,
+                initial_radius: radius,
+                start_time: sim_time.current,
+                start_radius: radius,
+                area_to_mass,
+            });
+        }
+        info!("Decay check: following {} reference objects between {:.0} and {:.0} km",
+              count, REFERENCE_ALTITUDES.0, REFERENCE_ALTITUDES.1);
+    }
+
+    let mut disturbed = Vec::new();
+    for reference in validation.references.iter_mut() {
+        let Ok((_, state, ..)) = objects.get(reference.entity) else {
+            continue;
+        };
+        let elapsed = sim_time.current - reference.start_time;
+        // A rewind or a burn that leaves the orbit eccentric restarts the window
+        let Some(radius) = semi_major_axis(state).filter(|_| elapsed >= 0.0) else {
+            reference.start_time = sim_time.current;
+            reference.start_radius = semi_major_axis(state).unwrap_or(reference.start_radius);
+            continue;
+        };
+        // Collisions and maneuvers are not drag; such references are replaced
+        if (radius - reference.start_radius).abs() > MAX_WINDOW_CHANGE_KM {
+            info!("Decay check: {} orbit changed by {:.0} km in {}, dropping it as a reference",
+                  reference.name, radius - reference.start_radius, format_lifetime(elapsed));
+            disturbed.push(reference.entity);
+            continue;
+        }
+        if elapsed < DECAY_VALIDATION_WINDOW {
+            continue;
+        }
+
+        let simulated_rate = (radius - reference.start_radius) / elapsed;
+        let analytic_rate = circular_decay_rate((radius + reference.start_radius) / 2.0, constants.earth_radius,
+                                                reference.area_to_mass, mu_km, &density);
+        let simulated_lifetime = if simulated_rate < 0.0 { (radius - reentry_radius) / -simulated_rate } else { f64::INFINITY };
+        let analytic_lifetime = circular_lifetime(radius, reentry_radius, constants.earth_radius, reference.area_to_mass, mu_km,
+                                                &density);
+        let ratio = simulated_rate / analytic_rate;
+
+        let message = format!(
+            "Decay check {} at {:.0} km: simulated {:+.1} m/day vs King-Hele {:+.1} m/day (ratio {:.2}), lifetime {} vs {}",
+            reference.name, radius - constants.earth_radius, simulated_rate * 1000.0 * 86400.0,
+            analytic_rate * 1000.0 * 86400.0, ratio, format_lifetime(simulated_lifetime), format_lifetime(analytic_lifetime)
+        );
+        if ratio.is_finite() && (1.0 / DECAY_TOLERANCE..=DECAY_TOLERANCE).contains(&ratio) {
+            info!("{}", message);
+        } else {
+            warn!("{} - outside {}x tolerance, check the drag model and timestep before long runs", message, DECAY_TOLERANCE);
+            failures += 1;
+        }
+
+        reference.start_time = sim_time.current;
+        reference.start_radius = radius;
+    }
+    validation.failures += failures;
+    validation.references.retain(|reference| !disturbed.contains(&reference.entity));
+}
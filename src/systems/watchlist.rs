@@ -0,0 +1,137 @@
+// Watched objects and the watchlist persisted in the settings file
+
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::export::ExportConfig;
+use crate::systems::rendering::{ObjectLabel, Trail};
+use crate::systems::search::SearchCommand;
+use crate::systems::selection::{spawn_object_label, Selection};
+use crate::systems::telemetry::{start_telemetry, Telemetry};
+
+/// Marker component for objects on the watchlist
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Watched;
+
+/// Command editing or listing the watchlist
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchCommand {
+    /// Watch the selected object, or stop watching it
+    Toggle,
+    List,
+}
+
+impl WatchCommand {
+    /// Parse `watch` or `watch list`; returns `None` when the text is not a watch command
+    pub fn parse(text: &str) -> Option<Result<Self, String>> {
+        let mut words = text.split_whitespace();
+        if !words.next()?.eq_ignore_ascii_case("watch") {
+            return None;
+        }
+        match words.next() {
+            None => Some(Ok(WatchCommand::Toggle)),
+            Some(word) if word.eq_ignore_ascii_case("list") => Some(Ok(WatchCommand::List)),
+            Some(word) => Some(Err(format!("unknown watch command {}", word))),
+        }
+    }
+}
+
+/// System to watch cataloged objects on the persisted watchlist as they appear
+pub fn watchlist_tagging_system(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    satellites: Query<(Entity, &Satellite), Added<Satellite>>,
+) {
+    for (entity, satellite) in satellites.iter() {
+        if settings.watchlist.contains(&satellite.norad_id) {
+            commands.entity(entity).try_insert(Watched);
+        }
+    }
+}
+
+/// System to toggle the watch on the selected object and save the watchlist
+pub fn watch_command_system(
+    mut commands: Commands,
+    mut events: EventReader<SearchCommand>,
+    selection: Res<Selection>,
+    mut settings: ResMut<Settings>,
+    objects: Query<(Option<&Satellite>, Has<Watched>)>,
+    watched: Query<&Satellite, With<Watched>>,
+) {
+    for command in events.read() {
+        let SearchCommand::Watch(command) = command else {
+            continue;
+        };
+        match command {
+            WatchCommand::List => {
+                let mut names: Vec<_> = watched.iter()
+                    .map(|s| format!("{} ({})", s.name.trim(), s.norad_id))
+                    .collect();
+                names.sort();
+                info!("Watching {} objects: {}", names.len(), names.join(", "));
+            }
+            WatchCommand::Toggle => {
+                let Some((entity, (satellite, is_watched))) = selection.selected
+                    .and_then(|e| objects.get(e).ok().map(|o| (e, o))) else {
+                    info!("Watch: no object selected");
+                    continue;
+                };
+                // The watchlist is keyed by NORAD ID, so uncataloged fragments cannot be watched
+                let Some(satellite) = satellite else {
+                    info!("Watch: only cataloged objects can be watched");
+                    continue;
+                };
+                if is_watched {
+                    commands.entity(entity).remove::<Watched>();
+                    settings.watchlist.remove(&satellite.norad_id);
+                    info!("Stopped watching {} (NORAD {})", satellite.name.trim(), satellite.norad_id);
+                } else {
+                    commands.entity(entity).insert(Watched);
+                    settings.watchlist.insert(satellite.norad_id);
+                    info!("Watching {} (NORAD {})", satellite.name.trim(), satellite.norad_id);
+                }
+                settings.save();
+            }
+        }
+    }
+}
+
+/// System to give watched objects a trail, label and telemetry stream, and take them away when unwatched
+#[allow(clippy::type_complexity)]
+pub fn watch_decoration_system(
+    mut commands: Commands,
+    export_config: Res<ExportConfig>,
+    selection: Res<Selection>,
+    added: Query<(Entity, &Satellite, Has<Trail>, Has<Telemetry>), Added<Watched>>,
+    mut removed: RemovedComponents<Watched>,
+    labels: Query<(Entity, &ObjectLabel)>,
+) {
+    for (entity, satellite, has_trail, has_telemetry) in added.iter() {
+        if !has_trail {
+            commands.entity(entity).insert(Trail::default());
+        }
+        if !labels.iter().any(|(_, label)| label.target == entity) {
+            spawn_object_label(&mut commands, entity, satellite.name.clone());
+        }
+        if !has_telemetry {
+            start_telemetry(&mut commands, &export_config, entity, satellite.name.trim(), satellite.norad_id);
+        }
+    }
+
+    for entity in removed.read() {
+        let Ok(mut entity_commands) = commands.get_entity(entity) else {
+            continue;
+        };
+        entity_commands.remove::<Telemetry>();
+        // The selection keeps its own trail and label
+        if selection.selected == Some(entity) {
+            continue;
+        }
+        entity_commands.remove::<Trail>();
+        for (label_entity, label) in labels.iter() {
+            if label.target == entity {
+                commands.entity(label_entity).despawn();
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+// Exponential atmosphere and thermosphere, drag and analytic orbit-lifetime estimates
+
+use bevy::math::DVec3;
+
+/// Base altitude (km), density (kg/m³) and scale height (km) of each layer (Vallado's exponential model)
+const ATMOSPHERE_LAYERS: [(f64, f64, f64); 28] = [
+    (0.0, 1.225, 7.249),
+    (25.0, 3.899e-2, 6.349),
+    (30.0, 1.774e-2, 6.682),
+    (40.0, 3.972e-3, 7.554),
+    (50.0, 1.057e-3, 8.382),
+    (60.0, 3.206e-4, 7.714),
+    (70.0, 8.770e-5, 6.549),
+    (80.0, 1.905e-5, 5.799),
+    (90.0, 3.396e-6, 5.382),
+    (100.0, 5.297e-7, 5.877),
+    (110.0, 9.661e-8, 7.263),
+    (120.0, 2.438e-8, 9.473),
+    (130.0, 8.484e-9, 12.636),
+    (140.0, 3.845e-9, 16.149),
+    (150.0, 2.070e-9, 22.523),
+    (180.0, 5.464e-10, 29.740),
+    (200.0, 2.789e-10, 37.105),
+    (250.0, 7.248e-11, 45.546),
+    (300.0, 2.418e-11, 53.628),
+    (350.0, 9.518e-12, 53.298),
+    (400.0, 3.725e-12, 58.515),
+    (450.0, 1.585e-12, 60.828),
+    (500.0, 6.967e-13, 63.822),
+    (600.0, 1.454e-13, 71.835),
+    (700.0, 3.614e-14, 88.667),
+    (800.0, 1.170e-14, 124.64),
+    (900.0, 5.245e-15, 181.05),
+    (1000.0, 3.019e-15, 268.00),
+];
+
+/// Base of the thermosphere model (km); the exponential layers apply below it
+pub const THERMOSPHERE_BASE_KM: f64 = 120.0;
+/// Temperature (K) and temperature gradient (K/km) at the thermosphere base
+const BASE_TEMPERATURE: f64 = 380.0;
+const BASE_TEMPERATURE_GRADIENT: f64 = 12.0;
+/// Earth radius of the geopotential-height conversion (km)
+const GEOPOTENTIAL_EARTH_RADIUS: f64 = 6356.766;
+/// Number density at the thermosphere base (m⁻³), molecular mass (amu) and thermal diffusion factor of
+/// N₂, O, O₂, Ar and He (US Standard Atmosphere 1976)
+const THERMOSPHERE_SPECIES: [(f64, f64, f64); 5] = [
+    (3.726e17, 28.0134, 0.0),
+    (9.275e16, 15.9994, 0.0),
+    (4.818e16, 31.9988, 0.0),
+    (1.375e15, 39.948, 0.0),
+    (3.067e13, 4.0026, -0.40),
+];
+/// Atomic mass unit (kg) and Boltzmann constant (J/K)
+const ATOMIC_MASS: f64 = 1.660_539e-27;
+const BOLTZMANN: f64 = 1.380_649e-23;
+
+/// Altitude above which drag is neglected (km)
+pub const DRAG_CEILING_KM: f64 = 1000.0;
+/// Altitude step of the lifetime integration (km)
+const LIFETIME_STEP_KM: f64 = 1.0;
+
+/// Atmospheric density (kg/m³) at an altitude (km)
+pub fn atmospheric_density(altitude_km: f64) -> f64 {
+    let altitude = altitude_km.max(0.0);
+    let (base, density, scale_height) = ATMOSPHERE_LAYERS
+        .iter()
+        .rev()
+        .find(|layer| layer.0 <= altitude)
+        .copied()
+        .unwrap_or(ATMOSPHERE_LAYERS[0]);
+    density * (-(altitude - base) / scale_height).exp()
+}
+
+/// Thermospheric density (kg/m³) at an altitude (km) for an exospheric temperature (K)
+///
+/// Each species is in diffusive equilibrium above 120 km along a Bates temperature profile, the form behind
+/// the Jacchia and MSIS models.
+pub fn thermosphere_density(altitude_km: f64, exospheric_temperature: f64) -> f64 {
+    if altitude_km < THERMOSPHERE_BASE_KM {
+        return atmospheric_density(altitude_km);
+    }
+    let t_inf = exospheric_temperature.max(BASE_TEMPERATURE + 1.0);
+    let base_radius = GEOPOTENTIAL_EARTH_RADIUS + THERMOSPHERE_BASE_KM;
+    // Geopotential height above the base and inverse temperature scale (1/km)
+    let zeta = (altitude_km - THERMOSPHERE_BASE_KM) * base_radius / (GEOPOTENTIAL_EARTH_RADIUS + altitude_km);
+    let sigma = BASE_TEMPERATURE_GRADIENT / (t_inf - BASE_TEMPERATURE);
+    let temperature = t_inf - (t_inf - BASE_TEMPERATURE) * (-sigma * zeta).exp();
+    let gravity = 9.80665 * (GEOPOTENTIAL_EARTH_RADIUS / base_radius).powi(2);
+
+    THERMOSPHERE_SPECIES.iter()
+        .map(|&(base_density, mass, alpha)| {
+            let mass = mass * ATOMIC_MASS;
+            let gamma = mass * gravity / (sigma / 1000.0 * BOLTZMANN * t_inf);
+            let number_density = base_density * (BASE_TEMPERATURE / temperature).powf(1.0 + alpha + gamma)
+                * (-sigma * gamma * zeta).exp();
+            number_density * mass
+        })
+        .sum()
+}
+
+/// Drag acceleration (km/s²) at an ECI position (km) and velocity (km/s) in an atmosphere of density `density`
+/// (kg/m³) co-rotating with the Earth
+///
+/// `ballistic_coefficient` is drag coefficient × area / mass (m²/kg).
+pub fn drag_acceleration(position: DVec3, velocity: DVec3, ballistic_coefficient: f64, density: f64, rotation_rate: f64) -> DVec3 {
+    let relative_velocity = velocity - DVec3::new(0.0, 0.0, rotation_rate).cross(position);
+    // a = -½ ρ B |v| v in SI units, with v in km/s: × 1e6 for v², ÷ 1e3 back to km/s²
+    -0.5 * density * ballistic_coefficient * 1000.0 * relative_velocity.length() * relative_velocity
+}
+
+/// King-Hele decay rate of a circular orbit (km/s, negative while decaying)
+///
+/// `area_to_mass` is drag coefficient × area / mass (m²/kg); `radius` is the orbit radius (km); `density`
+/// gives the atmospheric density (kg/m³) at an altitude (km).
+pub fn circular_decay_rate(radius: f64, earth_radius: f64, area_to_mass: f64, mu: f64, density: &impl Fn(f64) -> f64) -> f64 {
+    // da/dt = -ρ (Cd A / m) sqrt(μ a), evaluated in SI units
+    let (radius_m, mu_m) = (radius * 1000.0, mu * 1e9);
+    -density(radius - earth_radius) * area_to_mass * (mu_m * radius_m).sqrt() / 1000.0
+}
+
+/// Seconds until a circular orbit of radius `radius` (km) decays to `reentry_radius`, integrating the
+/// King-Hele decay rate through the atmosphere
+pub fn circular_lifetime(
+    radius: f64,
+    reentry_radius: f64,
+    earth_radius: f64,
+    area_to_mass: f64,
+    mu: f64,
+    density: &impl Fn(f64) -> f64,
+) -> f64 {
+    let mut lifetime = 0.0;
+    let mut r = radius;
+    while r > reentry_radius {
+        let step = LIFETIME_STEP_KM.min(r - reentry_radius);
+        let rate = circular_decay_rate(r - step / 2.0, earth_radius, area_to_mass, mu, density);
+        if rate >= 0.0 {
+            return f64::INFINITY;
+        }
+        lifetime += step / -rate;
+        r -= step;
+    }
+    lifetime
+}
@@ -0,0 +1,346 @@
+// CCSDS Conjunction Data Message parsing (KVN) and rendering (KVN and XML)
+
+use bevy::math::{DMat3, DVec3};
+use std::fmt::Write as _;
+use crate::utils::{ecef_to_eci, parse_iso8601, unix_to_iso8601};
+
+/// One object of a conjunction as described at TCA
+#[derive(Debug, Clone, Default)]
+pub struct CdmObject {
+    pub designator: String,
+    pub name: String,
+    pub ref_frame: String,
+    /// Position at TCA (km, in `ref_frame`)
+    pub position: DVec3,
+    /// Velocity at TCA (km/s, in `ref_frame`)
+    pub velocity: DVec3,
+    pub international_designator: String,
+    /// Whether the object can maneuver, when known
+    pub maneuverable: Option<bool>,
+    /// 1-sigma position uncertainty along the radial, transverse and normal directions at TCA (km)
+    pub position_sigma_rtn: DVec3,
+}
+
+impl CdmObject {
+    /// NORAD catalog number, when the designator is numeric
+    pub fn norad_id(&self) -> Option<u32> {
+        self.designator.trim().parse().ok()
+    }
+
+    /// State at TCA in the simulator's inertial frame
+    pub fn inertial_state(&self, tca_unix: f64) -> (DVec3, DVec3) {
+        match self.ref_frame.to_ascii_uppercase().as_str() {
+            "ITRF" | "ITRF2000" | "ITRF-93" | "ITRF-97" => ecef_to_eci(self.position, self.velocity, tca_unix),
+            _ => (self.position, self.velocity),
+        }
+    }
+}
+
+/// Conjunction Data Message contents needed to reconstruct the encounter
+#[derive(Debug, Clone, Default)]
+pub struct ConjunctionDataMessage {
+    pub message_id: String,
+    /// Time of closest approach (Unix seconds)
+    pub tca_unix: f64,
+    /// Reported miss distance (m)
+    pub miss_distance: Option<f64>,
+    /// Reported relative speed (m/s)
+    pub relative_speed: Option<f64>,
+    pub collision_probability: Option<f64>,
+    pub objects: [CdmObject; 2],
+}
+
+/// Errors raised while reading a CDM
+#[derive(Debug)]
+pub enum CdmParseError {
+    MissingField(String),
+    InvalidField(String),
+    Unsupported(String),
+}
+
+impl std::fmt::Display for CdmParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CdmParseError::MissingField(field) => write!(f, "Missing CDM field: {}", field),
+            CdmParseError::InvalidField(field) => write!(f, "Invalid CDM field: {}", field),
+            CdmParseError::Unsupported(what) => write!(f, "Unsupported {}", what),
+        }
+    }
+}
+
+impl std::error::Error for CdmParseError {}
+
+/// Parse a CCSDS CDM in keyword = value (KVN) form
+pub fn parse_cdm(text: &str) -> Result<ConjunctionDataMessage, CdmParseError> {
+    if text.trim_start().starts_with('<') {
+        return Err(CdmParseError::Unsupported("XML CDM, convert to KVN".to_string()));
+    }
+
+    let mut cdm = ConjunctionDataMessage::default();
+    let mut tca = None;
+    // Which object block subsequent keys belong to, and which state fields were seen
+    let mut current: Option<usize> = None;
+    let mut state_fields = [0u8; 2];
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("COMMENT") {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        // Drop trailing unit annotations such as "[km]"
+        let value = value.split('[').next().unwrap_or_default().trim();
+        let number = || value.parse::<f64>().map_err(|_| CdmParseError::InvalidField(key.to_string()));
+
+        match key {
+            "MESSAGE_ID" => cdm.message_id = value.to_string(),
+            "TCA" => tca = Some(parse_iso8601(value).ok_or_else(|| CdmParseError::InvalidField(key.to_string()))?),
+            "MISS_DISTANCE" => cdm.miss_distance = Some(number()?),
+            "RELATIVE_SPEED" => cdm.relative_speed = Some(number()?),
+            "COLLISION_PROBABILITY" => cdm.collision_probability = number().ok(),
+            "OBJECT" => {
+                current = match value {
+                    "OBJECT1" => Some(0),
+                    "OBJECT2" => Some(1),
+                    _ => return Err(CdmParseError::InvalidField(key.to_string())),
+                };
+            }
+            _ => {
+                let Some(index) = current else {
+                    continue;
+                };
+                let object = &mut cdm.objects[index];
+                match key {
+                    "OBJECT_DESIGNATOR" => object.designator = value.to_string(),
+                    "OBJECT_NAME" => object.name = value.to_string(),
+                    "REF_FRAME" => object.ref_frame = value.to_string(),
+                    _ => {}
+                }
+                let (component, bit) = match key {
+                    "X" => (&mut object.position.x, 0),
+                    "Y" => (&mut object.position.y, 1),
+                    "Z" => (&mut object.position.z, 2),
+                    "X_DOT" => (&mut object.velocity.x, 3),
+                    "Y_DOT" => (&mut object.velocity.y, 4),
+                    "Z_DOT" => (&mut object.velocity.z, 5),
+                    _ => continue,
+                };
+                *component = number()?;
+                state_fields[index] |= 1 << bit;
+            }
+        }
+    }
+
+    cdm.tca_unix = tca.ok_or_else(|| CdmParseError::MissingField("TCA".to_string()))?;
+    for (index, fields) in state_fields.iter().enumerate() {
+        if *fields != 0b11_1111 {
+            return Err(CdmParseError::MissingField(format!("OBJECT{} state vector", index + 1)));
+        }
+    }
+    Ok(cdm)
+}
+
+/// CDM encodings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CdmFormat {
+    Kvn,
+    Xml,
+}
+
+impl CdmFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CdmFormat::Kvn => "cdm",
+            CdmFormat::Xml => "xml",
+        }
+    }
+}
+
+impl std::str::FromStr for CdmFormat {
+    type Err = String;
+
+    /// Parse "kvn" or "xml"
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "kvn" => Ok(CdmFormat::Kvn),
+            "xml" => Ok(CdmFormat::Xml),
+            other => Err(format!("unknown CDM format {}", other)),
+        }
+    }
+}
+
+/// Keyword, value and unit of a CDM field
+type CdmField = (&'static str, String, Option<&'static str>);
+type CdmFields = Vec<CdmField>;
+
+/// Rotation taking inertial vectors into the radial, transverse and normal frame of a state
+fn rtn_frame(position: DVec3, velocity: DVec3) -> DMat3 {
+    let radial = position.normalize_or_zero();
+    let normal = position.cross(velocity).normalize_or_zero();
+    DMat3::from_cols(radial, normal.cross(radial), normal).transpose()
+}
+
+/// TCA, miss distance and the second object's state relative to the first, in the first object's RTN frame
+fn relative_fields(cdm: &ConjunctionDataMessage) -> CdmFields {
+    let [first, second] = &cdm.objects;
+    let rotation = rtn_frame(first.position, first.velocity);
+    let position = rotation * (second.position - first.position) * 1000.0;
+    let velocity = rotation * (second.velocity - first.velocity) * 1000.0;
+    let mut fields: CdmFields = vec![
+        ("TCA", unix_to_iso8601(cdm.tca_unix), None),
+        ("MISS_DISTANCE", format!("{:.1}", cdm.miss_distance.unwrap_or(position.length())), Some("m")),
+        ("RELATIVE_SPEED", format!("{:.1}", cdm.relative_speed.unwrap_or(velocity.length())), Some("m/s")),
+    ];
+    for (keyword, value) in [("RELATIVE_POSITION_R", position.x), ("RELATIVE_POSITION_T", position.y), ("RELATIVE_POSITION_N", position.z)] {
+        fields.push((keyword, format!("{:.1}", value), Some("m")));
+    }
+    for (keyword, value) in [("RELATIVE_VELOCITY_R", velocity.x), ("RELATIVE_VELOCITY_T", velocity.y), ("RELATIVE_VELOCITY_N", velocity.z)] {
+        fields.push((keyword, format!("{:.3}", value), Some("m/s")));
+    }
+    if let Some(probability) = cdm.collision_probability {
+        fields.push(("COLLISION_PROBABILITY", format!("{:.4e}", probability), None));
+        fields.push(("COLLISION_PROBABILITY_METHOD", "FOSTER-1992".to_string(), None));
+    }
+    fields
+}
+
+/// Identification and frame of one object
+fn object_metadata(index: usize, object: &CdmObject) -> CdmFields {
+    let or_unknown = |text: &str| if text.is_empty() { "UNKNOWN".to_string() } else { text.to_string() };
+    vec![
+        ("OBJECT", format!("OBJECT{}", index + 1), None),
+        ("OBJECT_DESIGNATOR", or_unknown(&object.designator), None),
+        ("CATALOG_NAME", "SATCAT".to_string(), None),
+        ("OBJECT_NAME", or_unknown(&object.name), None),
+        ("INTERNATIONAL_DESIGNATOR", or_unknown(&object.international_designator), None),
+        ("EPHEMERIS_NAME", "NONE".to_string(), None),
+        ("COVARIANCE_METHOD", "DEFAULT".to_string(), None),
+        ("MANEUVERABLE", match object.maneuverable {
+            Some(true) => "YES",
+            Some(false) => "NO",
+            None => "N/A",
+        }.to_string(), None),
+        ("REF_FRAME", if object.ref_frame.is_empty() { "EME2000".to_string() } else { object.ref_frame.clone() }, None),
+    ]
+}
+
+/// State vector at TCA
+fn object_state(object: &CdmObject) -> CdmFields {
+    let (p, v) = (object.position, object.velocity);
+    vec![
+        ("X", format!("{:.6}", p.x), Some("km")),
+        ("Y", format!("{:.6}", p.y), Some("km")),
+        ("Z", format!("{:.6}", p.z), Some("km")),
+        ("X_DOT", format!("{:.9}", v.x), Some("km/s")),
+        ("Y_DOT", format!("{:.9}", v.y), Some("km/s")),
+        ("Z_DOT", format!("{:.9}", v.z), Some("km/s")),
+    ]
+}
+
+/// Lower triangle of the RTN covariance; only the position variances are modelled, the other terms are zero
+fn object_covariance(object: &CdmObject) -> CdmFields {
+    const TERMS: [(&str, &str); 21] = [
+        ("CR_R", "m**2"), ("CT_R", "m**2"), ("CT_T", "m**2"), ("CN_R", "m**2"), ("CN_T", "m**2"), ("CN_N", "m**2"),
+        ("CRDOT_R", "m**2/s"), ("CRDOT_T", "m**2/s"), ("CRDOT_N", "m**2/s"), ("CRDOT_RDOT", "m**2/s**2"),
+        ("CTDOT_R", "m**2/s"), ("CTDOT_T", "m**2/s"), ("CTDOT_N", "m**2/s"), ("CTDOT_RDOT", "m**2/s**2"),
+        ("CTDOT_TDOT", "m**2/s**2"),
+        ("CNDOT_R", "m**2/s"), ("CNDOT_T", "m**2/s"), ("CNDOT_N", "m**2/s"), ("CNDOT_RDOT", "m**2/s**2"),
+        ("CNDOT_TDOT", "m**2/s**2"), ("CNDOT_NDOT", "m**2/s**2"),
+    ];
+    let variance = object.position_sigma_rtn * object.position_sigma_rtn * 1e6;
+    TERMS.iter()
+        .map(|&(keyword, unit)| {
+            let value = match keyword {
+                "CR_R" => variance.x,
+                "CT_T" => variance.y,
+                "CN_N" => variance.z,
+                _ => 0.0,
+            };
+            (keyword, format!("{:.6e}", value), Some(unit))
+        })
+        .collect()
+}
+
+fn write_kvn_fields(text: &mut String, fields: &[CdmField]) {
+    for (keyword, value, unit) in fields {
+        match unit {
+            Some(unit) => { let _ = writeln!(text, "{:<36} = {} [{}]", keyword, value, unit); }
+            None => { let _ = writeln!(text, "{:<36} = {}", keyword, value); }
+        }
+    }
+}
+
+fn write_xml_fields(text: &mut String, indent: &str, fields: &[CdmField]) {
+    for (keyword, value, unit) in fields {
+        let value = value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+        match unit {
+            Some(unit) => { let _ = writeln!(text, "{}<{} units=\"{}\">{}</{}>", indent, keyword, unit, value, keyword); }
+            None => { let _ = writeln!(text, "{}<{}>{}</{}>", indent, keyword, value, keyword); }
+        }
+    }
+}
+
+/// Render a CCSDS CDM 1.0 with states in the EME2000 frame, created at `creation_unix`
+///
+/// The miss distance and relative speed fall back to the ones implied by the two states when not given.
+pub fn format_cdm(cdm: &ConjunctionDataMessage, format: CdmFormat, creation_unix: f64) -> String {
+    let header: CdmFields = vec![
+        ("CREATION_DATE", unix_to_iso8601(creation_unix), None),
+        ("ORIGINATOR", "KESSLER SIMULATOR".to_string(), None),
+        ("MESSAGE_ID", cdm.message_id.clone(), None),
+    ];
+    let relative = relative_fields(cdm);
+    let mut text = String::new();
+    match format {
+        CdmFormat::Kvn => {
+            let _ = writeln!(text, "CCSDS_CDM_VERS = 1.0");
+            write_kvn_fields(&mut text, &header);
+            let _ = writeln!(text, "COMMENT Relative state of OBJECT2 in the RTN frame of OBJECT1");
+            write_kvn_fields(&mut text, &relative);
+            for (index, object) in cdm.objects.iter().enumerate() {
+                write_kvn_fields(&mut text, &object_metadata(index, object));
+                let _ = writeln!(text, "COMMENT Two-body state at TCA");
+                write_kvn_fields(&mut text, &object_state(object));
+                let _ = writeln!(text, "COMMENT Position covariance of the simulator's uncertainty model");
+                write_kvn_fields(&mut text, &object_covariance(object));
+            }
+        }
+        CdmFormat::Xml => {
+            let _ = writeln!(text, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+            let _ = writeln!(text, "<cdm id=\"CCSDS_CDM_VERS\" version=\"1.0\">");
+            let _ = writeln!(text, "  <header>");
+            write_xml_fields(&mut text, "    ", &header);
+            let _ = writeln!(text, "  </header>");
+            let _ = writeln!(text, "  <body>");
+            let _ = writeln!(text, "    <relativeMetadataData>");
+            // TCA, miss distance and relative speed, then the relative state, then the probability
+            write_xml_fields(&mut text, "      ", &relative[..3]);
+            let _ = writeln!(text, "      <relativeStateVector>");
+            write_xml_fields(&mut text, "        ", &relative[3..9]);
+            let _ = writeln!(text, "      </relativeStateVector>");
+            write_xml_fields(&mut text, "      ", &relative[9..]);
+            let _ = writeln!(text, "    </relativeMetadataData>");
+            for (index, object) in cdm.objects.iter().enumerate() {
+                let _ = writeln!(text, "    <segment>");
+                let _ = writeln!(text, "      <metadata>");
+                write_xml_fields(&mut text, "        ", &object_metadata(index, object));
+                let _ = writeln!(text, "      </metadata>");
+                let _ = writeln!(text, "      <data>");
+                let _ = writeln!(text, "        <stateVector>");
+                write_xml_fields(&mut text, "          ", &object_state(object));
+                let _ = writeln!(text, "        </stateVector>");
+                let _ = writeln!(text, "        <covarianceMatrix>");
+                write_xml_fields(&mut text, "          ", &object_covariance(object));
+                let _ = writeln!(text, "        </covarianceMatrix>");
+                let _ = writeln!(text, "      </data>");
+                let _ = writeln!(text, "    </segment>");
+            }
+            let _ = writeln!(text, "  </body>");
+            let _ = writeln!(text, "</cdm>");
+        }
+    }
+    text
+}
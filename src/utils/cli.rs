@@ -0,0 +1,387 @@
+// Command-line options
+
+use bevy::prelude::Resource;
+use std::path::PathBuf;
+use crate::resources::{BroadPhase, CollisionInterval, CollisionResolution, ForceModel, Integrator, ObjectCap, PhysicsBackend, RightOfWay, SpaceWeather, StmRegime};
+use crate::utils::{parse_iso8601, CdmFormat, GpFormat, PublishTarget, SsoPlane};
+
+const USAGE: &str = "\
+Usage: kessler [OPTIONS]
+
+Options:
+  --tle-file <PATH>      Load element sets from a .tle, .json, .csv or .xml file instead of Celestrak (repeatable)
+  --max-objects <N|all>  Cataloged objects spawned (default: 100)
+  --group <NAME>         Fetch a Celestrak group, e.g. starlink or cosmos-2251-debris, instead of the saved groups (repeatable)
+  --full-catalog         Fetch the active satellites plus the major debris clouds and spawn all of them
+  --tle-archive <DIR>    Replay a directory of dated catalog snapshots, starting at the earliest
+  --tle-refresh <HOURS>  Re-fetch the element sets every HOURS of wall-clock time while running
+  --scenario <PATH>      Run a TOML (.toml) or JSON scenario file: its objects, constants, scheduled events and duration
+  --preset <NAME>        Run a historical breakup: fengyun-1c (2007), iridium-cosmos (2009) or nudol (2021)
+  --discos               Look objects up in ESA DISCOS for dimensions, shapes and dry masses (token in $DISCOS_TOKEN)
+  --gp-format <FORMAT>   Element set format fetched from Celestrak: tle (default), json, csv or xml
+  --start <UTC>          Simulation start, e.g. 2024-06-01T12:00:00Z; every element set is propagated to it (default: now)
+  --timestep <SECONDS>   Physics timestep in seconds (the longest substep while the governor is on)
+  --no-governor          Integrate one timestep per frame instead of substepping to keep pace with time compression
+  --substep-budget <N>   Object-substeps integrated per frame before switching to Kepler (default: 1000000)
+  --integrator <NAME>    euler (default), verlet (keeps orbital energy bounded) or dopri (adaptive Dormand-Prince)
+  --integrator-tolerance <KM>  Position error allowed per adaptive step (default: 0.001)
+  --physics-backend <NAME>  optimized (default, parallel on a worker thread) or legacy (single-threaded)
+  --force-model <MODEL>  Gravity field: two-body, j2 (default), zonal[:DEGREE] or geopotential (EGM96 to J6 with C22/S22)
+  --no-third-body        Leave out the Sun's and Moon's gravity
+  --reentry-altitude <KM>  Altitude below which objects reenter and are removed (default: 100)
+  --mean-elements        Propagate objects analytically unless a conjunction or burn is imminent
+  --space-weather <MODE>  Thermosphere drivers: min, max, cycle (nominal 11-year solar cycle) or F107[:AP]
+  --settings <PATH>      Settings file (default: kessler_settings.json)
+  --collision-interval <N|Xs|frame>  Run collision detection every N steps or X simulated seconds
+  --collision-mode <MODE>  hard, or probabilistic[:INFLATION] to sample each encounter's collision probability
+  --broad-phase <NAME>   Collision broad-phase: octree, morton, hash-grid or sieve
+  --pc-threshold <P>     Report conjunctions whose probability of collision reaches P, e.g. 1e-4
+  --collision-avoidance  Active satellites dodge conjunctions screened above the Pc threshold with along-track burns,
+                         yielding per the STM regime (self-managed unless one is chosen)
+  --stm-regime <NAME>    Space traffic management: unregulated (default, nobody maneuvers), self-managed or coordinated
+  --right-of-way <RULE>  Who yields in the starting regime: none, both, newer-yields or lighter-yields
+  --look-ahead <HOURS>   Predict and list the closest approaches over the next HOURS (up to 72)
+  --near-misses <N>      Track the N closest approaches of the last 24 simulated hours
+  --export-span <SECONDS>  Span of exported ephemerides (default: 86400)
+  --export-step <SECONDS>  Interval between exported states (default: 60)
+  --checkpoint <HOURS>   Write the population as TLE and OMM catalogs every HOURS of simulated time
+  --ephemeris <PATH>     STK .e ephemeris driving an object (repeatable)
+  --cdm <PATH>           CCSDS conjunction data message to replay (repeatable)
+  --cdm-export <FORMAT>  Write a CDM (kvn or xml) for each conjunction screened above the Pc threshold
+  --telemetry <NORAD_ID>  Stream telemetry CSV for an object (repeatable)
+  --telemetry-cadence <SECONDS>  Simulated seconds between telemetry rows (default: 60)
+  --sso <ALT:LTAN[:COUNT]>  Spawn satellites in a sun-synchronous plane, e.g. 705:10.5:12 (repeatable)
+  --background <CM>      Add uncataloged debris down to this diameter from a MASTER-like density model
+  --background-weight <N>  Fragments represented by each background particle (default: 100)
+  --launch-traffic <PATH>  Keep launching satellites per a launches-per-year profile (JSON, or default)
+  --publish <URL>        Publish events to mqtt://host[:port]/topic or kafka://host[:port]/topic
+  --stress-config <PATH>  Stress-test scenario file (JSON)
+  --stress-objects <N>   Stress-test target object count
+  --stress-rate <N>      Stress-test objects spawned per frame
+  --stress-distribution <NAME>  Stress-test preset: bands, leo, sso or geo
+  --stress-sweep         Run the stress-test sweep on startup
+  --headless             Run without a window or renderer; sweeps quit when done
+  --filter <EXPR>        Show only matching objects, e.g. operator:SpaceX or owner:CIS,derelict
+  --disable <FEATURE>    Switch off collisions, debris, analytics or rendering (repeatable)
+  --diagnostics          Log frame time and simulation diagnostics every second
+  --validate-decay       Compare reference objects' simulated decay with King-Hele lifetime estimates
+  -h, --help             Print this help message";
+
+/// Options parsed from the command line
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CliOptions {
+    /// Element set files to load instead of fetching
+    pub tle_files: Vec<PathBuf>,
+    /// Cataloged object cap override
+    pub max_objects: Option<ObjectCap>,
+    /// Celestrak groups to fetch instead of the saved ones
+    pub groups: Vec<String>,
+    /// Fetch and spawn the full catalog
+    pub full_catalog: bool,
+    /// Directory of dated catalog snapshots to replay
+    pub tle_archive: Option<PathBuf>,
+    /// Wall-clock hours between element set refreshes
+    pub tle_refresh: Option<f64>,
+    /// Scenario file to run
+    pub scenario: Option<PathBuf>,
+    /// Built-in scenario preset to run
+    pub preset: Option<String>,
+    /// Fetch physical properties from ESA DISCOS
+    pub discos: bool,
+    /// Element set format requested from Celestrak
+    pub gp_format: Option<GpFormat>,
+    /// Simulation start as a UTC Unix time
+    pub start: Option<f64>,
+    /// Physics timestep override in seconds
+    pub timestep: Option<f64>,
+    /// Governor work budget override in object-substeps per frame
+    pub substep_budget: Option<u64>,
+    /// Integrator override
+    pub integrator: Option<Integrator>,
+    /// Adaptive integrator tolerance in km
+    pub integrator_tolerance: Option<f64>,
+    /// Physics pipeline override
+    pub physics_backend: Option<PhysicsBackend>,
+    /// Gravity field override
+    pub force_model: Option<ForceModel>,
+    /// Reentry altitude override in km
+    pub reentry_altitude: Option<f64>,
+    /// Solar flux and geomagnetic activity override
+    pub space_weather: Option<SpaceWeather>,
+    /// Settings file override
+    pub settings: Option<PathBuf>,
+    /// Collision detection interval override
+    pub collision_interval: Option<CollisionInterval>,
+    /// Collision resolution override
+    pub collision_resolution: Option<CollisionResolution>,
+    /// Collision broad-phase override
+    pub broad_phase: Option<BroadPhase>,
+    /// Probability of collision at which conjunctions are reported
+    pub pc_threshold: Option<f64>,
+    /// Look-ahead conjunction prediction span in hours
+    pub look_ahead: Option<f64>,
+    /// Number of closest approaches to track
+    pub near_misses: Option<usize>,
+    /// Span of exported ephemerides in seconds
+    pub export_span: Option<f64>,
+    /// Interval between exported states in seconds
+    pub export_step: Option<f64>,
+    /// Simulated hours between catalog checkpoints
+    pub checkpoint: Option<f64>,
+    /// STK ephemeris files to import
+    pub ephemerides: Vec<PathBuf>,
+    /// Conjunction data messages to replay
+    pub cdms: Vec<PathBuf>,
+    /// Encoding of exported conjunction data messages
+    pub cdm_export: Option<CdmFormat>,
+    /// NORAD IDs to stream telemetry for
+    pub telemetry: Vec<u32>,
+    /// Simulated seconds between telemetry rows
+    pub telemetry_cadence: Option<f64>,
+    /// Sun-synchronous planes to populate
+    pub sso: Vec<SsoPlane>,
+    /// Smallest diameter (cm) of the synthesized background population
+    pub background: Option<f64>,
+    /// Fragments per background particle
+    pub background_weight: Option<u32>,
+    /// Launches-per-year profile file, or "default" for the built-in one
+    pub launch_traffic: Option<PathBuf>,
+    /// Destination for collision, conjunction and reentry events
+    pub publish: Option<PublishTarget>,
+    /// Stress-test scenario file
+    pub stress_config: Option<PathBuf>,
+    /// Stress-test target object count
+    pub stress_objects: Option<usize>,
+    /// Stress-test objects spawned per frame
+    pub stress_rate: Option<usize>,
+    /// Stress-test spawn distribution name
+    pub stress_distribution: Option<String>,
+    /// Start the stress-test sweep on launch
+    pub stress_sweep: bool,
+    /// Run without a window or renderer
+    pub headless: bool,
+    /// Population filter expression
+    pub filter: Option<String>,
+    /// Subsystems switched off at startup
+    pub disable: Vec<String>,
+    /// Log Bevy diagnostics
+    pub diagnostics: bool,
+    /// Run the drag-decay validation
+    pub validate_decay: bool,
+    /// Switch off the accuracy governor
+    pub no_governor: bool,
+    /// Leave out lunisolar perturbations
+    pub no_third_body: bool,
+    /// Propagate quiescent objects on mean elements
+    pub mean_elements: bool,
+    /// Let active satellites maneuver away from high-Pc conjunctions
+    pub collision_avoidance: bool,
+    /// Space traffic management regime override
+    pub stm_regime: Option<StmRegime>,
+    /// Right-of-way rule replacing the starting regime's own
+    pub right_of_way: Option<RightOfWay>,
+}
+
+impl CliOptions {
+    /// Parse options from the process arguments, exiting on invalid input
+    pub fn from_env() -> Self {
+        match Self::parse(std::env::args().skip(1)) {
+            Ok(options) => options,
+            Err(message) => {
+                eprintln!("{}\n\n{}", message, USAGE);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    /// Parse options from an argument list (without the program name)
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--tle-file" => {
+                    options.tle_files.push(parse_value(&arg, args.next())?);
+                }
+                "--max-objects" => {
+                    options.max_objects = Some(parse_value(&arg, args.next())?);
+                }
+                "--group" => {
+                    options.groups.push(parse_value(&arg, args.next())?);
+                }
+                "--full-catalog" => {
+                    options.full_catalog = true;
+                }
+                "--tle-archive" => {
+                    options.tle_archive = Some(parse_value(&arg, args.next())?);
+                }
+                "--tle-refresh" => {
+                    options.tle_refresh = Some(parse_value(&arg, args.next())?);
+                }
+                "--scenario" => {
+                    options.scenario = Some(parse_value(&arg, args.next())?);
+                }
+                "--preset" => {
+                    options.preset = Some(parse_value(&arg, args.next())?);
+                }
+                "--discos" => {
+                    options.discos = true;
+                }
+                "--gp-format" => {
+                    options.gp_format = Some(parse_value(&arg, args.next())?);
+                }
+                "--start" => {
+                    let value: String = parse_value(&arg, args.next())?;
+                    options.start = Some(parse_iso8601(&value).ok_or_else(|| format!("Invalid value for {}: {}", arg, value))?);
+                }
+                "--timestep" => {
+                    options.timestep = Some(parse_value(&arg, args.next())?);
+                }
+                "--substep-budget" => {
+                    options.substep_budget = Some(parse_value(&arg, args.next())?);
+                }
+                "--settings" => {
+                    options.settings = Some(parse_value(&arg, args.next())?);
+                }
+                "--collision-interval" => {
+                    options.collision_interval = Some(parse_value(&arg, args.next())?);
+                }
+                "--integrator" => {
+                    options.integrator = Some(parse_value(&arg, args.next())?);
+                }
+                "--integrator-tolerance" => {
+                    options.integrator_tolerance = Some(parse_value(&arg, args.next())?);
+                }
+                "--physics-backend" => {
+                    options.physics_backend = Some(parse_value(&arg, args.next())?);
+                }
+                "--reentry-altitude" => {
+                    options.reentry_altitude = Some(parse_value(&arg, args.next())?);
+                }
+                "--force-model" => {
+                    options.force_model = Some(parse_value(&arg, args.next())?);
+                }
+                "--space-weather" => {
+                    options.space_weather = Some(parse_value(&arg, args.next())?);
+                }
+                "--collision-mode" => {
+                    options.collision_resolution = Some(parse_value(&arg, args.next())?);
+                }
+                "--broad-phase" => {
+                    options.broad_phase = Some(parse_value(&arg, args.next())?);
+                }
+                "--pc-threshold" => {
+                    options.pc_threshold = Some(parse_value(&arg, args.next())?);
+                }
+                "--look-ahead" => {
+                    options.look_ahead = Some(parse_value(&arg, args.next())?);
+                }
+                "--near-misses" => {
+                    options.near_misses = Some(parse_value(&arg, args.next())?);
+                }
+                "--export-span" => {
+                    options.export_span = Some(parse_value(&arg, args.next())?);
+                }
+                "--export-step" => {
+                    options.export_step = Some(parse_value(&arg, args.next())?);
+                }
+                "--checkpoint" => {
+                    options.checkpoint = Some(parse_value(&arg, args.next())?);
+                }
+                "--ephemeris" => {
+                    options.ephemerides.push(parse_value(&arg, args.next())?);
+                }
+                "--cdm" => {
+                    options.cdms.push(parse_value(&arg, args.next())?);
+                }
+                "--cdm-export" => {
+                    options.cdm_export = Some(parse_value(&arg, args.next())?);
+                }
+                "--telemetry" => {
+                    options.telemetry.push(parse_value(&arg, args.next())?);
+                }
+                "--telemetry-cadence" => {
+                    options.telemetry_cadence = Some(parse_value(&arg, args.next())?);
+                }
+                "--sso" => {
+                    options.sso.push(parse_value(&arg, args.next())?);
+                }
+                "--background" => {
+                    options.background = Some(parse_value(&arg, args.next())?);
+                }
+                "--background-weight" => {
+                    options.background_weight = Some(parse_value(&arg, args.next())?);
+                }
+                "--launch-traffic" => {
+                    options.launch_traffic = Some(parse_value(&arg, args.next())?);
+                }
+                "--publish" => {
+                    options.publish = Some(parse_value(&arg, args.next())?);
+                }
+                "--stress-config" => {
+                    options.stress_config = Some(parse_value(&arg, args.next())?);
+                }
+                "--stress-objects" => {
+                    options.stress_objects = Some(parse_value(&arg, args.next())?);
+                }
+                "--stress-rate" => {
+                    options.stress_rate = Some(parse_value(&arg, args.next())?);
+                }
+                "--stress-distribution" => {
+                    options.stress_distribution = Some(parse_value(&arg, args.next())?);
+                }
+                "--stress-sweep" => {
+                    options.stress_sweep = true;
+                }
+                "--headless" => {
+                    options.headless = true;
+                }
+                "--filter" => {
+                    options.filter = Some(parse_value(&arg, args.next())?);
+                }
+                "--disable" => {
+                    options.disable.push(parse_value(&arg, args.next())?);
+                }
+                "--diagnostics" => {
+                    options.diagnostics = true;
+                }
+                "--validate-decay" => {
+                    options.validate_decay = true;
+                }
+                "--no-governor" => {
+                    options.no_governor = true;
+                }
+                "--no-third-body" => {
+                    options.no_third_body = true;
+                }
+                "--mean-elements" => {
+                    options.mean_elements = true;
+                }
+                "--collision-avoidance" => {
+                    options.collision_avoidance = true;
+                }
+                "--stm-regime" => {
+                    options.stm_regime = Some(parse_value(&arg, args.next())?);
+                }
+                "--right-of-way" => {
+                    options.right_of_way = Some(parse_value(&arg, args.next())?);
+                }
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                other => return Err(format!("Unknown argument: {}", other)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Parse the value following a flag
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("Missing value for {}", flag))?;
+    value.parse::<T>()
+        .map_err(|_| format!("Invalid value for {}: {}", flag, value))
+}
@@ -0,0 +1,22 @@
+// CSV line splitting shared by the SATCAT and OMM CSV readers
+
+/// Split a CSV line on commas outside double quotes, unescaping `""` and trimming each field
+pub(crate) fn split_csv_line(line: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut value = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                value.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => values.push(std::mem::take(&mut value).trim().to_string()),
+            _ => value.push(c),
+        }
+    }
+    values.push(value.trim().to_string());
+    values
+}
@@ -0,0 +1,61 @@
+// ESA DISCOS (Database and Information System Characterising Objects in Space) API requests and responses
+
+/// DISCOSweb object endpoint
+pub const DISCOS_URL: &str = "https://discosweb.esoc.esa.int/api/objects";
+/// DISCOSweb API version sent with every request
+pub const DISCOS_API_VERSION: &str = "2";
+
+/// Physical description of one object as DISCOS catalogues it
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscosObject {
+    pub norad_id: u32,
+    /// Shape description, e.g. "Box + 2 Pan" or "Cyl"
+    pub shape: String,
+    /// Dry mass (kg)
+    pub mass: Option<f64>,
+    /// Height, width and depth (m)
+    pub height: Option<f64>,
+    pub width: Option<f64>,
+    pub depth: Option<f64>,
+    /// Diameter of round bodies (m)
+    pub diameter: Option<f64>,
+    /// Largest dimension, deployed appendages included (m)
+    pub span: Option<f64>,
+    /// Average cross-section over random orientations (m²)
+    pub cross_section: Option<f64>,
+}
+
+/// Query URL for the objects with the given NORAD IDs (up to one page of 100)
+pub fn discos_objects_url(norad_ids: &[u32]) -> String {
+    let ids: Vec<String> = norad_ids.iter().map(u32::to_string).collect();
+    format!("{}?filter=in(satno,({}))&page[size]=100", DISCOS_URL, ids.join(","))
+}
+
+/// Parse a DISCOSweb JSON:API object listing; objects without a NORAD ID are left out
+pub fn parse_discos_objects(text: &str) -> Result<Vec<DiscosObject>, String> {
+    let document: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    if let Some(error) = document.get("errors").and_then(|errors| errors.get(0)) {
+        let detail = error.get("detail").or_else(|| error.get("title")).and_then(|d| d.as_str()).unwrap_or("unknown error");
+        return Err(format!("DISCOS error: {}", detail));
+    }
+    let data = document.get("data").and_then(|data| data.as_array()).ok_or("DISCOS response has no data")?;
+
+    Ok(data
+        .iter()
+        .filter_map(|object| {
+            let attributes = object.get("attributes")?;
+            let number = |key: &str| attributes.get(key).and_then(|value| value.as_f64()).filter(|&value| value > 0.0);
+            Some(DiscosObject {
+                norad_id: attributes.get("satno")?.as_u64()? as u32,
+                shape: attributes.get("shape").and_then(|shape| shape.as_str()).unwrap_or_default().to_string(),
+                mass: number("mass"),
+                height: number("height"),
+                width: number("width"),
+                depth: number("depth"),
+                diameter: number("diameter"),
+                span: number("span"),
+                cross_section: number("xSectAvg"),
+            })
+        })
+        .collect())
+}
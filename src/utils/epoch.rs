@@ -0,0 +1,235 @@
+// Calendar and time-scale conversions for simulation epochs
+//
+// Unix seconds are UTC without leap seconds; TAI and TT are continuous SI-second scales.
+
+use bevy::math::DVec3;
+
+/// TT - TAI (seconds)
+pub const TT_MINUS_TAI: f64 = 32.184;
+/// Julian date of the J2000.0 epoch
+pub const J2000_JULIAN_DATE: f64 = 2451545.0;
+
+/// TAI - UTC in seconds from each leap second onward, keyed by the UTC Unix time it took effect
+pub const LEAP_SECONDS: [(f64, f64); 28] = [
+    (63072000.0, 10.0), // 1972-01-01
+    (78796800.0, 11.0), // 1972-07-01
+    (94694400.0, 12.0), // 1973-01-01
+    (126230400.0, 13.0), // 1974-01-01
+    (157766400.0, 14.0), // 1975-01-01
+    (189302400.0, 15.0), // 1976-01-01
+    (220924800.0, 16.0), // 1977-01-01
+    (252460800.0, 17.0), // 1978-01-01
+    (283996800.0, 18.0), // 1979-01-01
+    (315532800.0, 19.0), // 1980-01-01
+    (362793600.0, 20.0), // 1981-07-01
+    (394329600.0, 21.0), // 1982-07-01
+    (425865600.0, 22.0), // 1983-07-01
+    (489024000.0, 23.0), // 1985-07-01
+    (567993600.0, 24.0), // 1988-01-01
+    (631152000.0, 25.0), // 1990-01-01
+    (662688000.0, 26.0), // 1991-01-01
+    (709948800.0, 27.0), // 1992-07-01
+    (741484800.0, 28.0), // 1993-07-01
+    (773020800.0, 29.0), // 1994-07-01
+    (820454400.0, 30.0), // 1996-01-01
+    (867715200.0, 31.0), // 1997-07-01
+    (915148800.0, 32.0), // 1999-01-01
+    (1136073600.0, 33.0), // 2006-01-01
+    (1230768000.0, 34.0), // 2009-01-01
+    (1341100800.0, 35.0), // 2012-07-01
+    (1435708800.0, 36.0), // 2015-07-01
+    (1483228800.0, 37.0), // 2017-01-01
+];
+
+/// TAI - UTC (seconds) at a UTC Unix time; before 1972 the first value is used
+pub fn tai_minus_utc(unix_seconds: f64) -> f64 {
+    LEAP_SECONDS.iter()
+        .rev()
+        .find(|(since, _)| unix_seconds >= *since)
+        .map_or(LEAP_SECONDS[0].1, |(_, offset)| *offset)
+}
+
+/// TAI seconds (on the Unix-epoch origin) of a UTC Unix time
+pub fn utc_to_tai(unix_seconds: f64) -> f64 {
+    unix_seconds + tai_minus_utc(unix_seconds)
+}
+
+/// UTC Unix time of a TAI instant (an inserted leap second maps onto the following midnight)
+pub fn tai_to_utc(tai_seconds: f64) -> f64 {
+    let offset = LEAP_SECONDS.iter()
+        .rev()
+        .find(|(since, offset)| tai_seconds >= since + offset)
+        .map_or(LEAP_SECONDS[0].1, |(_, offset)| *offset);
+    tai_seconds - offset
+}
+
+/// Terrestrial Time Julian date of a UTC Unix time
+pub fn julian_date_tt(unix_seconds: f64) -> f64 {
+    unix_to_julian_date(unix_seconds) + (tai_minus_utc(unix_seconds) + TT_MINUS_TAI) / 86400.0
+}
+
+/// Unix time of a TLE epoch (two-digit year, fractional day of year starting at 1.0)
+pub fn tle_epoch_unix(epoch_year: u32, epoch_day: f64) -> f64 {
+    // Two-digit years 57-99 are 1957-1999, 00-56 are 2000-2056
+    let year = if epoch_year < 57 { 2000 + epoch_year } else { 1900 + epoch_year % 100 } as i64;
+    utc_to_unix(year, 1, 1, 0, 0, 0.0) + (epoch_day - 1.0) * 86400.0
+}
+
+/// Split Unix seconds into a civil UTC date and time of day
+///
+/// Returns (year, month, day, hour, minute, second).
+pub fn unix_to_utc(unix_seconds: f64) -> (i64, u32, u32, u32, u32, f64) {
+    let days = (unix_seconds / 86400.0).floor();
+    let seconds_of_day = unix_seconds - days * 86400.0;
+
+    // Civil-from-days (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    let hour = (seconds_of_day / 3600.0).floor() as u32;
+    let minute = ((seconds_of_day - hour as f64 * 3600.0) / 60.0).floor() as u32;
+    let second = seconds_of_day - hour as f64 * 3600.0 - minute as f64 * 60.0;
+
+    (year, month, day, hour, minute, second)
+}
+
+/// Convert a civil UTC date and time of day to Unix seconds
+pub fn utc_to_unix(year: i64, month: u32, day: u32, hour: u32, minute: u32, second: f64) -> f64 {
+    // Days-from-civil (Howard Hinnant's algorithm)
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = month as i64;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    days as f64 * 86400.0 + hour as f64 * 3600.0 + minute as f64 * 60.0 + second
+}
+
+/// TLE epoch fields: two-digit year and fractional day of year
+pub fn tle_epoch(unix_seconds: f64) -> (i64, f64) {
+    let (year, ..) = unix_to_utc(unix_seconds);
+    let day_of_year = (unix_seconds - utc_to_unix(year, 1, 1, 0, 0, 0.0)) / 86400.0 + 1.0;
+    (year.rem_euclid(100), day_of_year)
+}
+
+/// Format Unix seconds as an ISO 8601 UTC timestamp with millisecond precision
+pub fn unix_to_iso8601(unix_seconds: f64) -> String {
+    // Round first so 59.9996 s does not print as 60.000
+    let rounded = (unix_seconds * 1000.0).round() / 1000.0;
+    let (year, month, day, hour, minute, second) = unix_to_utc(rounded);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:06.3}", year, month, day, hour, minute, second)
+}
+
+/// Parse an ISO 8601 / CCSDS UTC timestamp ("2024-06-01T12:00:00.000" or day-of-year "2024-153T12:00:00")
+pub fn parse_iso8601(text: &str) -> Option<f64> {
+    let text = text.trim().trim_end_matches('Z');
+    let (date, time) = text.split_once('T').unwrap_or((text, "00:00:00"));
+
+    let date_fields: Vec<&str> = date.split('-').collect();
+    let days_offset = match date_fields.as_slice() {
+        [year, month, day] => utc_to_unix(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?, 0, 0, 0.0),
+        [year, day_of_year] => {
+            let day_of_year: u32 = day_of_year.parse().ok()?;
+            utc_to_unix(year.parse().ok()?, 1, 1, 0, 0, 0.0) + (day_of_year.checked_sub(1)? as f64) * 86400.0
+        }
+        _ => return None,
+    };
+
+    let time_fields: Vec<&str> = time.split(':').collect();
+    let [hour, minute, second] = time_fields.as_slice() else {
+        return None;
+    };
+    let seconds = hour.parse::<f64>().ok()? * 3600.0 + minute.parse::<f64>().ok()? * 60.0 + second.parse::<f64>().ok()?;
+    Some(days_offset + seconds)
+}
+
+/// Julian date (UTC) of a Unix time
+pub fn unix_to_julian_date(unix_seconds: f64) -> f64 {
+    2440587.5 + unix_seconds / 86400.0
+}
+
+/// Greenwich mean sidereal time in radians (IAU 1982 model, truncated; UT1 taken as UTC)
+pub fn gmst_radians(unix_seconds: f64) -> f64 {
+    let days_since_j2000 = unix_to_julian_date(unix_seconds) - J2000_JULIAN_DATE;
+    let centuries = days_since_j2000 / 36525.0;
+    let degrees = 280.460_618_37 + 360.985_647_366_29 * days_since_j2000
+        + 0.000_387_933 * centuries * centuries;
+    degrees.rem_euclid(360.0).to_radians()
+}
+
+/// Astronomical unit (km)
+pub const ASTRONOMICAL_UNIT_KM: f64 = 149_597_870.7;
+
+/// Geocentric equatorial position of the Sun in km (Astronomical Almanac low-precision formula, ~0.01°)
+pub fn sun_position(unix_seconds: f64) -> DVec3 {
+    let days_since_j2000 = julian_date_tt(unix_seconds) - J2000_JULIAN_DATE;
+    let mean_longitude = (280.460 + 0.985_647_4 * days_since_j2000).to_radians();
+    let mean_anomaly = (357.528 + 0.985_600_3 * days_since_j2000).to_radians();
+    let ecliptic_longitude = mean_longitude
+        + 1.915_f64.to_radians() * mean_anomaly.sin()
+        + 0.020_f64.to_radians() * (2.0 * mean_anomaly).sin();
+    let obliquity = (23.439 - 0.000_000_4 * days_since_j2000).to_radians();
+    let distance = (1.000_14 - 0.016_71 * mean_anomaly.cos() - 0.000_14 * (2.0 * mean_anomaly).cos())
+        * ASTRONOMICAL_UNIT_KM;
+    DVec3::new(
+        distance * ecliptic_longitude.cos(),
+        distance * obliquity.cos() * ecliptic_longitude.sin(),
+        distance * obliquity.sin() * ecliptic_longitude.sin(),
+    )
+}
+
+/// Geocentric equatorial position of the Moon in km (Montenbruck & Gill's truncated Brown series, ~0.1°,
+/// referred to the J2000 equinox)
+pub fn moon_position(unix_seconds: f64) -> DVec3 {
+    let centuries = (julian_date_tt(unix_seconds) - J2000_JULIAN_DATE) / 36525.0;
+    let degrees = |value: f64| value.rem_euclid(360.0).to_radians();
+    let arcseconds = |value: f64| (value / 3600.0).to_radians();
+    let mean_longitude = degrees(218.316_17 + 481_267.880_88 * centuries - 1.3972 * centuries);
+    let l = degrees(134.962_92 + 477_198.867_53 * centuries); // Moon's mean anomaly
+    let ls = degrees(357.525_43 + 35_999.049_44 * centuries); // Sun's mean anomaly
+    let f = degrees(93.272_83 + 483_202.018_73 * centuries); // Mean argument of latitude
+    let d = degrees(297.850_27 + 445_267.111_35 * centuries); // Mean elongation from the Sun
+
+    let longitude = mean_longitude
+        + arcseconds(22640.0 * l.sin() + 769.0 * (2.0 * l).sin() - 4586.0 * (l - 2.0 * d).sin()
+            + 2370.0 * (2.0 * d).sin() - 668.0 * ls.sin() - 412.0 * (2.0 * f).sin()
+            - 212.0 * (2.0 * l - 2.0 * d).sin() - 206.0 * (l + ls - 2.0 * d).sin()
+            + 192.0 * (l + 2.0 * d).sin() - 165.0 * (ls - 2.0 * d).sin() + 148.0 * (l - ls).sin()
+            - 125.0 * d.sin() - 110.0 * (l + ls).sin() - 55.0 * (2.0 * f - 2.0 * d).sin());
+    let latitude = arcseconds(18520.0 * (f + longitude - mean_longitude + arcseconds(412.0 * (2.0 * f).sin() + 541.0 * ls.sin())).sin()
+        - 526.0 * (f - 2.0 * d).sin() + 44.0 * (l + f - 2.0 * d).sin() - 31.0 * (-l + f - 2.0 * d).sin()
+        - 25.0 * (-2.0 * l + f).sin() - 23.0 * (ls + f - 2.0 * d).sin() + 21.0 * (-l + f).sin()
+        + 11.0 * (-ls + f - 2.0 * d).sin());
+    let distance = 385_000.0 - 20_905.0 * l.cos() - 3699.0 * (2.0 * d - l).cos() - 2956.0 * (2.0 * d).cos()
+        - 570.0 * (2.0 * l).cos() + 246.0 * (2.0 * l - 2.0 * d).cos() - 205.0 * (ls - 2.0 * d).cos()
+        - 171.0 * (l + 2.0 * d).cos() - 152.0 * (l + ls - 2.0 * d).cos();
+
+    // Ecliptic to equatorial
+    let obliquity = 23.439_291_11_f64.to_radians();
+    let (x, y, z) = (
+        distance * latitude.cos() * longitude.cos(),
+        distance * latitude.cos() * longitude.sin(),
+        distance * latitude.sin(),
+    );
+    DVec3::new(
+        x,
+        y * obliquity.cos() - z * obliquity.sin(),
+        y * obliquity.sin() + z * obliquity.cos(),
+    )
+}
+
+/// Right ascension of the Sun in radians
+pub fn sun_right_ascension(unix_seconds: f64) -> f64 {
+    let sun = sun_position(unix_seconds);
+    sun.y.atan2(sun.x).rem_euclid(std::f64::consts::TAU)
+}
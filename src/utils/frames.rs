@@ -0,0 +1,66 @@
+// Inertial (ECI) and Earth-fixed (ECEF) reference frames
+//
+// The simulation integrates in ECI with Z towards the north pole; the Earth-fixed frame turns about Z by the
+// Greenwich mean sidereal time, ignoring precession, nutation and polar motion.
+
+use bevy::math::{DQuat, DVec3};
+use crate::utils::gmst_radians;
+
+/// Earth's sidereal rotation rate (rad/s)
+pub const EARTH_ROTATION_RATE: f64 = 7.292_115e-5;
+
+/// Rotation taking Earth-fixed vectors into the inertial frame at a Unix time
+pub fn earth_rotation(unix_seconds: f64) -> DQuat {
+    DQuat::from_rotation_z(gmst_radians(unix_seconds))
+}
+
+/// Rotate an inertial position (km) into the Earth-fixed frame at a Unix time
+pub fn eci_to_ecef(position: DVec3, unix_seconds: f64) -> DVec3 {
+    earth_rotation(unix_seconds).inverse() * position
+}
+
+/// Rotate an Earth-fixed state (km, km/s) into the inertial frame at a Unix time
+pub fn ecef_to_eci(position: DVec3, velocity: DVec3, unix_seconds: f64) -> (DVec3, DVec3) {
+    let rotation = earth_rotation(unix_seconds);
+    let omega = DVec3::new(0.0, 0.0, EARTH_ROTATION_RATE);
+    let inertial_velocity = velocity + omega.cross(position);
+    (rotation * position, rotation * inertial_velocity)
+}
+
+/// Earth-fixed position (km) of a spherical-Earth latitude/longitude (degrees) and altitude (km)
+pub fn geodetic_to_ecef(latitude: f64, longitude: f64, altitude: f64, earth_radius_km: f64) -> DVec3 {
+    let (lat, lon) = (latitude.to_radians(), longitude.to_radians());
+    DVec3::new(lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()) * (earth_radius_km + altitude)
+}
+
+/// Spherical-Earth latitude/longitude (degrees) and altitude (km) of an Earth-fixed position (km)
+pub fn ecef_to_geodetic(position: DVec3, earth_radius_km: f64) -> (f64, f64, f64) {
+    let r = position.length();
+    if r <= 0.0 {
+        return (0.0, 0.0, -earth_radius_km);
+    }
+    let latitude = (position.z / r).asin().to_degrees();
+    // Wrap into [-180, 180)
+    let longitude = (position.y.atan2(position.x).to_degrees() + 180.0).rem_euclid(360.0) - 180.0;
+    (latitude, longitude, r - earth_radius_km)
+}
+
+/// Convert an ECI position (km) to spherical-Earth latitude/longitude (degrees) and altitude (km)
+pub fn eci_to_geodetic(position: DVec3, unix_seconds: f64, earth_radius_km: f64) -> (f64, f64, f64) {
+    ecef_to_geodetic(eci_to_ecef(position, unix_seconds), earth_radius_km)
+}
+
+/// Inertial vector of components along an orbit's radial, transverse and normal (RTN) directions
+pub fn rtn_to_eci(position: DVec3, velocity: DVec3, rtn: DVec3) -> DVec3 {
+    let radial = position.normalize_or_zero();
+    let normal = position.cross(velocity).normalize_or_zero();
+    let transverse = normal.cross(radial);
+    radial * rtn.x + transverse * rtn.y + normal * rtn.z
+}
+
+/// Elevation (degrees) of a target above an observer's local horizon, both Earth-fixed (km)
+pub fn elevation_angle(observer: DVec3, target: DVec3) -> f64 {
+    let line_of_sight = (target - observer).normalize_or_zero();
+    let up = observer.normalize_or_zero();
+    line_of_sight.dot(up).clamp(-1.0, 1.0).asin().to_degrees()
+}
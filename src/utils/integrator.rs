@@ -0,0 +1,121 @@
+// Integrators shared by the physics pipelines
+
+use bevy::math::DVec3;
+use crate::resources::{Integrator, IntegratorConfig};
+
+/// Smallest adaptive step (seconds); steps this short are accepted whatever their error
+const MIN_ADAPTIVE_STEP: f64 = 1e-3;
+/// Bounds on how much one adaptive step may shrink or grow the next
+const STEP_FACTOR_RANGE: (f64, f64) = (0.2, 5.0);
+/// Safety factor on the optimal adaptive step
+const STEP_SAFETY: f64 = 0.9;
+
+/// Dormand-Prince 5(4) tableau: nodes, stage coefficients, fifth-order weights and their difference
+/// from the embedded fourth-order weights
+const DP_C: [f64; 7] = [0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+const DP_A: [[f64; 6]; 7] = [
+    [0.0; 6],
+    [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+    [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+    [19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0, 0.0, 0.0],
+    [9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0, 0.0],
+    [35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0],
+];
+const DP_ERROR: [f64; 7] = [
+    71.0 / 57600.0, 0.0, -71.0 / 16695.0, 71.0 / 1920.0, -17253.0 / 339200.0, 22.0 / 525.0, -1.0 / 40.0,
+];
+
+/// Advance a state (km, km/s) by `steps` steps of `dt` seconds under `acceleration(position, elapsed)`
+/// (km/s², with `elapsed` the seconds since the first step)
+///
+/// The adaptive integrator covers the same span, starting from steps of `dt`.
+pub fn integrate(
+    config: IntegratorConfig,
+    mut position: DVec3,
+    mut velocity: DVec3,
+    dt: f64,
+    steps: u32,
+    acceleration: impl Fn(DVec3, f64) -> DVec3,
+) -> (DVec3, DVec3) {
+    match config.method {
+        Integrator::Euler => {
+            // Semi-implicit: the position moves with the updated velocity
+            for step in 0..steps {
+                velocity += acceleration(position, step as f64 * dt) * dt;
+                position += velocity * dt;
+            }
+        }
+        Integrator::Verlet => {
+            // Kick-drift-kick leapfrog; the closing acceleration of each step opens the next
+            let mut current = acceleration(position, 0.0);
+            for step in 0..steps {
+                velocity += current * (dt / 2.0);
+                position += velocity * dt;
+                current = acceleration(position, (step + 1) as f64 * dt);
+                velocity += current * (dt / 2.0);
+            }
+        }
+        Integrator::DormandPrince => {
+            let mut step = dt;
+            return integrate_adaptive(position, velocity, dt * steps as f64, &mut step, config.tolerance, acceleration);
+        }
+    }
+    (position, velocity)
+}
+
+/// Dormand-Prince 5(4) over `duration` seconds, keeping the estimated position error of each step within
+/// `tolerance` (km); velocity errors count by the position error they cause over the step
+///
+/// `step` is the first step to try, and is left at the step to try next so callers can carry it over.
+pub fn integrate_adaptive(
+    mut position: DVec3,
+    mut velocity: DVec3,
+    duration: f64,
+    step: &mut f64,
+    tolerance: f64,
+    acceleration: impl Fn(DVec3, f64) -> DVec3,
+) -> (DVec3, DVec3) {
+    let mut elapsed = 0.0;
+    *step = step.max(MIN_ADAPTIVE_STEP);
+    // First same as last: the final stage of an accepted step is the first stage of the next
+    let mut first = acceleration(position, 0.0);
+
+    while elapsed < duration {
+        let h = step.min(duration - elapsed);
+        let mut stage_velocity = [DVec3::ZERO; 7];
+        let mut stage_acceleration = [DVec3::ZERO; 7];
+        stage_velocity[0] = velocity;
+        stage_acceleration[0] = first;
+        for i in 1..7 {
+            let (mut dr, mut dv) = (DVec3::ZERO, DVec3::ZERO);
+            for j in 0..i {
+                dr += stage_velocity[j] * DP_A[i][j];
+                dv += stage_acceleration[j] * DP_A[i][j];
+            }
+            stage_velocity[i] = velocity + dv * h;
+            stage_acceleration[i] = acceleration(position + dr * h, elapsed + DP_C[i] * h);
+        }
+
+        let (mut error_r, mut error_v) = (DVec3::ZERO, DVec3::ZERO);
+        for i in 0..7 {
+            error_r += stage_velocity[i] * DP_ERROR[i];
+            error_v += stage_acceleration[i] * DP_ERROR[i];
+        }
+        let error = (error_r * h).length().max((error_v * h).length() * h) / tolerance;
+
+        let accepted = error <= 1.0 || h <= MIN_ADAPTIVE_STEP;
+        if accepted {
+            // The seventh stage is evaluated at the fifth-order solution
+            position += (0..6).map(|j| stage_velocity[j] * DP_A[6][j]).sum::<DVec3>() * h;
+            velocity = stage_velocity[6];
+            first = stage_acceleration[6];
+            elapsed += h;
+        }
+        let factor = if error > 0.0 { STEP_SAFETY * error.powf(-0.2) } else { STEP_FACTOR_RANGE.1 };
+        let proposed = (h * factor.clamp(STEP_FACTOR_RANGE.0, STEP_FACTOR_RANGE.1)).max(MIN_ADAPTIVE_STEP);
+        // A step cut short by the end of the span says nothing about growing beyond the current step
+        *step = if accepted && h < *step { step.min(proposed) } else { proposed };
+    }
+    (position, velocity)
+}
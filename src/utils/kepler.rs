@@ -0,0 +1,481 @@
+// Analytic two-body (Keplerian) propagation using universal variables
+
+use bevy::prelude::*;
+use bevy::math::{DQuat, DVec3};
+
+/// Maximum Newton iterations when solving the universal Kepler equation
+const MAX_ITERATIONS: usize = 50;
+/// Convergence tolerance on the universal anomaly
+const TOLERANCE: f64 = 1e-8;
+
+/// Stumpff function C(z)
+fn stumpff_c(z: f64) -> f64 {
+    if z > 1e-6 {
+        (1.0 - z.sqrt().cos()) / z
+    } else if z < -1e-6 {
+        ((-z).sqrt().cosh() - 1.0) / -z
+    } else {
+        0.5 - z / 24.0
+    }
+}
+
+/// Stumpff function S(z)
+fn stumpff_s(z: f64) -> f64 {
+    if z > 1e-6 {
+        let sz = z.sqrt();
+        (sz - sz.sin()) / (sz * sz * sz)
+    } else if z < -1e-6 {
+        let sz = (-z).sqrt();
+        (sz.sinh() - sz) / (sz * sz * sz)
+    } else {
+        1.0 / 6.0 - z / 120.0
+    }
+}
+
+/// Propagate a state vector analytically along its conic for `dt` seconds
+///
+/// Position in km, velocity in km/s, `mu` in km³/s². Works for elliptic and
+/// hyperbolic orbits; returns the input unchanged for degenerate states.
+pub fn propagate_kepler(r0: DVec3, v0: DVec3, dt: f64, mu: f64) -> (DVec3, DVec3) {
+    let r0_mag = r0.length();
+
+    if r0_mag <= 0.0 || mu <= 0.0 || dt == 0.0 {
+        return (r0, v0);
+    }
+
+    let sqrt_mu = mu.sqrt();
+    let vr0 = r0.dot(v0) / r0_mag;
+    // Reciprocal of the semi-major axis (negative for hyperbolic orbits)
+    let alpha = 2.0 / r0_mag - v0.length_squared() / mu;
+
+    // Initial guess for the universal anomaly
+    let mut chi = if alpha > 1e-9 {
+        sqrt_mu * alpha.abs() * dt
+    } else {
+        sqrt_mu * dt / r0_mag
+    };
+
+    for _ in 0..MAX_ITERATIONS {
+        let z = alpha * chi * chi;
+        let c = stumpff_c(z);
+        let s = stumpff_s(z);
+
+        let f = r0_mag * vr0 / sqrt_mu * chi * chi * c
+            + (1.0 - alpha * r0_mag) * chi * chi * chi * s
+            + r0_mag * chi
+            - sqrt_mu * dt;
+        let df = r0_mag * vr0 / sqrt_mu * chi * (1.0 - z * s)
+            + (1.0 - alpha * r0_mag) * chi * chi * c
+            + r0_mag;
+
+        if df == 0.0 {
+            break;
+        }
+        let step = f / df;
+        chi -= step;
+        if step.abs() < TOLERANCE {
+            break;
+        }
+    }
+
+    if !chi.is_finite() {
+        return (r0, v0);
+    }
+
+    // Lagrange coefficients
+    let z = alpha * chi * chi;
+    let c = stumpff_c(z);
+    let s = stumpff_s(z);
+
+    let f = 1.0 - chi * chi / r0_mag * c;
+    let g = dt - chi * chi * chi / sqrt_mu * s;
+    let r: DVec3 = f * r0 + g * v0;
+    let r_mag = r.length();
+
+    if r_mag <= 0.0 || !r_mag.is_finite() {
+        return (r0, v0);
+    }
+
+    let f_dot = sqrt_mu / (r_mag * r0_mag) * (alpha * chi * chi * chi * s - chi);
+    let g_dot = 1.0 - chi * chi / r_mag * c;
+    let v: DVec3 = f_dot * r0 + g_dot * v0;
+
+    (r, v)
+}
+
+/// Orbital period in seconds, or None for unbound orbits
+pub fn orbital_period(position: DVec3, velocity: DVec3, mu: f64) -> Option<f64> {
+    let r = position.length();
+    let alpha = 2.0 / r - velocity.length_squared() / mu;
+    if alpha <= 0.0 || !alpha.is_finite() {
+        return None;
+    }
+    let semi_major_axis = 1.0 / alpha;
+    Some(std::f64::consts::TAU * (semi_major_axis.powi(3) / mu).sqrt())
+}
+
+/// Classical orbital elements (km, radians)
+#[derive(Debug, Clone, Copy)]
+pub struct OrbitalElements {
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    pub inclination: f64,
+    pub raan: f64,
+    pub argument_of_perigee: f64,
+    pub mean_anomaly: f64,
+}
+
+impl OrbitalElements {
+    /// Mean motion in revolutions per day
+    pub fn mean_motion_rev_per_day(&self, mu: f64) -> f64 {
+        (mu / self.semi_major_axis.powi(3)).sqrt() * 86400.0 / std::f64::consts::TAU
+    }
+}
+
+/// Angle from `a` to `b` measured counter-clockwise about `normal`, in [0, 2π)
+fn angle_about(a: DVec3, b: DVec3, normal: DVec3) -> f64 {
+    normal.dot(a.cross(b)).atan2(a.dot(b)).rem_euclid(std::f64::consts::TAU)
+}
+
+/// Osculating Keplerian elements of a bound state, or None for unbound or degenerate states
+///
+/// Circular orbits report zero argument of perigee and equatorial orbits measure from the x axis.
+pub fn orbital_elements(r: DVec3, v: DVec3, mu: f64) -> Option<OrbitalElements> {
+    let r_mag = r.length();
+    let h = r.cross(v);
+    if r_mag <= 0.0 || h.length_squared() <= 0.0 {
+        return None;
+    }
+
+    let alpha = 2.0 / r_mag - v.length_squared() / mu;
+    let eccentricity_vector = ((v.length_squared() - mu / r_mag) * r - r.dot(v) * v) / mu;
+    let eccentricity = eccentricity_vector.length();
+    if alpha <= 0.0 || eccentricity >= 1.0 {
+        return None;
+    }
+
+    let h_hat = h.normalize();
+    let node = node_direction(h);
+
+    let inclination = h_hat.z.clamp(-1.0, 1.0).acos();
+    let raan = node.y.atan2(node.x).rem_euclid(std::f64::consts::TAU);
+    let argument_of_perigee = if eccentricity > 1e-9 {
+        angle_about(node, eccentricity_vector, h_hat)
+    } else {
+        0.0
+    };
+    let true_anomaly = (angle_about(node, r, h_hat) - argument_of_perigee).rem_euclid(std::f64::consts::TAU);
+
+    let eccentric_anomaly = 2.0 * (((1.0 - eccentricity) / (1.0 + eccentricity)).sqrt() * (true_anomaly / 2.0).tan()).atan();
+    let mean_anomaly = (eccentric_anomaly - eccentricity * eccentric_anomaly.sin()).rem_euclid(std::f64::consts::TAU);
+
+    Some(OrbitalElements {
+        semi_major_axis: 1.0 / alpha,
+        eccentricity,
+        inclination,
+        raan,
+        argument_of_perigee,
+        mean_anomaly,
+    })
+}
+
+/// Ascending node direction of an orbit with angular momentum `h` (the x axis for equatorial orbits)
+fn node_direction(h: DVec3) -> DVec3 {
+    let node = DVec3::new(-h.y, h.x, 0.0);
+    if node.length() > 1e-9 * h.length() { node.normalize() } else { DVec3::X }
+}
+
+/// Argument of latitude (radians from the ascending node, in [0, 2π)) of a state
+pub fn argument_of_latitude(r: DVec3, velocity: DVec3) -> f64 {
+    let h = r.cross(velocity);
+    if h.length_squared() <= 0.0 {
+        return 0.0;
+    }
+    angle_about(node_direction(h), r, h.normalize())
+}
+
+/// State vector on the orbit described by `elements` at the given true anomaly (radians); the mean anomaly is ignored
+pub fn state_at_true_anomaly(elements: &OrbitalElements, true_anomaly: f64, mu: f64) -> (DVec3, DVec3) {
+    let e = elements.eccentricity;
+    let p = elements.semi_major_axis * (1.0 - e * e);
+    let r = p / (1.0 + e * true_anomaly.cos());
+    let position = DVec3::new(r * true_anomaly.cos(), r * true_anomaly.sin(), 0.0);
+    let velocity = DVec3::new(-true_anomaly.sin(), e + true_anomaly.cos(), 0.0) * (mu / p).sqrt();
+
+    // Perifocal to inertial: rotate by RAAN, inclination and argument of perigee
+    let rotation = DQuat::from_rotation_z(elements.raan)
+        * DQuat::from_rotation_x(elements.inclination)
+        * DQuat::from_rotation_z(elements.argument_of_perigee);
+    (rotation * position, rotation * velocity)
+}
+
+/// Secular J2 drift rates (rad/s) of the RAAN and argument of perigee of an elliptic orbit
+///
+/// `reference_radius` is the equatorial radius (km) the J2 coefficient is normalized to.
+pub fn j2_secular_rates(elements: &OrbitalElements, j2: f64, reference_radius: f64, mu: f64) -> (f64, f64) {
+    let a = elements.semi_major_axis;
+    let p = a * (1.0 - elements.eccentricity * elements.eccentricity);
+    let mean_motion = (mu / a.powi(3)).sqrt();
+    let rate = 1.5 * mean_motion * j2 * (reference_radius / p).powi(2);
+    let cos_i = elements.inclination.cos();
+    (-rate * cos_i, 0.5 * rate * (5.0 * cos_i * cos_i - 1.0))
+}
+
+/// Kepler propagation including the secular J2 precession of the node and perigee
+///
+/// The conic is followed analytically and then turned about the orbit normal by the perigee drift and
+/// about the polar axis by the nodal drift; short-period J2 terms are ignored.
+pub fn propagate_kepler_j2(position: DVec3, velocity: DVec3, dt: f64, mu: f64, j2: f64, reference_radius: f64) -> (DVec3, DVec3) {
+    let (new_position, new_velocity) = propagate_kepler(position, velocity, dt, mu);
+    let Some(elements) = orbital_elements(position, velocity, mu).filter(|e| j2 != 0.0 && e.eccentricity < 1.0) else {
+        return (new_position, new_velocity);
+    };
+    let (raan_rate, perigee_rate) = j2_secular_rates(&elements, j2, reference_radius, mu);
+    let normal = position.cross(velocity).normalize_or_zero();
+    let rotation = DQuat::from_rotation_z(raan_rate * dt) * DQuat::from_axis_angle(normal, perigee_rate * dt);
+    (rotation * new_position, rotation * new_velocity)
+}
+
+/// Find the next closest approach of two objects within `horizon` seconds by propagating both conics
+///
+/// Only minima ahead of the current time count, so a receding pair reports its next encounter.
+/// Returns (seconds until closest approach, separation in km).
+pub fn closest_approach(
+    (position_a, velocity_a): (DVec3, DVec3),
+    (position_b, velocity_b): (DVec3, DVec3),
+    horizon: f64,
+    mu: f64,
+) -> (f64, f64) {
+    const SAMPLES: usize = 720;
+    const REFINE_ITERATIONS: usize = 30;
+
+    let separation = |t: f64| {
+        let (a, _) = propagate_kepler(position_a, velocity_a, t, mu);
+        let (b, _) = propagate_kepler(position_b, velocity_b, t, mu);
+        a.distance(b)
+    };
+
+    // Coarse sampling to bracket the deepest local minimum ahead of now
+    let step = horizon / SAMPLES as f64;
+    let samples: Vec<f64> = (0..=SAMPLES).map(|i| separation(i as f64 * step)).collect();
+    let mut best_index = SAMPLES;
+    let mut best_distance = samples[SAMPLES];
+    for (i, window) in samples.windows(3).enumerate() {
+        let distance = window[1];
+        if distance <= window[0] && distance <= window[2] && distance < best_distance {
+            best_distance = distance;
+            best_index = i + 1;
+        }
+    }
+
+    // Golden-section refinement inside the bracketing samples
+    let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+    let mut low = (best_index - 1) as f64 * step;
+    let mut high = ((best_index + 1).min(SAMPLES)) as f64 * step;
+    for _ in 0..REFINE_ITERATIONS {
+        let left = high - ratio * (high - low);
+        let right = low + ratio * (high - low);
+        if separation(left) < separation(right) {
+            high = right;
+        } else {
+            low = left;
+        }
+    }
+
+    let tca = (low + high) / 2.0;
+    let distance = separation(tca);
+    if distance < best_distance {
+        (tca, distance)
+    } else {
+        (best_index as f64 * step, best_distance)
+    }
+}
+
+/// Find the first approach of two objects closer than `threshold` km within `horizon` seconds
+///
+/// Every local minimum of the sampled separation is refined in turn, so brief crossing encounters are not
+/// skipped in favour of a deeper one later on. Returns (seconds until closest approach, separation in km,
+/// relative speed in km/s).
+pub fn next_approach_within(
+    (position_a, velocity_a): (DVec3, DVec3),
+    (position_b, velocity_b): (DVec3, DVec3),
+    threshold: f64,
+    horizon: f64,
+    mu: f64,
+) -> Option<(f64, f64, f64)> {
+    const STEP: f64 = 30.0;
+    const REFINE_ITERATIONS: usize = 40;
+
+    let states = |t: f64| {
+        (propagate_kepler(position_a, velocity_a, t, mu), propagate_kepler(position_b, velocity_b, t, mu))
+    };
+    let separation = |t: f64| {
+        let ((a, _), (b, _)) = states(t);
+        a.distance(b)
+    };
+
+    let steps = (horizon / STEP).ceil().max(2.0) as usize;
+    let step = horizon / steps as f64;
+    let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+    let (mut previous, mut current) = (separation(0.0), separation(step));
+    for i in 1..steps {
+        let next = separation((i + 1) as f64 * step);
+        if current <= previous && current <= next {
+            // Golden-section refinement inside the bracketing samples
+            let (mut low, mut high) = ((i - 1) as f64 * step, (i + 1) as f64 * step);
+            for _ in 0..REFINE_ITERATIONS {
+                let left = high - ratio * (high - low);
+                let right = low + ratio * (high - low);
+                if separation(left) < separation(right) {
+                    high = right;
+                } else {
+                    low = left;
+                }
+            }
+            let tca = (low + high) / 2.0;
+            let ((a, a_velocity), (b, b_velocity)) = states(tca);
+            let miss = a.distance(b);
+            if miss <= threshold {
+                return Some((tca, miss, (a_velocity - b_velocity).length()));
+            }
+        }
+        previous = current;
+        current = next;
+    }
+    None
+}
+
+/// Largest difference in orbit radius (km) still treated as a same-orbit rendezvous
+const SAME_ORBIT_TOLERANCE: f64 = 1.0;
+/// Longest coast (s) spent waiting for the Hohmann phase before a phasing orbit is flown instead
+const MAX_PHASING_WAIT: f64 = 24.0 * 3600.0;
+/// Most revolutions flown in a phasing orbit
+const MAX_PHASING_REVOLUTIONS: u32 = 15;
+
+/// How a rendezvous transfer reaches the target
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransferKind {
+    /// Coast until the phase is right, then a two-burn Hohmann transfer
+    Hohmann,
+    /// Phasing orbit to fix the phase, followed by a Hohmann transfer when the radii differ
+    Phasing { revolutions: u32 },
+}
+
+/// Prograde velocity change (km/s, negative is retrograde) at an offset (s) from the start of a transfer
+#[derive(Clone, Copy, Debug)]
+pub struct TransferBurn {
+    pub offset: f64,
+    pub delta_v: f64,
+}
+
+/// Rendezvous transfer between two near-circular orbits
+#[derive(Clone, Debug)]
+pub struct TransferPlan {
+    pub kind: TransferKind,
+    pub burns: Vec<TransferBurn>,
+    /// Seconds from now until the chaser arrives behind the target
+    pub duration: f64,
+    /// Angle between the two orbit planes (radians); plane changes are not part of the plan
+    pub plane_angle: f64,
+}
+
+impl TransferPlan {
+    /// Sum of burn magnitudes (km/s)
+    pub fn total_delta_v(&self) -> f64 {
+        self.burns.iter().map(|burn| burn.delta_v.abs()).sum()
+    }
+}
+
+/// Plan a rendezvous of `chaser` with `target`, arriving `standoff` km behind it along its orbit
+///
+/// Both orbits are treated as circular at their semi-major axes and in the chaser's plane. A phasing
+/// orbit never dips below `min_radius` (km).
+pub fn plan_transfer(
+    (chaser_position, chaser_velocity): (DVec3, DVec3),
+    (target_position, target_velocity): (DVec3, DVec3),
+    standoff: f64,
+    min_radius: f64,
+    mu: f64,
+) -> Option<TransferPlan> {
+    use std::f64::consts::{PI, TAU};
+
+    let chaser = orbital_elements(chaser_position, chaser_velocity, mu)?;
+    let target = orbital_elements(target_position, target_velocity, mu)?;
+    let (r1, r2) = (chaser.semi_major_axis, target.semi_major_axis);
+    let (n1, n2) = ((mu / r1.powi(3)).sqrt(), (mu / r2.powi(3)).sqrt());
+
+    // Phase of the target ahead of the chaser, measured in the chaser's plane
+    let h = chaser_position.cross(chaser_velocity).normalize();
+    let target_in_plane = target_position - h * h.dot(target_position);
+    let phase = angle_about(chaser_position, target_in_plane, h);
+    let plane_angle = h.angle_between(target_position.cross(target_velocity));
+    let standoff_angle = standoff / r2;
+
+    // Phase the target must lead by when the final leg starts, and the burns of that leg
+    let (goal, mut leg, leg_duration) = if (r2 - r1).abs() < SAME_ORBIT_TOLERANCE {
+        (standoff_angle, Vec::new(), 0.0)
+    } else {
+        let transfer_time = PI * (((r1 + r2) / 2.0).powi(3) / mu).sqrt();
+        let departure = (mu / r1).sqrt() * ((2.0 * r2 / (r1 + r2)).sqrt() - 1.0);
+        let arrival = (mu / r2).sqrt() * (1.0 - (2.0 * r1 / (r1 + r2)).sqrt());
+        let burns = vec![
+            TransferBurn { offset: 0.0, delta_v: departure },
+            TransferBurn { offset: transfer_time, delta_v: arrival },
+        ];
+        (PI + standoff_angle - n2 * transfer_time, burns, transfer_time)
+    };
+
+    // Coasting drifts the phase at the difference of mean motions
+    let rate = n2 - n1;
+    let drift_wait = if leg.is_empty() {
+        None
+    } else if rate > 0.0 {
+        Some((goal - phase).rem_euclid(TAU) / rate)
+    } else if rate < 0.0 {
+        Some((phase - goal).rem_euclid(TAU) / -rate)
+    } else {
+        None
+    };
+
+    let (kind, wait, mut burns) = match drift_wait {
+        Some(wait) if wait <= MAX_PHASING_WAIT => (TransferKind::Hohmann, wait, Vec::new()),
+        _ => {
+            // The chaser gains `ahead` radians on a lower, faster orbit or loses TAU - ahead on a higher one
+            let ahead = (phase - goal).rem_euclid(TAU);
+            let circular_speed = (mu / r1).sqrt();
+            let mut best: Option<(f64, u32, f64)> = None;
+            for revolutions in 1..=MAX_PHASING_REVOLUTIONS {
+                let k = revolutions as f64;
+                for lead in [ahead, ahead - TAU] {
+                    let period = (TAU * k - lead) / (k * n2);
+                    let semi_major_axis = (mu * (period / TAU).powi(2)).cbrt();
+                    if 2.0 * semi_major_axis - r1 < min_radius {
+                        continue;
+                    }
+                    let delta_v = (mu * (2.0 / r1 - 1.0 / semi_major_axis)).sqrt() - circular_speed;
+                    if best.is_none_or(|(best_delta_v, ..)| delta_v.abs() < best_delta_v.abs()) {
+                        best = Some((delta_v, revolutions, period * k));
+                    }
+                }
+            }
+            let (delta_v, revolutions, phasing_time) = best?;
+            let burns = vec![
+                TransferBurn { offset: 0.0, delta_v },
+                TransferBurn { offset: phasing_time, delta_v: -delta_v },
+            ];
+            (TransferKind::Phasing { revolutions }, phasing_time, burns)
+        }
+    };
+
+    for burn in leg.iter_mut() {
+        burn.offset += wait;
+    }
+    burns.extend(leg);
+    Some(TransferPlan {
+        kind,
+        burns,
+        duration: wait + leg_duration,
+        plane_angle,
+    })
+}
+
@@ -1,5 +1,34 @@
+pub mod atmosphere;
+pub mod cdm;
+pub mod cli;
+pub(crate) mod csv;
+pub mod discos;
+pub mod epoch;
+pub mod frames;
+pub mod integrator;
+pub mod kepler;
+pub mod omm;
+pub mod probability;
+pub mod publish;
+pub mod satcat;
+pub mod sso;
+pub mod stk_ephemeris;
 pub mod tle_parser;
 pub mod sgp4_wrapper;
 
+pub use atmosphere::*;
+pub use cdm::*;
+pub use cli::*;
+pub use discos::*;
+pub use epoch::*;
+pub use frames::*;
+pub use integrator::*;
+pub use kepler::*;
+pub use omm::*;
+pub use probability::*;
+pub use publish::*;
+pub use satcat::*;
+pub use sso::*;
+pub use stk_ephemeris::*;
 pub use tle_parser::*;
 pub use sgp4_wrapper::*;
\ No newline at end of file
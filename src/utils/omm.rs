@@ -0,0 +1,237 @@
+// CCSDS Orbit Mean-elements Messages in the JSON, CSV and XML layouts served by CelesTrak
+//
+// Every record is normalized to a TleRecord with element lines rebuilt from its fields, so OMM data is propagated
+// with SGP4 exactly like TLE text.
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+use crate::utils::{parse_iso8601, parse_tle_data, unix_to_utc, utc_to_unix, TleParseError, TleRecord};
+use crate::utils::csv::split_csv_line;
+
+/// General perturbations element set encodings, as named by the FORMAT parameter of the CelesTrak GP API
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GpFormat {
+    #[default]
+    Tle,
+    Json,
+    Csv,
+    Xml,
+}
+
+impl GpFormat {
+    /// Value of the FORMAT query parameter
+    pub fn query_value(&self) -> &'static str {
+        match self {
+            GpFormat::Tle => "tle",
+            GpFormat::Json => "json",
+            GpFormat::Csv => "csv",
+            GpFormat::Xml => "xml",
+        }
+    }
+
+    /// Format of a file by its extension, for files in the TLE directory
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "tle" => Some(GpFormat::Tle),
+            "json" => Some(GpFormat::Json),
+            "csv" => Some(GpFormat::Csv),
+            "xml" => Some(GpFormat::Xml),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for GpFormat {
+    type Err = String;
+
+    /// Parse "tle", "json", "csv" or "xml"
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "tle" | "3le" => Ok(GpFormat::Tle),
+            "json" => Ok(GpFormat::Json),
+            "csv" => Ok(GpFormat::Csv),
+            "xml" => Ok(GpFormat::Xml),
+            other => Err(format!("unknown GP format {}", other)),
+        }
+    }
+}
+
+/// Parse element sets in any GP format; records that fail to parse are skipped
+pub fn parse_gp_data(data: &str, format: GpFormat) -> Result<Vec<TleRecord>, TleParseError> {
+    let fields = match format {
+        GpFormat::Tle => return parse_tle_data(data),
+        GpFormat::Json => omm_json_fields(data)?,
+        GpFormat::Csv => omm_csv_fields(data)?,
+        GpFormat::Xml => omm_xml_fields(data),
+    };
+    Ok(fields
+        .iter()
+        .filter_map(|fields| match record_from_omm(fields) {
+            Ok(record) => Some(record),
+            Err(e) => {
+                debug!("Failed to parse OMM record for {:?}: {}", fields.get("OBJECT_NAME"), e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Keyword/value pairs of each record in an OMM JSON array (numbers and strings both accepted)
+fn omm_json_fields(data: &str) -> Result<Vec<HashMap<String, String>>, TleParseError> {
+    let value: serde_json::Value = serde_json::from_str(data).map_err(|_| TleParseError::InvalidFormat)?;
+    let records = match value {
+        serde_json::Value::Array(records) => records,
+        record @ serde_json::Value::Object(_) => vec![record],
+        _ => return Err(TleParseError::InvalidFormat),
+    };
+    Ok(records
+        .iter()
+        .filter_map(|record| record.as_object())
+        .map(|record| {
+            record.iter()
+                .filter_map(|(key, value)| {
+                    let value = match value {
+                        serde_json::Value::String(text) => text.clone(),
+                        serde_json::Value::Number(number) => number.to_string(),
+                        _ => return None,
+                    };
+                    Some((key.to_ascii_uppercase(), value))
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Keyword/value pairs of each row of an OMM CSV table, keyed by its header row
+fn omm_csv_fields(data: &str) -> Result<Vec<HashMap<String, String>>, TleParseError> {
+    let mut lines = data.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<String> = split_csv_line(lines.next().ok_or(TleParseError::InvalidFormat)?)
+        .into_iter()
+        .map(|key| key.to_ascii_uppercase())
+        .collect();
+    if !header.iter().any(|key| key == "NORAD_CAT_ID") {
+        return Err(TleParseError::InvalidFormat);
+    }
+    Ok(lines
+        .map(|line| header.iter().cloned().zip(split_csv_line(line)).collect())
+        .collect())
+}
+
+/// Keyword/value pairs of each `<omm>` element of an OMM XML document (the leaf elements, wherever they nest)
+fn omm_xml_fields(data: &str) -> Vec<HashMap<String, String>> {
+    let mut records = Vec::new();
+    let mut rest = data;
+    while let Some(start) = find_element(rest, "omm") {
+        let body = &rest[start..];
+        let end = body.find("</omm>").map_or(body.len(), |end| end + "</omm>".len());
+        records.push(xml_leaf_values(&body[..end]));
+        rest = &body[end..];
+    }
+    records
+}
+
+/// Offset of the first `<name>` or `<name ...>` start tag
+fn find_element(text: &str, name: &str) -> Option<usize> {
+    let open = format!("<{}", name);
+    let mut offset = 0;
+    while let Some(found) = text[offset..].find(&open) {
+        let at = offset + found;
+        match text[at + open.len()..].chars().next() {
+            Some('>') | Some(' ') | Some('\t') | Some('\n') | Some('\r') | Some('/') => return Some(at),
+            _ => offset = at + open.len(),
+        }
+    }
+    None
+}
+
+/// Text of every element that holds only text, keyed by element name
+fn xml_leaf_values(text: &str) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('<') {
+        rest = &rest[open + 1..];
+        let Some(close) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..close];
+        rest = &rest[close + 1..];
+        if tag.starts_with(['/', '?', '!']) || tag.ends_with('/') {
+            continue;
+        }
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        let Some(content_end) = rest.find('<') else {
+            break;
+        };
+        if rest[content_end..].starts_with(&format!("</{}>", name)) {
+            values.insert(name.to_ascii_uppercase(), unescape_xml(rest[..content_end].trim()));
+        }
+    }
+    values
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Build a TleRecord, element lines included, from OMM keyword/value pairs
+fn record_from_omm(fields: &HashMap<String, String>) -> Result<TleRecord, TleParseError> {
+    let text = |key: &str| fields.get(key).map(|value| value.trim()).filter(|value| !value.is_empty());
+    let number = |key: &str| -> Result<f64, TleParseError> {
+        text(key)
+            .and_then(|value| value.parse::<f64>().ok())
+            .ok_or_else(|| TleParseError::InvalidField(key.to_string()))
+    };
+    let optional = |key: &str| text(key).and_then(|value| value.parse::<f64>().ok()).unwrap_or(0.0);
+
+    let norad_id = number("NORAD_CAT_ID")? as u32;
+    // Five-digit element lines cannot carry larger catalog numbers
+    if norad_id > 99_999 {
+        return Err(TleParseError::InvalidField("NORAD_CAT_ID".to_string()));
+    }
+    let epoch = text("EPOCH")
+        .and_then(parse_iso8601)
+        .ok_or_else(|| TleParseError::InvalidField("EPOCH".to_string()))?;
+    let (year, ..) = unix_to_utc(epoch);
+    let epoch_day = (epoch - utc_to_unix(year, 1, 1, 0, 0, 0.0)) / 86400.0 + 1.0;
+
+    let mut record = TleRecord {
+        name: text("OBJECT_NAME").unwrap_or("UNKNOWN").to_string(),
+        norad_id,
+        classification: text("CLASSIFICATION_TYPE").and_then(|value| value.chars().next()).unwrap_or('U'),
+        international_designator: tle_designator(text("OBJECT_ID").unwrap_or_default()),
+        epoch_year: year.rem_euclid(100) as u32,
+        epoch_day,
+        mean_motion_dot: optional("MEAN_MOTION_DOT"),
+        mean_motion_ddot: optional("MEAN_MOTION_DDOT"),
+        bstar: optional("BSTAR"),
+        inclination: number("INCLINATION")?,
+        right_ascension: number("RA_OF_ASC_NODE")?,
+        eccentricity: number("ECCENTRICITY")?,
+        argument_of_perigee: number("ARG_OF_PERICENTER")?,
+        mean_anomaly: number("MEAN_ANOMALY")?,
+        mean_motion: number("MEAN_MOTION")?,
+        revolution_number: optional("REV_AT_EPOCH") as u32,
+        line1: String::new(),
+        line2: String::new(),
+    };
+    let element_set = optional("ELEMENT_SET_NO") as u32;
+    let ephemeris_type = optional("EPHEMERIS_TYPE") as u32;
+
+    record.format_element_lines(ephemeris_type, element_set);
+    Ok(record)
+}
+
+/// TLE international designator ("98067A") of a COSPAR ID ("1998-067A")
+fn tle_designator(object_id: &str) -> String {
+    match object_id.split_once('-') {
+        Some((year, piece)) if year.len() == 4 => format!("{}{}", &year[2..], piece),
+        _ => object_id.to_string(),
+    }
+    .chars()
+    .take(8)
+    .collect()
+}
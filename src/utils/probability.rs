@@ -0,0 +1,49 @@
+// Collision probability of a short-term encounter from the objects' position covariances
+
+use bevy::math::{DMat3, DVec3};
+
+/// Radial and angular samples of the hard-body disk
+const DISK_RINGS: usize = 12;
+const DISK_SECTORS: usize = 24;
+
+/// Foster-style collision probability of a linear encounter
+///
+/// The combined position covariance (km², inertial) is projected onto the encounter plane normal to the
+/// relative velocity, and the resulting 2D Gaussian centered on the miss vector is integrated over the
+/// combined hard-body disk (radius in km).
+pub fn foster_probability(miss: DVec3, relative_velocity: DVec3, covariance: DMat3, hard_body_radius: f64) -> f64 {
+    let along = relative_velocity.normalize_or_zero();
+    if along == DVec3::ZERO || hard_body_radius <= 0.0 {
+        return 0.0;
+    }
+    // Encounter-plane axes: towards the miss and perpendicular to it
+    let x_axis = (miss - along * miss.dot(along)).try_normalize().unwrap_or_else(|| along.any_orthonormal_vector());
+    let y_axis = along.cross(x_axis);
+
+    let (sxx, syy, sxy) = (
+        x_axis.dot(covariance * x_axis),
+        y_axis.dot(covariance * y_axis),
+        x_axis.dot(covariance * y_axis),
+    );
+    let determinant = sxx * syy - sxy * sxy;
+    if determinant <= 0.0 {
+        return 0.0;
+    }
+    let (ixx, iyy, ixy) = (syy / determinant, sxx / determinant, -sxy / determinant);
+    let miss_distance = miss.dot(x_axis);
+
+    // Midpoint rule in polar coordinates around the disk center
+    let dr = hard_body_radius / DISK_RINGS as f64;
+    let dtheta = std::f64::consts::TAU / DISK_SECTORS as f64;
+    let mut integral = 0.0;
+    for ring in 0..DISK_RINGS {
+        let r = (ring as f64 + 0.5) * dr;
+        for sector in 0..DISK_SECTORS {
+            let theta = (sector as f64 + 0.5) * dtheta;
+            let (x, y) = (r * theta.cos() + miss_distance, r * theta.sin());
+            let exponent = -0.5 * (ixx * x * x + 2.0 * ixy * x * y + iyy * y * y);
+            integral += exponent.exp() * r * dr * dtheta;
+        }
+    }
+    (integral / (std::f64::consts::TAU * determinant.sqrt())).min(1.0)
+}
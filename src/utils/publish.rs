@@ -0,0 +1,135 @@
+// Event publishing targets and a minimal MQTT 3.1.1 client
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+/// Where simulation events are published
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublishTarget {
+    /// MQTT broker; events go to `<topic>/<kind>`
+    Mqtt { host: String, port: u16, topic: String },
+    /// Kafka topic through a Confluent-compatible REST proxy; the event kind is the record key
+    Kafka { host: String, port: u16, topic: String },
+}
+
+impl std::fmt::Display for PublishTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PublishTarget::Mqtt { host, port, topic } => write!(f, "mqtt://{}:{}/{}", host, port, topic),
+            PublishTarget::Kafka { host, port, topic } => write!(f, "kafka://{}:{}/{}", host, port, topic),
+        }
+    }
+}
+
+impl std::str::FromStr for PublishTarget {
+    type Err = String;
+
+    /// Parse `mqtt://host[:port]/topic` or `kafka://host[:port]/topic`
+    fn from_str(url: &str) -> Result<Self, String> {
+        let (scheme, rest) = url.split_once("://").ok_or("expected mqtt:// or kafka:// URL")?;
+        let (authority, topic) = rest.split_once('/').ok_or("missing topic")?;
+        let topic = topic.trim_matches('/').to_string();
+        if topic.is_empty() {
+            return Err("missing topic".to_string());
+        }
+        let default_port = match scheme {
+            "mqtt" => 1883,
+            "kafka" => 8082,
+            other => return Err(format!("unsupported scheme {}", other)),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| format!("invalid port {}", port))?),
+            None => (authority, default_port),
+        };
+        if host.is_empty() {
+            return Err("missing host".to_string());
+        }
+        let host = host.to_string();
+
+        Ok(if scheme == "mqtt" {
+            PublishTarget::Mqtt { host, port, topic }
+        } else {
+            PublishTarget::Kafka { host, port, topic }
+        })
+    }
+}
+
+/// Fire-and-forget (QoS 0) MQTT 3.1.1 publisher
+pub struct MqttClient {
+    stream: TcpStream,
+    keep_alive: Duration,
+    last_sent: Instant,
+}
+
+impl MqttClient {
+    /// Open a clean session with the broker
+    pub fn connect(host: &str, port: u16, client_id: &str, keep_alive_secs: u16) -> std::io::Result<Self> {
+        let mut stream = TcpStream::connect((host, port))?;
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+        let mut body = Vec::new();
+        write_string(&mut body, "MQTT");
+        body.push(4); // Protocol level 3.1.1
+        body.push(0x02); // Clean session
+        body.extend_from_slice(&keep_alive_secs.to_be_bytes());
+        write_string(&mut body, client_id);
+        write_packet(&mut stream, 0x10, &body)?;
+
+        let mut connack = [0u8; 4];
+        stream.read_exact(&mut connack)?;
+        if connack[0] != 0x20 || connack[3] != 0 {
+            return Err(std::io::Error::other(format!("broker refused connection (code {})", connack[3])));
+        }
+
+        Ok(Self {
+            stream,
+            keep_alive: Duration::from_secs(keep_alive_secs as u64),
+            last_sent: Instant::now(),
+        })
+    }
+
+    /// Publish a message at QoS 0
+    pub fn publish(&mut self, topic: &str, payload: &[u8]) -> std::io::Result<()> {
+        let mut body = Vec::with_capacity(topic.len() + payload.len() + 2);
+        write_string(&mut body, topic);
+        body.extend_from_slice(payload);
+        write_packet(&mut self.stream, 0x30, &body)?;
+        self.last_sent = Instant::now();
+        Ok(())
+    }
+
+    /// Send a PINGREQ if the connection has been idle for half the keep-alive interval
+    pub fn keep_alive(&mut self) -> std::io::Result<()> {
+        if self.last_sent.elapsed() >= self.keep_alive / 2 {
+            write_packet(&mut self.stream, 0xC0, &[])?;
+            self.last_sent = Instant::now();
+        }
+        Ok(())
+    }
+}
+
+/// Append a length-prefixed UTF-8 string
+fn write_string(buffer: &mut Vec<u8>, text: &str) {
+    buffer.extend_from_slice(&(text.len() as u16).to_be_bytes());
+    buffer.extend_from_slice(text.as_bytes());
+}
+
+/// Write a control packet with its variable-length remaining-length header
+fn write_packet(stream: &mut TcpStream, header: u8, body: &[u8]) -> std::io::Result<()> {
+    let mut packet = vec![header];
+    let mut remaining = body.len();
+    loop {
+        let mut byte = (remaining % 128) as u8;
+        remaining /= 128;
+        if remaining > 0 {
+            byte |= 0x80;
+        }
+        packet.push(byte);
+        if remaining == 0 {
+            break;
+        }
+    }
+    packet.extend_from_slice(body);
+    stream.write_all(&packet)
+}
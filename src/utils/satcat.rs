@@ -0,0 +1,95 @@
+// CelesTrak SATCAT (satellite catalog) CSV parsing
+//
+// CelesTrak's SATCAT carries radar cross-sections; mass and size columns are read too when a snapshot has them.
+
+use std::collections::HashMap;
+use crate::utils::csv::split_csv_line;
+
+/// Catalog metadata of one object
+#[derive(Debug, Clone, PartialEq)]
+pub struct SatcatEntry {
+    /// Owner/country code, e.g. "US", "PRC", "CIS"
+    pub owner: String,
+    /// Object type: "PAY", "R/B", "DEB" or "UNK"
+    pub object_type: String,
+    /// Operational status code ("+" operational, "-" nonoperational, ...); empty for non-payloads
+    pub ops_status: String,
+    /// Radar cross-section (m²)
+    pub rcs: Option<f64>,
+    /// Mass (kg)
+    pub mass: Option<f64>,
+    /// Largest dimension (m)
+    pub span: Option<f64>,
+}
+
+/// Columns read for an object's mass, in order of preference
+const MASS_COLUMNS: [&str; 3] = ["MASS", "LAUNCH_MASS", "DRY_MASS"];
+/// Columns read for an object's largest dimension, in order of preference
+const SPAN_COLUMNS: [&str; 3] = ["SPAN", "LENGTH", "DIAMETER"];
+
+impl SatcatEntry {
+    /// Whether the object is a derelict: rocket body, debris or nonoperational payload
+    pub fn is_derelict(&self) -> bool {
+        match self.object_type.as_str() {
+            "PAY" => self.ops_status == "-",
+            "UNK" => false,
+            _ => true,
+        }
+    }
+
+    /// Characteristic length (m): the largest dimension, or the diameter of a disc matching the radar cross-section
+    pub fn characteristic_length(&self) -> Option<f64> {
+        self.span
+            .or_else(|| self.rcs.map(|rcs| (4.0 * rcs / std::f64::consts::PI).sqrt()))
+            .filter(|&length| length > 0.0)
+    }
+
+    /// Mass (kg) from the catalog, or for debris from its size with the NASA standard breakup model's
+    /// size-density relation
+    pub fn estimated_mass(&self) -> Option<f64> {
+        if let Some(mass) = self.mass.filter(|&mass| mass > 0.0) {
+            return Some(mass);
+        }
+        if self.object_type != "DEB" {
+            return None;
+        }
+        // ρ = 92.937 Lc^-0.74 kg/m³ over a sphere of diameter Lc
+        self.characteristic_length().map(|length| std::f64::consts::PI / 6.0 * 92.937 * length.powf(2.26))
+    }
+}
+
+/// Parse the SATCAT CSV into entries keyed by NORAD ID
+pub fn parse_satcat_csv(text: &str) -> Result<HashMap<u32, SatcatEntry>, String> {
+    let mut lines = text.lines();
+    let header = split_csv_line(lines.next().ok_or("empty SATCAT file")?);
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|field| field.trim() == name)
+            .ok_or(format!("SATCAT column {} missing", name))
+    };
+    let (norad, owner, object_type, ops_status) =
+        (column("NORAD_CAT_ID")?, column("OWNER")?, column("OBJECT_TYPE")?, column("OPS_STATUS_CODE")?);
+    let rcs = column("RCS").ok();
+    let first_column = |names: &[&str]| names.iter().find_map(|name| column(name).ok());
+    let (mass, span) = (first_column(&MASS_COLUMNS), first_column(&SPAN_COLUMNS));
+
+    let mut entries = HashMap::new();
+    for line in lines.filter(|line| !line.trim().is_empty()) {
+        let fields = split_csv_line(line);
+        let field = |index: usize| fields.get(index).map(|f| f.trim().to_string()).unwrap_or_default();
+        let number = |index: Option<usize>| index.and_then(|index| field(index).parse::<f64>().ok());
+        let Ok(norad_id) = field(norad).parse::<u32>() else {
+            continue;
+        };
+        entries.insert(norad_id, SatcatEntry {
+            owner: field(owner),
+            object_type: field(object_type),
+            ops_status: field(ops_status),
+            rcs: number(rcs),
+            mass: number(mass),
+            span: number(span),
+        });
+    }
+    Ok(entries)
+}
@@ -1,16 +1,80 @@
-// SGP4 wrapper - Simplified implementation for TLE to state vector conversion
-// For Phase 2, we'll implement a working version using basic orbital mechanics
-// This can be enhanced with full SGP4 later
+// SGP4 wrapper - TLE to state vector conversion and propagation
+// Records with element lines are propagated with SGP4/SDP4; records without them (the built-in
+// test dataset) fall back to two-body propagation of their mean elements
 
-use crate::utils::TleRecord;
-use bevy::prelude::Vec3;
+use crate::utils::{orbital_elements, tle_epoch_unix, unix_to_julian_date, OrbitalElements, TleRecord, J2000_JULIAN_DATE};
+use bevy::math::DVec3;
 use std::f64::consts::PI;
 
-/// Convert TLE data to initial position/velocity state vectors
-/// Uses simplified orbital mechanics (can be upgraded to full SGP4 later)
-pub fn tle_to_state_vectors(tle: &TleRecord) -> Result<(Vec3, Vec3), String> {
+/// Propagate a TLE to a UTC Unix time, returning TEME position (km) and velocity (km/s)
+pub fn propagate_tle(tle: &TleRecord, unix_seconds: f64) -> Result<(DVec3, DVec3), String> {
+    let minutes = (unix_seconds - tle_epoch_unix(tle.epoch_year, tle.epoch_day)) / 60.0;
+    if tle.line1.trim().is_empty() || tle.line2.trim().is_empty() {
+        let mut advanced = tle.clone();
+        let delta_mean_anomaly = tle.mean_motion * 2.0 * PI / 1440.0 * minutes;
+        advanced.mean_anomaly = (tle.mean_anomaly + delta_mean_anomaly.to_degrees()).rem_euclid(360.0);
+        return mean_elements_to_state_vectors(&advanced);
+    }
+
+    let elements = sgp4::Elements::from_tle(Some(tle.name.clone()), tle.line1.as_bytes(), tle.line2.as_bytes())
+        .map_err(|e| format!("invalid TLE: {}", e))?;
+    let constants = sgp4::Constants::from_elements(&elements).map_err(|e| format!("SGP4 initialization failed: {}", e))?;
+    let prediction = constants
+        .propagate(sgp4::MinutesSinceEpoch(minutes))
+        .map_err(|e| format!("SGP4 propagation failed: {}", e))?;
+
+    let [x, y, z] = prediction.position;
+    let [vx, vy, vz] = prediction.velocity;
+    Ok((DVec3::new(x, y, z), DVec3::new(vx, vy, vz)))
+}
+
+/// Maximum correction passes of the mean element fit
+const MEAN_ELEMENT_ITERATIONS: usize = 50;
+/// Position residual (km) below which fitted mean elements reproduce the state
+const MEAN_ELEMENT_TOLERANCE: f64 = 1e-6;
+
+/// SGP4 propagator for mean elements at a UTC Unix epoch, as built from a TLE carrying them
+fn sgp4_constants(elements: &OrbitalElements, epoch_unix: f64, bstar: f64, mu_km: f64) -> Result<sgp4::Constants, String> {
+    let orbit = sgp4::Orbit::from_kozai_elements(
+        &sgp4::WGS84,
+        elements.inclination,
+        elements.raan,
+        elements.eccentricity,
+        elements.argument_of_perigee,
+        elements.mean_anomaly,
+        elements.mean_motion_rev_per_day(mu_km) * 2.0 * PI / 1440.0,
+    ).map_err(|e| format!("invalid mean elements: {}", e))?;
+    let epoch = (unix_to_julian_date(epoch_unix) - J2000_JULIAN_DATE) / 365.25;
+    sgp4::Constants::new(sgp4::WGS84, sgp4::iau_epoch_to_sidereal_time, epoch, bstar, orbit)
+        .map_err(|e| format!("SGP4 initialization failed: {}", e))
+}
+
+/// Fit SGP4 mean elements that reproduce a TEME state (km, km/s) at a UTC Unix epoch
+///
+/// The osculating elements of the state are the first guess. Each pass propagates the guess with SGP4 to its epoch
+/// and shifts the state the guess is taken from by the residual, until SGP4 returns the original state.
+pub fn fit_mean_elements(position: DVec3, velocity: DVec3, epoch_unix: f64, bstar: f64, mu_km: f64) -> Result<OrbitalElements, String> {
+    let (mut fit_position, mut fit_velocity) = (position, velocity);
+    for _ in 0..MEAN_ELEMENT_ITERATIONS {
+        let elements = orbital_elements(fit_position, fit_velocity, mu_km).ok_or("unbound or degenerate orbit")?;
+        let prediction = sgp4_constants(&elements, epoch_unix, bstar, mu_km)?
+            .propagate(sgp4::MinutesSinceEpoch(0.0))
+            .map_err(|e| format!("SGP4 propagation failed: {}", e))?;
+        let [x, y, z] = prediction.position;
+        let [vx, vy, vz] = prediction.velocity;
+        let position_residual = position - DVec3::new(x, y, z);
+        if position_residual.length() < MEAN_ELEMENT_TOLERANCE {
+            return Ok(elements);
+        }
+        fit_position += position_residual;
+        fit_velocity += velocity - DVec3::new(vx, vy, vz);
+    }
+    Err("mean element fit did not converge".to_string())
+}
+
+/// Convert mean orbital elements to state vectors with two-body mechanics
+fn mean_elements_to_state_vectors(tle: &TleRecord) -> Result<(DVec3, DVec3), String> {
     // Convert orbital elements to Cartesian coordinates
-    // This is a simplified implementation - full SGP4 would be more accurate
     
     // Convert angles from degrees to radians
     let inclination = tle.inclination.to_radians();
@@ -32,8 +96,8 @@ pub fn tle_to_state_vectors(tle: &TleRecord) -> Result<(Vec3, Vec3), String> {
     let eccentric_anomaly = solve_keplers_equation(mean_anomaly, eccentricity)?;
     
     // Calculate true anomaly
-    let true_anomaly = 2.0 * ((1.0 + eccentricity)/(1.0 - eccentricity)).sqrt().atan() *
-                       (eccentric_anomaly / 2.0).tan();
+    let true_anomaly = 2.0 * (((1.0 + eccentricity)/(1.0 - eccentricity)).sqrt() *
+                       (eccentric_anomaly / 2.0).tan()).atan();
     
     // Calculate distance from Earth center
     let radius = semi_major_axis * (1.0 - eccentricity * eccentric_anomaly.cos());
@@ -42,17 +106,17 @@ pub fn tle_to_state_vectors(tle: &TleRecord) -> Result<(Vec3, Vec3), String> {
     let cos_true_anom = true_anomaly.cos();
     let sin_true_anom = true_anomaly.sin();
     
-    let pos_orbital = Vec3::new(
-        (radius * cos_true_anom) as f32,
-        (radius * sin_true_anom) as f32,
+    let pos_orbital = DVec3::new(
+        radius * cos_true_anom,
+        radius * sin_true_anom,
         0.0
     );
     
     // Velocity in orbital plane (km/s)
     let h = (mu * semi_major_axis * (1.0 - eccentricity * eccentricity)).sqrt(); // Angular momentum
-    let vel_orbital = Vec3::new(
-        (-mu / h * sin_true_anom) as f32,
-        (mu / h * (eccentricity + cos_true_anom)) as f32,
+    let vel_orbital = DVec3::new(
+        -mu / h * sin_true_anom,
+        mu / h * (eccentricity + cos_true_anom),
         0.0
     );
     
@@ -78,17 +142,17 @@ pub fn tle_to_state_vectors(tle: &TleRecord) -> Result<(Vec3, Vec3), String> {
     let p33 = cos_inc;
     
     // Transform position to ECI coordinates
-    let position = Vec3::new(
-        (p11 * pos_orbital.x as f64 + p12 * pos_orbital.y as f64 + p13 * pos_orbital.z as f64) as f32,
-        (p21 * pos_orbital.x as f64 + p22 * pos_orbital.y as f64 + p23 * pos_orbital.z as f64) as f32,
-        (p31 * pos_orbital.x as f64 + p32 * pos_orbital.y as f64 + p33 * pos_orbital.z as f64) as f32,
+    let position = DVec3::new(
+        p11 * pos_orbital.x + p12 * pos_orbital.y + p13 * pos_orbital.z,
+        p21 * pos_orbital.x + p22 * pos_orbital.y + p23 * pos_orbital.z,
+        p31 * pos_orbital.x + p32 * pos_orbital.y + p33 * pos_orbital.z,
     );
     
     // Transform velocity to ECI coordinates
-    let velocity = Vec3::new(
-        (p11 * vel_orbital.x as f64 + p12 * vel_orbital.y as f64 + p13 * vel_orbital.z as f64) as f32,
-        (p21 * vel_orbital.x as f64 + p22 * vel_orbital.y as f64 + p23 * vel_orbital.z as f64) as f32,
-        (p31 * vel_orbital.x as f64 + p32 * vel_orbital.y as f64 + p33 * vel_orbital.z as f64) as f32,
+    let velocity = DVec3::new(
+        p11 * vel_orbital.x + p12 * vel_orbital.y + p13 * vel_orbital.z,
+        p21 * vel_orbital.x + p22 * vel_orbital.y + p23 * vel_orbital.z,
+        p31 * vel_orbital.x + p32 * vel_orbital.y + p33 * vel_orbital.z,
     );
     
     Ok((position, velocity))
@@ -119,72 +183,12 @@ fn solve_keplers_equation(mean_anomaly: f64, eccentricity: f64) -> Result<f64, S
     Err("Failed to converge in Kepler's equation solution".to_string())
 }
 
-/// Get current Julian day number for epoch calculations
-pub fn current_julian_day() -> f64 {
+/// Propagate a satellite from its TLE epoch to the current wall-clock time
+pub fn propagate_to_current_time(tle: &TleRecord) -> Result<(DVec3, DVec3), String> {
     use std::time::{SystemTime, UNIX_EPOCH};
     let unix_time = SystemTime::now()
         .duration_since(UNIX_EPOCH)
-        .unwrap()
+        .map_err(|e| e.to_string())?
         .as_secs_f64();
-    
-    // Convert Unix timestamp to Julian Day Number
-    // Unix epoch (1970-01-01) is JD 2440587.5
-    2440587.5 + unix_time / 86400.0
+    propagate_tle(tle, unix_time)
 }
-
-/// Calculate time difference between TLE epoch and current time in days
-pub fn days_since_epoch(tle: &TleRecord) -> f64 {
-    let current_jd = current_julian_day();
-    
-    // Convert TLE epoch to Julian Day
-    let year = if tle.epoch_year < 57 {
-        tle.epoch_year + 2000
-    } else {
-        tle.epoch_year + 1900
-    } as f64;
-    
-    // Calculate Julian Day for TLE epoch
-    // Approximate calculation - good enough for this phase
-    let tle_jd = 365.25 * (year - 1.0) + tle.epoch_day + 1721425.5;
-    
-    current_jd - tle_jd
-}
-
-/// Propagate satellite using simplified orbital mechanics
-/// This advances the satellite from its TLE epoch to the current time
-pub fn propagate_to_current_time(tle: &TleRecord) -> Result<(Vec3, Vec3), String> {
-    // Get position/velocity at TLE epoch
-    let (mut pos, mut vel) = tle_to_state_vectors(tle)?;
-    
-    // Calculate time elapsed since TLE epoch
-    let days_elapsed = days_since_epoch(tle);
-    let seconds_elapsed = days_elapsed * 86400.0;
-    
-    // For now, use simple Keplerian propagation
-    // This could be enhanced with perturbation models later
-    
-    // Calculate mean motion in rad/s
-    let mean_motion_rad_per_sec = tle.mean_motion * 2.0 * PI / 86400.0;
-    
-    // Advance mean anomaly
-    let delta_mean_anomaly = mean_motion_rad_per_sec * seconds_elapsed;
-    
-    // Create updated TLE with advanced mean anomaly
-    let mut updated_tle = tle.clone();
-    updated_tle.mean_anomaly = (tle.mean_anomaly + delta_mean_anomaly.to_degrees()) % 360.0;
-    
-    // Recalculate position and velocity
-    tle_to_state_vectors(&updated_tle)
-}
-
-/// Convert TLE data to simplified orbital state (for immediate use)
-/// This provides basic orbital mechanics without full SGP4 complexity
-pub fn tle_to_simple_orbit(tle: &TleRecord) -> Result<(Vec3, Vec3, f64), String> {
-    let (pos, vel) = tle_to_state_vectors(tle)?;
-    
-    // Calculate orbital period in seconds
-    let mean_motion_rad_per_sec = tle.mean_motion * 2.0 * PI / 86400.0;
-    let orbital_period = 2.0 * PI / mean_motion_rad_per_sec;
-    
-    Ok((pos, vel, orbital_period))
-}
\ No newline at end of file
@@ -0,0 +1,75 @@
+// Sun-synchronous orbit geometry: inclination from altitude and RAAN from local time of ascending node
+
+use bevy::math::DVec3;
+use crate::utils::epoch::sun_right_ascension;
+
+/// WGS-84 J2 zonal harmonic
+pub const J2: f64 = 1.082_63e-3;
+/// Highest altitude (km) at which J2 can still match the Sun's mean motion
+pub const SSO_MAX_ALTITUDE: f64 = 5900.0;
+
+/// Inclination (degrees) whose J2 nodal precession matches the Sun's mean motion for a circular orbit
+pub fn sun_synchronous_inclination(altitude_km: f64) -> f64 {
+    let earth_radius = 6378.137_f64;
+    let mu = 398_600.44_f64;
+    let a = earth_radius + altitude_km;
+    let mean_motion = (mu / (a * a * a)).sqrt();
+    let target_precession = std::f64::consts::TAU / (365.2422 * 86400.0);
+    let cos_i = -target_precession / (1.5 * mean_motion * J2 * (earth_radius / a).powi(2));
+    cos_i.clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+/// RAAN (radians) placing the ascending node at a local solar time (hours) at a Unix time
+pub fn raan_for_ltan(ltan_hours: f64, unix_seconds: f64) -> f64 {
+    // Noon is the sub-solar meridian; each hour of local time is 15° of right ascension
+    (sun_right_ascension(unix_seconds) + ((ltan_hours - 12.0) * 15.0).to_radians()).rem_euclid(std::f64::consts::TAU)
+}
+
+/// Inertial state (km, km/s) on a circular orbit; `argument_of_latitude` is measured from the ascending node
+pub fn circular_orbit_state(altitude_km: f64, inclination: f64, raan: f64, argument_of_latitude: f64, earth_radius: f64, mu_km: f64) -> (DVec3, DVec3) {
+    let radius = earth_radius + altitude_km;
+    let speed = (mu_km / radius).sqrt();
+    let (sin_u, cos_u) = argument_of_latitude.sin_cos();
+    let (sin_i, cos_i) = inclination.sin_cos();
+    let (sin_o, cos_o) = raan.sin_cos();
+
+    // Perifocal directions rotated by inclination and RAAN (z is the polar axis)
+    let node = DVec3::new(cos_o, sin_o, 0.0);
+    let normal_in_plane = DVec3::new(-sin_o * cos_i, cos_o * cos_i, sin_i);
+    let position = (node * cos_u + normal_in_plane * sin_u) * radius;
+    let velocity = (node * -sin_u + normal_in_plane * cos_u) * speed;
+    (position, velocity)
+}
+
+/// A sun-synchronous orbit plane to populate, given as `ALT:LTAN[:COUNT]` (km, decimal hours)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SsoPlane {
+    pub altitude: f64,
+    pub ltan_hours: f64,
+    /// Satellites spread evenly around the plane
+    pub count: usize,
+}
+
+impl std::str::FromStr for SsoPlane {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = text.split(':').collect();
+        if !(2..=3).contains(&parts.len()) {
+            return Err(format!("expected ALT:LTAN[:COUNT], got {}", text));
+        }
+        let altitude: f64 = parts[0].parse().map_err(|_| format!("invalid altitude {}", parts[0]))?;
+        let ltan_hours: f64 = parts[1].parse().map_err(|_| format!("invalid LTAN {}", parts[1]))?;
+        let count = match parts.get(2) {
+            Some(count) => count.parse().map_err(|_| format!("invalid count {}", count))?,
+            None => 1,
+        };
+        if !(0.0..=SSO_MAX_ALTITUDE).contains(&altitude) {
+            return Err(format!("no sun-synchronous orbit at {} km", altitude));
+        }
+        if !(0.0..24.0).contains(&ltan_hours) {
+            return Err(format!("LTAN must be in [0, 24) hours, got {}", ltan_hours));
+        }
+        Ok(Self { altitude, ltan_hours, count })
+    }
+}
@@ -0,0 +1,197 @@
+// STK ephemeris (.e) file parsing and interpolation
+
+use bevy::math::DVec3;
+use crate::utils::utc_to_unix;
+
+/// Externally supplied trajectory loaded from an STK ephemeris file
+#[derive(Debug, Clone)]
+pub struct StkEphemeris {
+    /// Coordinate system named in the file
+    pub coordinate_system: String,
+    /// Samples as (unix time, position km, velocity km/s), sorted by time
+    pub samples: Vec<(f64, DVec3, DVec3)>,
+}
+
+impl StkEphemeris {
+    /// Time span covered by the ephemeris (Unix seconds)
+    pub fn span(&self) -> Option<(f64, f64)> {
+        Some((self.samples.first()?.0, self.samples.last()?.0))
+    }
+
+    /// Cubic Hermite interpolation of the state at a Unix time, None outside the ephemeris span
+    pub fn interpolate(&self, unix_seconds: f64) -> Option<(DVec3, DVec3)> {
+        let (start, end) = self.span()?;
+        if unix_seconds < start || unix_seconds > end {
+            return None;
+        }
+
+        let upper = self.samples.partition_point(|sample| sample.0 < unix_seconds);
+        if upper == 0 {
+            let (_, position, velocity) = self.samples[0];
+            return Some((position, velocity));
+        }
+        let (t0, p0, v0) = self.samples[upper - 1];
+        let (t1, p1, v1) = self.samples[upper];
+        let h = t1 - t0;
+        if h <= 0.0 {
+            return Some((p1, v1));
+        }
+
+        let s = (unix_seconds - t0) / h;
+        let (s2, s3) = (s * s, s * s * s);
+        let position = p0 * (2.0 * s3 - 3.0 * s2 + 1.0)
+            + v0 * h * (s3 - 2.0 * s2 + s)
+            + p1 * (-2.0 * s3 + 3.0 * s2)
+            + v1 * h * (s3 - s2);
+        let velocity = (p0 - p1) * ((6.0 * s2 - 6.0 * s) / h)
+            + v0 * (3.0 * s2 - 4.0 * s + 1.0)
+            + v1 * (3.0 * s2 - 2.0 * s);
+        Some((position, velocity))
+    }
+}
+
+/// Errors raised while reading an STK ephemeris file
+#[derive(Debug)]
+pub enum StkParseError {
+    MissingEpoch,
+    MissingData,
+    InvalidEpoch(String),
+    InvalidLine(usize),
+    Unsupported(String),
+}
+
+impl std::fmt::Display for StkParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StkParseError::MissingEpoch => write!(f, "No ScenarioEpoch in ephemeris"),
+            StkParseError::MissingData => write!(f, "No EphemerisTimePosVel or EphemerisTimePos samples"),
+            StkParseError::InvalidEpoch(epoch) => write!(f, "Invalid ScenarioEpoch: {}", epoch),
+            StkParseError::InvalidLine(line) => write!(f, "Invalid ephemeris sample on line {}", line),
+            StkParseError::Unsupported(what) => write!(f, "Unsupported {}", what),
+        }
+    }
+}
+
+impl std::error::Error for StkParseError {}
+
+/// Inertial coordinate systems accepted without transformation
+const INERTIAL_SYSTEMS: [&str; 5] = ["J2000", "ICRF", "MeanOfDate", "TrueOfDate", "TEMEOfDate"];
+
+/// Parse an STK ephemeris in EphemerisTimePosVel or EphemerisTimePos format
+pub fn parse_stk_ephemeris(text: &str) -> Result<StkEphemeris, StkParseError> {
+    let mut epoch_unix = None;
+    let mut coordinate_system = "J2000".to_string();
+    let mut distance_scale = 1e-3; // STK defaults to meters
+    let mut with_velocity = None;
+    let mut position_only = false;
+    let mut samples = Vec::new();
+
+    for (index, raw_line) in text.lines().enumerate() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // Inside a data block every line is a sample until the next keyword
+        if let Some(has_velocity) = with_velocity {
+            let fields: Option<Vec<f64>> = line.split_whitespace().map(|f| f.parse().ok()).collect();
+            if let Some(fields) = fields {
+                let epoch = epoch_unix.ok_or(StkParseError::MissingEpoch)?;
+                let expected = if has_velocity { 7 } else { 4 };
+                if fields.len() < expected {
+                    return Err(StkParseError::InvalidLine(index + 1));
+                }
+                let position = DVec3::new(fields[1], fields[2], fields[3]) * distance_scale;
+                let velocity = if has_velocity {
+                    DVec3::new(fields[4], fields[5], fields[6]) * distance_scale
+                } else {
+                    DVec3::ZERO
+                };
+                samples.push((epoch + fields[0], position, velocity));
+                continue;
+            }
+            with_velocity = None;
+        }
+
+        let mut parts = line.splitn(2, char::is_whitespace);
+        let keyword = parts.next().unwrap_or_default();
+        let value = parts.next().unwrap_or_default().trim();
+        match keyword {
+            "ScenarioEpoch" => epoch_unix = Some(parse_stk_date(value)?),
+            "CentralBody" if !value.eq_ignore_ascii_case("Earth") => {
+                return Err(StkParseError::Unsupported(format!("central body {}", value)));
+            }
+            "CoordinateSystem" => {
+                if !INERTIAL_SYSTEMS.iter().any(|s| s.eq_ignore_ascii_case(value)) {
+                    return Err(StkParseError::Unsupported(format!("coordinate system {}", value)));
+                }
+                coordinate_system = value.to_string();
+            }
+            "DistanceUnit" => {
+                distance_scale = match value.to_ascii_lowercase().as_str() {
+                    "meters" => 1e-3,
+                    "kilometers" => 1.0,
+                    _ => return Err(StkParseError::Unsupported(format!("distance unit {}", value))),
+                };
+            }
+            "EphemerisTimePosVel" => with_velocity = Some(true),
+            "EphemerisTimePos" => {
+                with_velocity = Some(false);
+                position_only = true;
+            }
+            _ => {}
+        }
+    }
+
+    if epoch_unix.is_none() {
+        return Err(StkParseError::MissingEpoch);
+    }
+    if samples.len() < 2 {
+        return Err(StkParseError::MissingData);
+    }
+    samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // Position-only files get velocities from central differences
+    if position_only {
+        let positions: Vec<(f64, DVec3)> = samples.iter().map(|s| (s.0, s.1)).collect();
+        for (i, sample) in samples.iter_mut().enumerate() {
+            let (ta, pa) = positions[i.saturating_sub(1)];
+            let (tb, pb) = positions[(i + 1).min(positions.len() - 1)];
+            if tb > ta {
+                sample.2 = (pb - pa) / (tb - ta);
+            }
+        }
+    }
+
+    Ok(StkEphemeris {
+        coordinate_system,
+        samples,
+    })
+}
+
+/// Parse an STK UTCG date such as "1 Jun 2024 12:00:00.000"
+fn parse_stk_date(value: &str) -> Result<f64, StkParseError> {
+    const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+    let invalid = || StkParseError::InvalidEpoch(value.to_string());
+
+    let fields: Vec<&str> = value.split_whitespace().collect();
+    let [day, month, year, time] = fields.as_slice() else {
+        return Err(invalid());
+    };
+    let day: u32 = day.parse().map_err(|_| invalid())?;
+    let month = MONTHS
+        .iter()
+        .position(|m| month.get(..3).is_some_and(|prefix| prefix.eq_ignore_ascii_case(m)))
+        .ok_or_else(invalid)? as u32 + 1;
+    let year: i64 = year.parse().map_err(|_| invalid())?;
+
+    let time_fields: Vec<&str> = time.split(':').collect();
+    let [hour, minute, second] = time_fields.as_slice() else {
+        return Err(invalid());
+    };
+    let hour: u32 = hour.parse().map_err(|_| invalid())?;
+    let minute: u32 = minute.parse().map_err(|_| invalid())?;
+    let second: f64 = second.parse().map_err(|_| invalid())?;
+
+    Ok(utc_to_unix(year, month, day, hour, minute, second))
+}
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use bevy::prelude::*;
 
+/// Skipped entries logged one by one before the rest are only counted
+const MAX_REPORTED_ISSUES: usize = 10;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TleRecord {
     pub name: String,
@@ -24,11 +27,27 @@ pub struct TleRecord {
 }
 
 impl TleRecord {
-    /// Parse a complete TLE record from three lines
+    /// Parse a complete TLE record from three lines, checking both element lines' numbers and checksums
     pub fn from_tle_lines(name: &str, line1: &str, line2: &str) -> Result<Self, TleParseError> {
+        if !line1.is_ascii() || !line2.is_ascii() {
+            return Err(TleParseError::InvalidFormat);
+        }
         if line1.len() < 69 || line2.len() < 69 {
             return Err(TleParseError::InvalidLength);
         }
+        for (number, line) in [(1, line1), (2, line2)] {
+            if !line.starts_with(&format!("{} ", number)) {
+                return Err(TleParseError::MissingLine(number));
+            }
+            let expected = tle_checksum(&line[..68]);
+            let found = line.as_bytes()[68] as char;
+            if found.to_digit(10) != Some(expected) {
+                return Err(TleParseError::Checksum { line: number, expected, found });
+            }
+        }
+        if line1[2..7] != line2[2..7] {
+            return Err(TleParseError::CatalogMismatch(line1[2..7].trim().to_string(), line2[2..7].trim().to_string()));
+        }
 
         // Parse Line 1
         let norad_id = line1[2..7].trim().parse::<u32>()
@@ -89,30 +108,159 @@ impl TleRecord {
             line2: line2.to_string(),
         })
     }
+
+    /// Rebuild both element lines, checksums included, from the record's fields
+    pub fn format_element_lines(&mut self, ephemeris_type: u32, element_set: u32) {
+        let line1 = format!(
+            "1 {:05}{} {:<8} {:02}{:012.8} {} {} {} {} {:4}",
+            self.norad_id % 100_000, self.classification, self.international_designator,
+            self.epoch_year % 100, self.epoch_day,
+            tle_first_derivative(self.mean_motion_dot), tle_exponent(self.mean_motion_ddot), tle_exponent(self.bstar),
+            ephemeris_type % 10, element_set % 10_000,
+        );
+        let line2 = format!(
+            "2 {:05} {:8.4} {:8.4} {:07} {:8.4} {:8.4} {:11.8}{:5}",
+            self.norad_id % 100_000,
+            tle_degrees(self.inclination),
+            tle_degrees(self.right_ascension),
+            ((self.eccentricity * 1e7).round() as u64).min(9_999_999),
+            tle_degrees(self.argument_of_perigee),
+            tle_degrees(self.mean_anomaly),
+            self.mean_motion,
+            self.revolution_number % 100_000,
+        );
+        self.line1 = format!("{}{}", line1, tle_checksum(&line1));
+        self.line2 = format!("{}{}", line2, tle_checksum(&line2));
+    }
 }
 
-/// Parse TLE data from a multi-line string
-pub fn parse_tle_data(data: &str) -> Result<Vec<TleRecord>, TleParseError> {
-    let lines: Vec<&str> = data.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect();
-    let mut records = Vec::new();
-    
+/// A TLE entry that was skipped, with the number of the line it starts on
+#[derive(Debug, Clone)]
+pub struct TleParseIssue {
+    pub line: usize,
+    /// Name line of the entry, for three-line element sets
+    pub name: Option<String>,
+    pub error: TleParseError,
+}
+
+impl std::fmt::Display for TleParseIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "line {} ({}): {}", self.line, name, self.error),
+            None => write!(f, "line {}: {}", self.line, self.error),
+        }
+    }
+}
+
+/// Records parsed from TLE text and the entries skipped as malformed
+#[derive(Debug, Clone, Default)]
+pub struct TleParseReport {
+    pub records: Vec<TleRecord>,
+    pub issues: Vec<TleParseIssue>,
+}
+
+/// Parse two-line and three-line element sets, keeping every valid record and the reason for each skipped entry
+///
+/// Name lines may carry the "0 " prefix of Space-Track's 3LE format. Two-line records are named by their catalog
+/// number.
+pub fn parse_tle_report(data: &str) -> TleParseReport {
+    let lines: Vec<(usize, &str)> = data.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .collect();
+    let is_line = |line: &str, number: char| line.starts_with(number) && line[1..].starts_with(' ');
+    let mut report = TleParseReport::default();
+
     let mut i = 0;
-    while i + 2 < lines.len() {
-        // TLE format: name line, line 1, line 2
-        if lines[i + 1].starts_with('1') && lines[i + 2].starts_with('2') {
-            match TleRecord::from_tle_lines(lines[i], lines[i + 1], lines[i + 2]) {
-                Ok(record) => records.push(record),
-                Err(e) => {
-                    debug!("Failed to parse TLE record for {}: {:?}", lines[i], e);
-                }
-            }
-            i += 3;
+    while i < lines.len() {
+        let (start, first) = lines[i];
+        let name = if is_line(first, '1') {
+            None
+        } else if lines.get(i + 1).is_some_and(|(_, next)| is_line(next, '1')) {
+            i += 1;
+            Some(first.strip_prefix("0 ").unwrap_or(first).trim().to_string())
         } else {
+            report.issues.push(TleParseIssue { line: start, name: None, error: TleParseError::UnexpectedLine });
+            i += 1;
+            continue;
+        };
+        let line1 = lines[i].1;
+        let Some(&(_, line2)) = lines.get(i + 1).filter(|(_, next)| is_line(next, '2')) else {
+            report.issues.push(TleParseIssue { line: start, name, error: TleParseError::MissingLine(2) });
             i += 1;
+            continue;
+        };
+        i += 2;
+
+        match TleRecord::from_tle_lines(name.as_deref().unwrap_or_default(), line1, line2) {
+            Ok(mut record) => {
+                if record.name.is_empty() {
+                    record.name = format!("NORAD {}", record.norad_id);
+                }
+                report.records.push(record);
+            }
+            Err(error) => report.issues.push(TleParseIssue { line: start, name, error }),
         }
     }
-    
-    Ok(records)
+
+    report
+}
+
+/// Parse TLE data from a multi-line string, logging each malformed entry that is skipped
+pub fn parse_tle_data(data: &str) -> Result<Vec<TleRecord>, TleParseError> {
+    let report = parse_tle_report(data);
+    for issue in report.issues.iter().take(MAX_REPORTED_ISSUES) {
+        warn!("Skipped TLE entry at {}", issue);
+    }
+    if report.issues.len() > MAX_REPORTED_ISSUES {
+        warn!("Skipped {} more malformed TLE entries", report.issues.len() - MAX_REPORTED_ISSUES);
+    }
+    Ok(report.records)
+}
+
+/// TLE line checksum: sum of digits with '-' counting as one, modulo 10
+pub fn tle_checksum(line: &str) -> u32 {
+    line.chars()
+        .map(|c| match c {
+            '-' => 1,
+            _ => c.to_digit(10).unwrap_or(0),
+        })
+        .sum::<u32>()
+        % 10
+}
+
+/// Angle in degrees wrapped so it never prints as 360.0000
+fn tle_degrees(degrees: f64) -> f64 {
+    ((degrees * 1e4).round() / 1e4).rem_euclid(360.0)
+}
+
+/// First derivative of mean motion in the TLE's signed decimal field (" .00001234")
+fn tle_first_derivative(value: f64) -> String {
+    let sign = if value < 0.0 { '-' } else { ' ' };
+    let digits = ((value.abs() * 1e8).round() as u64).min(99_999_999);
+    format!("{}.{:08}", sign, digits)
+}
+
+/// A value in the TLE's assumed-decimal exponent field (" 12345-3" is 0.12345e-3)
+///
+/// Values too small for the field print as zero and larger ones than it holds keep the largest exponent.
+pub fn tle_exponent(value: f64) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return " 00000-0".to_string();
+    }
+    let sign = if value < 0.0 { '-' } else { ' ' };
+    let mut exponent = value.abs().log10().floor() as i32 + 1;
+    let mut digits = (value.abs() / 10f64.powi(exponent) * 1e5).round() as u64;
+    if digits >= 100_000 {
+        digits /= 10;
+        exponent += 1;
+    }
+    if exponent < -9 {
+        return " 00000-0".to_string();
+    }
+    let exponent = exponent.min(9);
+    format!("{}{:05}{}{}", sign, digits, if exponent < 0 { '-' } else { '+' }, exponent.abs())
 }
 
 // Helper parsing functions
@@ -153,11 +301,20 @@ fn parse_decimal_fraction(s: &str) -> Result<f64, TleParseError> {
     Ok(value / 10f64.powi(trimmed.len() as i32))
 }
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Clone)]
 pub enum TleParseError {
     InvalidLength,
     InvalidField(String),
     InvalidFormat,
+    /// Element line 1 or 2 is missing where it was expected
+    MissingLine(u8),
+    /// A line that is neither a name nor an element line of an entry
+    UnexpectedLine,
+    /// Line checksum digit does not match the line
+    Checksum { line: u8, expected: u32, found: char },
+    /// The two element lines give different catalog numbers
+    CatalogMismatch(String, String),
 }
 
 impl std::fmt::Display for TleParseError {
@@ -166,6 +323,14 @@ impl std::fmt::Display for TleParseError {
             TleParseError::InvalidLength => write!(f, "TLE line has invalid length"),
             TleParseError::InvalidField(field) => write!(f, "Invalid field: {}", field),
             TleParseError::InvalidFormat => write!(f, "Invalid TLE format"),
+            TleParseError::MissingLine(number) => write!(f, "Missing element line {}", number),
+            TleParseError::UnexpectedLine => write!(f, "Unexpected line outside an element set"),
+            TleParseError::Checksum { line, expected, found } => {
+                write!(f, "Checksum mismatch on line {}: expected {}, found {}", line, expected, found)
+            }
+            TleParseError::CatalogMismatch(line1, line2) => {
+                write!(f, "Catalog numbers differ between lines: {} and {}", line1, line2)
+            }
         }
     }
 }